@@ -3,6 +3,8 @@
 Unit tests for the wdlparse Python library.
 """
 
+import asyncio
+import io
 import os
 import tempfile
 from pathlib import Path
@@ -215,6 +217,14 @@ task broken_task {
         assert result["diagnostics_count"] > 0
         # Note: Whether it has_errors depends on the parser's strictness
 
+    def test_diagnostic_filters(self, invalid_wdl):
+        """Test ignore/only filters on parse diagnostics."""
+        result = wdlparse.parse_text(invalid_wdl, output_format="human", ignore=["syntax-error"])
+        assert not result["has_errors"]
+
+        result = wdlparse.parse_text(invalid_wdl, output_format="human", only=["syntax-note"])
+        assert result["diagnostics_count"] == 0
+
     def test_output_format_enum(self):
         """Test OutputFormat enum values."""
         assert hasattr(wdlparse, "OutputFormat")
@@ -243,6 +253,91 @@ task broken_task {
         assert hasattr(wdlparse, "__version__")
         assert isinstance(wdlparse.__version__, str)
 
+    def test_scan(self, sample_wdl, invalid_wdl):
+        """Test scanning a directory yields a result per WDL file, lazily."""
+        with tempfile.TemporaryDirectory() as directory:
+            root = Path(directory)
+            (root / "nested").mkdir()
+            (root / "hello.wdl").write_text(sample_wdl)
+            (root / "nested" / "broken.wdl").write_text(invalid_wdl)
+            (root / "notes.txt").write_text("not WDL")
+
+            results = wdlparse.scan(root)
+            first = next(results)
+            assert first["path"] == root / "hello.wdl"
+            assert first["error"] is None
+            assert [task["name"] for task in first["info"]["tasks"]] == ["hello"]
+            rest = list(results)
+            assert [result["path"] for result in rest] == [root / "nested" / "broken.wdl"]
+            assert rest[0]["has_errors"]
+
+            files = {result["file"] for result in wdlparse.scan(root, workers=None)}
+            assert files == {str(root / "hello.wdl"), str(root / "nested" / "broken.wdl")}
+
+            nested = list(wdlparse.scan(root, "nested/*.wdl", workers=2))
+            assert [result["path"].name for result in nested] == ["broken.wdl"]
+
+            with pytest.raises(NotADirectoryError):
+                wdlparse.scan(root / "missing")
+            with pytest.raises(ValueError, match="workers"):
+                wdlparse.scan(root, workers=0)
+
+    def test_capabilities(self):
+        """Test the build reports its features and the WDL versions it reads."""
+        features = wdlparse.features()
+        assert features["python"] is True
+        assert features["mermaid"] is True
+        assert all(isinstance(enabled, bool) for enabled in features.values())
+
+        versions = wdlparse.supported_wdl_versions()
+        assert versions[:3] == ["1.0", "1.1", "1.2"]
+        assert "development" in versions
+
+    def test_walk(self, sample_wdl):
+        """Test walk visits every node and token with its span and depth."""
+        visited = []
+        wdlparse.walk(sample_wdl, lambda kind, text, span, depth: visited.append((kind, text, span, depth)))
+
+        root = visited[0]
+        assert root[0] == "RootNode"
+        assert root[2] == (0, len(sample_wdl.encode()))
+        assert root[3] == 0
+        for kind, text, (start, end), _ in visited:
+            assert sample_wdl.encode()[start:end].decode() == text
+        tasks = [entry for entry in visited if entry[0] == "TaskDefinitionNode"]
+        assert tasks and all(depth == 1 for *_, depth in tasks)
+        assert any(kind == "Ident" for kind, *_ in visited)
+
+        # Returning False skips a node's children
+        skipped = []
+        wdlparse.walk(
+            sample_wdl,
+            lambda kind, text, span, depth: skipped.append(kind) or kind != "TaskDefinitionNode",
+        )
+        assert "CommandSectionNode" not in skipped
+        assert "TaskDefinitionNode" in skipped
+
+        nodes = []
+        wdlparse.walk(sample_wdl, lambda kind, *_: nodes.append(kind), include_tokens=False)
+        assert all(kind.endswith("Node") for kind in nodes)
+
+        def fail(*_):
+            raise RuntimeError("stop")
+
+        with pytest.raises(RuntimeError, match="stop"):
+            wdlparse.walk(sample_wdl, fail)
+
+    def test_formatting(self):
+        """Test sizes and numbers are formatted as the command-line tool shows them."""
+        assert wdlparse.format_size(3 * 1024**3) == "3 GiB"
+        assert wdlparse.format_size(1536 * 1024**2) == "1.5 GiB"
+        assert wdlparse.format_size(1536 * 1024**2, si=True) == "1.61 GB"
+        assert wdlparse.format_size(0) == "0 B"
+        assert wdlparse.format_number(1234.5678) == "1234.57"
+        assert wdlparse.format_number(2.0) == "2"
+        assert wdlparse.format_number(-0.001) == "0"
+        assert wdlparse.format_number(0.125, decimals=1) == "0.1"
+
     def test_path_object_support(self, sample_wdl):
         """Test that Path objects are supported."""
         with tempfile.NamedTemporaryFile(mode="w", suffix=".wdl", delete=False) as f:
@@ -261,6 +356,96 @@ task broken_task {
         finally:
             temp_path.unlink()
 
+    def test_bytes_and_file_object_support(self, sample_wdl):
+        """Test documents passed as bytes or file objects, and the path of results."""
+        with tempfile.NamedTemporaryFile(mode="w", suffix=".wdl", delete=False) as f:
+            f.write(sample_wdl)
+            temp_path = Path(f.name)
+
+        try:
+            result = wdlparse.parse_wdl(temp_path)
+            assert isinstance(result.path, Path)
+            assert result.path == temp_path
+
+            result = wdlparse.parse(sample_wdl.encode())
+            assert result.path is None
+            assert result.file_path == "<bytes>"
+            assert not result.has_errors
+
+            with open(temp_path, "rb") as stream:
+                result = wdlparse.parse(stream)
+            assert result.path is None
+            assert result.file_path == str(temp_path)
+
+            text = wdlparse.info(io.StringIO(sample_wdl))
+            assert "WDL File: <stream>" in text
+            assert "hello" in text
+            assert "hello" in wdlparse.info(io.BytesIO(sample_wdl.encode()))
+
+            with pytest.raises(TypeError, match="int"):
+                wdlparse.parse_wdl(42)
+
+        finally:
+            temp_path.unlink()
+
+    def test_async_variants(self, sample_wdl):
+        """Test the async variants run alongside other tasks and match the sync results."""
+
+        async def run():
+            ticks = []
+
+            async def tick():
+                for _ in range(3):
+                    ticks.append(None)
+                    await asyncio.sleep(0)
+
+            parsed, info, _ = await asyncio.gather(
+                wdlparse.parse_wdl_async(sample_wdl.encode(), wdlparse.OutputFormat.Json),
+                wdlparse.info_wdl_async(io.StringIO(sample_wdl)),
+                tick(),
+            )
+            return parsed, info, ticks
+
+        parsed, info, ticks = asyncio.run(run())
+        assert isinstance(parsed, wdlparse.ParseResult)
+        assert not parsed.has_errors
+        expected = wdlparse.parse_wdl(sample_wdl.encode(), wdlparse.OutputFormat.Json)
+        assert parsed.output == expected.output
+        assert "Tasks: 1" in info
+        assert len(ticks) == 3
+
+        with pytest.raises(TypeError):
+            asyncio.run(wdlparse.parse_wdl_async(42))
+
+    def test_mermaid(self, sample_wdl):
+        """Test Mermaid diagrams with the options of the mermaid command."""
+        diagram = wdlparse.mermaid(sample_wdl.encode())
+        assert diagram.startswith("flowchart TD")
+        assert "input_person --> call_hello" in diagram
+
+        diagram = wdlparse.mermaid_wdl_string(
+            sample_wdl,
+            direction="LR",
+            include_inputs=False,
+            styles={"call": "fill:#eef"},
+        )
+        assert diagram.startswith("flowchart LR")
+        assert "input_person" not in diagram
+        assert "class call_hello call" in diagram
+
+        diagram = wdlparse.mermaid_wdl_string(sample_wdl, task="hello", include_outputs=False)
+        assert diagram.startswith("flowchart LR")
+        assert "output_greeting" not in diagram
+
+        with pytest.raises(ValueError, match="did you mean 'call'"):
+            wdlparse.mermaid_wdl_string(sample_wdl, styles={"cal": "fill:#eef"})
+        with pytest.raises(ValueError, match="Invalid direction"):
+            wdlparse.mermaid_wdl_string(sample_wdl, direction="up")
+        with pytest.raises(ValueError, match="not found"):
+            wdlparse.mermaid_wdl_string(sample_wdl, workflow="missing")
+        with pytest.raises(ValueError, match="expand_subworkflows needs a path"):
+            wdlparse.mermaid(io.StringIO(sample_wdl), expand_subworkflows=True)
+
 
 class TestRobustMetadata:
     """Test cases for robust WDL metadata extraction using extract_metadata parameter."""
@@ -415,6 +600,75 @@ workflow multi_task_workflow {
         finally:
             os.unlink(temp_path)
 
+    def test_info_sort_by(self, multi_task_wdl):
+        """Test info keeps source order by default and sorts by name on request."""
+        content = multi_task_wdl.replace("task task_alpha", "task task_gamma")
+        content = content.replace("call task_alpha", "call task_gamma")
+        with tempfile.NamedTemporaryFile(mode="w", suffix=".wdl", delete=False) as f:
+            f.write(content)
+            temp_path = f.name
+
+        try:
+            import json
+
+            by_position = json.loads(wdlparse.info(temp_path, output_format="json"))
+            tasks = [task["name"] for task in by_position["wdl"]["tasks"]]
+            assert tasks == ["task_gamma", "task_beta"]
+
+            by_name = json.loads(wdlparse.info(temp_path, output_format="json", sort_by="name"))
+            tasks = [task["name"] for task in by_name["wdl"]["tasks"]]
+            calls = [call["name"] for call in by_name["wdl"]["workflows"][0]["calls"]]
+            assert tasks == ["task_beta", "task_gamma"]
+            assert calls == ["task_beta", "task_gamma"]
+
+            with pytest.raises(ValueError):
+                wdlparse.info(temp_path, output_format="json", sort_by="size")
+
+        finally:
+            os.unlink(temp_path)
+
+    def test_to_records(self, multi_task_wdl):
+        """Test to_records flattens tasks, inputs, calls and runtime into uniform records."""
+        with tempfile.NamedTemporaryFile(mode="w", suffix=".wdl", delete=False) as f:
+            f.write(multi_task_wdl)
+            temp_path = f.name
+
+        try:
+            records = wdlparse.to_records(temp_path)
+            assert set(records) == {"tasks", "inputs", "calls", "runtime"}
+            for table in records.values():
+                keys = {tuple(record) for record in table}
+                assert len(keys) <= 1
+                for record in table:
+                    assert all(not isinstance(value, (dict, list)) for value in record.values())
+
+            tasks = [record["task"] for record in records["tasks"]]
+            assert tasks == ["task_alpha", "task_beta"]
+            assert records["tasks"][0]["file"] == temp_path
+            calls = [record["call"] for record in records["calls"]]
+            assert calls == ["task_alpha", "task_beta"]
+
+            with pytest.raises(FileNotFoundError):
+                wdlparse.to_records("missing.wdl")
+        finally:
+            os.unlink(temp_path)
+
+    def test_info_best_effort(self, malformed_wdl):
+        """Test info with best_effort=True keeps broken definitions, marked incomplete."""
+        with tempfile.NamedTemporaryFile(mode="w", suffix=".wdl", delete=False) as f:
+            f.write(malformed_wdl)
+            temp_path = f.name
+
+        try:
+            import json
+            info_data = json.loads(wdlparse.info(temp_path, output_format="json", best_effort=True))
+            entries = info_data["wdl"]["tasks"] + info_data["wdl"]["workflows"]
+            names = {entry["name"]: entry["complete"] for entry in entries}
+            assert names["broken_task"] is False
+            assert names["broken_workflow"] is False
+        finally:
+            os.unlink(temp_path)
+
     def test_extract_metadata_false_by_default(self, multi_task_wdl):
         """Test that extract_metadata=False by default (no extra work)."""
         result = wdlparse.parse_text(multi_task_wdl, output_format="json")
@@ -4,17 +4,30 @@ wdlparse: A Python interface to the wdlparse Rust library for parsing WDL files.
 This package provides Python bindings for parsing and analyzing WDL (Workflow Description Language) files.
 """
 
+import asyncio
+import json
+import os
 from pathlib import Path
-from typing import Any
+from typing import IO, Any, Iterator, Union
 
 # Import the Rust extension module
 try:
     from .wdlparse import (
         ParseResult,
         PyOutputFormat,
+        __version__,
+        features,
+        format_number,
+        format_size,
         info_wdl,
+        mermaid_wdl,
+        mermaid_wdl_string,
         parse_wdl,
         parse_wdl_string,
+        records_wdl,
+        scan_wdl,
+        supported_wdl_versions,
+        walk,
     )
 except ImportError as e:
     raise ImportError(
@@ -23,34 +36,63 @@ except ImportError as e:
         f"Original error: {e}"
     ) from e
 
-__version__ = "0.1.1"
 __author__ = "Scott Chamberlain"
 __email__ = "sachamber@fredhutch.org"
 
 # Re-export main classes and enums for convenience
 OutputFormat = PyOutputFormat
 
+# A WDL document: a path, its content as bytes, or a file object to read it from
+WDLSource = Union[str, "os.PathLike[str]", bytes, bytearray, IO[str], IO[bytes]]
+
+
+def _source(source: WDLSource) -> WDLSource:
+    """Check that a path exists; bytes and file objects are passed on as they are."""
+    if isinstance(source, (str, os.PathLike)):
+        path = Path(source)
+        if not path.exists():
+            raise FileNotFoundError(f"WDL file not found: {path}")
+        return str(path)
+    return source
+
 
 class WDLParser:
     """High-level interface for parsing WDL files."""
 
-    def __init__(self, verbose: bool = False):
+    def __init__(
+        self,
+        verbose: bool = False,
+        warnings_as_errors: bool = False,
+        ignore: list[str] | None = None,
+        only: list[str] | None = None,
+        sort_by: str = "position",
+    ):
         """
         Initialize the WDL parser.
 
         Args:
             verbose: Whether to include detailed diagnostic information
+            warnings_as_errors: Report warnings as errors
+            ignore: Diagnostic codes (e.g. "syntax-warning") not to report
+            only: When given, report only diagnostics with these codes
+            sort_by: Order of tasks, workflows, structs, imports and calls:
+                "position" (source order) or "name"
         """
         self.verbose = verbose
+        self.warnings_as_errors = warnings_as_errors
+        self.ignore = list(ignore or [])
+        self.only = list(only or [])
+        self.sort_by = sort_by
 
     def parse_file(
-        self, file_path: str | Path, output_format: str = "human", extract_metadata: bool = False
+        self, file_path: WDLSource, output_format: str = "human", extract_metadata: bool = False
     ) -> ParseResult:
         """
         Parse a WDL file from disk.
 
         Args:
-            file_path: Path to the WDL file to parse
+            file_path: Path to the WDL file to parse (str or os.PathLike), or
+                its content as bytes or a file object opened for reading
             output_format: Output format ("human", "json", or "tree")
             extract_metadata: Whether to extract basic metadata using robust fallback methods
 
@@ -61,12 +103,17 @@ class WDLParser:
             FileNotFoundError: If the file doesn't exist
             ValueError: If the output format is invalid
         """
-        file_path = Path(file_path)
-        if not file_path.exists():
-            raise FileNotFoundError(f"WDL file not found: {file_path}")
-
         format_enum = self._get_format_enum(output_format)
-        return parse_wdl(str(file_path), format_enum, self.verbose, extract_metadata)
+        return parse_wdl(
+            _source(file_path),
+            format_enum,
+            self.verbose,
+            extract_metadata,
+            self.warnings_as_errors,
+            self.ignore,
+            self.only,
+            self.sort_by,
+        )
 
     def parse_string(
         self, wdl_content: str, output_format: str = "human", extract_metadata: bool = False
@@ -86,16 +133,33 @@ class WDLParser:
             ValueError: If the output format is invalid
         """
         format_enum = self._get_format_enum(output_format)
-        return parse_wdl_string(wdl_content, format_enum, self.verbose, extract_metadata)
-
-    def get_info(self, file_path: str | Path, output_format: str = "human", extract_metadata: bool = False) -> str:
+        return parse_wdl_string(
+            wdl_content,
+            format_enum,
+            self.verbose,
+            extract_metadata,
+            self.warnings_as_errors,
+            self.ignore,
+            self.only,
+            self.sort_by,
+        )
+
+    def get_info(
+        self,
+        file_path: WDLSource,
+        output_format: str = "human",
+        extract_metadata: bool = False,
+        best_effort: bool = False,
+    ) -> str:
         """
         Get information about a WDL file (version, tasks, workflows, etc.).
 
         Args:
-            file_path: Path to the WDL file to analyze
+            file_path: Path to the WDL file to analyze (str or os.PathLike), or
+                its content as bytes or a file object opened for reading
             output_format: Output format ("human", "json", or "tree")
             extract_metadata: Whether to extract basic metadata using robust fallback methods
+            best_effort: Keep tasks and workflows with syntax errors, with `complete` set to false
 
         Returns:
             String containing file information
@@ -104,12 +168,10 @@ class WDLParser:
             FileNotFoundError: If the file doesn't exist
             ValueError: If the output format is invalid
         """
-        file_path = Path(file_path)
-        if not file_path.exists():
-            raise FileNotFoundError(f"WDL file not found: {file_path}")
-
         format_enum = self._get_format_enum(output_format)
-        return info_wdl(str(file_path), format_enum, extract_metadata)
+        return info_wdl(
+            _source(file_path), format_enum, extract_metadata, best_effort, self.sort_by
+        )
 
 
 
@@ -132,26 +194,50 @@ class WDLParser:
 
 # Convenience functions for direct use
 def parse(
-    file_path: str | Path, output_format: str = "human", verbose: bool = False, extract_metadata: bool = False
+    file_path: WDLSource,
+    output_format: str = "human",
+    verbose: bool = False,
+    extract_metadata: bool = False,
+    warnings_as_errors: bool = False,
+    ignore: list[str] | None = None,
+    only: list[str] | None = None,
+    sort_by: str = "position",
 ) -> ParseResult:
     """
     Parse a WDL file (convenience function).
 
     Args:
-        file_path: Path to the WDL file to parse
+        file_path: Path to the WDL file to parse, or its content as bytes or a file object
         output_format: Output format ("human", "json", or "tree")
         verbose: Whether to include detailed diagnostic information
         extract_metadata: Whether to extract basic metadata using robust fallback methods
+        warnings_as_errors: Report warnings as errors
+        ignore: Diagnostic codes (e.g. "syntax-warning") not to report
+        only: When given, report only diagnostics with these codes
+        sort_by: Order of tasks, workflows, structs, imports and calls ("position" or "name")
 
     Returns:
         ParseResult object containing parse results and diagnostics
     """
-    parser = WDLParser(verbose=verbose)
+    parser = WDLParser(
+        verbose=verbose,
+        warnings_as_errors=warnings_as_errors,
+        ignore=ignore,
+        only=only,
+        sort_by=sort_by,
+    )
     return parser.parse_file(file_path, output_format, extract_metadata)
 
 
 def parse_text(
-    wdl_content: str, output_format: str = "human", verbose: bool = False, extract_metadata: bool = False
+    wdl_content: str,
+    output_format: str = "human",
+    verbose: bool = False,
+    extract_metadata: bool = False,
+    warnings_as_errors: bool = False,
+    ignore: list[str] | None = None,
+    only: list[str] | None = None,
+    sort_by: str = "position",
 ) -> dict[str, Any]:
     """
     Parse WDL content from a string (convenience function).
@@ -161,32 +247,212 @@ def parse_text(
         output_format: Output format ("human", "json", or "tree")
         verbose: Whether to include detailed diagnostic information
         extract_metadata: Whether to extract basic metadata using robust fallback methods
+        warnings_as_errors: Report warnings as errors
+        ignore: Diagnostic codes (e.g. "syntax-warning") not to report
+        only: When given, report only diagnostics with these codes
+        sort_by: Order of tasks, workflows, structs, imports and calls ("position" or "name")
 
     Returns:
         Dictionary containing parse results and diagnostics
     """
-    parser = WDLParser(verbose=verbose)
+    parser = WDLParser(
+        verbose=verbose,
+        warnings_as_errors=warnings_as_errors,
+        ignore=ignore,
+        only=only,
+        sort_by=sort_by,
+    )
     return parser.parse_string(wdl_content, output_format, extract_metadata)
 
 
-def info(file_path: str | Path, output_format: str = "human", extract_metadata: bool = False) -> str:
+def info(
+    file_path: WDLSource,
+    output_format: str = "human",
+    extract_metadata: bool = False,
+    best_effort: bool = False,
+    sort_by: str = "position",
+) -> str:
     """
     Get information about a WDL file (convenience function).
 
     Args:
-        file_path: Path to the WDL file to analyze
+        file_path: Path to the WDL file to analyze, or its content as bytes or a file object
         output_format: Output format ("human", "json", or "tree")
         extract_metadata: Whether to extract basic metadata using robust fallback methods
+        best_effort: Keep tasks and workflows with syntax errors, with `complete` set to false
+        sort_by: Order of tasks, workflows, structs, imports and calls ("position" or "name")
 
     Returns:
         String containing file information
     """
-    parser = WDLParser()
-    return parser.get_info(file_path, output_format, extract_metadata)
+    parser = WDLParser(sort_by=sort_by)
+    return parser.get_info(file_path, output_format, extract_metadata, best_effort)
+
+
+
+def to_records(
+    file_path: WDLSource, sort_by: str = "position"
+) -> dict[str, list[dict[str, Any]]]:
+    """
+    Flatten a WDL file into tables of plain records, e.g. for pandas.
+
+    Every record of a table has the same keys and only scalar values, so each
+    table loads directly with ``pandas.DataFrame(records["tasks"])``.
+
+    Args:
+        file_path: Path to the WDL file, or its content as bytes or a file object
+        sort_by: Order of tasks, workflows and calls ("position" or "name")
+
+    Returns:
+        Dictionary with the tables:
+            tasks: file, task, line, description, inputs, outputs (counts),
+                command_language, container, memory_bytes, disk_gb
+            inputs: file, kind ("task" or "workflow"), definition, name,
+                wdl_type, optional, default_value
+            calls: file, workflow, call, target, alias, inputs (count)
+            runtime: file, task, key, value, line (one record per attribute)
+
+    Raises:
+        FileNotFoundError: If the file doesn't exist
+    """
+    return json.loads(records_wdl(_source(file_path), sort_by))
+
+
+def scan(
+    directory: str | os.PathLike[str],
+    pattern: str = "**/*.wdl",
+    *,
+    workers: int | None = 1,
+    sort_by: str = "position",
+    best_effort: bool = False,
+) -> Iterator[dict[str, Any]]:
+    """
+    Parse the WDL files of a directory lazily, yielding a result per file.
+
+    Files are parsed as the iterator is consumed, at most a few ahead of it,
+    so scanning a large repository doesn't hold every result in memory.
+    Hidden files and those excluded by .gitignore or .wdlparseignore are
+    skipped, as on the command line.
+
+    Args:
+        directory: Directory to search
+        pattern: Glob of the files to parse, relative to the directory
+        workers: Threads parsing files; None for one per CPU. One worker
+            yields files in path order, more yield them as they finish.
+        sort_by: Order of tasks, workflows and calls ("position" or "name")
+        best_effort: Keep tasks and workflows with syntax errors, with
+            `complete` set to false
+
+    Yields:
+        Dictionary per file: file, path (pathlib.Path), diagnostics_count,
+        has_errors, info (as in ``info(..., output_format="json")["wdl"]``)
+        and error (why the file couldn't be read, with info None)
+
+    Raises:
+        NotADirectoryError: If the directory doesn't exist
+        OSError: If the pattern is invalid or the directory can't be searched
+        ValueError: If workers or sort_by is invalid
+    """
+    directory = Path(directory)
+    if not directory.is_dir():
+        raise NotADirectoryError(f"Not a directory: {directory}")
+    results = scan_wdl(directory, pattern, workers, sort_by, best_effort)
+    return (_with_info(result) for result in results)
 
 
+def _with_info(result: dict[str, Any]) -> dict[str, Any]:
+    """Decode the info of a scan result."""
+    if result["info"] is not None:
+        result["info"] = json.loads(result["info"])
+    return result
 
 
+async def parse_wdl_async(
+    file_path: WDLSource, *args: Any, **kwargs: Any
+) -> ParseResult:
+    """
+    Parse a WDL file without blocking the event loop.
+
+    Takes the arguments of ``parse_wdl``. Parsing runs in a worker thread with
+    the GIL released, so other tasks keep running and several files can be
+    parsed at once, e.g. with ``asyncio.gather``.
+
+    Returns:
+        ParseResult object containing parsing results
+    """
+    return await asyncio.to_thread(parse_wdl, file_path, *args, **kwargs)
+
+
+async def info_wdl_async(file_path: WDLSource, *args: Any, **kwargs: Any) -> str:
+    """
+    Get information about a WDL file without blocking the event loop.
+
+    Takes the arguments of ``info_wdl`` and runs like ``parse_wdl_async``.
+
+    Returns:
+        String containing file information
+    """
+    return await asyncio.to_thread(info_wdl, file_path, *args, **kwargs)
+
+
+def mermaid(
+    file_path: WDLSource,
+    *,
+    workflow: str | None = None,
+    task: str | None = None,
+    direction: str | None = None,
+    include_inputs: bool = True,
+    include_outputs: bool = True,
+    imports: bool = False,
+    runtime: str | None = None,
+    edge_labels: bool = False,
+    expand_subworkflows: bool = False,
+    depth: int | None = None,
+    styles: dict[str, str] | None = None,
+) -> str:
+    """
+    Draw a workflow or task of a WDL file as a Mermaid flowchart.
+
+    The options mirror the flags of ``wdlparse mermaid``.
+
+    Args:
+        file_path: Path to the WDL file, or its content as bytes or a file object
+        workflow: Workflow to draw (defaults to the first workflow in the file)
+        task: Draw this task's inputs, command and outputs instead of a workflow
+        direction: "TD", "LR", "BT" or "RL" (default: TD for workflows, LR for tasks)
+        include_inputs: Draw input nodes and their edges
+        include_outputs: Draw output nodes and their edges
+        imports: Link imported calls to a node for the file they come from
+        runtime: Show cpu, memory and container as a "label" or a "tooltip"
+        edge_labels: Label edges from calls with the outputs they pass on
+        expand_subworkflows: Draw sub-workflows from local imports inside the
+            diagram, one subgraph per file; needs a path
+        depth: Levels of sub-workflows to expand (defaults to all)
+        styles: Mermaid CSS per class: "input", "call", "output" or
+            "imported", e.g. {"call": "fill:#eef,stroke:#336"}
+
+    Returns:
+        The flowchart, in Mermaid syntax
+
+    Raises:
+        FileNotFoundError: If the file doesn't exist
+        ValueError: If an option is invalid or the workflow or task isn't found
+    """
+    return mermaid_wdl(
+        _source(file_path),
+        workflow,
+        task,
+        direction,
+        include_inputs,
+        include_outputs,
+        imports,
+        runtime,
+        edge_labels,
+        expand_subworkflows,
+        depth,
+        styles,
+    )
+
 
 # Make main exports available at package level
 __all__ = [
@@ -196,8 +462,21 @@ __all__ = [
     "parse",
     "parse_text",
     "info",
+    "to_records",
+    "scan",
+    "mermaid",
+    "walk",
     "parse_wdl",
     "info_wdl",
     "parse_wdl_string",
+    "parse_wdl_async",
+    "info_wdl_async",
+    "mermaid_wdl",
+    "mermaid_wdl_string",
     "PyOutputFormat",
+    "WDLSource",
+    "format_size",
+    "format_number",
+    "features",
+    "supported_wdl_versions",
 ]
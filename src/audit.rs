@@ -0,0 +1,292 @@
+use crate::command::{CommandLanguage, CommandSection, CommandStyle};
+use crate::info::TaskInfo;
+use serde::{Deserialize, Serialize};
+
+/// Commands that usually reach the network
+pub const NETWORK_TOOLS: &[&str] = &[
+    "curl", "wget", "gsutil", "gcloud", "aws", "azcopy", "scp", "sftp", "ssh", "ftp", "nc",
+];
+
+/// Multi-word commands that usually reach the network
+const NETWORK_PHRASES: &[&str] = &[
+    "git clone",
+    "git pull",
+    "pip install",
+    "conda install",
+    "apt-get install",
+    "apt install",
+];
+
+/// Absolute paths that are safe to read and write anywhere
+const HARMLESS_PATHS: &[&str] = &["/dev/null", "/dev/stdout", "/dev/stderr", "/dev/stdin"];
+
+/// Commands whose last argument is a destination
+const COPY_COMMANDS: &[&str] = &["cp", "mv", "ln", "rsync", "install"];
+
+/// Commands that create or modify every path argument
+const WRITE_COMMANDS: &[&str] = &["mkdir", "touch", "tee", "rm", "chmod"];
+
+/// A line of the command that suggests network access
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NetworkHint {
+    pub tool: String,
+    /// 1-based line within the dedented command
+    pub line: usize,
+}
+
+/// What a task's command touches outside its inputs and working directory
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CommandAudit {
+    pub task: String,
+    /// Variables read with `$VAR`/`${VAR}` that the script doesn't set itself
+    pub env_vars: Vec<String>,
+    pub absolute_paths: Vec<String>,
+    pub network: Vec<NetworkHint>,
+    /// Absolute paths written by redirection, `tee`, `cp`, `mkdir`, ...
+    pub writes_outside: Vec<String>,
+}
+
+impl CommandAudit {
+    pub fn is_empty(&self) -> bool {
+        self.env_vars.is_empty()
+            && self.absolute_paths.is_empty()
+            && self.network.is_empty()
+            && self.writes_outside.is_empty()
+    }
+}
+
+pub fn audit_task(task: &TaskInfo) -> CommandAudit {
+    let mut audit = CommandAudit {
+        task: task.name.clone(),
+        ..Default::default()
+    };
+    let Some(section) = &task.command_section else {
+        return audit;
+    };
+    let script = without_placeholders(section);
+
+    // `$` only reads variables in shell scripts (in R, `df$column` is a field)
+    if task
+        .command_language
+        .is_none_or(|language| language == CommandLanguage::Bash)
+    {
+        let assigned = assigned_variables(&script);
+        for name in variables_read(&script) {
+            if !assigned.contains(&name) {
+                push_unique(&mut audit.env_vars, name);
+            }
+        }
+    }
+
+    for (index, line) in script.lines().enumerate() {
+        let line = line.split(" #").next().unwrap_or(line);
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || matches!(c, ';' | '|' | '&' | '(' | ')'))
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        for tool in NETWORK_TOOLS {
+            if words.contains(tool) {
+                audit.network.push(NetworkHint {
+                    tool: tool.to_string(),
+                    line: index + 1,
+                });
+            }
+        }
+        let normalized = words.join(" ");
+        for phrase in NETWORK_PHRASES {
+            if normalized.contains(phrase) {
+                audit.network.push(NetworkHint {
+                    tool: phrase.to_string(),
+                    line: index + 1,
+                });
+            }
+        }
+
+        for word in &words {
+            for path in absolute_paths(word) {
+                push_unique(&mut audit.absolute_paths, path);
+            }
+        }
+        for path in written_paths(&words) {
+            push_unique(&mut audit.writes_outside, path);
+        }
+    }
+    audit
+}
+
+/// The dedented command with WDL placeholders blanked out, leaving the shell script
+fn without_placeholders(section: &CommandSection) -> String {
+    let script = &section.dedented;
+    let sigil = match section.style {
+        CommandStyle::Heredoc => "~{",
+        CommandStyle::Braces => "{",
+    };
+    let mut out = String::new();
+    let mut rest = script.as_str();
+    while let Some(start) = rest.find(sigil) {
+        let before = &rest[..start];
+        let is_placeholder = section.style == CommandStyle::Heredoc
+            || before.ends_with('~')
+            || before.ends_with('$');
+        if !is_placeholder {
+            out.push_str(&rest[..start + sigil.len()]);
+            rest = &rest[start + sigil.len()..];
+            continue;
+        }
+        let trimmed = if section.style == CommandStyle::Braces {
+            &before[..before.len() - 1]
+        } else {
+            before
+        };
+        out.push_str(trimmed);
+        out.push_str("WDL_VALUE");
+        rest = rest[start..]
+            .find('}')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Names read as `$NAME` or `${NAME...}`
+fn variables_read(script: &str) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut names = Vec::new();
+    let mut in_single_quotes = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\'' => in_single_quotes = !in_single_quotes,
+            '\\' => i += 1,
+            '$' if !in_single_quotes => {
+                let braced = chars.get(i + 1) == Some(&'{');
+                let start = if braced { i + 2 } else { i + 1 };
+                let mut end = start;
+                while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_')
+                {
+                    end += 1;
+                }
+                let name: String = chars[start..end].iter().collect();
+                // `$1` is an argument and `$?`, `$#`, ... are shell specials
+                let positional = name.chars().next().is_some_and(|c| c.is_ascii_digit());
+                if !name.is_empty() && !positional && !names.contains(&name) {
+                    names.push(name);
+                }
+                i = end.max(i + 1);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    names
+}
+
+/// Variables the script sets itself: `NAME=...`, `export NAME=...`,
+/// `for NAME in`, `read NAME`, and `local`/`declare` names
+fn assigned_variables(script: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for statement in script.split(['\n', ';', '&', '|']) {
+        let words: Vec<&str> = statement.split_whitespace().collect();
+        for (i, word) in words.iter().enumerate() {
+            let after_keyword = i > 0
+                && matches!(
+                    words[i - 1],
+                    "export" | "local" | "declare" | "readonly" | "for" | "read"
+                );
+            let name = match word.split_once('=') {
+                Some((name, _)) if i == 0 || after_keyword => name,
+                None if after_keyword => word,
+                _ => continue,
+            };
+            if !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !names.iter().any(|n| n == name)
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Absolute paths in one shell word, e.g. `--ref=/data/hg38.fa` or `>/tmp/out`
+fn absolute_paths(word: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for part in word.split(['=', '>', '<', ',', ':', '"', '\'', '`']) {
+        let path = part.trim_end_matches(['.', ')']);
+        let is_path = path.len() > 1
+            && path.starts_with('/')
+            && !path.starts_with("//")
+            && path[1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        if is_path && !HARMLESS_PATHS.contains(&path) && !word.contains("://") {
+            paths.push(path.to_string());
+        }
+    }
+    paths
+}
+
+/// Absolute paths the words of one line write to
+fn written_paths(words: &[&str]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut push = |word: &str| {
+        for path in absolute_paths(word) {
+            paths.push(path);
+        }
+    };
+
+    for (i, word) in words.iter().enumerate() {
+        // `> /out`, `>> /out`, `2>/out`, `>/out`
+        if let Some(target) = word
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .strip_prefix('>')
+        {
+            let target = target.trim_start_matches('>');
+            if target.is_empty() {
+                if let Some(next) = words.get(i + 1) {
+                    push(next);
+                }
+            } else {
+                push(target);
+            }
+        }
+        if matches!(*word, "-o" | "--output" | "--out") {
+            if let Some(next) = words.get(i + 1) {
+                push(next);
+            }
+        }
+        if WRITE_COMMANDS.contains(word) {
+            for argument in words[i + 1..].iter().take_while(|w| !w.starts_with('>')) {
+                if !argument.starts_with('-') {
+                    push(argument);
+                }
+            }
+        }
+        if COPY_COMMANDS.contains(word) {
+            let arguments: Vec<&&str> = words[i + 1..]
+                .iter()
+                .take_while(|w| !w.starts_with('>'))
+                .filter(|w| !w.starts_with('-'))
+                .collect();
+            if arguments.len() > 1 {
+                if let Some(destination) = arguments.last() {
+                    push(destination);
+                }
+            }
+        }
+    }
+    paths
+}
+
+fn push_unique(items: &mut Vec<String>, item: String) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::config::BackendProfile;
+use crate::info::{RuntimeItem, TaskInfo};
+use crate::numbers;
+use crate::units;
+use serde::{Deserialize, Serialize};
+
+/// A runtime requirement of a task that a backend profile cannot satisfy
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackendIssue {
+    pub task: String,
+    pub attribute: String,
+    pub message: String,
+}
+
+/// Check a task's runtime section against a backend profile.
+/// Only literal runtime values can be checked; computed values are skipped.
+pub fn check_task(task: &TaskInfo, profile: &BackendProfile) -> Vec<BackendIssue> {
+    let mut issues = Vec::new();
+    let mut has_container = false;
+
+    for item in &task.runtime {
+        match item.key.as_str() {
+            "docker" | "container" => {
+                has_container = true;
+            }
+            "cpu" => {
+                if let (Some(max_cpu), Some(cpu)) = (profile.max_cpu, parse_number(item)) {
+                    if cpu > max_cpu {
+                        issues.push(BackendIssue {
+                            task: task.name.clone(),
+                            attribute: item.key.clone(),
+                            message: format!(
+                                "requests {} cpu, backend allows {}",
+                                numbers::number(cpu),
+                                numbers::number(max_cpu)
+                            ),
+                        });
+                    }
+                }
+            }
+            "memory" => {
+                let limit = profile.max_memory.as_deref().and_then(units::parse_size);
+                let requested = item.literal_value().and_then(units::parse_size);
+                if let (Some(limit), Some(requested)) = (limit, requested) {
+                    if requested > limit {
+                        issues.push(BackendIssue {
+                            task: task.name.clone(),
+                            attribute: item.key.clone(),
+                            message: format!(
+                                "requests {} memory ({}), backend allows {} ({})",
+                                item.value.trim(),
+                                numbers::size(requested),
+                                profile.max_memory.as_deref().unwrap_or_default(),
+                                numbers::size(limit)
+                            ),
+                        });
+                    }
+                }
+            }
+            "gpu" | "gpuCount" | "gpuType" | "nvidiaDriverVersion" => {
+                let requests_gpu = match item.key.as_str() {
+                    "gpu" => item.value.trim() != "false",
+                    "gpuCount" => parse_number(item).is_none_or(|count| count > 0.0),
+                    _ => true,
+                };
+                if requests_gpu && !profile.gpu {
+                    issues.push(BackendIssue {
+                        task: task.name.clone(),
+                        attribute: item.key.clone(),
+                        message: "requests GPUs, backend has none".to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if profile.require_container && !has_container {
+        issues.push(BackendIssue {
+            task: task.name.clone(),
+            attribute: "container".to_string(),
+            message: "no docker/container image declared, backend requires one".to_string(),
+        });
+    }
+
+    issues
+}
+
+/// Parse a numeric runtime value, accepting quoted numbers like `"4"`
+fn parse_number(item: &RuntimeItem) -> Option<f64> {
+    item.literal_value()
+        .unwrap_or(&item.value)
+        .trim()
+        .parse()
+        .ok()
+}
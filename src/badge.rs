@@ -0,0 +1,74 @@
+use crate::graph::WorkflowGraph;
+use crate::info::{WdlInfo, WorkflowInfo};
+use crate::mermaid::{self, DiagramOptions};
+
+/// Where the badge images come from
+const SHIELDS: &str = "https://img.shields.io/badge";
+
+/// A Markdown snippet for a pipeline README: badges for the validation
+/// status (syntax errors), WDL version and task count, the containers the
+/// tasks run in, and the Mermaid diagram of `workflow` when there is one
+pub fn readme_snippet(info: &WdlInfo, workflow: Option<&WorkflowInfo>, errors: usize) -> String {
+    let (status, color) = match errors {
+        0 => ("valid".to_string(), "brightgreen"),
+        1 => ("1 error".to_string(), "red"),
+        count => (format!("{} errors", count), "red"),
+    };
+    let version = info.version.as_deref().unwrap_or("draft-2");
+    let badges = [
+        badge("WDL", &status, color),
+        badge("WDL version", version, "blue"),
+        badge("tasks", &info.tasks.len().to_string(), "blue"),
+    ];
+    let mut out = format!("{}\n\n", badges.join(" "));
+
+    let mut containers: Vec<&str> = Vec::new();
+    for container in info
+        .tasks
+        .iter()
+        .filter_map(|task| task.normalized_runtime.container.as_deref())
+    {
+        if !containers.contains(&container) {
+            containers.push(container);
+        }
+    }
+    if containers.is_empty() {
+        out.push_str("**Containers:** none declared\n");
+    } else {
+        let list: Vec<String> = containers
+            .iter()
+            .map(|container| format!("`{}`", container))
+            .collect();
+        out.push_str(&format!("**Containers:** {}\n", list.join(", ")));
+    }
+
+    if let Some(workflow) = workflow {
+        let diagram = mermaid::workflow_diagram(
+            &WorkflowGraph::build(workflow),
+            info,
+            &DiagramOptions::default(),
+        );
+        out.push_str(&format!("\n```mermaid\n{}```\n", diagram));
+    }
+    out
+}
+
+/// Image link to a static shields.io badge
+fn badge(label: &str, message: &str, color: &str) -> String {
+    format!(
+        "![{}]({}/{}-{}-{})",
+        label,
+        SHIELDS,
+        badge_text(label),
+        badge_text(message),
+        color
+    )
+}
+
+/// `text` as one dash-separated part of a badge URL: dashes and underscores
+/// doubled, spaces encoded
+fn badge_text(text: &str) -> String {
+    text.replace('-', "--")
+        .replace('_', "__")
+        .replace(' ', "%20")
+}
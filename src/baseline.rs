@@ -0,0 +1,82 @@
+use crate::lint::Finding;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Known lint findings recorded so that only new ones fail a run
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Baseline {
+    pub findings: Vec<BaselineEntry>,
+}
+
+/// A recorded finding. Lines and columns are left out so that edits elsewhere
+/// in a file don't turn old findings into new ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BaselineEntry {
+    pub file: String,
+    pub rule: String,
+    pub location: String,
+    pub message: String,
+}
+
+impl BaselineEntry {
+    pub fn new(file: &str, finding: &Finding) -> Self {
+        // `task align (line 42, column 17)` -> `task align`
+        let location = finding.location.split(" (line ").next().unwrap_or_default();
+        Self {
+            file: file.to_string(),
+            rule: finding.rule.clone(),
+            location: location.to_string(),
+            message: finding.message.clone(),
+        }
+    }
+}
+
+impl Baseline {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline file: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse baseline file: {}", path.display()))
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        self.findings.sort();
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content + "\n")
+            .with_context(|| format!("Failed to write baseline file: {}", path.display()))
+    }
+
+    pub fn record(&mut self, file: &str, findings: &[Finding]) {
+        self.findings.extend(
+            findings
+                .iter()
+                .map(|finding| BaselineEntry::new(file, finding)),
+        );
+    }
+
+    /// Findings of `file` not covered by the baseline, and how many were. A
+    /// finding recorded once covers one occurrence, so a second copy of a known
+    /// problem still counts as new.
+    pub fn new_findings(&self, file: &str, findings: Vec<Finding>) -> (Vec<Finding>, usize) {
+        let mut known: Vec<&BaselineEntry> = self
+            .findings
+            .iter()
+            .filter(|entry| entry.file == file)
+            .collect();
+        let mut fresh = Vec::new();
+        let mut matched = 0;
+        for finding in findings {
+            let entry = BaselineEntry::new(file, &finding);
+            match known.iter().position(|known| **known == entry) {
+                Some(index) => {
+                    known.swap_remove(index);
+                    matched += 1;
+                }
+                None => fresh.push(finding),
+            }
+        }
+        (fresh, matched)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::commands::extract_semantic_info;
+use crate::graph::WorkflowGraph;
+use crate::info::SortBy;
+use crate::mermaid::{self, DiagramOptions};
+use crate::source::SourceText;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use wdl_grammar::SyntaxTree;
+
+/// Aggregated timings of one pipeline stage, in milliseconds
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct StageTiming {
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+}
+
+impl StageTiming {
+    fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        Self {
+            mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+            min_ms: ms.iter().copied().fold(f64::INFINITY, f64::min),
+            max_ms: ms.iter().copied().fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Per-stage timings for a single file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileBenchmark {
+    pub file: String,
+    pub bytes: usize,
+    pub iterations: usize,
+    pub read: StageTiming,
+    pub parse: StageTiming,
+    pub extract: StageTiming,
+    pub graph: StageTiming,
+}
+
+impl FileBenchmark {
+    /// Mean time of all stages together
+    pub fn total_mean_ms(&self) -> f64 {
+        self.read.mean_ms + self.parse.mean_ms + self.extract.mean_ms + self.graph.mean_ms
+    }
+}
+
+/// Time reading, parsing, semantic extraction, and graph building with
+/// Mermaid rendering of a file
+pub fn bench_file(path: &Path, iterations: usize) -> Result<FileBenchmark> {
+    let iterations = iterations.max(1);
+    let mut read = Vec::with_capacity(iterations);
+    let mut parse = Vec::with_capacity(iterations);
+    let mut extract = Vec::with_capacity(iterations);
+    let mut graph = Vec::with_capacity(iterations);
+    let mut bytes = 0;
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let content = SourceText::read(path)?.text;
+        read.push(start.elapsed());
+        bytes = content.len();
+
+        let start = Instant::now();
+        let (tree, _) = SyntaxTree::parse(&content);
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        let info = extract_semantic_info(&tree.root(), SortBy::Position);
+        extract.push(start.elapsed());
+
+        let start = Instant::now();
+        for workflow in &info.workflows {
+            let workflow_graph = WorkflowGraph::build(workflow);
+            std::hint::black_box(mermaid::workflow_diagram(
+                &workflow_graph,
+                &info,
+                &DiagramOptions::default(),
+            ));
+        }
+        graph.push(start.elapsed());
+    }
+
+    Ok(FileBenchmark {
+        file: path.display().to_string(),
+        bytes,
+        iterations,
+        read: StageTiming::from_samples(&read),
+        parse: StageTiming::from_samples(&parse),
+        extract: StageTiming::from_samples(&extract),
+        graph: StageTiming::from_samples(&graph),
+    })
+}
@@ -0,0 +1,18 @@
+/// Subsystems a build of wdlparse may include, with whether this one does,
+/// so integrations can adapt to the build instead of probing for errors
+pub const FEATURES: &[(&str, bool)] = &[
+    // Python bindings (the `python` Cargo feature)
+    ("python", cfg!(feature = "python")),
+    // Flat tables for data frames (Python `to_records`)
+    ("records", cfg!(feature = "python")),
+    // Syntax tree traversal from Python (`walk`)
+    ("walk", cfg!(feature = "python")),
+    // Lazy, parallel parsing of a directory from Python (`scan`)
+    ("scan", cfg!(feature = "python")),
+    // Mermaid diagrams of workflows and tasks
+    ("mermaid", true),
+    // Remote imports and container registry lookups
+    ("network", true),
+    // HTTP and JSON-RPC servers for long-running integrations
+    ("server", true),
+];
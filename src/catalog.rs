@@ -0,0 +1,87 @@
+use crate::info::{Authorship, WdlInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Owner name used for entries without an author
+pub const UNOWNED: &str = "(no author)";
+
+/// A task or workflow in a repository catalog
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CatalogEntry {
+    pub file: String,
+    /// `task` or `workflow`
+    pub kind: String,
+    pub name: String,
+    /// False for definitions with syntax errors, indexed from what could be
+    /// recovered
+    pub complete: bool,
+    #[serde(flatten)]
+    pub authorship: Authorship,
+}
+
+/// Everything attributed to one author
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OwnerSummary {
+    pub owner: String,
+    pub emails: Vec<String>,
+    pub workflows: usize,
+    pub tasks: usize,
+    pub entries: Vec<CatalogEntry>,
+}
+
+/// Catalog entries for the workflows and tasks of one file
+pub fn entries(file: &str, info: &WdlInfo) -> Vec<CatalogEntry> {
+    let workflows = info
+        .workflows
+        .iter()
+        .map(|w| ("workflow", &w.name, w.complete, &w.authorship));
+    let tasks = info
+        .tasks
+        .iter()
+        .map(|t| ("task", &t.name, t.complete, &t.authorship));
+    workflows
+        .chain(tasks)
+        .map(|(kind, name, complete, authorship)| CatalogEntry {
+            file: file.to_string(),
+            kind: kind.to_string(),
+            name: name.clone(),
+            complete: complete != Some(false),
+            authorship: authorship.clone(),
+        })
+        .collect()
+}
+
+/// Group entries by author, sorted by owner name; entries listing several
+/// authors count toward each of them
+pub fn by_owner(entries: &[CatalogEntry]) -> Vec<OwnerSummary> {
+    let mut owners: BTreeMap<String, OwnerSummary> = BTreeMap::new();
+    for entry in entries {
+        let mut authors = entry.authorship.authors();
+        if authors.is_empty() {
+            authors.push(UNOWNED);
+        }
+
+        for author in authors {
+            let summary = owners
+                .entry(author.to_string())
+                .or_insert_with(|| OwnerSummary {
+                    owner: author.to_string(),
+                    emails: Vec::new(),
+                    workflows: 0,
+                    tasks: 0,
+                    entries: Vec::new(),
+                });
+            if let Some(email) = &entry.authorship.email {
+                if !summary.emails.contains(email) {
+                    summary.emails.push(email.clone());
+                }
+            }
+            match entry.kind.as_str() {
+                "workflow" => summary.workflows += 1,
+                _ => summary.tasks += 1,
+            }
+            summary.entries.push(entry.clone());
+        }
+    }
+    owners.into_values().collect()
+}
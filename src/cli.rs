@@ -0,0 +1,808 @@
+use crate::docs::{IndexFormat, SiteFormat};
+use crate::info::{SortBy, MAX_SOURCE_BYTES};
+use crate::lineage::LineageFormat;
+use crate::logging::{LogFormat, LogLevel};
+use crate::mermaid::{self, Direction, RuntimeAnnotation};
+use crate::output_schema::SelfSchema;
+use crate::scaffold::TestStyle;
+use crate::schema::SchemaStyle;
+use crate::spec::SpecVersion;
+use crate::struct_usage::GraphMode;
+use crate::template::NewKind;
+use crate::version;
+use crate::OutputFormat;
+use clap::{Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "wdlparse")]
+#[command(about = "A command-line tool for parsing WDL (Workflow Description Language) files")]
+#[command(version)]
+pub struct Cli {
+    /// Path to a configuration file (defaults to .wdlparse.toml if present)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Increase log verbosity (-L info, -LL debug, -LLL trace); `-v` is `parse --verbose`
+    #[arg(short = 'L', action = clap::ArgAction::Count, global = true)]
+    pub verbosity: u8,
+
+    /// Set the log level explicitly (overrides -L)
+    #[arg(long, value_enum, global = true)]
+    pub log_level: Option<LogLevel>,
+
+    /// Log output format
+    #[arg(long, value_enum, global = true, default_value = "text")]
+    pub log_format: LogFormat,
+
+    /// Forbid network access: remote downloads and registry lookups are reported instead of made
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Report warnings as errors (parse and lint)
+    #[arg(long, global = true)]
+    pub warnings_as_errors: bool,
+
+    /// Don't report diagnostics or findings with this code, e.g. a lint rule or `syntax-warning`; repeatable
+    #[arg(long, global = true, value_name = "CODE")]
+    pub ignore: Vec<String>,
+
+    /// Report only diagnostics and findings with this code; repeatable
+    #[arg(long, global = true, value_name = "CODE")]
+    pub only: Vec<String>,
+
+    /// Make JSON output the same on every machine: sort collections, make paths relative, fix timestamps and round numbers
+    #[arg(long, global = true)]
+    pub normalize: bool,
+
+    /// Order of tasks, workflows, structs, imports and calls: source position or name
+    #[arg(long, value_enum, global = true, default_value = "position")]
+    pub sort_by: SortBy,
+
+    /// Show sizes in powers of 1000 (KB, MB, GB)
+    #[arg(long, global = true, conflicts_with = "binary")]
+    pub si: bool,
+
+    /// Show sizes in powers of 1024 (KiB, MiB, GiB), the default
+    #[arg(long, global = true)]
+    pub binary: bool,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Parse a WDL file and display the syntax tree
+    Parse {
+        /// Path to the WDL file to parse
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "tree")]
+        format: OutputFormat,
+
+        /// Show detailed diagnostic information
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Extract basic metadata using robust fallback methods
+        #[arg(long)]
+        extract_metadata: bool,
+
+        /// Stop tree output after this many bytes
+        #[arg(long, value_name = "BYTES")]
+        max_output_bytes: Option<usize>,
+
+        /// Report at most this many root causes; follow-on diagnostics are grouped under each
+        #[arg(long, value_name = "N")]
+        max_diagnostics: Option<usize>,
+    },
+    /// Show information about a WDL file (version, tasks, workflows, etc.)
+    Info {
+        /// Path to the WDL file to analyze
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+
+        /// Extract basic metadata using robust fallback methods
+        #[arg(long)]
+        extract_metadata: bool,
+
+        /// Show how each call's inputs are bound and which fall back to defaults
+        #[arg(long)]
+        wiring: bool,
+
+        /// Print each task's command, with placeholders highlighted (human format)
+        #[arg(long)]
+        show_commands: bool,
+
+        /// Print each task's runtime attributes as a table (human format)
+        #[arg(long)]
+        show_runtime: bool,
+
+        /// Show only this task: its inputs, outputs, command and runtime
+        #[arg(long, conflicts_with = "workflow")]
+        task: Option<String>,
+
+        /// Show only this workflow: its inputs, calls and outputs
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Keep tasks and workflows with syntax errors, marked incomplete, and recover ones the parser lost
+        #[arg(long)]
+        best_effort: bool,
+
+        /// Embed each task's and workflow's source text and byte range in JSON output
+        #[arg(long)]
+        include_source: bool,
+
+        /// Cut embedded source text after this many bytes per definition
+        #[arg(long, value_name = "BYTES", default_value_t = MAX_SOURCE_BYTES, requires = "include_source")]
+        max_source_bytes: usize,
+    },
+    /// Check a WDL file against lint rules
+    Lint {
+        /// WDL files, directories or quoted glob patterns (e.g. 'workflows/**/*.wdl')
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+
+        /// Query registries and rewrite unpinned container images to sha256 digests in place
+        #[arg(long)]
+        resolve_digests: bool,
+
+        /// Run an external rule executable (`wdlparse-rule-NAME` on PATH, or a path); repeatable
+        #[arg(long = "plugin", value_name = "NAME")]
+        plugins: Vec<String>,
+
+        /// Run the plugins listed in a .wdlparse.toml found in the current directory
+        #[arg(long)]
+        trust_plugins: bool,
+
+        /// Ignore `# wdlparse-disable` and `# wdlparse-disable-next-line` comments
+        #[arg(long)]
+        no_inline_config: bool,
+
+        /// Only fail on findings not recorded in this baseline file
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Record the current findings in the --baseline file instead of reporting them
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+
+        /// Check version-gated features against this WDL version and the file's declared one
+        #[arg(long, value_enum, value_name = "VERSION")]
+        spec: Option<SpecVersion>,
+    },
+    /// Report the calls, inputs, structs and imports a call needs to run
+    Closure {
+        /// Path to the WDL file to analyze
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Call to report on (its alias, for aliased calls)
+        #[arg(long)]
+        call: String,
+
+        /// Workflow containing the call (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Remove uncalled tasks and unused structs and imports, printing the smaller WDL
+    Strip {
+        /// Path to the WDL file to strip
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Write the stripped WDL to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Also remove comments
+        #[arg(long)]
+        comments: bool,
+
+        /// Also remove meta and parameter_meta sections
+        #[arg(long)]
+        meta: bool,
+
+        /// Directory to search for imports not found next to the importing file; repeatable
+        #[arg(long = "import-dir", value_name = "DIR")]
+        import_dirs: Vec<PathBuf>,
+    },
+    /// Copy a task (and the structs it uses) from a library file into a WDL file, or import it, and add a call stub
+    AddTask {
+        /// WDL file to add the task to; edited in place unless --output is given
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// WDL file the task is defined in
+        #[arg(long, value_name = "LIBRARY")]
+        from: PathBuf,
+
+        /// Name of the task to add
+        #[arg(long)]
+        task: String,
+
+        /// Import the library and call the task through its namespace instead of copying it
+        #[arg(long)]
+        import: bool,
+
+        /// Workflow to add the call stub to (defaults to the first workflow in the file)
+        #[arg(short, long, conflicts_with = "no_call")]
+        workflow: Option<String>,
+
+        /// Don't add a call stub
+        #[arg(long)]
+        no_call: bool,
+
+        /// Write the result to this file instead of editing FILE
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Insert missing version statements and move misplaced ones to the top of the file
+    FixVersion {
+        /// WDL files, directories or quoted glob patterns (e.g. 'workflows/**/*.wdl')
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Version to insert into files that have none
+        #[arg(long, value_name = "VERSION", default_value = version::DEFAULT_TARGET)]
+        target: String,
+
+        /// Report the files that need fixing without changing them; exits 4 if there are any
+        #[arg(long)]
+        check: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Download remote imports into a directory and point the imports at the local copies
+    Vendor {
+        /// Path to the WDL file whose imports to vendor
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Directory to write the downloaded files to
+        #[arg(long, value_name = "DIR", default_value = "vendor")]
+        dest: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Rewrite import URI prefixes in place, e.g. to point public URLs at an internal mirror
+    RewriteImports {
+        /// WDL files, directories or quoted glob patterns (e.g. 'workflows/**/*.wdl')
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Replace the URI prefix FROM with TO; repeatable, added to [imports.rewrites] in the config
+        #[arg(long = "map", value_name = "FROM=TO")]
+        rules: Vec<String>,
+
+        /// Report the imports that would change without rewriting; exits 4 if there are any
+        #[arg(long)]
+        check: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Report which runtime attributes each call can override through its task inputs
+    RuntimeOverrides {
+        /// Path to the WDL file to analyze
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to analyze (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Report which runtime attributes of each task are literals and which task inputs the others are computed from
+    RuntimeSensitivity {
+        /// Path to the WDL file to analyze
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Only report this task
+        #[arg(short, long)]
+        task: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Trace each workflow output back to the calls and inputs that produce it
+    Lineage {
+        /// Path to the WDL file to analyze
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to trace (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: LineageFormat,
+    },
+    /// List the workflows and tasks of a repository with their authors
+    Catalog {
+        /// WDL files, directories or quoted glob patterns (e.g. 'workflows/**/*.wdl')
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Group entries by author (`meta` keys author/authors/maintainer)
+        #[arg(long)]
+        owners: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Generate Markdown documentation, one page per file plus an index page, and optionally a search index
+    Docs {
+        /// WDL files, directories or quoted glob patterns (e.g. 'workflows/**/*.wdl')
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Directory to write the pages to
+        #[arg(short, long, value_name = "DIR", default_value = "docs")]
+        output: PathBuf,
+
+        /// Lay the output out as a ready-to-serve site: a page per workflow and task, a nav and Mermaid diagrams
+        #[arg(long, value_enum, value_name = "GENERATOR")]
+        site: Option<SiteFormat>,
+
+        /// Also write search_index.json in this layout, for the site's search
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        index: Option<IndexFormat>,
+
+        /// Compare with the docs in the output directory instead of writing them; exits 4 if they are out of date
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print a Markdown snippet for a pipeline README: status, version and task badges, containers and the workflow diagram
+    Badge {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to draw (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+    },
+    /// Compare the tasks of several versions or forks of a pipeline: shared and unique names, identical and diverged content
+    Compare {
+        /// WDL files, directories or quoted glob patterns; each argument is one column of the matrix
+        #[arg(value_name = "PATH", num_args = 2.., required = true)]
+        sources: Vec<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Show changed task commands and runtime sections of two versions of a WDL file side by side
+    Diff {
+        /// The earlier version
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// The later version
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Only show this task
+        #[arg(short, long)]
+        task: Option<String>,
+
+        /// Unchanged lines shown around each change
+        #[arg(short = 'U', long, default_value_t = 3)]
+        context: usize,
+
+        /// Width of the side-by-side view (default: $COLUMNS, or 120)
+        #[arg(long)]
+        width: Option<usize>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// List environment variables, absolute paths, network tools and outside writes in task commands
+    CommandAudit {
+        /// WDL files, directories or quoted glob patterns (e.g. 'workflows/**/*.wdl')
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Show size and structure statistics for a WDL file
+    Stats {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Include workflow graph metrics: longest path, stage widths, fan-in/fan-out
+        #[arg(long)]
+        graph: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Print an inputs JSON template for a workflow or task
+    Inputs {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow or task to describe (defaults to the first workflow, then the first task)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Also list optional inputs and inputs with defaults
+        #[arg(long)]
+        optional: bool,
+
+        /// Fill in plausible example values instead of nulls
+        #[arg(long)]
+        synthesize: bool,
+    },
+    /// Show which calls, blocks and outputs read each workflow input, directly or through declarations
+    InputUsage {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to analyze (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Print a Mermaid flowchart linking inputs to what reads them
+        #[arg(long)]
+        mermaid: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Write each task's command to a standalone script, with placeholders as environment variables
+    ExtractScripts {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Directory for the scripts, one per task
+        #[arg(long, value_name = "DIR", default_value = "scripts")]
+        dest: PathBuf,
+
+        /// Put the (edited) scripts in the directory back into the task commands instead
+        #[arg(long)]
+        reinject: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// List the File and Directory inputs of each task and workflow, to estimate what an engine stages
+    Localization {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Generate a pytest test module per workflow with inputs, expected outputs and TODOs
+    ScaffoldTests {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to generate a test for (defaults to every workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Test framework to target
+        #[arg(long, value_enum, default_value = "pytest-wdl")]
+        style: TestStyle,
+
+        /// Write the module to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Create a WDL file: a workflow calling stub tasks one after another, or a single stub task
+    New {
+        /// What to create
+        #[arg(value_enum)]
+        kind: NewKind,
+
+        /// Name of the workflow or task
+        #[arg(long)]
+        name: String,
+
+        /// Tasks the workflow calls, in order, each taking the previous one's output (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+        tasks: Vec<String>,
+
+        /// Write the file here instead of stdout; an existing file is left alone
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Generate a JSON Schema describing a workflow's parameters (for launch UIs and validation)
+    Schema {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE", required_unless_present = "self_schema")]
+        file: Option<PathBuf>,
+
+        /// Workflow to describe (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Schema layout
+        #[arg(long, value_enum, default_value = "json-schema")]
+        style: SchemaStyle,
+
+        /// Print the schema of one of wdlparse's own JSON reports instead (all of them by default)
+        #[arg(
+            long = "self",
+            value_enum,
+            value_name = "REPORT",
+            num_args = 0..=1,
+            default_missing_value = "all",
+            conflicts_with_all = ["file", "workflow"]
+        )]
+        self_schema: Option<SelfSchema>,
+    },
+    /// Generate a Cromwell workflow options JSON with defaults from the file's runtime sections
+    OptionsTemplate {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Generate a Terra/AnVIL method configuration mapping inputs and outputs to data table columns
+    TerraConfig {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to configure (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Data table (root entity type) the workflow runs on
+        #[arg(long, default_value = "sample")]
+        entity: String,
+    },
+    /// Validate an inputs JSON file against a workflow or task's input types
+    CheckInputs {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Inputs JSON file to validate
+        #[arg(value_name = "INPUTS")]
+        inputs: PathBuf,
+
+        /// Workflow or task the inputs are for (defaults to the first workflow, then the first task)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Render a workflow (or a single task) as a Mermaid flowchart
+    Mermaid {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Workflow to render (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Render a task's inputs, command and outputs instead of a workflow
+        #[arg(short, long, conflicts_with = "workflow")]
+        task: Option<String>,
+
+        /// Link imported calls to a node for the file they are imported from
+        #[arg(long)]
+        imports: bool,
+
+        /// Annotate call and command nodes with cpu, memory and container
+        #[arg(long, value_enum, value_name = "WHERE")]
+        runtime: Option<RuntimeAnnotation>,
+
+        /// Label edges from calls with the outputs they pass on (e.g. "bam, bai")
+        #[arg(long, conflicts_with = "task")]
+        edge_labels: bool,
+
+        /// Which way the flowchart runs (default: TD for workflows, LR for tasks)
+        #[arg(long, value_enum, ignore_case = true)]
+        direction: Option<Direction>,
+
+        /// Leave out input nodes and their edges
+        #[arg(long)]
+        no_inputs: bool,
+
+        /// Leave out output nodes and their edges
+        #[arg(long)]
+        no_outputs: bool,
+
+        /// Style a class of nodes (input, call, output or imported) with Mermaid CSS, e.g. 'call=fill:#eef,stroke:#336'; repeatable
+        #[arg(long = "style", value_name = "CLASS=STYLE", value_parser = mermaid::parse_style)]
+        styles: Vec<(String, String)>,
+
+        /// Draw sub-workflows from local imports inside the workflow's diagram, one subgraph per file
+        #[arg(long, conflicts_with_all = ["task", "imports", "runtime"])]
+        workspace: bool,
+
+        /// Levels of sub-workflows to expand with --workspace (defaults to all)
+        #[arg(long, requires = "workspace")]
+        depth: Option<usize>,
+
+        /// Directory to search for imports not found next to the importing file; repeatable
+        #[arg(long = "import-dir", value_name = "DIR")]
+        import_dirs: Vec<PathBuf>,
+    },
+    /// Print the data-flow graph of each workflow: inputs, calls and outputs and the edges between them
+    Graph {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// What to show: data flow in workflows, or which tasks and workflows use each struct
+        #[arg(long, value_enum, default_value = "dataflow")]
+        mode: GraphMode,
+
+        /// Only this workflow (defaults to every workflow in the file; dataflow mode)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Merge in the graphs of sub-workflows called through local imports, recursively; in structs mode, include every imported file
+        #[arg(long)]
+        workspace: bool,
+
+        /// Levels of sub-workflows to merge with --workspace (defaults to all; dataflow mode)
+        #[arg(long, requires = "workspace")]
+        depth: Option<usize>,
+
+        /// Directory to search for imports not found next to the importing file; repeatable
+        #[arg(long = "import-dir", value_name = "DIR")]
+        import_dirs: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Estimate a workflow's critical path and run time from per-task durations
+    Plan {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// JSON object of durations keyed by call alias or task name
+        #[arg(long, value_name = "FILE")]
+        durations: PathBuf,
+
+        /// Workflow to plan (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Print a Mermaid flowchart with durations and the critical path highlighted
+        #[arg(long)]
+        mermaid: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Report scatter widths and task instance counts for queue-capacity planning
+    Scatters {
+        /// Path to the WDL file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Inputs JSON used to size collections that come from workflow inputs
+        #[arg(short, long, value_name = "FILE")]
+        inputs: Option<PathBuf>,
+
+        /// Workflow to report on (defaults to the first workflow in the file)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Check that a caller's calls to a subworkflow match the subworkflow's inputs and outputs
+    Compat {
+        /// WDL file containing the calling workflow
+        #[arg(value_name = "CALLER")]
+        caller: PathBuf,
+
+        /// WDL file defining the called subworkflow
+        #[arg(value_name = "CALLEE")]
+        callee: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Time reading, parsing, extraction and graph building of WDL files
+    Bench {
+        /// WDL files, directories or quoted glob patterns to benchmark
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Number of times to process each file
+        #[arg(short, long, default_value_t = 10)]
+        iterations: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Run an HTTP server exposing parse/info/lint/lineage/mermaid as JSON endpoints
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+
+        /// Address to bind
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+    /// Answer newline-delimited JSON-RPC requests on stdin (for editor plugins and services)
+    Rpc,
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Write man pages for wdlparse and each subcommand into a directory
+    Manpages {
+        /// Directory to write the man pages to (created if missing)
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+    /// Check task runtime requirements against a backend profile from the config
+    CheckBackend {
+        /// Path to the WDL file to check
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Name of the backend profile defined in the config file
+        #[arg(short, long)]
+        profile: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+}
@@ -0,0 +1,199 @@
+use crate::expr;
+use crate::info::{InputInfo, WdlInfo, WorkflowInfo};
+use crate::types::WdlType;
+use crate::workspace::import_namespace;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Everything a call needs to run: the calls upstream of it, the workflow
+/// inputs and declarations its inputs are computed from, and the tasks,
+/// structs and imports those depend on. Lists follow the order of the file.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Closure {
+    pub call: String,
+    /// Calls whose outputs flow into `call`, directly or transitively
+    pub calls: Vec<String>,
+    /// Local tasks targeted by `call` and the upstream calls
+    pub tasks: Vec<String>,
+    /// Workflow inputs the calls read
+    pub inputs: Vec<String>,
+    /// Workflow declarations the calls read
+    pub declarations: Vec<String>,
+    /// Structs used by the inputs and tasks above, including nested fields
+    pub structs: Vec<String>,
+    /// URIs of imports providing the targets of namespaced calls
+    pub imports: Vec<String>,
+}
+
+/// The closure of `call` in `workflow`; `None` if there is no such call
+pub fn call_closure(info: &WdlInfo, workflow: &WorkflowInfo, call: &str) -> Option<Closure> {
+    let target = workflow.calls.iter().find(|c| c.name == call)?;
+
+    let mut calls = BTreeSet::from([target.name.as_str()]);
+    let mut inputs = BTreeSet::new();
+    let mut declarations = BTreeSet::new();
+    let mut seen_scatters = BTreeSet::new();
+    let mut pending: Vec<&str> = Vec::new();
+    pending.extend(call_expressions(workflow, &target.name));
+
+    while let Some(expression) = pending.pop() {
+        for root in expr::referenced_roots(expression) {
+            if let Some(upstream) = workflow.calls.iter().find(|c| c.name == root) {
+                if calls.insert(upstream.name.as_str()) {
+                    pending.extend(call_expressions(workflow, &upstream.name));
+                }
+            } else if let Some(input) = workflow.inputs.iter().find(|i| i.name == root) {
+                inputs.insert(input.name.as_str());
+            } else if let Some(declaration) = workflow.declarations.iter().find(|d| d.name == root)
+            {
+                if declarations.insert(declaration.name.as_str()) {
+                    pending.extend(declaration.default_value.as_deref());
+                    pending.extend(enclosing_conditions(workflow, &declaration.name));
+                }
+            } else if let Some(scatter) = workflow.scatters.iter().find(|s| s.variable == root) {
+                if seen_scatters.insert(scatter.variable.as_str()) {
+                    pending.push(&scatter.expression);
+                }
+            }
+        }
+    }
+
+    let needed_calls: Vec<_> = workflow
+        .calls
+        .iter()
+        .filter(|c| calls.contains(c.name.as_str()))
+        .collect();
+    let targets: BTreeSet<&str> = needed_calls.iter().map(|c| c.target.as_str()).collect();
+    let tasks: Vec<_> = info
+        .tasks
+        .iter()
+        .filter(|task| targets.contains(task.name.as_str()))
+        .collect();
+    let namespaces: BTreeSet<&str> = targets
+        .iter()
+        .filter_map(|target| target.split_once('.').map(|(namespace, _)| namespace))
+        .collect();
+
+    let mut declared: Vec<&InputInfo> = workflow
+        .inputs
+        .iter()
+        .filter(|i| inputs.contains(i.name.as_str()))
+        .collect();
+    declared.extend(
+        workflow
+            .declarations
+            .iter()
+            .filter(|d| declarations.contains(d.name.as_str())),
+    );
+    for task in &tasks {
+        declared.extend(task.inputs.iter().chain(&task.declarations));
+    }
+
+    Some(Closure {
+        call: target.name.clone(),
+        calls: needed_calls
+            .iter()
+            .filter(|c| c.name != target.name)
+            .map(|c| c.name.clone())
+            .collect(),
+        tasks: tasks.iter().map(|task| task.name.clone()).collect(),
+        inputs: declared_names(&workflow.inputs, &inputs),
+        declarations: declared_names(&workflow.declarations, &declarations),
+        structs: struct_closure(
+            info,
+            declared.iter().filter_map(|d| d.wdl_type_parsed.as_ref()),
+        ),
+        imports: info
+            .imports
+            .iter()
+            .filter(|import| {
+                namespaces.contains(import_namespace(&import.uri, import.alias.as_deref()).as_str())
+            })
+            .map(|import| import.uri.clone())
+            .collect(),
+    })
+}
+
+/// Expressions a call depends on: its input values and the collections and
+/// conditions of the scatters and `if` blocks around it
+fn call_expressions<'a>(workflow: &'a WorkflowInfo, call: &str) -> Vec<&'a str> {
+    let target = workflow.calls.iter().find(|c| c.name == call);
+    let mut expressions: Vec<&str> = target
+        .into_iter()
+        .flat_map(|c| c.inputs.iter().map(|input| input.value.as_str()))
+        .collect();
+    expressions.extend(
+        workflow
+            .scatters
+            .iter()
+            .filter(|scatter| scatter.calls.iter().any(|name| name == call))
+            .map(|scatter| scatter.expression.as_str()),
+    );
+    expressions.extend(
+        workflow
+            .conditionals
+            .iter()
+            .filter(|conditional| conditional.calls.iter().any(|name| name == call))
+            .map(|conditional| conditional.expression.as_str()),
+    );
+    expressions
+}
+
+fn enclosing_conditions<'a>(workflow: &'a WorkflowInfo, declaration: &str) -> Vec<&'a str> {
+    workflow
+        .conditionals
+        .iter()
+        .filter(|conditional| conditional.declarations.iter().any(|d| d == declaration))
+        .map(|conditional| conditional.expression.as_str())
+        .collect()
+}
+
+fn declared_names(declarations: &[InputInfo], needed: &BTreeSet<&str>) -> Vec<String> {
+    declarations
+        .iter()
+        .filter(|d| needed.contains(d.name.as_str()))
+        .map(|d| d.name.clone())
+        .collect()
+}
+
+/// Local structs named in `types`, and the structs their fields use in
+/// turn, in the order they're defined in the file
+pub fn struct_closure<'a>(
+    info: &'a WdlInfo,
+    types: impl IntoIterator<Item = &'a WdlType>,
+) -> Vec<String> {
+    let used = used_structs(info, types);
+    info.structs
+        .iter()
+        .filter(|s| used.contains(s.name.as_str()))
+        .map(|s| s.name.clone())
+        .collect()
+}
+
+/// Every struct name reachable from `types` through the fields of local
+/// structs, including names defined elsewhere (e.g. by imports)
+pub fn used_structs<'a>(
+    info: &'a WdlInfo,
+    types: impl IntoIterator<Item = &'a WdlType>,
+) -> BTreeSet<&'a str> {
+    let mut used = BTreeSet::new();
+    let mut pending: Vec<&str> = types
+        .into_iter()
+        .flat_map(|wdl_type| wdl_type.struct_names())
+        .collect();
+    while let Some(name) = pending.pop() {
+        if !used.insert(name) {
+            continue;
+        }
+        if let Some(definition) = info.structs.iter().find(|s| s.name == name) {
+            pending.extend(
+                definition
+                    .fields
+                    .iter()
+                    .filter_map(|field| field.wdl_type_parsed.as_ref())
+                    .flat_map(|wdl_type| wdl_type.struct_names()),
+            );
+        }
+    }
+    used
+}
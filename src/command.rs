@@ -0,0 +1,359 @@
+use crate::lines::LineIndex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use wdl_grammar::{SyntaxNode, SyntaxToken};
+
+/// Delimiters used by a task's command section
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandStyle {
+    /// `command <<< ... >>>`
+    Heredoc,
+    /// `command { ... }`
+    Braces,
+}
+
+impl CommandStyle {
+    pub fn delimiters(self) -> (&'static str, &'static str) {
+        match self {
+            CommandStyle::Heredoc => ("<<<", ">>>"),
+            CommandStyle::Braces => ("{", "}"),
+        }
+    }
+}
+
+/// Language a task's command is written in, named as Markdown code fences name it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandLanguage {
+    Bash,
+    Python,
+    R,
+    Perl,
+    Ruby,
+    Javascript,
+}
+
+impl CommandLanguage {
+    pub fn name(self) -> &'static str {
+        match self {
+            CommandLanguage::Bash => "bash",
+            CommandLanguage::Python => "python",
+            CommandLanguage::R => "r",
+            CommandLanguage::Perl => "perl",
+            CommandLanguage::Ruby => "ruby",
+            CommandLanguage::Javascript => "javascript",
+        }
+    }
+
+    /// The language an interpreter runs, from its command name or path with
+    /// any version suffix (`/usr/bin/python3.11`)
+    pub fn from_interpreter(command: &str) -> Option<Self> {
+        let name = command.rsplit('/').next().unwrap_or(command);
+        match name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "bash" | "sh" | "zsh" | "dash" => Some(CommandLanguage::Bash),
+            "python" | "pypy" => Some(CommandLanguage::Python),
+            "Rscript" | "R" => Some(CommandLanguage::R),
+            "perl" => Some(CommandLanguage::Perl),
+            "ruby" => Some(CommandLanguage::Ruby),
+            "node" | "nodejs" => Some(CommandLanguage::Javascript),
+            _ => None,
+        }
+    }
+}
+
+/// A task command section, kept precisely enough to be written back unchanged
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommandSection {
+    pub style: CommandStyle,
+    /// Text between the delimiters exactly as written, including placeholders and escapes
+    pub raw: String,
+    /// `raw` with the leading and trailing blank lines and common indentation removed,
+    /// as the command will be executed
+    pub dedented: String,
+    /// 1-based line and column in the source file where `raw` starts
+    #[serde(default)]
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
+}
+
+/// A placeholder found in the raw command text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceholderSpan {
+    /// `~` or `$`
+    pub sigil: char,
+    /// Expression between the braces, trimmed
+    pub expression: String,
+    /// Char index of the sigil within `raw`
+    pub offset: usize,
+    /// Char index within `raw` just past the closing `}`
+    pub end: usize,
+    /// False when the command ends before the closing `}`
+    pub closed: bool,
+}
+
+impl CommandSection {
+    /// Build the model from a `CommandSectionNode`, with `lines` indexing the whole file
+    pub fn from_node(node: &SyntaxNode, lines: &LineIndex) -> Self {
+        let style = match delimiters(node).0.as_ref().map(|token| token.text()) {
+            Some("{") => CommandStyle::Braces,
+            _ => CommandStyle::Heredoc,
+        };
+
+        let text = node.text().to_string();
+        let node_start = usize::from(node.text_range().start());
+        let range = Self::raw_range(node);
+        let raw = text[range.start - node_start..range.end - node_start].to_string();
+
+        // Position of `raw` in the whole file, for reporting problems inside it
+        let (line, column) = lines.position(range.start);
+
+        Self {
+            style,
+            dedented: strip_common_indent(&raw),
+            raw,
+            line,
+            column,
+        }
+    }
+
+    /// Byte range in the source file of the text between the delimiters of
+    /// a `CommandSectionNode`
+    pub fn raw_range(node: &SyntaxNode) -> Range<usize> {
+        let (open, close) = delimiters(node);
+        let node_range = node.text_range();
+        let start = open.map_or(node_range.start(), |token| token.text_range().end());
+        let end = close
+            .map_or(node_range.end(), |token| token.text_range().start())
+            .max(start);
+        usize::from(start)..usize::from(end)
+    }
+
+    /// Placeholder contents (`~{...}`, and `${...}` in brace-style commands) in order
+    pub fn placeholders(&self) -> Vec<String> {
+        self.placeholder_spans()
+            .into_iter()
+            .map(|span| span.expression)
+            .collect()
+    }
+
+    /// Placeholders with their position; in heredoc commands `${...}` is
+    /// shell syntax and not a placeholder
+    pub fn placeholder_spans(&self) -> Vec<PlaceholderSpan> {
+        let chars: Vec<char> = self.raw.chars().collect();
+        let mut spans = Vec::new();
+        let mut i = 0;
+        while i + 1 < chars.len() {
+            let opens = chars[i + 1] == '{'
+                && (chars[i] == '~' || (chars[i] == '$' && self.style == CommandStyle::Braces));
+            if !opens {
+                i += 1;
+                continue;
+            }
+
+            let start = i + 2;
+            let mut depth = 1;
+            let mut end = start;
+            while end < chars.len() {
+                match chars[end] {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                end += 1;
+            }
+            spans.push(PlaceholderSpan {
+                sigil: chars[i],
+                expression: chars[start..end.min(chars.len())]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .to_string(),
+                offset: i,
+                end: (end + 1).min(chars.len()),
+                closed: end < chars.len(),
+            });
+            i = end + 1;
+        }
+        spans
+    }
+
+    /// 1-based line and column in the source file of the char at `offset` in `raw`
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let (mut line, mut column) = (self.line, self.column);
+        for c in self.raw.chars().take(offset) {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        (line, column)
+    }
+
+    /// The language of the command: the interpreter of a `#!` line, or one
+    /// whose inline script (`python <<CODE`, `Rscript -e '...'`) makes up
+    /// most of the command; `bash` otherwise
+    pub fn language(&self) -> CommandLanguage {
+        let lines: Vec<&str> = self.dedented.lines().collect();
+        if let Some(language) = lines.first().and_then(|line| shebang_language(line)) {
+            return language;
+        }
+
+        let non_blank =
+            |lines: &[&str]| lines.iter().filter(|line| !line.trim().is_empty()).count();
+        let mut embedded: Vec<(CommandLanguage, usize)> = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let Some((language, length)) = inline_script(&lines[i..]) else {
+                i += 1;
+                continue;
+            };
+            let count = non_blank(&lines[i..i + length]);
+            match embedded.iter_mut().find(|(l, _)| *l == language) {
+                Some((_, total)) => *total += count,
+                None => embedded.push((language, count)),
+            }
+            i += length;
+        }
+        embedded
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .filter(|(_, count)| count * 2 > non_blank(&lines))
+            .map_or(CommandLanguage::Bash, |(language, _)| language)
+    }
+
+    /// The section as WDL source, byte-for-byte the same as the original
+    pub fn to_source(&self) -> String {
+        let (open, close) = self.style.delimiters();
+        format!("command {}{}{}", open, self.raw, close)
+    }
+}
+
+/// The language of the interpreter a `#!` line runs, directly or through
+/// `env`
+pub fn shebang_language(line: &str) -> Option<CommandLanguage> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = words.next().unwrap_or_default();
+    if interpreter.ends_with("/env") {
+        interpreter = words
+            .find(|word| !word.starts_with('-'))
+            .unwrap_or_default();
+    }
+    CommandLanguage::from_interpreter(interpreter)
+}
+
+/// The opening and closing delimiter tokens of a `CommandSectionNode`
+fn delimiters(node: &SyntaxNode) -> (Option<SyntaxToken>, Option<SyntaxToken>) {
+    let tokens: Vec<_> = node
+        .children_with_tokens()
+        .filter_map(|child| child.into_token())
+        .collect();
+    let open = tokens
+        .iter()
+        .find(|token| matches!(token.text(), "<<<" | "{"))
+        .cloned();
+    let close = tokens
+        .iter()
+        .rev()
+        .find(|token| matches!(token.text(), ">>>" | "}"))
+        .cloned();
+    (open, close)
+}
+
+/// An interpreter other than a shell reading a script from a heredoc or an
+/// `-e`/`-c` argument on the first of `lines`, with the number of lines the
+/// invocation and script span
+fn inline_script(lines: &[&str]) -> Option<(CommandLanguage, usize)> {
+    let line = lines[0].trim_start();
+    let line = line
+        .strip_prefix("exec ")
+        .or_else(|| line.strip_prefix("time "))
+        .unwrap_or(line)
+        .trim_start();
+    let command = line
+        .split(|c: char| c.is_whitespace() || c == '<')
+        .next()
+        .unwrap_or_default();
+    let language = CommandLanguage::from_interpreter(command)
+        .filter(|language| *language != CommandLanguage::Bash)?;
+
+    if let Some((_, after)) = line.split_once("<<") {
+        let delimiter: String = after
+            .trim_start_matches(['-', '~'])
+            .trim_start()
+            .chars()
+            .take_while(|c| !c.is_whitespace() && !matches!(c, ';' | '|' | '&' | ')'))
+            .filter(|c| !matches!(c, '\'' | '"' | '\\'))
+            .collect();
+        let end = lines
+            .iter()
+            .skip(1)
+            .position(|line| line.trim() == delimiter)
+            .map_or(lines.len(), |index| index + 2);
+        return Some((language, end));
+    }
+
+    let argument = line
+        .split_whitespace()
+        .position(|word| matches!(word, "-e" | "-c" | "--eval"))?;
+    let script = line
+        .split_whitespace()
+        .skip(argument + 1)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let Some(quote) = script.chars().next().filter(|c| matches!(c, '\'' | '"')) else {
+        return Some((language, 1));
+    };
+    if script[1..].contains(quote) {
+        return Some((language, 1));
+    }
+    let end = lines
+        .iter()
+        .skip(1)
+        .position(|line| line.contains(quote))
+        .map_or(lines.len(), |index| index + 2);
+    Some((language, end))
+}
+
+/// Remove common leading whitespace as the WDL spec describes for command sections.
+///
+/// A whitespace-only first line (the remainder of the opening delimiter's line)
+/// and last line (the indentation before the closing delimiter) are dropped,
+/// then the smallest indentation of the non-blank lines is removed from every
+/// line. Blank lines don't count toward the common indentation.
+pub fn strip_common_indent(raw: &str) -> String {
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+    if lines.len() > 1 && lines.first().is_some_and(|line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    if lines.len() > 1 && lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ""
+            } else {
+                &line[indent..]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,176 +1,3593 @@
+use crate::audit;
+use crate::backend::{self, BackendIssue};
+use crate::badge;
+use crate::baseline::Baseline;
+use crate::bench;
+use crate::catalog;
+use crate::cli::Cli;
+use crate::closure;
+use crate::command::CommandSection;
+use crate::compare;
+use crate::compat;
+use crate::config::{Config, DEFAULT_CONFIG_FILE};
+use crate::cromwell;
+use crate::diagnostics::{self, DiagnosticGroup};
+use crate::diff;
+use crate::docs::{self, IndexFormat, SiteFormat};
+use crate::edit::{self, SourceEdit};
+use crate::exit::{self, ExitStatus};
+use crate::fanout;
+use crate::files;
+use crate::filter::DiagnosticFilter;
+use crate::graph::{node_id, NodeKind, WorkflowGraph, WorkspaceGraph};
 use crate::info::{
-    CallInfo, CallInputItem, ImportInfo, InputInfo, MetaItem, OutputInfo, RuntimeItem, StructInfo,
-    TaskInfo, WdlInfo, WorkflowInfo,
+    Authorship, CallInfo, CallInputItem, ConditionalInfo, FilePatterns, ImportInfo, InputInfo,
+    MetaItem, OutputInfo, RuntimeItem, ScatterInfo, SortBy, SourceExcerpt, StructInfo, TaskInfo,
+    WdlInfo, WorkflowInfo,
 };
+use crate::inputs::{self, InputSignature};
+use crate::library;
+use crate::lineage::{self, LineageFormat};
+use crate::lines::LineIndex;
+use crate::lint::{self, Finding, Severity};
+use crate::localization;
+use crate::markdown;
+use crate::mermaid;
 use crate::metadata::BasicWdlMetadata;
+use crate::network::{BlockedRequest, NetworkAccess, RequestKind};
+use crate::normalize::{self, Normalizer};
+use crate::numbers;
+use crate::output_schema::{self, SelfSchema};
+use crate::overrides;
+use crate::plan;
+use crate::plugin;
+use crate::progress::{self, BatchSummary};
+use crate::provenance;
+use crate::registry::{self, ImageRef};
+use crate::rewrite;
+use crate::runtime::NormalizedRuntime;
+use crate::scaffold::{self, TestStyle};
+use crate::scatter;
+use crate::schema::{self, SchemaStyle};
+use crate::scripts;
+use crate::source::{SourceFlavor, SourceText};
+use crate::spec::{self, SpecVersion};
+use crate::stats;
+use crate::strip;
+use crate::struct_usage;
+use crate::suggest;
+use crate::suppress::{self, Suppressions};
+use crate::template::{self, NewKind};
+use crate::terra;
+use crate::tree;
+use crate::types::WdlType;
+use crate::vendor;
+use crate::version::{self, VersionChange};
+use crate::wiring::{self, CallWiring, SourceKind};
+use crate::workspace::{ImportOptions, SymbolKind, Workspace};
 use crate::OutputFormat;
+use crate::{rpc, server, service};
 use anyhow::{Context, Result};
+use clap::CommandFactory;
+use clap_complete::Shell;
 use colored::*;
 use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use wdl_grammar::{SyntaxKind, SyntaxTree};
 
+/// Output limits for `parse`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParseLimits {
+    /// Bytes of tree output
+    pub output_bytes: Option<usize>,
+    /// Diagnostic groups (root causes) reported
+    pub diagnostics: Option<usize>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn parse_command(
     file: PathBuf,
-    format: OutputFormat,
-    verbose: bool,
-    extract_metadata: bool,
+    format: OutputFormat,
+    verbose: bool,
+    extract_metadata: bool,
+    limits: ParseLimits,
+    filter: DiagnosticFilter,
+    config_path: Option<PathBuf>,
+    sort_by: SortBy,
+) -> Result<()> {
+    let max_output_bytes = limits.output_bytes;
+    let filter = Config::load(config_path.as_deref())?
+        .diagnostics
+        .with(filter);
+    let content = read_wdl_file(&file)?;
+    let (tree, diagnostics) = SyntaxTree::parse(&content);
+    let diagnostics = filter.diagnostics(diagnostics);
+    let lines = LineIndex::new(&content);
+    let mut groups = diagnostics::group(&diagnostics, &tree.root(), &lines);
+    filter.escalate(&mut groups);
+    let omitted: Vec<DiagnosticGroup> = match limits.diagnostics {
+        Some(max) if groups.len() > max => groups.split_off(max),
+        _ => Vec::new(),
+    };
+
+    // Extract basic metadata if requested
+    let basic_metadata = if extract_metadata {
+        Some(BasicWdlMetadata::extract_from_text(&content, sort_by))
+    } else {
+        None
+    };
+
+    if verbose && !diagnostics.is_empty() {
+        println!("{}", "Diagnostics:".yellow().bold());
+        for group in &groups {
+            let cause = &group.cause;
+            let location = cause
+                .line
+                .zip(cause.column)
+                .map(|(line, column)| format!(" (line {}, column {})", line, column))
+                .unwrap_or_default();
+            println!(
+                "  {}: {}{}",
+                capitalize(&cause.severity).red(),
+                cause.message,
+                location
+            );
+            if !group.related.is_empty() {
+                println!(
+                    "      {}",
+                    format!("↳ {} follow-on diagnostic(s)", group.related.len()).dimmed()
+                );
+            }
+        }
+        if !omitted.is_empty() {
+            println!(
+                "  {}",
+                format!(
+                    "… {} more root cause(s) with {} diagnostic(s) not shown (--max-diagnostics)",
+                    omitted.len(),
+                    omitted.iter().map(DiagnosticGroup::count).sum::<usize>()
+                )
+                .yellow()
+            );
+        }
+        println!();
+    }
+
+    match format {
+        OutputFormat::Tree => {
+            if max_output_bytes.is_none() && content.len() > tree::LARGE_INPUT_BYTES {
+                tracing::warn!(
+                    file = %file.display(),
+                    bytes = content.len(),
+                    "input is large; tree output will be very large (consider --max-output-bytes)"
+                );
+            }
+
+            println!("{}", "Syntax Tree:".green().bold());
+            let mut writer = BufWriter::new(io::stdout().lock());
+            let truncated = tree::write_tree_limited(&tree.root(), &mut writer, max_output_bytes)?;
+            writer.flush()?;
+            drop(writer);
+
+            if truncated {
+                println!();
+                tracing::warn!(
+                    "output truncated after {} bytes",
+                    max_output_bytes.unwrap_or_default()
+                );
+            }
+        }
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let semantic_info = extract_semantic_info(&tree.root(), sort_by);
+            let mut json_output = serde_json::json!({
+                "file": file.display().to_string(),
+                "diagnostics": diagnostics.len(),
+                "has_errors": diagnostics.iter().any(|d| filter.is_error(d)),
+                "diagnostic_groups": groups,
+                "wdl": semantic_info
+            });
+            if !omitted.is_empty() {
+                json_output["omitted_diagnostic_groups"] = omitted.len().into();
+            }
+
+            if let Some(metadata) = &basic_metadata {
+                json_output["basic_metadata"] = serde_json::to_value(metadata)?;
+            }
+
+            print_json(&json_output, &format)?;
+        }
+        OutputFormat::Human | OutputFormat::Markdown => {
+            println!("{} {}", "Parsed:".green().bold(), file.display());
+            println!("Root node: {}", format!("{:?}", tree.root().kind()).cyan());
+            if !diagnostics.is_empty() {
+                println!(
+                    "Diagnostics: {} ({} root cause(s))",
+                    diagnostics.len().to_string().yellow(),
+                    groups.len() + omitted.len()
+                );
+            } else {
+                println!("{}", "No issues found".green());
+            }
+        }
+    }
+
+    let errors = diagnostics.iter().filter(|d| filter.is_error(d)).count();
+    if errors > 0 {
+        return Err(exit::failure(
+            ExitStatus::ParseErrors,
+            format!("{} syntax error(s) in {}", errors, file.display()),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Set the `source` of each task and workflow to its text, from the keyword
+/// to the closing brace. Definitions recovered from the text alone by
+/// best-effort extraction have no node and keep none.
+fn attach_sources(root: &wdl_grammar::SyntaxNode, content: &str, info: &mut WdlInfo, limit: usize) {
+    let lines = LineIndex::new(content);
+    for node in root.descendants().filter(|n| {
+        matches!(
+            n.kind(),
+            SyntaxKind::TaskDefinitionNode | SyntaxKind::WorkflowDefinitionNode
+        )
+    }) {
+        let Some(name) = find_identifier_name(&node) else {
+            continue;
+        };
+        let line = line_number(&node, &lines);
+        let source = if node.kind() == SyntaxKind::TaskDefinitionNode {
+            info.tasks
+                .iter_mut()
+                .find(|t| t.name == name && t.line == line)
+                .map(|t| &mut t.source)
+        } else {
+            info.workflows
+                .iter_mut()
+                .find(|w| w.name == name && w.line == line)
+                .map(|w| &mut w.source)
+        };
+        if let Some(source) = source {
+            *source = Some(SourceExcerpt::new(content, edit::node_range(&node), limit));
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// What `info` reports beyond the file summary
+#[derive(Debug, Default)]
+pub struct InfoOptions {
+    /// Extract basic metadata with the regex fallback
+    pub extract_metadata: bool,
+    pub show_wiring: bool,
+    /// Print each task's command in human output
+    pub show_commands: bool,
+    /// Print each task's runtime attributes in human output
+    pub show_runtime: bool,
+    /// Report only this task
+    pub task: Option<String>,
+    /// Report only this workflow
+    pub workflow: Option<String>,
+    /// Keep definitions with syntax errors, marked incomplete
+    pub best_effort: bool,
+    /// Embed the source text of each task and workflow, cut after this many
+    /// bytes each
+    pub include_source: Option<usize>,
+}
+
+pub fn info_command(
+    file: PathBuf,
+    format: OutputFormat,
+    options: InfoOptions,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, diagnostics) = SyntaxTree::parse(&content);
+
+    let mut info = if options.best_effort {
+        extract_partial_info(&tree.root(), &diagnostics, sort_by)
+    } else {
+        let mut info = WdlInfo::new();
+        collect_semantic_info(&tree.root(), &mut info, &LineIndex::new(&content));
+        info.sort(sort_by);
+        info
+    };
+    if let Some(limit) = options.include_source {
+        attach_sources(&tree.root(), &content, &mut info, limit);
+    }
+
+    if let Some(name) = &options.task {
+        let task = info
+            .tasks
+            .iter()
+            .find(|t| &t.name == name)
+            .with_context(|| format!("Task '{}' not found in {}", name, file.display()))?;
+        match format {
+            OutputFormat::Json | OutputFormat::Jsonl => print_json(
+                &serde_json::json!({ "file": file.display().to_string(), "task": task }),
+                &format,
+            )?,
+            OutputFormat::Markdown => print!("{}", markdown::task_markdown(task)),
+            _ => print_task_details(task),
+        }
+        return Ok(());
+    }
+    if let Some(name) = &options.workflow {
+        let workflow = select_workflow(&info, Some(name.as_str()), &file)?;
+        match format {
+            OutputFormat::Json | OutputFormat::Jsonl => print_json(
+                &serde_json::json!({ "file": file.display().to_string(), "workflow": workflow }),
+                &format,
+            )?,
+            OutputFormat::Markdown => print!("{}", markdown::workflow_markdown(workflow)),
+            _ => print_workflow_details(workflow),
+        }
+        return Ok(());
+    }
+
+    // Extract basic metadata if requested
+    let basic_metadata = if options.extract_metadata {
+        Some(BasicWdlMetadata::extract_from_text(&content, sort_by))
+    } else {
+        None
+    };
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let mut json_output = serde_json::json!({
+                "file": file.display().to_string(),
+                "version": info.version,
+                "tasks": info.tasks,
+                "workflows": info.workflows,
+                "structs": info.structs,
+                "imports": info.imports
+            });
+
+            if let Some(metadata) = &basic_metadata {
+                json_output["basic_metadata"] = serde_json::to_value(metadata)?;
+            }
+
+            if options.show_wiring {
+                json_output["wiring"] = serde_json::to_value(wiring::call_wiring(&info))?;
+            }
+
+            print_json(&json_output, &format)?;
+        }
+        OutputFormat::Markdown => {
+            print!(
+                "{}",
+                markdown::info_markdown(&file.display().to_string(), &info)
+            );
+        }
+        _ => {
+            println!("{} {}", "WDL File Info:".cyan().bold(), file.display());
+            println!("{}", "─".repeat(50));
+
+            if let Some(version) = &info.version {
+                println!("{}: {}", "Version".green().bold(), version);
+            }
+
+            println!("{}: {}", "Tasks".green().bold(), info.tasks.len());
+            for task in &info.tasks {
+                println!("  • {}{}", task.name, incomplete_marker(task.complete));
+                if options.show_runtime && !task.runtime.is_empty() {
+                    println!("      {}", "Runtime:".bold());
+                    print_runtime(&task.runtime, 8);
+                }
+                if options.show_commands {
+                    if let Some(section) = &task.command_section {
+                        println!("      {}", "Command:".bold());
+                        print_command(section, 8);
+                    }
+                }
+            }
+
+            println!("{}: {}", "Workflows".green().bold(), info.workflows.len());
+            for workflow in &info.workflows {
+                println!(
+                    "  • {}{}",
+                    workflow.name,
+                    incomplete_marker(workflow.complete)
+                );
+            }
+
+            println!("{}: {}", "Structs".green().bold(), info.structs.len());
+            for struct_name in &info.structs {
+                println!(
+                    "  • {}{}",
+                    struct_name.name,
+                    incomplete_marker(struct_name.complete)
+                );
+            }
+
+            println!("{}: {}", "Imports".green().bold(), info.imports.len());
+            for import in &info.imports {
+                let display = if let Some(alias) = &import.alias {
+                    format!("{} as {}", import.uri, alias)
+                } else {
+                    import.uri.clone()
+                };
+                println!("  • {}", display);
+            }
+
+            if options.show_wiring {
+                print_wiring(&wiring::call_wiring(&info));
+            }
+
+            if !diagnostics.is_empty() {
+                println!();
+                println!("{}: {}", "Diagnostics".yellow().bold(), diagnostics.len());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// ` (incomplete)` after definitions best-effort extraction found syntax
+/// errors in
+fn incomplete_marker(complete: Option<bool>) -> String {
+    match complete {
+        Some(false) => format!(" {}", "(incomplete)".yellow()),
+        _ => String::new(),
+    }
+}
+
+fn print_task_details(task: &TaskInfo) {
+    println!("{} {}", "Task:".cyan().bold(), task.name);
+    println!("{}", "─".repeat(50));
+    print_description(&task.authorship, task.doc.as_deref());
+    print_declarations("Inputs", &task.inputs);
+    print_outputs(&task.outputs);
+    if let Some(section) = &task.command_section {
+        println!("{}:", "Command".green().bold());
+        print_command(section, 4);
+    }
+    println!("{}: {}", "Runtime".green().bold(), task.runtime.len());
+    print_runtime(&task.runtime, 4);
+}
+
+fn print_workflow_details(workflow: &WorkflowInfo) {
+    println!("{} {}", "Workflow:".cyan().bold(), workflow.name);
+    println!("{}", "─".repeat(50));
+    print_description(&workflow.authorship, workflow.doc.as_deref());
+    print_declarations("Inputs", &workflow.inputs);
+    println!("{}: {}", "Calls".green().bold(), workflow.calls.len());
+    for call in &workflow.calls {
+        if call.name == call.target {
+            println!("  • {}", call.name);
+        } else {
+            println!("  • {} ({})", call.name, call.target);
+        }
+    }
+    print_outputs(&workflow.outputs);
+}
+
+/// The `meta` description, or else the comment block above the definition
+fn print_description(authorship: &Authorship, doc: Option<&str>) {
+    if let Some(description) = authorship.description.as_deref().or(doc) {
+        println!("{}", description);
+        println!();
+    }
+}
+
+fn print_declarations(title: &str, declarations: &[InputInfo]) {
+    println!("{}: {}", title.green().bold(), declarations.len());
+    for declaration in declarations {
+        match &declaration.default_value {
+            Some(default) => println!(
+                "  • {}: {} = {}",
+                declaration.name, declaration.wdl_type, default
+            ),
+            None => println!("  • {}: {}", declaration.name, declaration.wdl_type),
+        }
+    }
+}
+
+fn print_outputs(outputs: &[OutputInfo]) {
+    println!("{}: {}", "Outputs".green().bold(), outputs.len());
+    for output in outputs {
+        println!(
+            "  • {}: {} = {}",
+            output.name, output.wdl_type, output.expression
+        );
+    }
+}
+
+/// The dedented command with placeholders highlighted and comment lines dimmed
+fn print_command(section: &CommandSection, indent: usize) {
+    // Dedenting keeps the placeholders, so they are found the same way in
+    // the dedented text
+    let dedented = CommandSection {
+        raw: section.dedented.clone(),
+        ..section.clone()
+    };
+    let spans = dedented.placeholder_spans();
+    let mut offset = 0;
+    for line in dedented.raw.lines() {
+        let length = line.chars().count();
+        let placeholders: Vec<Range<usize>> = spans
+            .iter()
+            .map(|span| span.offset.max(offset)..span.end.min(offset + length))
+            .filter(|range| range.start < range.end)
+            .map(|range| range.start - offset..range.end - offset)
+            .collect();
+        println!(
+            "{}{}",
+            " ".repeat(indent),
+            highlight_command_line(line, &placeholders)
+        );
+        offset += length + 1;
+    }
+}
+
+/// `line` with the char ranges of `placeholders` highlighted, or dimmed
+/// when it is a comment
+fn highlight_command_line(line: &str, placeholders: &[Range<usize>]) -> String {
+    if line.trim_start().starts_with('#') {
+        return line.dimmed().to_string();
+    }
+    let chars: Vec<char> = line.chars().collect();
+    let mut highlighted = String::new();
+    let mut cursor = 0;
+    for range in placeholders {
+        highlighted.extend(&chars[cursor..range.start]);
+        let placeholder: String = chars[range.clone()].iter().collect();
+        highlighted.push_str(&placeholder.yellow().to_string());
+        cursor = range.end;
+    }
+    highlighted.extend(&chars[cursor..]);
+    highlighted
+}
+
+/// Runtime attributes as a two-column table, keys aligned
+fn print_runtime(runtime: &[RuntimeItem], indent: usize) {
+    let width = runtime.iter().map(|item| item.key.len()).max().unwrap_or(0);
+    for item in runtime {
+        println!(
+            "{}{}  {}",
+            " ".repeat(indent),
+            format!("{:width$}", item.key, width = width).bold(),
+            item.value.trim()
+        );
+    }
+}
+
+fn print_wiring(wirings: &[CallWiring]) {
+    println!();
+    println!("{}: {}", "Call Wiring".green().bold(), wirings.len());
+    for wiring in wirings {
+        let label = if wiring.call == wiring.target {
+            wiring.call.clone()
+        } else {
+            format!("{} ({})", wiring.call, wiring.target)
+        };
+        println!("  • {}", label.bold());
+
+        for binding in &wiring.bindings {
+            let source = match binding.source {
+                SourceKind::WorkflowInput => "workflow input",
+                SourceKind::CallOutput => "call output",
+                SourceKind::Declaration => "declaration",
+                SourceKind::ScatterVariable => "scatter variable",
+                SourceKind::Literal => "literal",
+                SourceKind::Expression => "expression",
+                SourceKind::Unresolved => "unresolved",
+            };
+            println!(
+                "      {} = {} {}",
+                binding.input,
+                binding.value,
+                format!("[{}]", source).dimmed()
+            );
+        }
+
+        if !wiring.resolved {
+            println!("      {}", "(task not defined in this file)".dimmed());
+        }
+        for input in &wiring.unbound {
+            match (&input.default_value, input.required) {
+                (Some(default), _) => println!(
+                    "      {} = {} {}",
+                    input.name,
+                    default,
+                    "[default]".dimmed()
+                ),
+                (None, false) => println!("      {} {}", input.name, "[optional, unset]".dimmed()),
+                (None, true) => println!("      {} {}", input.name, "[unbound]".red()),
+            }
+        }
+    }
+}
+
+pub fn check_backend_command(
+    file: PathBuf,
+    profile_name: String,
+    config_path: Option<PathBuf>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let config = Config::load(config_path.as_deref())?;
+    let profile = config.backends.get(&profile_name).with_context(|| {
+        let available: Vec<&str> = config.backends.keys().map(String::as_str).collect();
+        format!(
+            "Unknown backend profile '{}' (available: {})",
+            profile_name,
+            if available.is_empty() {
+                "none".to_string()
+            } else {
+                available.join(", ")
+            }
+        )
+    })?;
+
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+
+    let issues: Vec<BackendIssue> = info
+        .tasks
+        .iter()
+        .flat_map(|task| backend::check_task(task, profile))
+        .collect();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let json_output = serde_json::json!({
+                "file": file.display().to_string(),
+                "profile": profile_name,
+                "compatible": issues.is_empty(),
+                "issues": issues
+            });
+            print_json(&json_output, &format)?;
+        }
+        _ => {
+            println!(
+                "{} {} ({} {})",
+                "Backend Check:".cyan().bold(),
+                file.display(),
+                "profile".dimmed(),
+                profile_name
+            );
+            println!("{}", "─".repeat(50));
+
+            if issues.is_empty() {
+                println!("{}", "All tasks are compatible".green());
+            }
+            for issue in &issues {
+                println!(
+                    "  {} {} [{}]: {}",
+                    "✗".red(),
+                    issue.task.bold(),
+                    issue.attribute,
+                    issue.message
+                );
+            }
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(exit::failure(
+            ExitStatus::CheckFailed,
+            format!(
+                "{} runtime requirement(s) incompatible with backend profile '{}'",
+                issues.len(),
+                profile_name
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// How `lint` treats the files it checks, beyond rule levels from the config
+#[derive(Debug, Default)]
+pub struct LintOptions {
+    /// Rewrite unpinned container images to their digests in place
+    pub resolve_digests: bool,
+    /// Rule executables to run in addition to those in the config
+    pub plugins: Vec<String>,
+    /// Run the plugins of a discovered config; those of an explicit
+    /// `--config` always run
+    pub trust_plugins: bool,
+    /// Honour `# wdlparse-disable` comments
+    pub inline_config: bool,
+    pub baseline: Option<PathBuf>,
+    /// Record current findings in `baseline` instead of reporting them
+    pub update_baseline: bool,
+    /// Whether `resolve_digests` may query registries
+    pub network: NetworkAccess,
+    /// Findings to drop or raise to errors, on top of the config's
+    pub filter: DiagnosticFilter,
+    /// Report features newer than this version or the file's declared one
+    pub spec: Option<SpecVersion>,
+}
+
+pub fn lint_command(
+    paths: Vec<String>,
+    format: OutputFormat,
+    options: LintOptions,
+    config_path: Option<PathBuf>,
+    sort_by: SortBy,
+) -> Result<()> {
+    let LintOptions {
+        resolve_digests,
+        plugins,
+        trust_plugins,
+        inline_config,
+        baseline: baseline_path,
+        update_baseline,
+        network,
+        filter,
+        spec,
+    } = options;
+    let config = Config::load(config_path.as_deref())?;
+    // A checked-out repository shouldn't run programs just by being linted
+    if config_path.is_none() && !config.lint.plugins.is_empty() && !trust_plugins {
+        anyhow::bail!(
+            "{} lists lint plugins ({}); pass --trust-plugins to run them",
+            DEFAULT_CONFIG_FILE,
+            config.lint.plugins.join(", ")
+        );
+    }
+    let filter = config.diagnostics.clone().with(filter);
+    let mut baseline = match &baseline_path {
+        Some(path) if !update_baseline => Baseline::from_file(path)?,
+        _ => Baseline::default(),
+    };
+    let mut baselined = 0;
+    let plugins: Vec<String> = config.lint.plugins.iter().cloned().chain(plugins).collect();
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
+
+    let progress = progress::file_progress(files.len());
+    let mut summary = BatchSummary::default();
+    let mut errors = 0;
+    let mut blocked = 0;
+    let mut results = Vec::new();
+    for file in &files {
+        progress.set_message(file.display().to_string());
+        let source = read_wdl_source(file)?;
+        let mut content = source.text;
+        let mut blocked_requests = Vec::new();
+        if resolve_digests {
+            (content, blocked_requests) =
+                pin_image_digests(file, &content, source.flavor, network)?;
+        }
+        blocked += blocked_requests.len();
+
+        let (tree, _) = SyntaxTree::parse(&content);
+        let info = extract_semantic_info(&tree.root(), sort_by);
+        let mut findings: Vec<Finding> = blocked_requests.iter().map(|b| b.finding()).collect();
+        findings.extend(lint::lint(&info, &config.lint));
+        if let Some(spec) = spec {
+            findings.extend(spec::check(
+                &tree.root(),
+                &content,
+                info.version.as_deref(),
+                spec,
+                &config.lint,
+            ));
+        }
+        let file_name = file.display().to_string();
+        let request = plugin::PluginRequest {
+            file: &file_name,
+            source: &content,
+            info: &info,
+        };
+        for name in &plugins {
+            findings.extend(plugin::run(name, &request, &config.lint)?);
+        }
+        findings = filter.findings(findings);
+        if inline_config {
+            findings = Suppressions::parse(&content).apply(findings);
+        }
+        if update_baseline {
+            baseline.record(&file_name, &findings);
+            baselined += findings.len();
+            findings.clear();
+        } else if baseline_path.is_some() {
+            let (fresh, known) = baseline.new_findings(&file_name, findings);
+            findings = fresh;
+            baselined += known;
+        }
+        let file_errors = findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .count();
+        summary.record(file_errors, findings.len() - file_errors);
+        errors += file_errors;
+
+        let file_output = serde_json::json!({
+            "file": file.display().to_string(),
+            "findings": findings
+        });
+        match format {
+            OutputFormat::Json => results.push(file_output),
+            OutputFormat::Jsonl => progress.suspend(|| print_json(&file_output, &format))?,
+            _ => progress.suspend(|| print_findings(file, &findings)),
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    match format {
+        OutputFormat::Json => {
+            let json_output = serde_json::json!({ "files": results });
+            print_json(&json_output, &format)?;
+        }
+        OutputFormat::Jsonl => {}
+        _ if files.len() > 1 => summary.print(),
+        _ => {}
+    }
+
+    if let Some(path) = &baseline_path {
+        let action = if update_baseline {
+            baseline.save(path)?;
+            "recorded in"
+        } else {
+            "hidden by"
+        };
+        if matches!(format, OutputFormat::Human) {
+            println!(
+                "{} {} known finding(s) {} {}",
+                "Baseline:".cyan().bold(),
+                baselined,
+                action,
+                path.display()
+            );
+        }
+    }
+
+    if errors > 0 {
+        return Err(exit::failure(
+            ExitStatus::LintFailures,
+            format!("{} lint error(s) found", errors),
+        ));
+    }
+    if blocked > 0 {
+        return Err(exit::failure(
+            ExitStatus::NetworkDisabled,
+            format!(
+                "{} registry lookup(s) skipped because of --offline",
+                blocked
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn print_findings(file: &Path, findings: &[Finding]) {
+    println!("{} {}", "Lint:".cyan().bold(), file.display());
+    println!("{}", "─".repeat(50));
+
+    if findings.is_empty() {
+        println!("{}", "No issues found".green());
+        return;
+    }
+
+    for finding in findings {
+        let severity = match finding.severity {
+            Severity::Warning => "warning".yellow().bold(),
+            Severity::Error => "error".red().bold(),
+        };
+        println!(
+            "  {}[{}] {}: {}",
+            severity,
+            finding.rule,
+            finding.location.bold(),
+            finding.message
+        );
+    }
+}
+
+/// Rewrite unpinned container images in the file to include their registry
+/// digest. Only the image strings in runtime sections change, so comments,
+/// layout, line endings and other mentions of the image stay as written.
+/// Lookups refused because the network is disabled are returned.
+fn pin_image_digests(
+    file: &Path,
+    content: &str,
+    flavor: SourceFlavor,
+    network: NetworkAccess,
+) -> Result<(String, Vec<BlockedRequest>)> {
+    let (tree, _) = SyntaxTree::parse(content);
+    let info = extract_semantic_info(&tree.root(), SortBy::Position);
+
+    let mut images: Vec<&str> = lint::container_images(&info)
+        .into_iter()
+        .map(|(_, image)| image)
+        .collect();
+    images.sort_unstable();
+    images.dedup();
+
+    let mut pinned_images = Vec::new();
+    let mut blocked = Vec::new();
+    for image in images {
+        let Some(image_ref) = ImageRef::parse(image) else {
+            continue;
+        };
+        if image_ref.has_digest() {
+            continue;
+        }
+        if let Err(e) = network.check(RequestKind::RegistryDigest, image) {
+            blocked.extend(e.downcast::<BlockedRequest>().ok());
+            continue;
+        }
+
+        match registry::resolve_digest(&image_ref) {
+            Ok(digest) => {
+                let pinned = ImageRef {
+                    digest: Some(digest),
+                    ..image_ref
+                };
+                tracing::info!(%image, %pinned, "pinned container image to digest");
+                pinned_images.push((image, pinned.to_string()));
+            }
+            Err(e) => {
+                tracing::warn!(%image, "could not resolve digest: {:#}", e);
+            }
+        }
+    }
+
+    // Only the value of a runtime `docker` or `container` item names an
+    // image; the same text elsewhere is left alone
+    let edits: Vec<SourceEdit> = tree
+        .root()
+        .descendants()
+        .filter(|node| node.kind() == SyntaxKind::RuntimeItemNode)
+        .filter(|item| {
+            item.children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .find(|token| token.kind() == SyntaxKind::Ident)
+                .is_some_and(|key| matches!(key.text(), "docker" | "container"))
+        })
+        .filter_map(|item| item.children().next())
+        .flat_map(|value| value.descendants_with_tokens())
+        .filter_map(|element| element.into_token())
+        .filter(|token| token.kind() == SyntaxKind::LiteralStringText)
+        .filter_map(|token| {
+            let (_, pinned) = pinned_images
+                .iter()
+                .find(|(image, _)| *image == token.text())?;
+            Some(SourceEdit::replace_token(&token, pinned.as_str()))
+        })
+        .collect();
+    if edits.is_empty() {
+        return Ok((content.to_string(), blocked));
+    }
+
+    let updated = edit::apply(content, &edits)?;
+    fs::write(file, flavor.encode(&updated))
+        .with_context(|| format!("Failed to write file: {}", file.display()))?;
+    Ok((updated, blocked))
+}
+
+pub fn closure_command(
+    file: PathBuf,
+    call: String,
+    workflow_name: Option<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+    let closure = closure::call_closure(&info, workflow, &call)
+        .with_context(|| format!("Call '{}' not found in workflow '{}'", call, workflow.name))?;
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => print_json(
+            &serde_json::json!({
+                "file": file.display().to_string(),
+                "workflow": workflow.name,
+                "closure": closure
+            }),
+            &format,
+        )?,
+        _ => {
+            println!("{} {}", "Closure:".cyan().bold(), closure.call);
+            println!("{}", "─".repeat(50));
+            for (title, names) in [
+                ("Calls", &closure.calls),
+                ("Tasks", &closure.tasks),
+                ("Inputs", &closure.inputs),
+                ("Declarations", &closure.declarations),
+                ("Structs", &closure.structs),
+                ("Imports", &closure.imports),
+            ] {
+                println!("{}: {}", title.green().bold(), names.len());
+                for name in names {
+                    println!("  • {}", name);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn strip_command(
+    file: PathBuf,
+    output: Option<PathBuf>,
+    options: strip::StripOptions,
+    import_dirs: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    let workspace = load_workspace(&file, import_dirs, config_path)?;
+    let document = workspace.root();
+    if document.info.workflows.is_empty() {
+        anyhow::bail!("No workflow found in {}", file.display());
+    }
+    for (path, error) in workspace.errors() {
+        tracing::warn!(import = %path.display(), "keeping import that failed to load: {}", error);
+    }
+
+    let stripped = strip::strip(&workspace, options)?;
+    for (kind, names) in [
+        ("task", &stripped.tasks),
+        ("struct", &stripped.structs),
+        ("import", &stripped.imports),
+    ] {
+        for name in names {
+            tracing::info!(kind, name = %name, "removed unused definition");
+        }
+    }
+
+    match output {
+        Some(path) => {
+            fs::write(&path, document.flavor.encode(&stripped.source))
+                .with_context(|| format!("Failed to write file: {}", path.display()))?;
+            println!("{} {}", "Wrote:".green().bold(), path.display());
+            println!(
+                "Removed: {} task(s), {} struct(s), {} import(s)",
+                stripped.tasks.len(),
+                stripped.structs.len(),
+                stripped.imports.len()
+            );
+        }
+        None => print!("{}", stripped.source),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_task_command(
+    file: PathBuf,
+    library_path: PathBuf,
+    task: String,
+    import: bool,
+    workflow: Option<String>,
+    call: bool,
+    output: Option<PathBuf>,
+    sort_by: SortBy,
+) -> Result<()> {
+    let target_source = read_wdl_source(&file)?;
+    let library_content = read_wdl_file(&library_path)?;
+    let mut options = library::AddTaskOptions {
+        import_uri: None,
+        workflow,
+        call,
+    };
+    if import {
+        let directory = fs::canonicalize(&file)
+            .with_context(|| format!("Failed to resolve {}", file.display()))?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let library_file = fs::canonicalize(&library_path)
+            .with_context(|| format!("Failed to resolve {}", library_path.display()))?;
+        options.import_uri = Some(vendor::relative_path(&directory, &library_file));
+    }
+
+    let (target_tree, _) = SyntaxTree::parse(&target_source.text);
+    let target_info = extract_semantic_info(&target_tree.root(), sort_by);
+    let (library_tree, _) = SyntaxTree::parse(&library_content);
+    let library_info = extract_semantic_info(&library_tree.root(), sort_by);
+    let added = library::add_task(
+        &library::Document {
+            source: &target_source.text,
+            root: &target_tree.root(),
+            info: &target_info,
+        },
+        &library::Document {
+            source: &library_content,
+            root: &library_tree.root(),
+            info: &library_info,
+        },
+        &task,
+        &options,
+    )
+    .with_context(|| {
+        format!(
+            "Failed to add task '{}' from {}",
+            task,
+            library_path.display()
+        )
+    })?;
+
+    for name in &added.missing_structs {
+        tracing::warn!(
+            "struct '{}' used by task '{}' is not defined in {}; add it yourself",
+            name,
+            task,
+            library_path.display()
+        );
+    }
+    let path = output.unwrap_or(file);
+    fs::write(&path, target_source.flavor.encode(&added.source))
+        .with_context(|| format!("Failed to write file: {}", path.display()))?;
+
+    println!("{} {}", "Wrote:".green().bold(), path.display());
+    match &added.import {
+        Some(uri) => println!("  • import \"{}\"", uri),
+        None => println!("  • task {}", added.task),
+    }
+    for name in &added.structs {
+        println!("  • struct {}", name);
+    }
+    if let (Some(workflow), Some(call)) = (&added.workflow, &added.call) {
+        println!("  • call {} in workflow {}", call, workflow);
+    }
+    Ok(())
+}
+
+pub fn fix_version_command(
+    paths: Vec<String>,
+    target: String,
+    check: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
+
+    let mut results = Vec::new();
+    for file in &files {
+        let source = read_wdl_source(file)?;
+        let fixed = version::fix(&source.text, &target)?;
+        if fixed.change != VersionChange::Unchanged && !check {
+            fs::write(file, source.flavor.encode(&fixed.source))
+                .with_context(|| format!("Failed to write file: {}", file.display()))?;
+        }
+        results.push((file, fixed));
+    }
+    let changed = results
+        .iter()
+        .filter(|(_, fixed)| fixed.change != VersionChange::Unchanged)
+        .count();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let entries: Vec<_> = results
+                .iter()
+                .map(|(file, fixed)| {
+                    serde_json::json!({
+                        "file": file.display().to_string(),
+                        "change": fixed.change,
+                        "version": fixed.version
+                    })
+                })
+                .collect();
+            if matches!(format, OutputFormat::Jsonl) {
+                for entry in &entries {
+                    print_json(entry, &format)?;
+                }
+            } else {
+                print_json(
+                    &serde_json::json!({ "check": check, "changed": changed, "files": entries }),
+                    &format,
+                )?;
+            }
+        }
+        _ => {
+            println!("{} {} file(s)", "Version:".cyan().bold(), files.len());
+            println!("{}", "─".repeat(50));
+            for (file, fixed) in &results {
+                let message = match (fixed.change, check) {
+                    (VersionChange::Unchanged, _) => continue,
+                    (VersionChange::Inserted, true) => "missing version statement".to_string(),
+                    (VersionChange::Moved, true) => {
+                        format!("version {} is not at the top of the file", fixed.version)
+                    }
+                    (VersionChange::Inserted, false) => {
+                        format!("inserted version {}", fixed.version)
+                    }
+                    (VersionChange::Moved, false) => {
+                        format!("moved version {} to the top of the file", fixed.version)
+                    }
+                };
+                println!("  • {}: {}", file.display().to_string().bold(), message);
+            }
+            let summary = if check { "need fixing" } else { "fixed" };
+            println!("{}: {} {}", "Summary".green().bold(), changed, summary);
+        }
+    }
+
+    if check && changed > 0 {
+        return Err(exit::failure(
+            ExitStatus::CheckFailed,
+            format!("{} file(s) need a version statement fixed", changed),
+        ));
+    }
+    Ok(())
+}
+
+pub fn vendor_command(
+    file: PathBuf,
+    dest: PathBuf,
+    format: OutputFormat,
+    network: NetworkAccess,
+) -> Result<()> {
+    if !file.exists() {
+        anyhow::bail!("File does not exist: {}", file.display());
+    }
+    let report = vendor::vendor(&file, &dest, |url| vendor::fetch_url(url, network))?;
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => print_json(
+            &serde_json::json!({
+                "file": file.display().to_string(),
+                "dest": dest.display().to_string(),
+                "downloaded": report.downloaded,
+                "rewritten": report.rewritten,
+                "blocked": report.blocked
+            }),
+            &format,
+        )?,
+        _ => {
+            println!(
+                "{} {} → {}",
+                "Vendor:".cyan().bold(),
+                file.display(),
+                dest.display()
+            );
+            println!("{}", "─".repeat(50));
+            println!(
+                "{}: {}",
+                "Downloaded".green().bold(),
+                report.downloaded.len()
+            );
+            for import in &report.downloaded {
+                println!("  • {} → {}", import.url, dest.join(&import.path).display());
+            }
+            println!("{}: {}", "Rewritten".green().bold(), report.rewritten.len());
+            for rewritten in &report.rewritten {
+                println!(
+                    "  • {}: {} import(s)",
+                    rewritten.file.display(),
+                    rewritten.rewrites.len()
+                );
+            }
+            if !report.blocked.is_empty() {
+                println!("{}: {}", "Blocked".red().bold(), report.blocked.len());
+                for blocked in &report.blocked {
+                    println!("  • {}", blocked.resource);
+                }
+            }
+        }
+    }
+
+    if !report.blocked.is_empty() {
+        return Err(exit::failure(
+            ExitStatus::NetworkDisabled,
+            format!(
+                "{} download(s) skipped because of --offline; nothing was written",
+                report.blocked.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+pub fn rewrite_imports_command(
+    paths: Vec<String>,
+    rules: Vec<String>,
+    check: bool,
+    format: OutputFormat,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    let config = Config::load(config_path.as_deref())?;
+    let mut rewrites = config.imports.rewrites;
+    for rule in &rules {
+        let (from, to) = rewrite::parse_rule(rule)?;
+        rewrites.insert(from, to);
+    }
+    if rewrites.is_empty() {
+        anyhow::bail!(
+            "No import rewrites given; pass --map FROM=TO or set [imports.rewrites] in the config"
+        );
+    }
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
+
+    let mut results = Vec::new();
+    for file in &files {
+        let source = read_wdl_source(file)?;
+        let rewritten = rewrite::rewrite_imports(&source.text, &rewrites)?;
+        if rewritten.rewrites.is_empty() {
+            continue;
+        }
+        if !check {
+            fs::write(file, source.flavor.encode(&rewritten.source))
+                .with_context(|| format!("Failed to write file: {}", file.display()))?;
+        }
+        results.push((file, rewritten.rewrites));
+    }
+    let count: usize = results.iter().map(|(_, rewrites)| rewrites.len()).sum();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let entries: Vec<_> = results
+                .iter()
+                .map(|(file, rewrites)| {
+                    serde_json::json!({
+                        "file": file.display().to_string(),
+                        "rewrites": rewrites
+                    })
+                })
+                .collect();
+            if matches!(format, OutputFormat::Jsonl) {
+                for entry in &entries {
+                    print_json(entry, &format)?;
+                }
+            } else {
+                print_json(
+                    &serde_json::json!({ "check": check, "files": entries }),
+                    &format,
+                )?;
+            }
+        }
+        _ => {
+            println!(
+                "{} {} file(s)",
+                "Rewrite Imports:".cyan().bold(),
+                files.len()
+            );
+            println!("{}", "─".repeat(50));
+            for (file, rewrites) in &results {
+                println!("{}:", file.display().to_string().bold());
+                for rewrite in rewrites {
+                    let alias = rewrite
+                        .alias
+                        .as_ref()
+                        .map(|alias| format!(" (as {})", alias).dimmed().to_string())
+                        .unwrap_or_default();
+                    println!(
+                        "  • line {}: {} → {}{}",
+                        rewrite.line,
+                        rewrite.from,
+                        rewrite.to.green(),
+                        alias
+                    );
+                }
+            }
+            let summary = if check { "to rewrite" } else { "rewritten" };
+            println!(
+                "{}: {} import(s) {} in {} file(s)",
+                "Summary".green().bold(),
+                count,
+                summary,
+                results.len()
+            );
+        }
+    }
+
+    if check && count > 0 {
+        return Err(exit::failure(
+            ExitStatus::CheckFailed,
+            format!("{} import(s) need rewriting", count),
+        ));
+    }
+    Ok(())
+}
+
+pub fn runtime_overrides_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+    let calls = overrides::call_overrides(&info, workflow);
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => print_json(
+            &serde_json::json!({
+                "file": file.display().to_string(),
+                "workflow": workflow.name,
+                "calls": calls
+            }),
+            &format,
+        )?,
+        _ => {
+            println!("{} {}", "Runtime Overrides:".cyan().bold(), workflow.name);
+            println!("{}", "─".repeat(50));
+            for call in &calls {
+                let label = if call.call == call.target {
+                    call.call.clone()
+                } else {
+                    format!("{} ({})", call.call, call.target)
+                };
+                if !call.resolved {
+                    println!(
+                        "{}: {}",
+                        label.green().bold(),
+                        "task not defined in this file".dimmed()
+                    );
+                    continue;
+                }
+                if call.overrides.is_empty() {
+                    println!("{}: {}", label.green().bold(), "none".dimmed());
+                    continue;
+                }
+                println!("{}:", label.green().bold());
+                for item in &call.overrides {
+                    let input = if item.fields.is_empty() {
+                        item.input.clone()
+                    } else {
+                        format!("{}.{{{}}}", item.input, item.fields.join(", "))
+                    };
+                    println!(
+                        "  • {} ← {} {}",
+                        item.key.bold(),
+                        input,
+                        format!("({})", item.expression).dimmed()
+                    );
+                    match &item.binding {
+                        Some(value) if item.workflow_inputs.is_empty() => {
+                            println!("      bound to {}", value);
+                        }
+                        Some(value) => println!(
+                            "      bound to {} from workflow input(s): {}",
+                            value,
+                            item.workflow_inputs.join(", ").yellow()
+                        ),
+                        None => println!(
+                            "      unbound; set {}.{}.{} to override (default: {})",
+                            workflow.name,
+                            call.call,
+                            item.input,
+                            item.default_value.as_deref().unwrap_or("none")
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn runtime_sensitivity_command(
+    file: PathBuf,
+    task: Option<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+
+    if let Some(task) = &task {
+        let names = info.tasks.iter().map(|t| t.name.as_str());
+        if !names.clone().any(|name| name == task) {
+            let suggestions = suggest::similar_names(task, names);
+            anyhow::bail!(
+                "Task '{}' not found in {}{}",
+                task,
+                file.display(),
+                suggest::did_you_mean(&suggestions)
+            );
+        }
+    }
+    let tasks: Vec<overrides::TaskSensitivity> = info
+        .tasks
+        .iter()
+        .filter(|t| task.as_ref().is_none_or(|name| &t.name == name))
+        .map(overrides::runtime_sensitivity)
+        .collect();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => print_json(
+            &serde_json::json!({
+                "file": file.display().to_string(),
+                "tasks": tasks
+            }),
+            &format,
+        )?,
+        _ => {
+            println!(
+                "{} {}",
+                "Runtime Sensitivity:".cyan().bold(),
+                file.display()
+            );
+            println!("{}", "─".repeat(50));
+            for task in &tasks {
+                if task.attributes.is_empty() {
+                    println!("{}: {}", task.task.green().bold(), "no runtime".dimmed());
+                    continue;
+                }
+                println!("{}:", task.task.green().bold());
+                for attribute in &task.attributes {
+                    let source = match attribute.source {
+                        overrides::RuntimeSource::Static => "static".normal(),
+                        overrides::RuntimeSource::Computed => {
+                            let inputs: Vec<String> = attribute
+                                .inputs
+                                .iter()
+                                .map(|input| {
+                                    let name = if input.fields.is_empty() {
+                                        input.name.clone()
+                                    } else {
+                                        format!("{}.{{{}}}", input.name, input.fields.join(", "))
+                                    };
+                                    match &input.default_value {
+                                        Some(value) => format!("{} (default {})", name, value),
+                                        None => name,
+                                    }
+                                })
+                                .collect();
+                            format!("computed from {}", inputs.join(", ")).yellow()
+                        }
+                    };
+                    println!(
+                        "  • {}: {} {}",
+                        attribute.key.bold(),
+                        source,
+                        format!("({})", attribute.expression).dimmed()
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn lineage_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    format: LineageFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+
+    let nodes = lineage::workflow_lineage(workflow);
+
+    match format {
+        LineageFormat::Json => {
+            let json_output = serde_json::json!({
+                "file": file.display().to_string(),
+                "workflow": workflow.name,
+                "outputs": nodes
+            });
+            print_json(&json_output, &OutputFormat::Json)?;
+        }
+        LineageFormat::Mermaid => print!("{}", lineage::to_mermaid(&nodes)),
+        LineageFormat::Human => {
+            println!("{} {}", "Lineage:".cyan().bold(), workflow.name);
+            println!("{}", "─".repeat(50));
+            print!("{}", lineage::to_text(&nodes));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn mermaid_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    task_name: Option<String>,
+    options: mermaid::DiagramOptions,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    print!(
+        "{}",
+        mermaid_diagram(
+            &info,
+            &file.display().to_string(),
+            workflow_name.as_deref(),
+            task_name.as_deref(),
+            &options
+        )?
+    );
+    Ok(())
+}
+
+/// The Mermaid diagram of a task, or else of a workflow (the first one in
+/// the document by default); `file` names the document in errors
+pub fn mermaid_diagram(
+    info: &WdlInfo,
+    file: &str,
+    workflow_name: Option<&str>,
+    task_name: Option<&str>,
+    options: &mermaid::DiagramOptions,
+) -> Result<String> {
+    if let Some(name) = task_name {
+        let task = info
+            .tasks
+            .iter()
+            .find(|t| t.name == name)
+            .with_context(|| format!("Task '{}' not found in {}", name, file))?;
+        return Ok(mermaid::task_diagram(task, options));
+    }
+
+    let workflow = select_workflow(info, workflow_name, Path::new(file))?;
+    Ok(mermaid::workflow_diagram(
+        &WorkflowGraph::build(workflow),
+        info,
+        options,
+    ))
+}
+
+pub fn graph_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    if let Some(name) = &workflow_name {
+        select_workflow(&info, Some(name.as_str()), &file)?;
+    }
+    let graphs: Vec<WorkflowGraph> = info
+        .workflows
+        .iter()
+        .filter(|w| workflow_name.as_ref().is_none_or(|name| &w.name == name))
+        .map(WorkflowGraph::build)
+        .collect();
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => print_json(
+            &serde_json::json!({ "file": file.display().to_string(), "graphs": graphs }),
+            &format,
+        )?,
+        _ => {
+            for graph in &graphs {
+                println!("{} {}", "Graph:".cyan().bold(), graph.workflow);
+                println!("{}", "─".repeat(50));
+                println!("{}:", "Nodes".green().bold());
+                for node in &graph.nodes {
+                    match &node.target {
+                        Some(target) => println!("  • {} ({:?} → {})", node.id, node.kind, target),
+                        None => println!("  • {} ({:?})", node.id, node.kind),
+                    }
+                }
+                println!("{}:", "Edges".green().bold());
+                for edge in &graph.edges {
+                    match edge.label() {
+                        Some(label) => println!("  • {} → {} ({})", edge.from, edge.to, label),
+                        None => println!("  • {} → {}", edge.from, edge.to),
+                    }
+                }
+                println!();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn workspace_mermaid_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    depth: Option<usize>,
+    import_dirs: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+    options: mermaid::DiagramOptions,
+) -> Result<()> {
+    let graph = workspace_graph(&file, workflow_name, depth, import_dirs, config_path)?;
+    print!("{}", mermaid::workspace_diagram(&graph, &options));
+    Ok(())
+}
+
+pub fn workspace_graph_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    depth: Option<usize>,
+    import_dirs: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+    format: OutputFormat,
+) -> Result<()> {
+    let graph = workspace_graph(&file, workflow_name, depth, import_dirs, config_path)?;
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let mut value = serde_json::to_value(&graph)?;
+            value["file"] = file.display().to_string().into();
+            print_json(&value, &format)?
+        }
+        _ => {
+            for sub in &graph.graphs {
+                println!(
+                    "{} {} ({})",
+                    "Graph:".cyan().bold(),
+                    sub.graph.workflow,
+                    sub.file
+                );
+                println!("{}", "─".repeat(50));
+                if let Some(parent) = &sub.parent {
+                    println!("{}: {} in {}", "Called by".green().bold(), sub.id, parent);
+                }
+                println!("{}:", "Nodes".green().bold());
+                for node in &sub.graph.nodes {
+                    match &node.target {
+                        Some(target) => println!("  • {} ({:?} → {})", node.id, node.kind, target),
+                        None => println!("  • {} ({:?})", node.id, node.kind),
+                    }
+                }
+                println!("{}:", "Edges".green().bold());
+                for edge in &sub.graph.edges {
+                    match edge.label() {
+                        Some(label) => println!("  • {} → {} ({})", edge.from, edge.to, label),
+                        None => println!("  • {} → {}", edge.from, edge.to),
+                    }
+                }
+                println!();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Graph of a workflow (the first one by default) merged with the
+/// sub-workflows it calls through local imports
+pub fn workspace_graph(
+    file: &Path,
+    workflow_name: Option<String>,
+    depth: Option<usize>,
+    import_dirs: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+) -> Result<WorkspaceGraph> {
+    let workspace = load_workspace(file, import_dirs, config_path)?;
+    for (path, error) in workspace.errors() {
+        tracing::warn!(import = %path.display(), "sub-workflows of import not expanded: {}", error);
+    }
+    let info = &workspace.root().info;
+    let workflow = select_workflow(info, workflow_name.as_deref(), file)?;
+    Ok(WorkspaceGraph::build(&workspace, workflow, depth))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn struct_graph_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    depth: Option<usize>,
+    workspace: bool,
+    import_dirs: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    if workflow_name.is_some() || depth.is_some() {
+        anyhow::bail!("--workflow and --depth only apply to --mode dataflow");
+    }
+    let usage = if workspace {
+        let loaded = load_workspace(&file, import_dirs, config_path)?;
+        for (path, error) in loaded.errors() {
+            tracing::warn!(import = %path.display(), "structs of import not included: {}", error);
+        }
+        let directory = loaded.root().path.parent().unwrap_or(Path::new(""));
+        let documents: Vec<(String, &WdlInfo)> = loaded
+            .documents()
+            .map(|document| {
+                (
+                    vendor::relative_path(directory, &document.path),
+                    &document.info,
+                )
+            })
+            .collect();
+        struct_usage::struct_usage(&documents)
+    } else {
+        let content = read_wdl_file(&file)?;
+        let (tree, _) = SyntaxTree::parse(&content);
+        let info = extract_semantic_info(&tree.root(), sort_by);
+        struct_usage::struct_usage(&[(file.display().to_string(), &info)])
+    };
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => print_json(
+            &serde_json::json!({ "file": file.display().to_string(), "structs": usage }),
+            &format,
+        )?,
+        _ => {
+            println!("{} {}", "Struct usage:".cyan().bold(), file.display());
+            println!("{}", "─".repeat(50));
+            if usage.is_empty() {
+                println!("No structs defined or used");
+            }
+            for entry in &usage {
+                let defined = match entry.defined_in.as_slice() {
+                    [] => "not defined in these files".to_string(),
+                    files => format!("defined in {}", files.join(", ")),
+                };
+                println!(
+                    "{} ({}): {} task(s), {} workflow(s)",
+                    entry.name.green().bold(),
+                    defined,
+                    entry.tasks,
+                    entry.workflows
+                );
+                for site in &entry.uses {
+                    let kind = match site.kind {
+                        SymbolKind::Task => "task",
+                        SymbolKind::Workflow => "workflow",
+                        SymbolKind::Struct => "struct",
+                    };
+                    let mut line = format!(
+                        "  • {} {}: {} {}",
+                        kind, site.name, site.section, site.declaration
+                    );
+                    if let Some(through) = &site.through {
+                        line.push_str(&format!(" (through {})", through));
+                    }
+                    if workspace {
+                        line.push_str(&format!(" [{}]", site.file));
+                    }
+                    println!("{}", line);
+                }
+                if entry.uses.is_empty() {
+                    println!("  {}", "unused".dimmed());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn plan_command(
+    file: PathBuf,
+    durations_path: PathBuf,
+    workflow_name: Option<String>,
+    mermaid_output: bool,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+
+    let durations_content = fs::read_to_string(&durations_path)
+        .with_context(|| format!("Failed to read file: {}", durations_path.display()))?;
+    let durations: plan::Durations =
+        serde_json::from_str(&durations_content).with_context(|| {
+            format!(
+                "Expected a JSON object of numeric durations in {}",
+                durations_path.display()
+            )
+        })?;
+
+    let graph = WorkflowGraph::build(workflow);
+    let plan = plan::plan(&graph, &durations);
+    for call in &plan.missing {
+        tracing::warn!("No duration for call '{}', counting it as 0", call);
+    }
+
+    if mermaid_output {
+        let options = mermaid::DiagramOptions {
+            annotations: plan
+                .calls
+                .iter()
+                .map(|t| {
+                    let id = node_id(NodeKind::Call, &t.call);
+                    let times = [t.duration, t.start, t.finish].map(numbers::number);
+                    (id, format!("{} ({}–{})", times[0], times[1], times[2]))
+                })
+                .collect(),
+            highlight: plan.critical_ids(),
+            ..Default::default()
+        };
+        print!("{}", mermaid::workflow_diagram(&graph, &info, &options));
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            print_json(&serde_json::to_value(&plan)?, &format)?;
+        }
+        _ => {
+            println!("{} {}", "Plan:".cyan().bold(), plan.workflow);
+            println!("{}", "─".repeat(50));
+            println!(
+                "{}: {}",
+                "Serial time".green().bold(),
+                numbers::number(plan.serial_time)
+            );
+            println!(
+                "{}: {}",
+                "Parallel time".green().bold(),
+                numbers::number(plan.parallel_time)
+            );
+            println!(
+                "{}: {}",
+                "Critical path".green().bold(),
+                plan.critical_path.join(" → ")
+            );
+            for timing in &plan.calls {
+                let marker = if timing.critical { "*" } else { " " };
+                println!(
+                    "  • {}{} {} (start {}, finish {})",
+                    timing.call.bold(),
+                    marker,
+                    numbers::number(timing.duration),
+                    numbers::number(timing.start),
+                    numbers::number(timing.finish)
+                );
+            }
+            if !plan.missing.is_empty() {
+                println!(
+                    "{}: {}",
+                    "No duration".yellow().bold(),
+                    plan.missing.join(", ")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn scatters_command(
+    file: PathBuf,
+    inputs_path: Option<PathBuf>,
+    workflow_name: Option<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+
+    let inputs_json: Option<serde_json::Value> = match &inputs_path {
+        Some(path) => {
+            let inputs_content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read file: {}", path.display()))?;
+            Some(
+                serde_json::from_str(&inputs_content)
+                    .with_context(|| format!("Invalid JSON in {}", path.display()))?,
+            )
+        }
+        None => None,
+    };
+    let report = scatter::report(workflow, inputs_json.as_ref());
+
+    let count = |n: Option<usize>| n.map_or_else(|| "unknown".to_string(), |n| n.to_string());
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            print_json(&serde_json::to_value(&report)?, &format)?;
+        }
+        _ => {
+            println!("{} {}", "Scatters:".cyan().bold(), report.workflow);
+            println!("{}", "─".repeat(50));
+            if report.scatters.is_empty() {
+                println!("No scatters");
+            }
+            for scatter in &report.scatters {
+                println!(
+                    "  • {} in {}: length({})={} ({})",
+                    scatter.variable.bold(),
+                    scatter.expression,
+                    scatter.expression,
+                    count(scatter.width),
+                    scatter.calls.join(", ")
+                );
+            }
+            println!();
+            println!("{}", "Task instances:".green().bold());
+            for call in &report.calls {
+                println!("  • {}: {}", call.call, count(call.instances));
+            }
+            println!(
+                "{}: {}",
+                "Total".green().bold(),
+                count(report.total_instances)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn compat_command(
+    caller_file: PathBuf,
+    callee_file: PathBuf,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let caller_content = read_wdl_file(&caller_file)?;
+    let (caller_tree, _) = SyntaxTree::parse(&caller_content);
+    let caller = extract_semantic_info(&caller_tree.root(), sort_by);
+
+    let callee_content = read_wdl_file(&callee_file)?;
+    let (callee_tree, _) = SyntaxTree::parse(&callee_content);
+    let callee_info = extract_semantic_info(&callee_tree.root(), sort_by);
+    let callee = select_workflow(&callee_info, None, &callee_file)?;
+
+    let calls = compat::matching_calls(&caller, callee).len();
+    if calls == 0 {
+        anyhow::bail!(
+            "No calls to workflow '{}' found in {}",
+            callee.name,
+            caller_file.display()
+        );
+    }
+    let issues = compat::check(&caller, callee);
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let json_output = serde_json::json!({
+                "caller": caller_file.display().to_string(),
+                "callee": callee_file.display().to_string(),
+                "workflow": callee.name,
+                "calls": calls,
+                "compatible": issues.is_empty(),
+                "issues": issues
+            });
+            print_json(&json_output, &format)?;
+        }
+        _ => {
+            println!(
+                "{} {} {} {} ({} call(s) to {})",
+                "Compat:".cyan().bold(),
+                caller_file.display(),
+                "→".dimmed(),
+                callee_file.display(),
+                calls,
+                callee.name
+            );
+            println!("{}", "─".repeat(50));
+            if issues.is_empty() {
+                println!("{}", "Compatible".green());
+            }
+            for issue in &issues {
+                println!(
+                    "  • {}.{} [{}]: {}",
+                    issue.workflow,
+                    issue.call.bold(),
+                    issue.kind.label().yellow(),
+                    issue.message
+                );
+            }
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(exit::failure(
+            ExitStatus::CheckFailed,
+            format!("{} incompatible binding(s)", issues.len()),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn stats_command(
+    file: PathBuf,
+    graph: bool,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let mut stats = stats::file_stats(&file.display().to_string(), &content, &info);
+    if graph {
+        stats.graphs = info
+            .workflows
+            .iter()
+            .map(|workflow| WorkflowGraph::build(workflow).stats())
+            .collect();
+    }
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            print_json(&serde_json::to_value(&stats)?, &format)?;
+        }
+        OutputFormat::Markdown => print!("{}", markdown::stats_markdown(&stats)),
+        _ => {
+            println!("{} {}", "Statistics:".cyan().bold(), file.display());
+            println!("{}", "─".repeat(50));
+            println!("{}: {}", "Lines".green().bold(), stats.lines);
+            println!("{}: {}", "Tasks".green().bold(), stats.tasks);
+            println!("{}: {}", "Workflows".green().bold(), stats.workflows);
+            println!("{}: {}", "Structs".green().bold(), stats.structs);
+            println!("{}: {}", "Imports".green().bold(), stats.imports);
+            println!("{}: {}", "Calls".green().bold(), stats.calls);
+            let sizes: Vec<u64> = stats
+                .task_stats
+                .iter()
+                .filter_map(|task| task.memory_bytes)
+                .collect();
+            if !sizes.is_empty() {
+                println!(
+                    "{}: {} across {} task(s)",
+                    "Memory".green().bold(),
+                    numbers::size(sizes.iter().sum()),
+                    sizes.len()
+                );
+            }
+
+            for task in &stats.task_stats {
+                println!(
+                    "  • {} ({} inputs, {} outputs, {} command lines)",
+                    task.name.bold(),
+                    task.inputs,
+                    task.outputs,
+                    task.command_lines
+                );
+                let resources: Vec<String> = [
+                    ("cpu", &task.cpu),
+                    ("memory", &task.memory),
+                    ("container", &task.container),
+                ]
+                .iter()
+                .filter_map(|(key, value)| value.as_ref().map(|v| format!("{}: {}", key, v)))
+                .collect();
+                if !resources.is_empty() {
+                    println!("      {}", resources.join(", ").dimmed());
+                }
+            }
+
+            for graph in &stats.graphs {
+                println!();
+                println!("{} {}", "Graph:".cyan().bold(), graph.workflow);
+                println!("{}", "─".repeat(50));
+                println!(
+                    "{}: {} nodes, {} edges",
+                    "Size".green().bold(),
+                    graph.nodes,
+                    graph.edges
+                );
+                println!("{}: {}", "Longest path".green().bold(), graph.longest_path);
+                println!(
+                    "{}: {} (max {})",
+                    "Stage widths".green().bold(),
+                    graph
+                        .stage_widths
+                        .iter()
+                        .map(usize::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    graph.max_width
+                );
+                for degree in &graph.degrees {
+                    println!(
+                        "  • {} (fan-in {}, fan-out {})",
+                        degree.id, degree.fan_in, degree.fan_out
+                    );
+                }
+                if !graph.isolated.is_empty() {
+                    println!(
+                        "{}: {}",
+                        "Isolated".yellow().bold(),
+                        graph.isolated.join(", ")
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn command_audit_command(
+    paths: Vec<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
+
+    let progress = progress::file_progress(files.len());
+    let mut reports = Vec::new();
+    for file in &files {
+        progress.set_message(file.display().to_string());
+        let content = read_wdl_file(file)?;
+        let (tree, _) = SyntaxTree::parse(&content);
+        let info = extract_semantic_info(&tree.root(), sort_by);
+        let report = serde_json::json!({
+            "file": file.display().to_string(),
+            "tasks": info.tasks.iter().map(audit::audit_task).collect::<Vec<_>>()
+        });
+
+        if matches!(format, OutputFormat::Jsonl) {
+            progress.suspend(|| print_json(&report, &format))?;
+        } else if matches!(format, OutputFormat::Json) {
+            reports.push(report);
+        } else {
+            progress.suspend(|| print_command_audit(file, &info.tasks));
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    if matches!(format, OutputFormat::Json) {
+        print_json(&serde_json::json!({ "files": reports }), &format)?;
+    }
+    Ok(())
+}
+
+fn print_command_audit(file: &Path, tasks: &[TaskInfo]) {
+    println!("{} {}", "Command audit:".cyan().bold(), file.display());
+    println!("{}", "─".repeat(50));
+    for task in tasks {
+        let audit = audit::audit_task(task);
+        if audit.is_empty() {
+            println!(
+                "  • {} {}",
+                task.name.bold(),
+                "(nothing to report)".dimmed()
+            );
+            continue;
+        }
+        println!("  • {}", task.name.bold());
+        if !audit.env_vars.is_empty() {
+            println!("      environment: {}", audit.env_vars.join(", "));
+        }
+        if !audit.absolute_paths.is_empty() {
+            println!("      absolute paths: {}", audit.absolute_paths.join(", "));
+        }
+        for hint in &audit.network {
+            println!(
+                "      {} {} (command line {})",
+                "network:".yellow(),
+                hint.tool,
+                hint.line
+            );
+        }
+        if !audit.writes_outside.is_empty() {
+            println!(
+                "      {} {}",
+                "writes outside working directory:".red(),
+                audit.writes_outside.join(", ")
+            );
+        }
+    }
+    println!();
+}
+
+pub fn compare_command(sources: Vec<String>, format: OutputFormat) -> Result<()> {
+    let mut digests = Vec::new();
+    for source in &sources {
+        let files = files::expand_inputs(std::slice::from_ref(source))?;
+        if files.is_empty() {
+            anyhow::bail!("No .wdl files found in {}", source);
+        }
+        let mut tasks = Vec::new();
+        for file in &files {
+            let content = read_wdl_file(file)?;
+            let (tree, _) = SyntaxTree::parse(&content);
+            tasks.extend(compare::task_digests(
+                &file.display().to_string(),
+                &tree.root(),
+            ));
+        }
+        digests.push((source.clone(), files.len(), tasks));
+    }
+    let comparison = compare::compare(&digests);
+
+    match format {
+        OutputFormat::Json => print_json(&serde_json::to_value(&comparison)?, &format)?,
+        OutputFormat::Jsonl => {
+            for row in &comparison.tasks {
+                print_json(&serde_json::to_value(row)?, &format)?;
+            }
+        }
+        _ => {
+            println!(
+                "{} {} task name(s) across {} source(s)",
+                "Compare:".cyan().bold(),
+                comparison.tasks.len(),
+                sources.len()
+            );
+            println!("{}", "─".repeat(50));
+            let name_width = comparison
+                .tasks
+                .iter()
+                .map(|row| row.name.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max("task".len());
+            let widths: Vec<usize> = sources.iter().map(|s| s.chars().count().max(1)).collect();
+            let mut header = format!("{:<name_width$}", "task");
+            for (source, &width) in sources.iter().zip(&widths) {
+                header.push_str(&format!("  {:<width$}", source));
+            }
+            println!("{}", header.bold());
+            for row in &comparison.tasks {
+                let mut line = format!("{:<name_width$}", row.name);
+                for (variant, &width) in row.variants.iter().zip(&widths) {
+                    line.push_str(&format!("  {:<width$}", variant.as_deref().unwrap_or("-")));
+                }
+                let status = match row.status {
+                    compare::TaskStatus::Identical => "identical".green(),
+                    compare::TaskStatus::Diverged => "diverged".yellow(),
+                    compare::TaskStatus::Unique => "unique".dimmed(),
+                };
+                println!("{}  {}", line, status);
+            }
+
+            if !comparison.renamed.is_empty() {
+                println!();
+                println!("{}:", "Same content, different names".green().bold());
+                for group in &comparison.renamed {
+                    let tasks: Vec<String> = group
+                        .tasks
+                        .iter()
+                        .map(|task| format!("{} ({})", task.name, task.file))
+                        .collect();
+                    println!("  • {}", tasks.join(" = "));
+                }
+            }
+
+            println!();
+            for summary in &comparison.sources {
+                println!(
+                    "{}: {} task(s) in {} file(s), {} identical, {} diverged, {} unique",
+                    summary.source.green().bold(),
+                    summary.tasks,
+                    summary.files,
+                    summary.identical,
+                    summary.diverged,
+                    summary.unique
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn diff_command(
+    old: PathBuf,
+    new: PathBuf,
+    task: Option<String>,
+    context: usize,
+    width: Option<usize>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let mut infos = Vec::new();
+    for path in [&old, &new] {
+        let content = read_wdl_file(path)?;
+        let (tree, _) = SyntaxTree::parse(&content);
+        infos.push(extract_semantic_info(&tree.root(), sort_by));
+    }
+    let mut diffs = diff::task_diffs(&infos[0], &infos[1]);
+    if let Some(task) = &task {
+        let names = infos
+            .iter()
+            .flat_map(|info| info.tasks.iter().map(|t| t.name.as_str()));
+        if !names.clone().any(|name| name == task) {
+            let suggestions = suggest::similar_names(task, names);
+            anyhow::bail!(
+                "Task '{}' not found in either file{}",
+                task,
+                suggest::did_you_mean(&suggestions)
+            );
+        }
+        diffs.retain(|task_diff| &task_diff.name == task);
+    }
+
+    match format {
+        OutputFormat::Json => print_json(
+            &serde_json::json!({
+                "old": old.display().to_string(),
+                "new": new.display().to_string(),
+                "tasks": diffs,
+            }),
+            &format,
+        )?,
+        OutputFormat::Jsonl => {
+            for task_diff in &diffs {
+                print_json(&serde_json::to_value(task_diff)?, &format)?;
+            }
+        }
+        _ => {
+            println!(
+                "{} {} → {}",
+                "Diff:".cyan().bold(),
+                old.display(),
+                new.display()
+            );
+            println!("{}", "─".repeat(50));
+            if diffs.is_empty() {
+                println!("No changes to task commands or runtime sections");
+                return Ok(());
+            }
+
+            let total = width
+                .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+                .unwrap_or(120);
+            // Line number, space, text on each side and " │ " between them
+            let side = total.saturating_sub(13).max(40) / 2;
+            for task_diff in &diffs {
+                let status = match task_diff.status {
+                    diff::RowKind::Added => "added".green(),
+                    diff::RowKind::Removed => "removed".red(),
+                    _ => "changed".yellow(),
+                };
+                println!();
+                println!("{} ({})", task_diff.name.green().bold(), status);
+                for (section, rows) in [
+                    ("command", &task_diff.command),
+                    ("runtime", &task_diff.runtime),
+                ] {
+                    if rows.is_empty() {
+                        continue;
+                    }
+                    println!("  {}", section.bold());
+                    let mut previous = None;
+                    for index in diff::visible_rows(rows, context) {
+                        if previous.is_some_and(|previous| previous + 1 != index) {
+                            println!("{}", format!("{:>4} {}", "⋯", " ".repeat(side)).dimmed());
+                        }
+                        previous = Some(index);
+                        print_diff_row(&rows[index], side);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// One aligned row of `diff`: the old line on the left, the new one on the
+/// right, removed text in red and added text in green
+fn print_diff_row(row: &diff::Row, side: usize) {
+    let changed = row.kind != diff::RowKind::Same;
+    let cell = |line: &Option<diff::Line>, color: Color| {
+        let number = line
+            .as_ref()
+            .map_or(String::new(), |line| line.number.to_string());
+        let text = diff::fit(line.as_ref().map_or("", |line| line.text.as_str()), side);
+        let text = if changed {
+            text.color(color)
+        } else {
+            text.normal()
+        };
+        format!("{} {}", format!("{:>4}", number).dimmed(), text)
+    };
+    println!(
+        "{}{}{}",
+        cell(&row.old, Color::Red),
+        " │ ".dimmed(),
+        cell(&row.new, Color::Green)
+    );
+}
+
+pub fn catalog_command(
+    paths: Vec<String>,
+    owners: bool,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
+
+    let progress = progress::file_progress(files.len());
+    let mut entries = Vec::new();
+    for file in &files {
+        progress.set_message(file.display().to_string());
+        let content = read_wdl_file(file)?;
+        let (tree, diagnostics) = SyntaxTree::parse(&content);
+        let info = extract_partial_info(&tree.root(), &diagnostics, sort_by);
+        let file_entries = catalog::entries(&file.display().to_string(), &info);
+
+        if !owners && matches!(format, OutputFormat::Jsonl) {
+            for entry in &file_entries {
+                progress.suspend(|| print_json(&serde_json::to_value(entry)?, &format))?;
+            }
+        }
+        entries.extend(file_entries);
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    if owners {
+        let summaries = catalog::by_owner(&entries);
+        match format {
+            OutputFormat::Json => {
+                let json_output = serde_json::json!({
+                    "files": files.len(),
+                    "owners": summaries
+                });
+                print_json(&json_output, &format)?;
+            }
+            OutputFormat::Jsonl => {
+                for summary in &summaries {
+                    print_json(&serde_json::to_value(summary)?, &format)?;
+                }
+            }
+            _ => {
+                println!(
+                    "{} {} owner(s) across {} file(s)",
+                    "Owners:".cyan().bold(),
+                    summaries.len(),
+                    files.len()
+                );
+                println!("{}", "─".repeat(50));
+                for summary in &summaries {
+                    let emails = if summary.emails.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" <{}>", summary.emails.join(", "))
+                    };
+                    println!(
+                        "{}{} ({} workflow(s), {} task(s))",
+                        summary.owner.green().bold(),
+                        emails,
+                        summary.workflows,
+                        summary.tasks
+                    );
+                    for entry in &summary.entries {
+                        println!(
+                            "  • {} {} {}",
+                            entry.kind.dimmed(),
+                            entry.name,
+                            format!("({})", entry.file).dimmed()
+                        );
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let json_output = serde_json::json!({
+                "files": files.len(),
+                "entries": entries
+            });
+            print_json(&json_output, &format)?;
+        }
+        OutputFormat::Jsonl => {}
+        _ => {
+            println!(
+                "{} {} entries across {} file(s)",
+                "Catalog:".cyan().bold(),
+                entries.len(),
+                files.len()
+            );
+            println!("{}", "─".repeat(50));
+            for entry in &entries {
+                let author = entry
+                    .authorship
+                    .author
+                    .as_deref()
+                    .unwrap_or(catalog::UNOWNED);
+                println!(
+                    "  • {} {}{} {} - {}",
+                    entry.kind.dimmed(),
+                    entry.name.bold(),
+                    incomplete_marker(Some(entry.complete)),
+                    format!("({})", entry.file).dimmed(),
+                    author
+                );
+                if let Some(description) = &entry.authorship.description {
+                    println!("      {}", description);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn docs_command(
+    paths: Vec<String>,
+    output: PathBuf,
+    site: Option<SiteFormat>,
+    index: Option<IndexFormat>,
+    check: bool,
+    sort_by: SortBy,
+) -> Result<()> {
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
+
+    let mut infos = Vec::new();
+    for file in &files {
+        let content = read_wdl_file(file)?;
+        let (tree, _) = SyntaxTree::parse(&content);
+        infos.push((
+            file.display().to_string(),
+            extract_semantic_info(&tree.root(), sort_by),
+        ));
+    }
+    let documents: Vec<(String, &WdlInfo)> = infos
+        .iter()
+        .map(|(file, info)| (file.clone(), info))
+        .collect();
+
+    let mut pages = docs::pages(&documents, site);
+    let page_count = pages.len();
+    let entries = index.map(|format| {
+        let entries = docs::search_entries(&documents, site);
+        let index = docs::search_index(&entries, format);
+        (entries.len(), index)
+    });
+    if let Some((_, index)) = &entries {
+        pages.push(docs::Page {
+            path: docs::index_path(site),
+            content: serde_json::to_string_pretty(index)? + "\n",
+        });
+    }
+
+    if check {
+        return check_docs(&pages, &output, site);
+    }
+
+    for page in &pages {
+        let path = output.join(&page.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        fs::write(&path, &page.content)
+            .with_context(|| format!("Failed to write file: {}", path.display()))?;
+    }
+    println!(
+        "{} {} page(s) in {}",
+        "Wrote:".green().bold(),
+        page_count,
+        output.display()
+    );
+    if let Some((count, _)) = entries {
+        println!(
+            "{} {} ({} entries)",
+            "Index:".green().bold(),
+            output.join(docs::index_path(site)).display(),
+            count
+        );
+    }
+    Ok(())
+}
+
+/// Compare freshly generated docs with the files in `output`: generated
+/// files that are missing or differ, and, for sites, pages of workflows and
+/// tasks that no longer exist
+fn check_docs(pages: &[docs::Page], output: &Path, site: Option<SiteFormat>) -> Result<()> {
+    let mut drift = Vec::new();
+    for page in pages {
+        let path = output.join(&page.path);
+        let Ok(current) = fs::read_to_string(&path) else {
+            drift.push((page.path.clone(), "missing".to_string()));
+            continue;
+        };
+        if current != page.content {
+            let lines = |text: &str| text.lines().map(str::to_string).collect::<Vec<_>>();
+            let rows = diff::side_by_side(&lines(&current), &lines(&page.content));
+            let changed = rows
+                .iter()
+                .filter(|row| row.kind != diff::RowKind::Same)
+                .count();
+            drift.push((
+                page.path.clone(),
+                format!("out of date ({} line(s) differ)", changed),
+            ));
+        }
+    }
+    for dir in docs::page_dirs(site) {
+        let Ok(entries) = fs::read_dir(output.join(&dir)) else {
+            continue;
+        };
+        let mut stale: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| dir.join(entry.file_name()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .filter(|path| !pages.iter().any(|page| &page.path == path))
+            .collect();
+        stale.sort();
+        drift.extend(
+            stale
+                .into_iter()
+                .map(|path| (path, "stale: documents nothing that exists".to_string())),
+        );
+    }
+
+    println!(
+        "{} {} file(s) in {}",
+        "Docs:".cyan().bold(),
+        pages.len(),
+        output.display()
+    );
+    println!("{}", "─".repeat(50));
+    for (path, message) in &drift {
+        println!("  • {}: {}", path.display().to_string().bold(), message);
+    }
+    println!("{}: {} out of date", "Summary".green().bold(), drift.len());
+
+    if !drift.is_empty() {
+        return Err(exit::failure(
+            ExitStatus::CheckFailed,
+            format!(
+                "{} docs file(s) out of date; run `wdlparse docs` without --check to update",
+                drift.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+pub fn badge_command(file: PathBuf, workflow_name: Option<String>, sort_by: SortBy) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, diagnostics) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflow = match workflow_name.as_deref() {
+        Some(name) => Some(select_workflow(&info, Some(name), &file)?),
+        None => info.workflows.first(),
+    };
+    let errors = diagnostics
+        .iter()
+        .filter(|d| matches!(d.severity(), wdl_grammar::Severity::Error))
+        .count();
+    print!("{}", badge::readme_snippet(&info, workflow, errors));
+    Ok(())
+}
+
+pub fn extract_scripts_command(
+    file: PathBuf,
+    dest: PathBuf,
+    reinject: bool,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    if reinject {
+        return reinject_scripts(&file, &dest, format);
+    }
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let name = file.display().to_string();
+    let extracted: Vec<scripts::Script> = info
+        .tasks
+        .iter()
+        .filter_map(|task| scripts::extract(task, &name))
+        .collect();
+
+    fs::create_dir_all(&dest)
+        .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
+    for script in &extracted {
+        let path = dest.join(&script.name);
+        fs::write(&path, &script.content)
+            .with_context(|| format!("Failed to write file: {}", path.display()))?;
+    }
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let entries: Vec<_> = extracted
+                .iter()
+                .map(|script| {
+                    let variables: serde_json::Map<String, serde_json::Value> = script
+                        .variables
+                        .iter()
+                        .map(|(name, written)| (name.clone(), written.clone().into()))
+                        .collect();
+                    serde_json::json!({
+                        "task": script.task,
+                        "path": dest.join(&script.name).display().to_string(),
+                        "variables": variables,
+                    })
+                })
+                .collect();
+            print_json(
+                &serde_json::json!({ "file": name, "scripts": entries }),
+                &format,
+            )?;
+        }
+        _ => {
+            println!("{} {}", "Scripts:".cyan().bold(), file.display());
+            println!("{}", "─".repeat(50));
+            for script in &extracted {
+                println!(
+                    "  • {}: {}",
+                    script.task.bold(),
+                    dest.join(&script.name).display()
+                );
+                for (variable, written) in &script.variables {
+                    println!("      {} = {}", variable.cyan(), written);
+                }
+            }
+            println!(
+                "{} {} script(s) in {}",
+                "Wrote:".green().bold(),
+                extracted.len(),
+                dest.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Put the scripts in `dest` back into the commands of the tasks they were
+/// extracted from, leaving tasks without a script alone
+fn reinject_scripts(file: &Path, dest: &Path, format: OutputFormat) -> Result<()> {
+    let source = read_wdl_source(file)?;
+    let (tree, diagnostics) = SyntaxTree::parse(&source.text);
+    let lines = LineIndex::new(&source.text);
+    let mut edits = Vec::new();
+    let mut results = Vec::new();
+    for node in tree
+        .root()
+        .descendants()
+        .filter(|node| node.kind() == SyntaxKind::CommandSectionNode)
+    {
+        let Some(task) = node.parent().and_then(|task| strip::definition_name(&task)) else {
+            continue;
+        };
+        let script = scripts::EXTENSIONS
+            .iter()
+            .map(|extension| dest.join(format!("{}.{}", task, extension)))
+            .find(|path| path.is_file());
+        let Some(script) = script else {
+            results.push((task, None, "no script"));
+            continue;
+        };
+        let text = fs::read_to_string(&script)
+            .with_context(|| format!("Failed to read file: {}", script.display()))?;
+        let section = CommandSection::from_node(&node, &lines);
+        let raw = scripts::reinject(&text, &section)
+            .with_context(|| format!("Failed to reinject {}", script.display()))?;
+        let status = match raw {
+            Some(raw) => {
+                edits.push(SourceEdit::replace(CommandSection::raw_range(&node), raw));
+                "updated"
+            }
+            None => "unchanged",
+        };
+        results.push((task, Some(script), status));
+    }
+
+    if !edits.is_empty() {
+        let updated = edit::apply(&source.text, &edits)?;
+        let errors = |diagnostics: &[wdl_grammar::Diagnostic]| {
+            diagnostics
+                .iter()
+                .filter(|d| matches!(d.severity(), wdl_grammar::Severity::Error))
+                .count()
+        };
+        if errors(&SyntaxTree::parse(&updated).1) > errors(&diagnostics) {
+            anyhow::bail!(
+                "The scripts in {} would leave {} with syntax errors; nothing was written",
+                dest.display(),
+                file.display()
+            );
+        }
+        fs::write(file, source.flavor.encode(&updated))
+            .with_context(|| format!("Failed to write file: {}", file.display()))?;
+    }
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let entries: Vec<_> = results
+                .iter()
+                .map(|(task, script, status)| {
+                    serde_json::json!({
+                        "task": task,
+                        "script": script.as_ref().map(|path| path.display().to_string()),
+                        "status": status,
+                    })
+                })
+                .collect();
+            print_json(
+                &serde_json::json!({
+                    "file": file.display().to_string(),
+                    "updated": edits.len(),
+                    "tasks": entries,
+                }),
+                &format,
+            )?;
+        }
+        _ => {
+            println!("{} {}", "Reinject:".cyan().bold(), file.display());
+            println!("{}", "─".repeat(50));
+            for (task, _, status) in &results {
+                println!("  • {}: {}", task.bold(), status);
+            }
+            println!("{}: {} updated", "Summary".green().bold(), edits.len());
+        }
+    }
+    Ok(())
+}
+
+pub fn localization_command(file: PathBuf, format: OutputFormat, sort_by: SortBy) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let report = localization::localization(&info);
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let value = serde_json::json!({
+                "file": file.display().to_string(),
+                "definitions": report,
+            });
+            print_json(&value, &format)?
+        }
+        _ => {
+            println!("{} {}", "Localization:".cyan().bold(), file.display());
+            println!("{}", "─".repeat(50));
+            for entry in &report {
+                let kind = match entry.kind {
+                    SymbolKind::Task => "task",
+                    SymbolKind::Workflow => "workflow",
+                    SymbolKind::Struct => "struct",
+                };
+                println!(
+                    "{} {}: {} File, {} Directory, {} required",
+                    kind,
+                    entry.name.green().bold(),
+                    entry.files,
+                    entry.directories,
+                    entry.required
+                );
+                for input in &entry.inputs {
+                    let mut notes = vec![if input.optional {
+                        "optional"
+                    } else {
+                        "required"
+                    }];
+                    if input.collection {
+                        notes.push("count known at runtime");
+                    }
+                    let mut line = format!(
+                        "  • {} ({}): {}",
+                        input.name,
+                        input.wdl_type,
+                        notes.join(", ")
+                    );
+                    if !input.through.is_empty() {
+                        line.push_str(&format!(" via struct {}", input.through.join(", ")));
+                    }
+                    println!("{}", line);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn input_usage_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    mermaid_output: bool,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+    let report = fanout::fan_out(workflow);
+
+    if mermaid_output {
+        print!("{}", mermaid::fanout_diagram(&report));
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let mut value = serde_json::to_value(&report)?;
+            value["file"] = file.display().to_string().into();
+            print_json(&value, &format)?
+        }
+        _ => {
+            println!(
+                "{} workflow {}",
+                "Input usage:".cyan().bold(),
+                report.workflow
+            );
+            println!("{}", "─".repeat(50));
+            for input in &report.inputs {
+                let summary = match input.uses.len() {
+                    0 => "unused".yellow(),
+                    _ => format!("{} call(s), {} use(s)", input.calls, input.uses.len()).normal(),
+                };
+                println!(
+                    "{} ({}): {}",
+                    input.name.green().bold(),
+                    input.wdl_type,
+                    summary
+                );
+                for found in &input.uses {
+                    let kind = match found.kind {
+                        fanout::ConsumerKind::Call => "call",
+                        fanout::ConsumerKind::Scatter => "scatter",
+                        fanout::ConsumerKind::Conditional => "if",
+                        fanout::ConsumerKind::Output => "output",
+                    };
+                    let mut line = format!("  • {} {}", kind, found.name);
+                    if let Some(parameter) = &found.parameter {
+                        line.push_str(&format!(" (input {})", parameter));
+                    }
+                    if !found.via.is_empty() {
+                        line.push_str(&format!(" via {}", found.via.join(" → ")));
+                    }
+                    println!("{}", line);
+                }
+            }
+            if !report.unused.is_empty() {
+                println!();
+                println!(
+                    "{}: {}",
+                    "Unused inputs".yellow().bold(),
+                    report.unused.join(", ")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn inputs_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    include_optional: bool,
+    synthesize: bool,
+    sort_by: SortBy,
 ) -> Result<()> {
     let content = read_wdl_file(&file)?;
-    let (tree, diagnostics) = SyntaxTree::parse(&content);
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
 
-    // Extract basic metadata if requested
-    let basic_metadata = if extract_metadata {
-        Some(BasicWdlMetadata::extract_from_text(&content))
+    let signature = InputSignature::select(&info, workflow_name.as_deref())?;
+    let template = if synthesize {
+        inputs::synthesize(&signature, &info.structs, include_optional)
     } else {
-        None
+        inputs::template(&signature, include_optional)
     };
+    println!("{}", serde_json::to_string_pretty(&template)?);
 
-    if verbose && !diagnostics.is_empty() {
-        println!("{}", "Diagnostics:".yellow().bold());
-        for diagnostic in &diagnostics {
-            println!(
-                "  {}: {}",
-                format!("{:?}", diagnostic.severity()).red(),
-                diagnostic.message()
-            );
-        }
-        println!();
+    Ok(())
+}
+
+pub fn scaffold_tests_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    style: TestStyle,
+    output: Option<PathBuf>,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflows: Vec<&WorkflowInfo> = match &workflow_name {
+        Some(name) => vec![select_workflow(&info, Some(name.as_str()), &file)?],
+        None => info.workflows.iter().collect(),
+    };
+    if workflows.is_empty() {
+        anyhow::bail!("No workflow found in {}", file.display());
     }
 
-    match format {
-        OutputFormat::Tree => {
-            println!("{}", "Syntax Tree:".green().bold());
-            println!("{:#?}", tree);
+    let module = scaffold::test_module(&file.display().to_string(), &workflows, style);
+    match output {
+        Some(path) => {
+            fs::write(&path, module)
+                .with_context(|| format!("Failed to write file: {}", path.display()))?;
+            println!("{} {}", "Wrote:".green().bold(), path.display());
         }
-        OutputFormat::Json => {
-            let semantic_info = extract_semantic_info(&tree.root());
-            let mut json_output = serde_json::json!({
-                "file": file.display().to_string(),
-                "diagnostics": diagnostics.len(),
-                "has_errors": diagnostics.iter().any(|d| matches!(d.severity(), wdl_grammar::Severity::Error)),
-                "wdl": semantic_info
-            });
-
-            if let Some(metadata) = &basic_metadata {
-                json_output["basic_metadata"] = serde_json::to_value(metadata)?;
-            }
+        None => print!("{}", module),
+    }
+    Ok(())
+}
 
-            println!("{}", serde_json::to_string_pretty(&json_output)?);
+pub fn new_command(
+    kind: NewKind,
+    name: String,
+    tasks: Vec<String>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let content = match kind {
+        NewKind::Workflow => template::workflow_file(&name, &tasks)?,
+        NewKind::Task if !tasks.is_empty() => {
+            anyhow::bail!("--tasks only applies to `new workflow`")
         }
-        OutputFormat::Human => {
-            println!("{} {}", "Parsed:".green().bold(), file.display());
-            println!("Root node: {}", format!("{:?}", tree.root().kind()).cyan());
-            if !diagnostics.is_empty() {
-                println!("Diagnostics: {}", diagnostics.len().to_string().yellow());
-            } else {
-                println!("{}", "No issues found".green());
+        NewKind::Task => template::task_file(&name)?,
+    };
+    match output {
+        Some(path) => {
+            if path.exists() {
+                anyhow::bail!("{} already exists", path.display());
             }
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write file: {}", path.display()))?;
+            println!("{} {}", "Wrote:".green().bold(), path.display());
         }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+pub fn schema_command(
+    file: Option<PathBuf>,
+    workflow_name: Option<String>,
+    style: SchemaStyle,
+    self_schema: Option<SelfSchema>,
+    sort_by: SortBy,
+) -> Result<()> {
+    if let Some(report) = self_schema {
+        println!("{}", serde_json::to_string_pretty(&report.schema())?);
+        return Ok(());
     }
+    let file = file.context("A WDL file is required unless --self is given")?;
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
 
+    let schema = schema::parameter_schema(&info, workflow, style);
+    println!("{}", serde_json::to_string_pretty(&schema)?);
     Ok(())
 }
 
-pub fn info_command(file: PathBuf, format: OutputFormat, extract_metadata: bool) -> Result<()> {
+pub fn options_template_command(file: PathBuf, sort_by: SortBy) -> Result<()> {
     let content = read_wdl_file(&file)?;
-    let (tree, diagnostics) = SyntaxTree::parse(&content);
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
 
-    let mut info = WdlInfo::new();
-    collect_semantic_info(&tree.root(), &mut info);
+    let options = cromwell::options_template(&info);
+    println!("{}", serde_json::to_string_pretty(&options)?);
+    Ok(())
+}
 
-    // Extract basic metadata if requested
-    let basic_metadata = if extract_metadata {
-        Some(BasicWdlMetadata::extract_from_text(&content))
-    } else {
-        None
-    };
+pub fn terra_config_command(
+    file: PathBuf,
+    workflow_name: Option<String>,
+    entity: String,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let workflow = select_workflow(&info, workflow_name.as_deref(), &file)?;
+
+    let config = terra::method_config(workflow, &entity);
+    println!("{}", serde_json::to_string_pretty(&config)?);
+    Ok(())
+}
+
+pub fn check_inputs_command(
+    file: PathBuf,
+    inputs_path: PathBuf,
+    workflow_name: Option<String>,
+    format: OutputFormat,
+    sort_by: SortBy,
+) -> Result<()> {
+    let content = read_wdl_file(&file)?;
+    let (tree, _) = SyntaxTree::parse(&content);
+    let info = extract_semantic_info(&tree.root(), sort_by);
+    let signature = InputSignature::select(&info, workflow_name.as_deref())?;
+
+    let inputs_content = fs::read_to_string(&inputs_path)
+        .with_context(|| format!("Failed to read file: {}", inputs_path.display()))?;
+    let inputs_json: serde_json::Value = serde_json::from_str(&inputs_content)
+        .with_context(|| format!("Invalid JSON in {}", inputs_path.display()))?;
+
+    let problems = inputs::check(&signature, &info.structs, &inputs_json);
 
     match format {
-        OutputFormat::Json => {
-            let mut json_output = serde_json::json!({
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            let json_output = serde_json::json!({
                 "file": file.display().to_string(),
-                "version": info.version,
-                "tasks": info.tasks,
-                "workflows": info.workflows,
-                "structs": info.structs,
-                "imports": info.imports
+                "inputs": inputs_path.display().to_string(),
+                "target": signature.name,
+                "valid": problems.is_empty(),
+                "problems": problems
             });
-
-            if let Some(metadata) = &basic_metadata {
-                json_output["basic_metadata"] = serde_json::to_value(metadata)?;
-            }
-
-            println!("{}", serde_json::to_string_pretty(&json_output)?);
+            print_json(&json_output, &format)?;
         }
         _ => {
-            println!("{} {}", "WDL File Info:".cyan().bold(), file.display());
+            println!(
+                "{} {} ({} {})",
+                "Inputs:".cyan().bold(),
+                inputs_path.display(),
+                "for".dimmed(),
+                signature.name
+            );
             println!("{}", "─".repeat(50));
-
-            if let Some(version) = &info.version {
-                println!("{}: {}", "Version".green().bold(), version);
+            if problems.is_empty() {
+                println!("{}", "All inputs are valid".green());
             }
-
-            println!("{}: {}", "Tasks".green().bold(), info.tasks.len());
-            for task in &info.tasks {
-                println!("  • {}", task.name);
+            for problem in &problems {
+                println!("  • {}: {}", problem.key.bold(), problem.message);
             }
+        }
+    }
 
-            println!("{}: {}", "Workflows".green().bold(), info.workflows.len());
-            for workflow in &info.workflows {
-                println!("  • {}", workflow.name);
-            }
+    if !problems.is_empty() {
+        return Err(exit::failure(
+            ExitStatus::CheckFailed,
+            format!("{} problem(s) in {}", problems.len(), inputs_path.display()),
+        ));
+    }
 
-            println!("{}: {}", "Structs".green().bold(), info.structs.len());
-            for struct_name in &info.structs {
-                println!("  • {}", struct_name.name);
-            }
+    Ok(())
+}
 
-            println!("{}: {}", "Imports".green().bold(), info.imports.len());
-            for import in &info.imports {
-                let display = if let Some(alias) = &import.alias {
-                    format!("{} as {}", import.uri, alias)
-                } else {
-                    import.uri.clone()
-                };
-                println!("  • {}", display);
-            }
+pub fn bench_command(paths: Vec<String>, iterations: usize, format: OutputFormat) -> Result<()> {
+    let files = files::expand_inputs(&paths)?;
+    if files.is_empty() {
+        anyhow::bail!("No .wdl files found in {}", paths.join(", "));
+    }
 
-            if !diagnostics.is_empty() {
-                println!();
-                println!("{}: {}", "Diagnostics".yellow().bold(), diagnostics.len());
-            }
+    if !matches!(format, OutputFormat::Json | OutputFormat::Jsonl) {
+        println!(
+            "{} {} file(s), {} iteration(s), mean ms",
+            "Benchmark:".cyan().bold(),
+            files.len(),
+            iterations.max(1)
+        );
+        println!("{}", "─".repeat(80));
+        println!(
+            "{:>10} {:>10} {:>10} {:>10} {:>10}  {}",
+            "read", "parse", "extract", "graph", "total", "file"
+        );
+    }
+
+    let mut results = Vec::new();
+    for file in &files {
+        let result = bench::bench_file(file, iterations)?;
+        match format {
+            OutputFormat::Json => results.push(result),
+            OutputFormat::Jsonl => print_json(&serde_json::to_value(&result)?, &format)?,
+            _ => println!(
+                "{:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3}  {}",
+                result.read.mean_ms,
+                result.parse.mean_ms,
+                result.extract.mean_ms,
+                result.graph.mean_ms,
+                result.total_mean_ms(),
+                result.file
+            ),
         }
     }
 
+    if let OutputFormat::Json = format {
+        let json_output = serde_json::json!({
+            "iterations": iterations.max(1),
+            "files": results
+        });
+        print_json(&json_output, &format)?;
+    }
+
+    Ok(())
+}
+
+/// Print a JSON report: pretty with a `generator` block for `json`, a single
+/// line without one for `jsonl`
+fn print_json(value: &serde_json::Value, format: &OutputFormat) -> Result<()> {
+    let mut value = value.clone();
+    if let serde_json::Value::Object(object) = &mut value {
+        object.insert(
+            "format_version".to_string(),
+            output_schema::FORMAT_VERSION.into(),
+        );
+    }
+    if !matches!(format, OutputFormat::Jsonl) {
+        provenance::stamp(&mut value, std::env::args().skip(1).collect());
+    }
+    if normalize::enabled() {
+        Normalizer::from_env().normalize(&mut value);
+    }
+    match format {
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(&value)?),
+        _ => println!("{}", serde_json::to_string_pretty(&value)?),
+    }
+    Ok(())
+}
+
+pub fn serve_command(
+    host: String,
+    port: u16,
+    config_path: Option<PathBuf>,
+    sort_by: SortBy,
+) -> Result<()> {
+    let config = Config::load(config_path.as_deref())?;
+    println!(
+        "{} http://{}:{} (POST /{})",
+        "Serving:".green().bold(),
+        host,
+        port,
+        service::METHODS.join(", POST /")
+    );
+    server::serve(&host, port, &config, sort_by)
+}
+
+pub fn rpc_command(config_path: Option<PathBuf>, sort_by: SortBy) -> Result<()> {
+    let config = Config::load(config_path.as_deref())?;
+    rpc::run(io::stdin().lock(), io::stdout().lock(), &config, sort_by)
+}
+
+pub fn completions_command(shell: Shell) -> Result<()> {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+    Ok(())
+}
+
+pub fn manpages_command(dir: PathBuf) -> Result<()> {
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let command = Cli::command();
+    let mut pages = vec![(command.get_name().to_string(), command.clone())];
+    for subcommand in command.get_subcommands() {
+        let name = format!("{}-{}", command.get_name(), subcommand.get_name());
+        pages.push((name, subcommand.clone()));
+    }
+
+    for (name, page) in pages {
+        let path = dir.join(format!("{}.1", name));
+        let mut buffer = Vec::new();
+        clap_mangen::Man::new(page)
+            .title(&name)
+            .render(&mut buffer)?;
+        fs::write(&path, buffer)
+            .with_context(|| format!("Failed to write man page: {}", path.display()))?;
+        println!("{} {}", "Wrote:".green().bold(), path.display());
+    }
+
     Ok(())
 }
 
 fn read_wdl_file(path: &Path) -> Result<String> {
+    Ok(read_wdl_source(path)?.text)
+}
+
+/// Load a file and its imports, searching `--import-dir` directories and then
+/// the configured search paths for imports not found next to the importer,
+/// after applying the configured import rewrites
+fn load_workspace(
+    file: &Path,
+    mut import_dirs: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+) -> Result<Workspace> {
+    if !file.exists() {
+        anyhow::bail!("File does not exist: {}", file.display());
+    }
+    let config = Config::load(config_path.as_deref())?;
+    import_dirs.extend(config.imports.search_paths);
+    for dir in import_dirs.iter().filter(|dir| !dir.is_dir()) {
+        tracing::warn!(dir = %dir.display(), "import directory does not exist");
+    }
+    let options = ImportOptions {
+        dirs: import_dirs,
+        rewrites: config.imports.rewrites,
+    };
+    Workspace::load_with_options(file, options)
+}
+
+/// Select the workflow named `name` in `file`, defaulting to the first
+/// workflow, and suggest close names when there is no such workflow
+fn select_workflow<'a>(
+    info: &'a WdlInfo,
+    name: Option<&str>,
+    file: &Path,
+) -> Result<&'a WorkflowInfo> {
+    let Some(name) = name else {
+        return info
+            .workflows
+            .first()
+            .with_context(|| format!("No workflow found in {}", file.display()));
+    };
+    match info.workflows.iter().find(|w| w.name == name) {
+        Some(workflow) => Ok(workflow),
+        None => {
+            let names = info.workflows.iter().map(|w| w.name.as_str());
+            anyhow::bail!(
+                "Workflow '{}' not found in {}{}",
+                name,
+                file.display(),
+                suggest::did_you_mean(&suggest::similar_names(name, names))
+            )
+        }
+    }
+}
+
+/// Read a WDL file with its byte order mark stripped and line endings
+/// normalized, keeping the original flavor for writing it back
+fn read_wdl_source(path: &Path) -> Result<SourceText> {
     if !path.exists() {
         anyhow::bail!("File does not exist: {}", path.display());
     }
 
     if let Some(extension) = path.extension() {
         if extension != "wdl" {
-            eprintln!(
-                "{} File does not have .wdl extension: {}",
-                "Warning:".yellow().bold(),
-                path.display()
-            );
+            tracing::warn!(file = %path.display(), "file does not have .wdl extension");
         }
     }
 
-    fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path.display()))
+    let bytes =
+        fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    provenance::record_input(path, &bytes);
+    let source = SourceText::decode(&bytes)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    tracing::debug!(
+        file = %path.display(),
+        bytes = source.text.len(),
+        bom = source.flavor.bom,
+        line_ending = ?source.flavor.line_ending,
+        "read file"
+    );
+    Ok(source)
 }
 
-pub fn extract_semantic_info(node: &wdl_grammar::SyntaxNode) -> WdlInfo {
+/// The tasks, workflows, structs and imports of a document, in `order`
+pub fn extract_semantic_info(node: &wdl_grammar::SyntaxNode, order: SortBy) -> WdlInfo {
+    let start = std::time::Instant::now();
     let mut info = WdlInfo::new();
-    collect_semantic_info(node, &mut info);
+    collect_semantic_info(node, &mut info, &LineIndex::new(&node.text().to_string()));
+    info.sort(order);
+    tracing::debug!(
+        tasks = info.tasks.len(),
+        workflows = info.workflows.len(),
+        elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+        "extracted semantic info"
+    );
+    info
+}
+
+/// Best-effort extraction for files with syntax errors, e.g. work in progress.
+/// Definitions that contain an error or lack their closing brace are kept and
+/// marked incomplete, and `task`, `workflow` and `struct` headers the parser
+/// lost while recovering from an error are added from the text as incomplete
+/// entries holding just the name and line.
+pub fn extract_partial_info(
+    node: &wdl_grammar::SyntaxNode,
+    diagnostics: &[wdl_grammar::Diagnostic],
+    order: SortBy,
+) -> WdlInfo {
+    let mut info = extract_semantic_info(node, order);
+    let content = node.text().to_string();
+    let lines = LineIndex::new(&content);
+    let errors: Vec<usize> = diagnostics
+        .iter()
+        .filter(|d| matches!(d.severity(), wdl_grammar::Severity::Error))
+        .filter_map(diagnostics::offset)
+        .collect();
+
+    let mut found = Vec::new();
+    for definition in node.descendants().filter(|n| {
+        matches!(
+            n.kind(),
+            SyntaxKind::TaskDefinitionNode
+                | SyntaxKind::WorkflowDefinitionNode
+                | SyntaxKind::StructDefinitionNode
+        )
+    }) {
+        let Some(name) = find_identifier_name(&definition) else {
+            continue;
+        };
+        let line = line_number(&definition, &lines);
+        let range = definition.text_range();
+        let range = usize::from(range.start())..usize::from(range.end());
+        let closed = definition
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .filter(|token| !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))
+            .last()
+            .is_some_and(|token| token.text() == "}");
+        let complete = closed && !errors.iter().any(|offset| range.contains(offset));
+        match definition.kind() {
+            SyntaxKind::TaskDefinitionNode => {
+                for task in info
+                    .tasks
+                    .iter_mut()
+                    .filter(|t| t.name == name && t.line == line)
+                {
+                    task.complete = Some(complete);
+                }
+                found.push(("task", name));
+            }
+            SyntaxKind::WorkflowDefinitionNode => {
+                for workflow in info
+                    .workflows
+                    .iter_mut()
+                    .filter(|w| w.name == name && w.line == line)
+                {
+                    workflow.complete = Some(complete);
+                }
+                found.push(("workflow", name));
+            }
+            _ => {
+                for definition in info
+                    .structs
+                    .iter_mut()
+                    .filter(|s| s.name == name && s.line == line)
+                {
+                    definition.complete = Some(complete);
+                }
+                found.push(("struct", name));
+            }
+        }
+    }
+
+    let header =
+        regex::Regex::new(r"(?m)^[ \t]*(task|workflow|struct)[ \t]+([A-Za-z_][A-Za-z0-9_]*)\s*\{")
+            .expect("valid regex");
+    for captures in header.captures_iter(&content) {
+        let keyword = captures.get(1).expect("keyword group");
+        let name = captures[2].to_string();
+        if found
+            .iter()
+            .any(|(kind, n)| *kind == keyword.as_str() && *n == name)
+        {
+            continue;
+        }
+        let line = lines.line(keyword.start());
+        match keyword.as_str() {
+            "task" => info.tasks.push(TaskInfo {
+                name: name.clone(),
+                line,
+                complete: Some(false),
+                ..TaskInfo::default()
+            }),
+            "workflow" => info.workflows.push(WorkflowInfo {
+                name: name.clone(),
+                line,
+                complete: Some(false),
+                ..WorkflowInfo::default()
+            }),
+            _ => info.structs.push(StructInfo {
+                name: name.clone(),
+                line,
+                complete: Some(false),
+                ..StructInfo::default()
+            }),
+        }
+        found.push((keyword.as_str(), name));
+    }
+    info.sort(order);
     info
 }
 
-fn collect_semantic_info(node: &wdl_grammar::SyntaxNode, info: &mut WdlInfo) {
+fn collect_semantic_info(node: &wdl_grammar::SyntaxNode, info: &mut WdlInfo, lines: &LineIndex) {
     match node.kind() {
         SyntaxKind::VersionStatementNode => {
             for child in node.children_with_tokens() {
@@ -183,17 +3600,17 @@ fn collect_semantic_info(node: &wdl_grammar::SyntaxNode, info: &mut WdlInfo) {
             }
         }
         SyntaxKind::TaskDefinitionNode => {
-            if let Some(task_info) = extract_task_info(&node) {
+            if let Some(task_info) = extract_task_info(&node, lines) {
                 info.tasks.push(task_info);
             }
         }
         SyntaxKind::WorkflowDefinitionNode => {
-            if let Some(workflow_info) = extract_workflow_info(&node) {
+            if let Some(workflow_info) = extract_workflow_info(&node, lines) {
                 info.workflows.push(workflow_info);
             }
         }
         SyntaxKind::StructDefinitionNode => {
-            if let Some(struct_info) = extract_struct_info(&node) {
+            if let Some(struct_info) = extract_struct_info(&node, lines) {
                 info.structs.push(struct_info);
             }
         }
@@ -207,7 +3624,7 @@ fn collect_semantic_info(node: &wdl_grammar::SyntaxNode, info: &mut WdlInfo) {
 
     // Recursively process child nodes
     for child in node.children() {
-        collect_semantic_info(&child, info);
+        collect_semantic_info(&child, info, lines);
     }
 }
 
@@ -222,16 +3639,81 @@ fn find_identifier_name(node: &wdl_grammar::SyntaxNode) -> Option<String> {
     None
 }
 
-fn extract_task_info(node: &wdl_grammar::SyntaxNode) -> Option<TaskInfo> {
+/// 1-based line of the first token of `node` that isn't whitespace or a comment
+fn line_number(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> usize {
+    start_position(node, lines).0
+}
+
+/// 1-based line and column of the first token of `node` that isn't
+/// whitespace or a comment
+fn start_position(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> (usize, usize) {
+    let start = node
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))
+        .map_or(node.text_range().start(), |token| {
+            token.text_range().start()
+        });
+    lines.position(usize::from(start))
+}
+
+/// The block of full-line `#` comments directly above `node`, without the
+/// `#` markers. A blank line ends the block, and comments that follow code on
+/// the same line or are wdlparse directives don't count.
+fn leading_doc(node: &wdl_grammar::SyntaxNode) -> Option<String> {
+    let first = node
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))?;
+
+    let mut lines = Vec::new();
+    let mut token = first.prev_token();
+    while let Some(current) = token {
+        match current.kind() {
+            SyntaxKind::Whitespace if current.text().matches('\n').count() > 1 => break,
+            SyntaxKind::Whitespace => {}
+            SyntaxKind::Comment => {
+                let own_line = current.prev_token().is_none_or(|prev| {
+                    prev.kind() == SyntaxKind::Whitespace && prev.text().contains('\n')
+                });
+                if !own_line {
+                    break;
+                }
+                let text = current.text().trim_start_matches('#');
+                let text = text.strip_prefix(' ').unwrap_or(text).trim_end();
+                if !text.starts_with(suppress::DISABLE) {
+                    lines.push(text.to_string());
+                }
+            }
+            _ => break,
+        }
+        token = current.prev_token();
+    }
+
+    lines.reverse();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn extract_task_info(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> Option<TaskInfo> {
     let name = find_identifier_name(&node)?;
     let mut task = TaskInfo {
         name,
+        line: line_number(node, lines),
+        complete: None,
+        doc: leading_doc(node),
         inputs: Vec::new(),
         outputs: Vec::new(),
+        declarations: Vec::new(),
         command: None,
+        command_section: None,
+        command_language: None,
         runtime: Vec::new(),
+        normalized_runtime: NormalizedRuntime::default(),
         meta: Vec::new(),
         parameter_meta: Vec::new(),
+        authorship: Authorship::default(),
+        source: None,
     };
 
     for child in node.children() {
@@ -240,13 +3722,21 @@ fn extract_task_info(node: &wdl_grammar::SyntaxNode) -> Option<TaskInfo> {
                 task.inputs.extend(extract_inputs(&child));
             }
             SyntaxKind::OutputSectionNode => {
-                task.outputs.extend(extract_outputs(&child));
+                task.outputs.extend(extract_outputs(&child, lines));
+            }
+            SyntaxKind::BoundDeclNode => {
+                if let Some(declaration) = extract_declaration(&child) {
+                    task.declarations.push(declaration);
+                }
             }
             SyntaxKind::CommandSectionNode => {
-                task.command = Some(extract_command_text(&child));
+                let section = CommandSection::from_node(&child, lines);
+                task.command = Some(section.raw.clone());
+                task.command_language = Some(section.language());
+                task.command_section = Some(section);
             }
             SyntaxKind::RuntimeSectionNode => {
-                task.runtime.extend(extract_runtime_items(&child));
+                task.runtime.extend(extract_runtime_items(&child, lines));
             }
             SyntaxKind::MetadataSectionNode => {
                 task.meta.extend(extract_meta_items(&child));
@@ -257,19 +3747,32 @@ fn extract_task_info(node: &wdl_grammar::SyntaxNode) -> Option<TaskInfo> {
             _ => {}
         }
     }
+    task.authorship = Authorship::from_meta(&task.meta);
+    task.normalized_runtime = NormalizedRuntime::from_items(&task.runtime);
 
     Some(task)
 }
 
-fn extract_workflow_info(node: &wdl_grammar::SyntaxNode) -> Option<WorkflowInfo> {
+fn extract_workflow_info(
+    node: &wdl_grammar::SyntaxNode,
+    lines: &LineIndex,
+) -> Option<WorkflowInfo> {
     let name = find_identifier_name(&node)?;
     let mut workflow = WorkflowInfo {
         name,
+        line: line_number(node, lines),
+        complete: None,
+        doc: leading_doc(node),
         inputs: Vec::new(),
         outputs: Vec::new(),
         calls: Vec::new(),
+        declarations: Vec::new(),
+        scatters: Vec::new(),
+        conditionals: Vec::new(),
         meta: Vec::new(),
         parameter_meta: Vec::new(),
+        authorship: Authorship::default(),
+        source: None,
     };
 
     for child in node.children() {
@@ -278,12 +3781,7 @@ fn extract_workflow_info(node: &wdl_grammar::SyntaxNode) -> Option<WorkflowInfo>
                 workflow.inputs.extend(extract_inputs(&child));
             }
             SyntaxKind::OutputSectionNode => {
-                workflow.outputs.extend(extract_outputs(&child));
-            }
-            SyntaxKind::CallStatementNode => {
-                if let Some(call) = extract_call_info(&child) {
-                    workflow.calls.push(call);
-                }
+                workflow.outputs.extend(extract_outputs(&child, lines));
             }
             SyntaxKind::MetadataSectionNode => {
                 workflow.meta.extend(extract_meta_items(&child));
@@ -291,17 +3789,97 @@ fn extract_workflow_info(node: &wdl_grammar::SyntaxNode) -> Option<WorkflowInfo>
             SyntaxKind::ParameterMetadataSectionNode => {
                 workflow.parameter_meta.extend(extract_meta_items(&child));
             }
-            _ => {}
+            _ => collect_workflow_statement(&child, lines, &mut workflow),
         }
     }
+    workflow.authorship = Authorship::from_meta(&workflow.meta);
 
     Some(workflow)
 }
 
-fn extract_struct_info(node: &wdl_grammar::SyntaxNode) -> Option<StructInfo> {
+/// Collect calls, declarations and scatters from a workflow body statement,
+/// descending into scatter and conditional blocks
+fn collect_workflow_statement(
+    node: &wdl_grammar::SyntaxNode,
+    lines: &LineIndex,
+    workflow: &mut WorkflowInfo,
+) {
+    match node.kind() {
+        SyntaxKind::CallStatementNode => {
+            if let Some(call) = extract_call_info(node, lines) {
+                workflow.calls.push(call);
+            }
+        }
+        SyntaxKind::BoundDeclNode => {
+            if let Some(declaration) = extract_declaration(node) {
+                workflow.declarations.push(declaration);
+            }
+        }
+        SyntaxKind::ScatterStatementNode => {
+            let mut children = node.children();
+            let scatter = match (find_identifier_name(node), children.next()) {
+                (Some(variable), Some(expression)) => {
+                    workflow.scatters.push(ScatterInfo {
+                        variable,
+                        expression: expression.text().to_string(),
+                        calls: Vec::new(),
+                    });
+                    Some(workflow.scatters.len() - 1)
+                }
+                _ => None,
+            };
+            let first_call = workflow.calls.len();
+            for child in children {
+                collect_workflow_statement(&child, lines, workflow);
+            }
+            if let Some(index) = scatter {
+                workflow.scatters[index].calls = call_names_since(workflow, first_call);
+            }
+        }
+        SyntaxKind::ConditionalStatementNode => {
+            // The first child node is the condition expression
+            let mut children = node.children();
+            let conditional = children.next().map(|expression| {
+                workflow.conditionals.push(ConditionalInfo {
+                    expression: expression.text().to_string(),
+                    line: line_number(node, lines),
+                    calls: Vec::new(),
+                    declarations: Vec::new(),
+                });
+                workflow.conditionals.len() - 1
+            });
+            let first_call = workflow.calls.len();
+            let first_declaration = workflow.declarations.len();
+            for child in children {
+                collect_workflow_statement(&child, lines, workflow);
+            }
+            if let Some(index) = conditional {
+                workflow.conditionals[index].calls = call_names_since(workflow, first_call);
+                workflow.conditionals[index].declarations = workflow.declarations
+                    [first_declaration..]
+                    .iter()
+                    .map(|declaration| declaration.name.clone())
+                    .collect();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Names of the calls collected after index `first`, i.e. those inside a block just walked
+fn call_names_since(workflow: &WorkflowInfo, first: usize) -> Vec<String> {
+    workflow.calls[first..]
+        .iter()
+        .map(|call| call.name.clone())
+        .collect()
+}
+
+fn extract_struct_info(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> Option<StructInfo> {
     let name = find_identifier_name(&node)?;
     let mut struct_info = StructInfo {
         name,
+        line: line_number(node, lines),
+        complete: None,
         fields: Vec::new(),
     };
 
@@ -373,11 +3951,16 @@ fn extract_inputs(node: &wdl_grammar::SyntaxNode) -> Vec<InputInfo> {
     inputs
 }
 
-fn extract_outputs(node: &wdl_grammar::SyntaxNode) -> Vec<OutputInfo> {
+/// Output declarations, including ones missing their value so they can be
+/// reported
+fn extract_outputs(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> Vec<OutputInfo> {
     let mut outputs = Vec::new();
     for child in node.children() {
-        if child.kind() == SyntaxKind::BoundDeclNode {
-            if let Some(output) = extract_output_declaration(&child) {
+        if matches!(
+            child.kind(),
+            SyntaxKind::BoundDeclNode | SyntaxKind::UnboundDeclNode
+        ) {
+            if let Some(output) = extract_output_declaration(&child, lines) {
                 outputs.push(output);
             }
         }
@@ -389,8 +3972,10 @@ fn extract_declaration(node: &wdl_grammar::SyntaxNode) -> Option<InputInfo> {
     let mut input = InputInfo {
         name: String::new(),
         wdl_type: String::new(),
+        wdl_type_parsed: None,
         optional: false,
         default_value: None,
+        doc: leading_doc(node),
     };
 
     // Find type and name
@@ -403,7 +3988,11 @@ fn extract_declaration(node: &wdl_grammar::SyntaxNode) -> Option<InputInfo> {
             | SyntaxKind::ObjectTypeNode
             | SyntaxKind::TypeRefNode => {
                 input.wdl_type = child.text().to_string();
-                input.optional = child.text().contains_char('?');
+                input.wdl_type_parsed = WdlType::parse(&input.wdl_type);
+                input.optional = match &input.wdl_type_parsed {
+                    Some(parsed) => parsed.is_optional(),
+                    None => input.wdl_type.trim_end().ends_with('?'),
+                };
             }
             _ => {}
         }
@@ -459,11 +4048,19 @@ fn extract_declaration(node: &wdl_grammar::SyntaxNode) -> Option<InputInfo> {
     }
 }
 
-fn extract_output_declaration(node: &wdl_grammar::SyntaxNode) -> Option<OutputInfo> {
+fn extract_output_declaration(
+    node: &wdl_grammar::SyntaxNode,
+    lines: &LineIndex,
+) -> Option<OutputInfo> {
+    let (line, column) = start_position(node, lines);
     let mut output = OutputInfo {
         name: String::new(),
+        line,
+        column,
         wdl_type: String::new(),
+        wdl_type_parsed: None,
         expression: String::new(),
+        files: FilePatterns::default(),
     };
 
     // Find type and name
@@ -476,6 +4073,7 @@ fn extract_output_declaration(node: &wdl_grammar::SyntaxNode) -> Option<OutputIn
             | SyntaxKind::ObjectTypeNode
             | SyntaxKind::TypeRefNode => {
                 output.wdl_type = child.text().to_string();
+                output.wdl_type_parsed = WdlType::parse(&output.wdl_type);
             }
             _ => {}
         }
@@ -504,46 +4102,17 @@ fn extract_output_declaration(node: &wdl_grammar::SyntaxNode) -> Option<OutputIn
     if output.name.is_empty() {
         None
     } else {
+        output.files =
+            FilePatterns::from_expression(&output.expression, output.wdl_type_parsed.as_ref());
         Some(output)
     }
 }
 
-fn extract_command_text(node: &wdl_grammar::SyntaxNode) -> String {
-    let mut command_parts = Vec::new();
-    for child in node.children_with_tokens() {
-        if let Some(token) = child.as_token() {
-            match token.kind() {
-                SyntaxKind::LiteralCommandText => {
-                    command_parts.push(token.text().to_string());
-                }
-                _ => {}
-            }
-        } else if let Some(child_node) = child.as_node() {
-            if child_node.kind() == SyntaxKind::PlaceholderNode {
-                command_parts.push(format!("~{{{}}}", extract_placeholder_expr(&child_node)));
-            }
-        }
-    }
-    command_parts.join("")
-}
-
-fn extract_placeholder_expr(node: &wdl_grammar::SyntaxNode) -> String {
-    for child in node.children() {
-        if matches!(
-            child.kind(),
-            SyntaxKind::NameRefExprNode | SyntaxKind::AccessExprNode | SyntaxKind::CallExprNode
-        ) {
-            return child.text().to_string();
-        }
-    }
-    String::new()
-}
-
-fn extract_runtime_items(node: &wdl_grammar::SyntaxNode) -> Vec<RuntimeItem> {
+fn extract_runtime_items(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> Vec<RuntimeItem> {
     let mut items = Vec::new();
     for child in node.children() {
         if child.kind() == SyntaxKind::RuntimeItemNode {
-            if let Some(item) = extract_runtime_item(&child) {
+            if let Some(item) = extract_runtime_item(&child, lines) {
                 items.push(item);
             }
         }
@@ -551,7 +4120,7 @@ fn extract_runtime_items(node: &wdl_grammar::SyntaxNode) -> Vec<RuntimeItem> {
     items
 }
 
-fn extract_runtime_item(node: &wdl_grammar::SyntaxNode) -> Option<RuntimeItem> {
+fn extract_runtime_item(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> Option<RuntimeItem> {
     let mut key = String::new();
     let mut value = String::new();
     let mut found_colon = false;
@@ -577,7 +4146,11 @@ fn extract_runtime_item(node: &wdl_grammar::SyntaxNode) -> Option<RuntimeItem> {
     if key.is_empty() || value.is_empty() {
         None
     } else {
-        Some(RuntimeItem { key, value })
+        Some(RuntimeItem {
+            key,
+            value,
+            line: line_number(node, lines),
+        })
     }
 }
 
@@ -623,20 +4196,30 @@ fn extract_meta_item(node: &wdl_grammar::SyntaxNode) -> Option<MetaItem> {
     }
 }
 
-fn extract_call_info(node: &wdl_grammar::SyntaxNode) -> Option<CallInfo> {
+fn extract_call_info(node: &wdl_grammar::SyntaxNode, lines: &LineIndex) -> Option<CallInfo> {
     let mut call = CallInfo {
         name: String::new(),
         target: String::new(),
         alias: None,
+        line: line_number(node, lines),
         inputs: Vec::new(),
     };
 
     for child in node.children() {
         match child.kind() {
             SyntaxKind::CallTargetNode => {
-                if let Some(name) = find_identifier_name(&child) {
-                    call.target = name.clone();
-                    call.name = name;
+                // Targets may be namespaced (`lib.task`); the call is named after the last part
+                let parts: Vec<String> = child
+                    .children_with_tokens()
+                    .filter_map(|c| c.into_token())
+                    .filter(|t| t.kind() == SyntaxKind::Ident)
+                    .map(|t| t.text().to_string())
+                    .collect();
+                if let Some(name) = parts.last() {
+                    if call.alias.is_none() {
+                        call.name = name.clone();
+                    }
+                    call.target = parts.join(".");
                 }
             }
             SyntaxKind::CallAliasNode => {
@@ -684,7 +4267,15 @@ fn extract_call_input_item(node: &wdl_grammar::SyntaxNode) -> Option<CallInputIt
         }
     }
 
-    if name.is_empty() || value.is_empty() {
+    if name.is_empty() {
+        return None;
+    }
+    // WDL 1.1 allows `input: x` as shorthand for `input: x = x`
+    if value.is_empty() && !found_assignment {
+        value = name.clone();
+    }
+
+    if value.is_empty() {
         None
     } else {
         Some(CallInputItem { name, value })
@@ -0,0 +1,209 @@
+use crate::provenance;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wdl_grammar::{SyntaxKind, SyntaxNode};
+
+/// A task definition found in one of the compared sources
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaskDigest {
+    pub name: String,
+    pub file: String,
+    /// SHA-256 of the task body, ignoring its name, comments and whitespace
+    /// between tokens
+    pub digest: String,
+}
+
+/// How a task name is spread across the compared sources
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    /// In several sources, with the same content everywhere
+    Identical,
+    /// In several sources, with different content
+    Diverged,
+    /// In one source only
+    Unique,
+}
+
+/// One row of the comparison matrix
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskRow {
+    pub name: String,
+    pub status: TaskStatus,
+    /// Per source, in argument order: which content variant of the task it
+    /// has (`A`, `B`, ... by first appearance), or `None` when it has no task
+    /// of this name
+    pub variants: Vec<Option<String>>,
+    pub digests: Vec<Option<String>>,
+}
+
+/// Tasks with the same content under more than one name
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentGroup {
+    pub digest: String,
+    pub tasks: Vec<TaskDigest>,
+}
+
+/// Task counts of one compared source
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceSummary {
+    pub source: String,
+    pub files: usize,
+    pub tasks: usize,
+    /// Task names no other source has
+    pub unique: usize,
+    /// Tasks another source has with the same name and content
+    pub identical: usize,
+    /// Tasks another source has with the same name but other content
+    pub diverged: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Comparison {
+    pub sources: Vec<SourceSummary>,
+    /// One row per task name, sorted by name
+    pub tasks: Vec<TaskRow>,
+    /// Same content found under different names
+    pub renamed: Vec<ContentGroup>,
+}
+
+/// Digests of the tasks defined in a file
+pub fn task_digests(file: &str, root: &SyntaxNode) -> Vec<TaskDigest> {
+    root.children()
+        .filter(|node| node.kind() == SyntaxKind::TaskDefinitionNode)
+        .filter_map(|node| {
+            let mut tokens = node
+                .descendants_with_tokens()
+                .filter_map(|element| element.into_token())
+                .filter(|token| {
+                    !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment)
+                });
+            let name = tokens
+                .by_ref()
+                .find(|token| token.kind() == SyntaxKind::Ident)?
+                .text()
+                .to_string();
+            let body: Vec<String> = tokens.map(|token| token.text().to_string()).collect();
+            Some(TaskDigest {
+                name,
+                file: file.to_string(),
+                digest: provenance::sha256_hex(body.join(" ").as_bytes()),
+            })
+        })
+        .collect()
+}
+
+/// Compare the tasks of several sources; `sources` pairs each source's label
+/// and the number of files in it with the tasks found there
+pub fn compare(sources: &[(String, usize, Vec<TaskDigest>)]) -> Comparison {
+    let mut names: BTreeMap<&str, Vec<Option<&TaskDigest>>> = BTreeMap::new();
+    for (index, (_, _, tasks)) in sources.iter().enumerate() {
+        for task in tasks {
+            let row = names
+                .entry(task.name.as_str())
+                .or_insert_with(|| vec![None; sources.len()]);
+            // A name defined twice in one source keeps its first definition
+            row[index].get_or_insert(task);
+        }
+    }
+
+    let rows: Vec<TaskRow> = names
+        .into_iter()
+        .map(|(name, cells)| {
+            let mut seen: Vec<&str> = Vec::new();
+            let variants = cells
+                .iter()
+                .map(|cell| {
+                    let digest = (*cell)?.digest.as_str();
+                    let index = seen.iter().position(|d| *d == digest).unwrap_or_else(|| {
+                        seen.push(digest);
+                        seen.len() - 1
+                    });
+                    Some(variant_label(index))
+                })
+                .collect();
+            let present = cells.iter().flatten().count();
+            let status = if present == 1 {
+                TaskStatus::Unique
+            } else if seen.len() == 1 {
+                TaskStatus::Identical
+            } else {
+                TaskStatus::Diverged
+            };
+            TaskRow {
+                name: name.to_string(),
+                status,
+                variants,
+                digests: cells
+                    .iter()
+                    .map(|cell| cell.map(|task| task.digest.clone()))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let summaries = sources
+        .iter()
+        .enumerate()
+        .map(|(index, (source, files, tasks))| {
+            let mut summary = SourceSummary {
+                source: source.clone(),
+                files: *files,
+                tasks: tasks.len(),
+                unique: 0,
+                identical: 0,
+                diverged: 0,
+            };
+            for row in rows.iter().filter(|row| row.digests[index].is_some()) {
+                let others = row
+                    .digests
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, digest)| *other != index && digest.is_some());
+                let mut others = others.map(|(_, digest)| digest).peekable();
+                if others.peek().is_none() {
+                    summary.unique += 1;
+                } else if others.any(|digest| *digest == row.digests[index]) {
+                    summary.identical += 1;
+                } else {
+                    summary.diverged += 1;
+                }
+            }
+            summary
+        })
+        .collect();
+
+    let mut by_digest: BTreeMap<&str, Vec<&TaskDigest>> = BTreeMap::new();
+    for (_, _, tasks) in sources {
+        for task in tasks {
+            by_digest
+                .entry(task.digest.as_str())
+                .or_default()
+                .push(task);
+        }
+    }
+    let renamed = by_digest
+        .into_iter()
+        .filter(|(_, tasks)| tasks.iter().any(|task| task.name != tasks[0].name))
+        .map(|(digest, tasks)| ContentGroup {
+            digest: digest.to_string(),
+            tasks: tasks.into_iter().cloned().collect(),
+        })
+        .collect();
+
+    Comparison {
+        sources: summaries,
+        tasks: rows,
+        renamed,
+    }
+}
+
+/// `A`, `B`, ... `Z`, then `AA`, `AB`, ...
+fn variant_label(index: usize) -> String {
+    let letter = |i: usize| char::from(b'A' + (i % 26) as u8);
+    if index < 26 {
+        letter(index).to_string()
+    } else {
+        format!("{}{}", letter(index / 26 - 1), letter(index))
+    }
+}
@@ -0,0 +1,197 @@
+use crate::expr;
+use crate::info::{CallInfo, WdlInfo, WorkflowInfo};
+use crate::inputs::is_required;
+use crate::types::WdlType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatIssueKind {
+    /// A required callee input the call doesn't bind
+    MissingInput,
+    /// A call binding for an input the callee doesn't declare
+    ExtraInput,
+    /// A binding whose type can't be coerced to the callee's input type
+    TypeMismatch,
+    /// A callee output the caller reads but the callee doesn't declare
+    MissingOutput,
+}
+
+impl CompatIssueKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CompatIssueKind::MissingInput => "missing_input",
+            CompatIssueKind::ExtraInput => "extra_input",
+            CompatIssueKind::TypeMismatch => "type_mismatch",
+            CompatIssueKind::MissingOutput => "missing_output",
+        }
+    }
+}
+
+/// An incompatibility between a call in the caller and the callee's signature
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompatIssue {
+    pub workflow: String,
+    pub call: String,
+    pub kind: CompatIssueKind,
+    pub name: String,
+    pub message: String,
+}
+
+/// Calls in `caller` that target `callee` (matched on the last segment of the call target)
+pub fn matching_calls<'a>(
+    caller: &'a WdlInfo,
+    callee: &WorkflowInfo,
+) -> Vec<(&'a WorkflowInfo, &'a CallInfo)> {
+    caller
+        .workflows
+        .iter()
+        .flat_map(|workflow| workflow.calls.iter().map(move |call| (workflow, call)))
+        .filter(|(_, call)| call.target.rsplit('.').next() == Some(callee.name.as_str()))
+        .collect()
+}
+
+/// Check every call of `callee` in `caller` against the callee's inputs and outputs
+pub fn check(caller: &WdlInfo, callee: &WorkflowInfo) -> Vec<CompatIssue> {
+    let mut issues = Vec::new();
+    for (workflow, call) in matching_calls(caller, callee) {
+        let mut report = |kind, name: &str, message: String| {
+            issues.push(CompatIssue {
+                workflow: workflow.name.clone(),
+                call: call.name.clone(),
+                kind,
+                name: name.to_string(),
+                message,
+            })
+        };
+
+        for input in callee.inputs.iter().filter(|input| is_required(input)) {
+            if !call.inputs.iter().any(|binding| binding.name == input.name) {
+                report(
+                    CompatIssueKind::MissingInput,
+                    &input.name,
+                    format!("required input of type {} is not bound", input.wdl_type),
+                );
+            }
+        }
+
+        for binding in &call.inputs {
+            let Some(input) = callee.inputs.iter().find(|i| i.name == binding.name) else {
+                report(
+                    CompatIssueKind::ExtraInput,
+                    &binding.name,
+                    format!("'{}' is not an input of {}", binding.name, callee.name),
+                );
+                continue;
+            };
+
+            let source = infer_type(caller, workflow, &binding.value);
+            if let (Some(source), Some(target)) = (source, &input.wdl_type_parsed) {
+                if !source.is_coercible_to(target) {
+                    report(
+                        CompatIssueKind::TypeMismatch,
+                        &binding.name,
+                        format!("{} is bound to a {} value", target, source),
+                    );
+                }
+            }
+        }
+
+        for output in referenced_members(workflow, &call.name) {
+            if !callee.outputs.iter().any(|o| o.name == output) {
+                report(
+                    CompatIssueKind::MissingOutput,
+                    &output,
+                    format!("'{}' is not an output of {}", output, callee.name),
+                );
+            }
+        }
+    }
+    issues
+}
+
+/// Members of a call (`call.member`) read anywhere in the workflow
+fn referenced_members(workflow: &WorkflowInfo, call_name: &str) -> Vec<String> {
+    let expressions = workflow
+        .calls
+        .iter()
+        .flat_map(|call| call.inputs.iter().map(|input| input.value.as_str()))
+        .chain(workflow.outputs.iter().map(|o| o.expression.as_str()))
+        .chain(
+            workflow
+                .declarations
+                .iter()
+                .filter_map(|d| d.default_value.as_deref()),
+        );
+
+    let mut members = Vec::new();
+    for expression in expressions {
+        for reference in expr::references(expression) {
+            let mut parts = reference.split('.');
+            if parts.next() == Some(call_name) {
+                if let Some(member) = parts.next() {
+                    if !members.iter().any(|m| m == member) {
+                        members.push(member.to_string());
+                    }
+                }
+            }
+        }
+    }
+    members
+}
+
+/// Type of a call binding when it can be known without evaluation: a literal,
+/// a workflow input or declaration, or an output of a task call in the same file
+fn infer_type(info: &WdlInfo, workflow: &WorkflowInfo, value: &str) -> Option<WdlType> {
+    let value = value.trim();
+    let primitive = |name: &str| {
+        Some(WdlType::Primitive {
+            name: name.to_string(),
+        })
+    };
+
+    if value == "true" || value == "false" {
+        return primitive("Boolean");
+    }
+    if value.parse::<i64>().is_ok() {
+        return primitive("Int");
+    }
+    if value.parse::<f64>().is_ok() {
+        return primitive("Float");
+    }
+    if (value.starts_with('"') && value.ends_with('"'))
+        || (value.starts_with('\'') && value.ends_with('\''))
+    {
+        return primitive("String");
+    }
+
+    // Only plain references have a type that doesn't depend on evaluation
+    let references = expr::references(value);
+    if references.len() != 1 || references[0] != value {
+        return None;
+    }
+    let mut parts = value.split('.');
+    let root = parts.next()?;
+    let member = parts.next();
+
+    match member {
+        None => workflow
+            .inputs
+            .iter()
+            .chain(&workflow.declarations)
+            .find(|decl| decl.name == root)
+            .and_then(|decl| decl.wdl_type_parsed.clone()),
+        Some(member) => {
+            // Outputs of calls inside scatters become arrays, so skip scattered calls
+            if !workflow.scatters.is_empty() {
+                return None;
+            }
+            let call = workflow.calls.iter().find(|c| c.name == root)?;
+            let task = info.tasks.iter().find(|t| t.name == call.target)?;
+            task.outputs
+                .iter()
+                .find(|o| o.name == member)
+                .and_then(|o| o.wdl_type_parsed.clone())
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::filter::DiagnosticFilter;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the configuration file looked up in the current directory
+pub const DEFAULT_CONFIG_FILE: &str = ".wdlparse.toml";
+
+/// Project configuration loaded from `.wdlparse.toml` (or `--config`)
+#[derive(Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Config {
+    /// Named backend profiles used by `check-backend`
+    pub backends: BTreeMap<String, BackendProfile>,
+    /// Settings for the `lint` command
+    pub lint: LintConfig,
+    /// Where to look for imported files
+    pub imports: ImportConfig,
+    /// Which parse diagnostics and lint findings are reported; the
+    /// `--warnings-as-errors`, `--ignore` and `--only` flags add to it
+    pub diagnostics: DiagnosticFilter,
+}
+
+/// Import resolution settings
+#[derive(Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ImportConfig {
+    /// Directories searched for imports not found next to the importing
+    /// file, after any `--import-dir`; relative paths are relative to the
+    /// config file
+    pub search_paths: Vec<PathBuf>,
+    /// Import URI prefixes mapped to replacements, used by `rewrite-imports`
+    /// and applied when resolving imports (e.g. a GitHub prefix to a mirror)
+    pub rewrites: BTreeMap<String, String>,
+}
+
+/// Lint settings: per-rule levels override each rule's default
+#[derive(Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LintConfig {
+    pub rules: BTreeMap<String, RuleLevel>,
+    /// Options for the naming convention rules
+    pub naming: NamingConfig,
+    /// External rule executables: a name `foo` runs `wdlparse-rule-foo` from `PATH`,
+    /// anything containing a `/` is run as a path, relative to the config file
+    pub plugins: Vec<String>,
+}
+
+/// Options for the naming convention rules
+#[derive(Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct NamingConfig {
+    /// Prefix every workflow output name must start with (e.g. "out_")
+    pub workflow_output_prefix: Option<String>,
+}
+
+/// Configured level of a lint rule
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Off,
+    Warning,
+    Error,
+}
+
+/// Resource limits and requirements of an execution backend
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BackendProfile {
+    /// Optional human-readable description of the backend
+    pub description: Option<String>,
+    /// Maximum number of CPUs a single task may request
+    pub max_cpu: Option<f64>,
+    /// Maximum memory a single task may request (e.g. "512G")
+    pub max_memory: Option<String>,
+    /// Whether the backend can schedule tasks that request GPUs
+    pub gpu: bool,
+    /// Whether every task must declare a `docker`/`container` image
+    pub require_container: bool,
+}
+
+impl Config {
+    /// Load configuration from an explicit path, or from `.wdlparse.toml`
+    /// in the current directory when it exists. Falls back to defaults.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::from_file(path),
+            None => {
+                let default_path = Path::new(DEFAULT_CONFIG_FILE);
+                if default_path.exists() {
+                    Self::from_file(default_path)
+                } else {
+                    Ok(Self::default())
+                }
+            }
+        }
+    }
+
+    /// Load configuration from a TOML file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let mut config: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        for search_path in &mut config.imports.search_paths {
+            *search_path = base.join(&*search_path);
+        }
+        for plugin in &mut config.lint.plugins {
+            if plugin.contains('/') && Path::new(plugin.as_str()).is_relative() {
+                *plugin = base.join(&*plugin).display().to_string();
+            }
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::info::WdlInfo;
+use serde_json::{Map, Value};
+
+/// Runtime attributes Cromwell accepts in `default_runtime_attributes`
+const DEFAULT_RUNTIME_KEYS: &[&str] = &[
+    "docker",
+    "cpu",
+    "memory",
+    "disks",
+    "zones",
+    "preemptible",
+    "maxRetries",
+    "bootDiskSizeGb",
+    "noAddress",
+    "continueOnReturnCode",
+    "failOnStderr",
+];
+
+/// Cromwell workflow options skeleton: output and log directories to fill in,
+/// call caching switched on, and default runtime attributes taken from the
+/// values most tasks in the file already use
+pub fn options_template(info: &WdlInfo) -> Value {
+    serde_json::json!({
+        "final_workflow_outputs_dir": null,
+        "use_relative_output_paths": false,
+        "final_call_logs_dir": null,
+        "final_workflow_log_dir": null,
+        "write_to_cache": true,
+        "read_from_cache": true,
+        "default_runtime_attributes": common_runtime(info),
+    })
+}
+
+/// The most common literal value of each runtime attribute across tasks;
+/// ties go to the value seen first. `container` counts as `docker`.
+pub fn common_runtime(info: &WdlInfo) -> Map<String, Value> {
+    let mut counts: Vec<(&str, Value, usize)> = Vec::new();
+    for task in &info.tasks {
+        for item in &task.runtime {
+            let key = match item.key.as_str() {
+                "container" => "docker",
+                key => key,
+            };
+            let Some(key) = DEFAULT_RUNTIME_KEYS.iter().find(|known| **known == key) else {
+                continue;
+            };
+            let value = match item.literal_value() {
+                Some(text) => Value::from(text),
+                None => match serde_json::from_str::<Value>(item.value.trim()) {
+                    Ok(value) if value.is_number() || value.is_boolean() => value,
+                    _ => continue,
+                },
+            };
+            match counts.iter_mut().find(|(k, v, _)| k == key && *v == value) {
+                Some((_, _, count)) => *count += 1,
+                None => counts.push((key, value, 1)),
+            }
+        }
+    }
+
+    let mut attributes = Map::new();
+    for key in DEFAULT_RUNTIME_KEYS {
+        let mut best: Option<&(&str, Value, usize)> = None;
+        for entry in counts.iter().filter(|(k, _, _)| k == key) {
+            if best.is_none_or(|(_, _, count)| entry.2 > *count) {
+                best = Some(entry);
+            }
+        }
+        if let Some((_, value, _)) = best {
+            attributes.insert(key.to_string(), value.clone());
+        }
+    }
+    attributes
+}
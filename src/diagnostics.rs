@@ -0,0 +1,110 @@
+use crate::lines::LineIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use wdl_grammar::{Diagnostic, SyntaxNode};
+
+/// A parser diagnostic with its position resolved; the position is `None`
+/// when the parser gave no location
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Located {
+    /// `syntax-error`, `syntax-warning` or `syntax-note`, for `--ignore` and `--only`
+    pub code: String,
+    pub severity: String,
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Located {
+    pub fn new(diagnostic: &Diagnostic, lines: &LineIndex) -> Self {
+        let position = lines.diagnostic_position(diagnostic);
+        Self {
+            code: code(diagnostic),
+            severity: format!("{:?}", diagnostic.severity()).to_lowercase(),
+            message: diagnostic.message().to_string(),
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+        }
+    }
+}
+
+/// The first diagnostic of a cascade and the ones that follow from it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticGroup {
+    #[serde(flatten)]
+    pub cause: Located,
+    /// Later diagnostics in the same top-level definition, or repeats of the
+    /// same message right after it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<Located>,
+}
+
+impl DiagnosticGroup {
+    /// Number of diagnostics in the group, the cause included
+    pub fn count(&self) -> usize {
+        self.related.len() + 1
+    }
+}
+
+/// Code of a parse diagnostic, from its severity: `syntax-error`,
+/// `syntax-warning` or `syntax-note`
+pub fn code(diagnostic: &Diagnostic) -> String {
+    format!("syntax-{:?}", diagnostic.severity()).to_lowercase()
+}
+
+/// Byte offset where a diagnostic's first label starts
+pub fn offset(diagnostic: &Diagnostic) -> Option<usize> {
+    diagnostic.labels().next().map(|label| label.span().start())
+}
+
+/// Diagnostics in file order, those without a position last, with exact
+/// duplicates (same position and message) dropped
+pub fn sorted(diagnostics: &[Diagnostic]) -> Vec<&Diagnostic> {
+    let mut sorted: Vec<&Diagnostic> = diagnostics.iter().collect();
+    sorted.sort_by_key(|d| (offset(d).is_none(), offset(d)));
+    let mut seen = BTreeSet::new();
+    sorted.retain(|d| seen.insert((offset(d), d.message().to_string())));
+    sorted
+}
+
+/// Group diagnostics by root cause. A parser error usually makes it lose
+/// track of the rest of the definition it's in, so every later diagnostic in
+/// the same top-level definition (task, workflow, struct, ...) is treated as
+/// following from the first one. Repeats of a message are folded into it too.
+pub fn group(
+    diagnostics: &[Diagnostic],
+    root: &SyntaxNode,
+    lines: &LineIndex,
+) -> Vec<DiagnosticGroup> {
+    let definitions: Vec<_> = root
+        .children()
+        .map(|node| {
+            let range = node.text_range();
+            usize::from(range.start())..usize::from(range.end())
+        })
+        .collect();
+    let definition = |offset: Option<usize>| {
+        let offset = offset?;
+        definitions.iter().position(|range| range.contains(&offset))
+    };
+
+    let mut groups: Vec<DiagnosticGroup> = Vec::new();
+    let mut current_definition = None;
+    for diagnostic in sorted(diagnostics) {
+        let located = Located::new(diagnostic, lines);
+        let in_definition = definition(offset(diagnostic));
+        if let Some(group) = groups.last_mut() {
+            let same_definition = in_definition.is_some() && in_definition == current_definition;
+            if same_definition || group.cause.message == located.message {
+                group.related.push(located);
+                continue;
+            }
+        }
+        current_definition = in_definition;
+        groups.push(DiagnosticGroup {
+            cause: located,
+            related: Vec::new(),
+        });
+    }
+    groups
+}
@@ -0,0 +1,203 @@
+use crate::info::{TaskInfo, WdlInfo};
+use serde::{Deserialize, Serialize};
+
+/// How a row of a side-by-side diff differs between its two sides
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowKind {
+    Same,
+    Changed,
+    Removed,
+    Added,
+}
+
+/// A numbered line of one side
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// 1-based line within the compared block
+    pub number: usize,
+    pub text: String,
+}
+
+/// One row of a side-by-side diff; a side is `None` where the other side has
+/// a line with no counterpart
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub kind: RowKind,
+    pub old: Option<Line>,
+    pub new: Option<Line>,
+}
+
+/// A task whose command or runtime section differs, or that only one file has
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskDiff {
+    pub name: String,
+    /// `changed`, `added` or `removed`
+    pub status: RowKind,
+    /// Rows of the dedented command; empty when it is unchanged
+    pub command: Vec<Row>,
+    /// Rows of `key: value` runtime lines, sorted by key; empty when unchanged
+    pub runtime: Vec<Row>,
+}
+
+/// Tasks of `new` whose command or runtime differ from the task of the same
+/// name in `old`, then tasks only one of them has, in file order
+pub fn task_diffs(old: &WdlInfo, new: &WdlInfo) -> Vec<TaskDiff> {
+    let mut diffs = Vec::new();
+    for task in &new.tasks {
+        let Some(previous) = old.tasks.iter().find(|t| t.name == task.name) else {
+            diffs.push(TaskDiff {
+                name: task.name.clone(),
+                status: RowKind::Added,
+                command: side_by_side(&[], &command_lines(task)),
+                runtime: side_by_side(&[], &runtime_lines(task)),
+            });
+            continue;
+        };
+        let command = side_by_side(&command_lines(previous), &command_lines(task));
+        let runtime = side_by_side(&runtime_lines(previous), &runtime_lines(task));
+        let changed = |rows: &[Row]| rows.iter().any(|row| row.kind != RowKind::Same);
+        if changed(&command) || changed(&runtime) {
+            diffs.push(TaskDiff {
+                name: task.name.clone(),
+                status: RowKind::Changed,
+                command: if changed(&command) {
+                    command
+                } else {
+                    Vec::new()
+                },
+                runtime: if changed(&runtime) {
+                    runtime
+                } else {
+                    Vec::new()
+                },
+            });
+        }
+    }
+    for task in &old.tasks {
+        if !new.tasks.iter().any(|t| t.name == task.name) {
+            diffs.push(TaskDiff {
+                name: task.name.clone(),
+                status: RowKind::Removed,
+                command: side_by_side(&command_lines(task), &[]),
+                runtime: side_by_side(&runtime_lines(task), &[]),
+            });
+        }
+    }
+    diffs
+}
+
+fn command_lines(task: &TaskInfo) -> Vec<String> {
+    let text = match &task.command_section {
+        Some(section) => section.dedented.clone(),
+        None => task.command.clone().unwrap_or_default(),
+    };
+    text.lines()
+        .map(|line| line.replace('\t', "    "))
+        .collect()
+}
+
+fn runtime_lines(task: &TaskInfo) -> Vec<String> {
+    let mut lines: Vec<String> = task
+        .runtime
+        .iter()
+        .map(|item| format!("{}: {}", item.key, item.value))
+        .collect();
+    lines.sort();
+    lines
+}
+
+/// Align two versions of a block line by line: lines of a longest common
+/// subsequence are `same`, and a run of removed lines followed by added ones
+/// is paired up into `changed` rows
+pub fn side_by_side(old: &[String], new: &[String]) -> Vec<Row> {
+    // lengths[i][j]: longest common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let line = |lines: &[String], index: usize| Line {
+        number: index + 1,
+        text: lines[index].clone(),
+    };
+    let mut rows = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            pair_up(&mut rows, &mut removed, &mut added);
+            rows.push(Row {
+                kind: RowKind::Same,
+                old: Some(line(old, i)),
+                new: Some(line(new, j)),
+            });
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            added.push(line(new, j));
+            j += 1;
+        } else {
+            removed.push(line(old, i));
+            i += 1;
+        }
+    }
+    pair_up(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// Turn pending removed and added lines into rows, side by side as far as
+/// both go
+fn pair_up(rows: &mut Vec<Row>, removed: &mut Vec<Line>, added: &mut Vec<Line>) {
+    let count = removed.len().max(added.len());
+    let mut removed = removed.drain(..);
+    let mut added = added.drain(..);
+    for _ in 0..count {
+        let (old, new) = (removed.next(), added.next());
+        let kind = match (&old, &new) {
+            (Some(_), Some(_)) => RowKind::Changed,
+            (Some(_), None) => RowKind::Removed,
+            _ => RowKind::Added,
+        };
+        rows.push(Row { kind, old, new });
+    }
+}
+
+/// Indices of the rows to show: every row that differs and up to `context`
+/// unchanged rows around each one. A gap between shown rows is where
+/// unchanged rows were left out.
+pub fn visible_rows(rows: &[Row], context: usize) -> Vec<usize> {
+    let changes: Vec<usize> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.kind != RowKind::Same)
+        .map(|(index, _)| index)
+        .collect();
+    (0..rows.len())
+        .filter(|index| {
+            changes
+                .iter()
+                .any(|change| index.abs_diff(*change) <= context)
+        })
+        .collect()
+}
+
+/// `text` cut or padded to exactly `width` characters
+pub fn fit(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    if count <= width {
+        format!("{}{}", text, " ".repeat(width - count))
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut cut: String = text.chars().take(width - 1).collect();
+        cut.push('…');
+        cut
+    }
+}
@@ -0,0 +1,469 @@
+use crate::graph::WorkflowGraph;
+use crate::info::{InputInfo, MetaItem, TaskInfo, WdlInfo, WorkflowInfo};
+use crate::markdown;
+use crate::mermaid::{self, DiagramOptions};
+use crate::workspace::SymbolKind;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Component, Path, PathBuf};
+
+/// `meta` keys whose values become search keywords
+const KEYWORD_KEYS: &[&str] = &["keywords", "keyword", "tags", "tag"];
+
+/// File the search index is written to, next to the pages
+pub const INDEX_FILE: &str = "search_index.json";
+
+/// Directory of the pages of an MkDocs site, next to `mkdocs.yml`
+const MKDOCS_PAGES: &str = "docs";
+
+/// `mkdocs.yml` settings besides the name and nav: the Material theme, which
+/// renders `mermaid` code blocks as diagrams
+const MKDOCS_SETTINGS: &str = "theme:
+  name: material
+markdown_extensions:
+  - tables
+  - pymdownx.superfences:
+      custom_fences:
+        - name: mermaid
+          class: mermaid
+          format: !!python/name:pymdownx.superfences.fence_code_format
+";
+
+/// Title of the index page and of generated sites
+const TITLE: &str = "WDL documentation";
+
+/// Layout of the search index `docs --index` writes
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexFormat {
+    /// Documents with `location`, `title` and `text`, as lunr.js search in
+    /// MkDocs themes loads them
+    Lunr,
+    /// One structured entry per task and workflow
+    Json,
+}
+
+/// Static site generator `docs --site` lays the output out for
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SiteFormat {
+    /// `mkdocs.yml` with a nav and Mermaid diagrams enabled, and a page per
+    /// workflow and per task under `docs/`
+    Mkdocs,
+}
+
+/// A generated file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// Path under the output directory, e.g. `tools/align.md`
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// A task or workflow as the search index describes it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SearchEntry {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub file: String,
+    /// Page and anchor of its section, e.g. `tools/align.md#task-align`
+    pub location: String,
+    /// The `meta` description, or else the comment block above it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub inputs: Vec<String>,
+    /// Values of the `keywords` and `tags` meta entries
+    pub keywords: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Definition<'a> {
+    Workflow(&'a WorkflowInfo),
+    Task(&'a TaskInfo),
+}
+
+impl<'a> Definition<'a> {
+    fn kind(self) -> SymbolKind {
+        match self {
+            Definition::Workflow(_) => SymbolKind::Workflow,
+            Definition::Task(_) => SymbolKind::Task,
+        }
+    }
+
+    fn name(self) -> &'a str {
+        match self {
+            Definition::Workflow(workflow) => &workflow.name,
+            Definition::Task(task) => &task.name,
+        }
+    }
+
+    /// The `meta` description, or else the comment block above it
+    fn description(self) -> Option<&'a str> {
+        let (authorship, doc) = match self {
+            Definition::Workflow(workflow) => (&workflow.authorship, &workflow.doc),
+            Definition::Task(task) => (&task.authorship, &task.doc),
+        };
+        authorship.description.as_deref().or(doc.as_deref())
+    }
+
+    fn inputs(self) -> &'a [InputInfo] {
+        match self {
+            Definition::Workflow(workflow) => &workflow.inputs,
+            Definition::Task(task) => &task.inputs,
+        }
+    }
+
+    fn meta(self) -> &'a [MetaItem] {
+        match self {
+            Definition::Workflow(workflow) => &workflow.meta,
+            Definition::Task(task) => &task.meta,
+        }
+    }
+
+    fn anchor(self) -> String {
+        markdown::anchor(kind_name(self.kind()), self.name())
+    }
+}
+
+/// A task or workflow with the page it is documented on
+struct Documented<'a> {
+    file: &'a str,
+    info: &'a WdlInfo,
+    definition: Definition<'a>,
+    /// Path of the page below the pages directory
+    page: PathBuf,
+}
+
+/// Every workflow, then every task, of each file. Without a site a file's
+/// definitions share its page; an MkDocs site gives each its own, under
+/// `workflows/` or `tasks/`, numbering names used more than once.
+fn locate<'a>(
+    documents: &'a [(String, &'a WdlInfo)],
+    site: Option<SiteFormat>,
+) -> Vec<Documented<'a>> {
+    let base = common_dir(documents.iter().map(|(file, _)| Path::new(file)));
+    let mut used: Vec<PathBuf> = Vec::new();
+    let mut found = Vec::new();
+    for (file, info) in documents {
+        let workflows = info.workflows.iter().map(Definition::Workflow);
+        let tasks = info.tasks.iter().map(Definition::Task);
+        for definition in workflows.chain(tasks) {
+            let page = match site {
+                None => page_path(Path::new(file), &base),
+                Some(SiteFormat::Mkdocs) => {
+                    let dir = match definition.kind() {
+                        SymbolKind::Workflow => "workflows",
+                        _ => "tasks",
+                    };
+                    let mut page = Path::new(dir).join(format!("{}.md", definition.name()));
+                    let mut number = 2;
+                    while used.contains(&page) {
+                        page = Path::new(dir).join(format!("{}-{}.md", definition.name(), number));
+                        number += 1;
+                    }
+                    used.push(page.clone());
+                    page
+                }
+            };
+            found.push(Documented {
+                file,
+                info,
+                definition,
+                page,
+            });
+        }
+    }
+    found
+}
+
+/// The documentation files. Without a site, one page per file, at its path
+/// relative to the directory all files share and with `.md` for `.wdl`, then
+/// `index.md` linking to every page. An MkDocs site has `mkdocs.yml` and,
+/// under `docs/`, `index.md` and a page per workflow (with its Mermaid
+/// diagram) and per task (with its command).
+pub fn pages(documents: &[(String, &WdlInfo)], site: Option<SiteFormat>) -> Vec<Page> {
+    match site {
+        None => file_pages(documents),
+        Some(SiteFormat::Mkdocs) => mkdocs_pages(documents),
+    }
+}
+
+fn file_pages(documents: &[(String, &WdlInfo)]) -> Vec<Page> {
+    let base = common_dir(documents.iter().map(|(file, _)| Path::new(file)));
+    let mut pages: Vec<Page> = documents
+        .iter()
+        .map(|(file, info)| Page {
+            path: page_path(Path::new(file), &base),
+            content: markdown::info_markdown(file, info),
+        })
+        .collect();
+
+    let mut index = format!("# {}\n\n", TITLE);
+    index.push_str("| File | Workflows | Tasks |\n|---|---|---|\n");
+    for ((file, info), page) in documents.iter().zip(&pages) {
+        let location = location(&page.path);
+        let links = |kind: &str, names: Vec<&String>| {
+            names
+                .iter()
+                .map(|name| {
+                    format!(
+                        "[`{}`]({}#{})",
+                        name,
+                        location,
+                        markdown::anchor(kind, name)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        index.push_str(&format!(
+            "| [{}]({}) | {} | {} |\n",
+            file,
+            location,
+            links("workflow", info.workflows.iter().map(|w| &w.name).collect()),
+            links("task", info.tasks.iter().map(|t| &t.name).collect()),
+        ));
+    }
+    pages.push(Page {
+        path: PathBuf::from("index.md"),
+        content: index,
+    });
+    pages
+}
+
+fn mkdocs_pages(documents: &[(String, &WdlInfo)]) -> Vec<Page> {
+    let found = locate(documents, Some(SiteFormat::Mkdocs));
+    let mut pages = Vec::new();
+    for documented in &found {
+        let mut content = match documented.definition {
+            Definition::Workflow(workflow) => {
+                let mut content = markdown::workflow_markdown(workflow);
+                let diagram = mermaid::workflow_diagram(
+                    &WorkflowGraph::build(workflow),
+                    documented.info,
+                    &DiagramOptions::default(),
+                );
+                content.push_str(&format!("#### Diagram\n\n```mermaid\n{}```\n\n", diagram));
+                content
+            }
+            Definition::Task(task) => markdown::task_markdown(task),
+        };
+        if !content.ends_with("\n\n") {
+            content.push('\n');
+        }
+        content.push_str(&format!("Source: `{}`\n", documented.file));
+        pages.push(Page {
+            path: Path::new(MKDOCS_PAGES).join(&documented.page),
+            content,
+        });
+    }
+
+    let mut index = format!("# {}\n\n", TITLE);
+    let mut nav = format!("  - {}: index.md\n", yaml_string("Home"));
+    for (kind, heading) in [
+        (SymbolKind::Workflow, "Workflows"),
+        (SymbolKind::Task, "Tasks"),
+    ] {
+        let listed: Vec<&Documented> = found
+            .iter()
+            .filter(|d| d.definition.kind() == kind)
+            .collect();
+        if listed.is_empty() {
+            continue;
+        }
+        index.push_str(&format!(
+            "## {}\n\n| Name | File | Description |\n|---|---|---|\n",
+            heading
+        ));
+        nav.push_str(&format!("  - {}:\n", yaml_string(heading)));
+        for documented in listed {
+            let page = location(&documented.page);
+            let description = documented.definition.description().unwrap_or_default();
+            index.push_str(&format!(
+                "| [`{}`]({}) | `{}` | {} |\n",
+                documented.definition.name(),
+                page,
+                documented.file,
+                markdown::cell(description)
+            ));
+            nav.push_str(&format!(
+                "      - {}: {}\n",
+                yaml_string(documented.definition.name()),
+                yaml_string(&page)
+            ));
+        }
+        index.push('\n');
+    }
+    pages.push(Page {
+        path: Path::new(MKDOCS_PAGES).join("index.md"),
+        content: index,
+    });
+    pages.push(Page {
+        path: PathBuf::from("mkdocs.yml"),
+        content: format!(
+            "site_name: {}\n{}nav:\n{}",
+            yaml_string(TITLE),
+            MKDOCS_SETTINGS,
+            nav
+        ),
+    });
+    pages
+}
+
+/// Where `docs --index` writes the search index: next to the pages
+pub fn index_path(site: Option<SiteFormat>) -> PathBuf {
+    match site {
+        None => PathBuf::from(INDEX_FILE),
+        Some(SiteFormat::Mkdocs) => Path::new(MKDOCS_PAGES).join(INDEX_FILE),
+    }
+}
+
+/// Directories holding only generated pages, where any other page is stale
+pub fn page_dirs(site: Option<SiteFormat>) -> Vec<PathBuf> {
+    match site {
+        None => Vec::new(),
+        Some(SiteFormat::Mkdocs) => ["workflows", "tasks"]
+            .iter()
+            .map(|dir| Path::new(MKDOCS_PAGES).join(dir))
+            .collect(),
+    }
+}
+
+/// Search entries for the workflows, then the tasks, of every file, located
+/// relative to the pages directory
+pub fn search_entries(
+    documents: &[(String, &WdlInfo)],
+    site: Option<SiteFormat>,
+) -> Vec<SearchEntry> {
+    locate(documents, site)
+        .into_iter()
+        .map(|documented| {
+            let definition = documented.definition;
+            SearchEntry {
+                kind: definition.kind(),
+                name: definition.name().to_string(),
+                file: documented.file.to_string(),
+                location: format!("{}#{}", location(&documented.page), definition.anchor()),
+                description: definition.description().map(str::to_string),
+                inputs: definition
+                    .inputs()
+                    .iter()
+                    .map(|input| input.name.clone())
+                    .collect(),
+                keywords: keywords(definition.meta()),
+            }
+        })
+        .collect()
+}
+
+/// The index in `format`
+pub fn search_index(entries: &[SearchEntry], format: IndexFormat) -> serde_json::Value {
+    match format {
+        IndexFormat::Json => json!({ "documents": entries }),
+        IndexFormat::Lunr => {
+            let docs: Vec<serde_json::Value> = entries
+                .iter()
+                .map(|entry| {
+                    let mut text = vec![entry.description.clone().unwrap_or_default()];
+                    if !entry.inputs.is_empty() {
+                        text.push(format!("Inputs: {}", entry.inputs.join(" ")));
+                    }
+                    if !entry.keywords.is_empty() {
+                        text.push(format!("Keywords: {}", entry.keywords.join(" ")));
+                    }
+                    json!({
+                        "location": entry.location,
+                        "title": format!("{} {}", kind_name(entry.kind), entry.name),
+                        "text": text.join("\n").trim(),
+                    })
+                })
+                .collect();
+            json!({
+                "config": {
+                    "lang": ["en"],
+                    "separator": "[\\s\\-_]+",
+                    "pipeline": ["stopWordFilter"]
+                },
+                "docs": docs
+            })
+        }
+    }
+}
+
+fn kind_name(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Task => "task",
+        SymbolKind::Workflow => "workflow",
+        SymbolKind::Struct => "struct",
+    }
+}
+
+/// Keywords listed in `meta`, as an array (`["qc", "bam"]`) or a
+/// comma-separated string, each once
+fn keywords(meta: &[MetaItem]) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for item in meta
+        .iter()
+        .filter(|item| KEYWORD_KEYS.contains(&item.key.as_str()))
+    {
+        let value = item.value.trim();
+        let value = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .unwrap_or(value);
+        for keyword in value.split(',') {
+            let keyword = keyword
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .trim();
+            if !keyword.is_empty() && !keywords.iter().any(|k| k == keyword) {
+                keywords.push(keyword.to_string());
+            }
+        }
+    }
+    keywords
+}
+
+/// Directory containing every file, empty when they share none
+fn common_dir<'a>(files: impl Iterator<Item = &'a Path>) -> PathBuf {
+    let mut common: Option<Vec<Component>> = None;
+    for file in files {
+        let parent: Vec<Component> = file
+            .parent()
+            .map(|dir| dir.components().collect())
+            .unwrap_or_default();
+        common = Some(match common {
+            None => parent,
+            Some(common) => common
+                .into_iter()
+                .zip(parent)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    common.unwrap_or_default().into_iter().collect()
+}
+
+/// Page of `file` under the output directory: its path below `base`, without
+/// root or `..` components, ending in `.md`
+fn page_path(file: &Path, base: &Path) -> PathBuf {
+    let relative = file.strip_prefix(base).unwrap_or(file);
+    let mut path: PathBuf = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect();
+    path.set_extension("md");
+    path
+}
+
+/// `text` as a double-quoted YAML scalar
+fn yaml_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `path` as a link target, with `/` separators
+fn location(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,138 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use wdl_grammar::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// Kind of a token that carries no meaning but is kept for round-tripping
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriviaKind {
+    Whitespace,
+    Comment,
+}
+
+/// A whitespace run or comment and the byte range it covers in the source
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// Every whitespace and comment token under `root`, in source order. The
+/// syntax tree is lossless, so together with the significant tokens these
+/// cover the file byte for byte.
+pub fn trivia(root: &SyntaxNode) -> Vec<Trivia> {
+    root.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter_map(|token| {
+            let kind = match token.kind() {
+                SyntaxKind::Whitespace => TriviaKind::Whitespace,
+                SyntaxKind::Comment => TriviaKind::Comment,
+                _ => return None,
+            };
+            Some(Trivia {
+                kind,
+                range: token_range(&token),
+                text: token.text().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Byte range of `token` in the source
+pub fn token_range(token: &SyntaxToken) -> Range<usize> {
+    let range = token.text_range();
+    usize::from(range.start())..usize::from(range.end())
+}
+
+/// Byte range of `node` without the whitespace and comments at its edges, so
+/// replacing it leaves the indentation and comment blocks around it alone
+pub fn node_range(node: &SyntaxNode) -> Range<usize> {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment));
+    match tokens.next() {
+        Some(first) => {
+            let last = tokens.last().unwrap_or_else(|| first.clone());
+            token_range(&first).start..token_range(&last).end
+        }
+        None => {
+            let range = node.text_range();
+            usize::from(range.start())..usize::from(range.end())
+        }
+    }
+}
+
+/// Replacement text for one byte range of a source file; an empty range is
+/// an insertion and empty text a deletion
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl SourceEdit {
+    pub fn replace(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    pub fn insert(offset: usize, text: impl Into<String>) -> Self {
+        Self::replace(offset..offset, text)
+    }
+
+    pub fn delete(range: Range<usize>) -> Self {
+        Self::replace(range, String::new())
+    }
+
+    pub fn replace_token(token: &SyntaxToken, text: impl Into<String>) -> Self {
+        Self::replace(token_range(token), text)
+    }
+
+    /// Replace `node` up to its first and last significant tokens
+    pub fn replace_node(node: &SyntaxNode, text: impl Into<String>) -> Self {
+        Self::replace(node_range(node), text)
+    }
+}
+
+/// `source` with `edits` applied. Edits can come in any order but must not
+/// overlap; insertions at the same offset keep their order. Everything
+/// outside the edited ranges is copied unchanged.
+pub fn apply(source: &str, edits: &[SourceEdit]) -> Result<String> {
+    let mut sorted: Vec<&SourceEdit> = edits.iter().collect();
+    sorted.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in sorted {
+        let (start, end) = (edit.range.start, edit.range.end);
+        if start > end || end > source.len() {
+            bail!(
+                "edit {}..{} is outside the {}-byte source",
+                start,
+                end,
+                source.len()
+            );
+        }
+        if !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+            bail!("edit {}..{} splits a UTF-8 character", start, end);
+        }
+        if start < cursor {
+            bail!(
+                "edit {}..{} overlaps an earlier edit ending at {}",
+                start,
+                end,
+                cursor
+            );
+        }
+        output.push_str(&source[cursor..start]);
+        output.push_str(&edit.text);
+        cursor = end;
+    }
+    output.push_str(&source[cursor..]);
+    Ok(output)
+}
@@ -0,0 +1,68 @@
+use crate::network::BlockedRequest;
+use std::fmt;
+use std::process::ExitCode;
+
+/// Process exit codes, so scripts can branch on the kind of failure
+/// instead of scraping stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Command completed without problems
+    Success = 0,
+    /// The WDL source has syntax errors
+    ParseErrors = 1,
+    /// Lint findings at error level
+    LintFailures = 2,
+    /// Unreadable files, bad arguments or configuration, and other failures
+    Usage = 3,
+    /// A compatibility check (e.g. `check-backend`) did not pass
+    CheckFailed = 4,
+    /// A network request was refused because of `--offline`
+    NetworkDisabled = 5,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Exit status for an error returned by a command; uncategorized errors are usage errors
+    pub fn of(error: &anyhow::Error) -> Self {
+        if error.downcast_ref::<BlockedRequest>().is_some() {
+            return ExitStatus::NetworkDisabled;
+        }
+        error
+            .downcast_ref::<Failure>()
+            .map(|failure| failure.status)
+            .unwrap_or(ExitStatus::Usage)
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status.code())
+    }
+}
+
+/// An error carrying the exit status it should produce
+#[derive(Debug)]
+pub struct Failure {
+    pub status: ExitStatus,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Build an error that exits with `status`
+pub fn failure(status: ExitStatus, message: impl Into<String>) -> anyhow::Error {
+    Failure {
+        status,
+        message: message.into(),
+    }
+    .into()
+}
@@ -0,0 +1,309 @@
+/// Keywords and literals that can appear where a name would
+const KEYWORDS: &[&str] = &[
+    "true", "false", "if", "then", "else", "None", "null", "object", "as", "in",
+];
+
+/// Names referenced by a WDL expression, as dotted member paths in order of
+/// first appearance (e.g. `align_reads.bam`, `samples`).
+///
+/// Function names, literal keywords, struct/object literal keys and
+/// placeholder options are skipped; placeholders inside string literals are
+/// scanned recursively.
+pub fn references(expr: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    scan(&expr.chars().collect::<Vec<_>>(), &mut refs);
+    refs
+}
+
+/// The first segment of each referenced path, deduplicated
+pub fn referenced_roots(expr: &str) -> Vec<String> {
+    let mut roots: Vec<String> = Vec::new();
+    for path in references(expr) {
+        let root = path.split('.').next().unwrap_or_default().to_string();
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// A whole-expression function call `name(arg, ...)`, split into the
+/// function name and its top-level arguments
+pub fn function_call(expr: &str) -> Option<(&str, Vec<&str>)> {
+    let expr = expr.trim();
+    let open = expr.find('(')?;
+    let name = expr[..open].trim();
+    if name.is_empty() || !name.chars().all(is_ident_char) || !expr.ends_with(')') {
+        return None;
+    }
+    // `f(a) + g(b)` ends with `)` but is not a single call
+    if closing_paren(&expr[open..])? != expr.len() - open - 1 {
+        return None;
+    }
+    Some((name, split_top_level(&expr[open + 1..expr.len() - 1])))
+}
+
+/// The elements of an array literal `[a, b, c]`
+pub fn array_literal(expr: &str) -> Option<Vec<&str>> {
+    let inner = expr.trim().strip_prefix('[')?.strip_suffix(']')?;
+    // A trailing comma leaves an empty last element
+    Some(
+        split_top_level(inner)
+            .into_iter()
+            .filter(|item| !item.is_empty())
+            .collect(),
+    )
+}
+
+/// Split on commas outside brackets, parentheses, braces and strings;
+/// empty text gives no parts
+pub fn split_top_level(text: &str) -> Vec<&str> {
+    let parts = split_outside_groups(text, ",");
+    if parts.len() == 1 && parts[0].is_empty() {
+        return Vec::new();
+    }
+    parts
+}
+
+/// Operands of a binary operator such as `&&` at the top level of an
+/// expression; a single element when the operator doesn't appear there
+pub fn split_operator<'a>(expr: &'a str, operator: &str) -> Vec<&'a str> {
+    split_outside_groups(expr, operator)
+}
+
+/// The expression without redundant outer parentheses: `((a || b))` gives `a || b`
+pub fn strip_parens(expr: &str) -> &str {
+    let mut expr = expr.trim();
+    while expr.starts_with('(') && closing_paren(expr) == Some(expr.len() - 1) {
+        expr = expr[1..expr.len() - 1].trim();
+    }
+    expr
+}
+
+/// The expression with every call to one of `functions` (arguments
+/// included) removed, e.g. to ignore references guarded by `select_first`
+pub fn without_calls(expr: &str, functions: &[&str]) -> String {
+    let mut out = String::new();
+    let mut rest = expr;
+    'outer: while !rest.is_empty() {
+        for function in functions {
+            if let Some(after) = rest.strip_prefix(function) {
+                let preceded_by_ident = out.chars().last().is_some_and(is_ident_char);
+                let open = after.len() - after.trim_start().len();
+                if !preceded_by_ident && after.trim_start().starts_with('(') {
+                    if let Some(close) = closing_paren(&after[open..]) {
+                        rest = &after[open + close + 1..];
+                        continue 'outer;
+                    }
+                }
+            }
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+/// Split on `separator` where it appears outside brackets, parentheses,
+/// braces and strings, trimming each part
+fn split_outside_groups<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ if depth == 0 && i >= start && text[i..].starts_with(separator) => {
+                parts.push(text[start..i].trim());
+                start = i + separator.len();
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// String literals in an expression as (byte offset of the opening quote,
+/// contents as written), placeholders included
+pub fn string_literals(expr: &str) -> Vec<(usize, String)> {
+    let mut literals = Vec::new();
+    let mut chars = expr.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c != '"' && c != '\'' {
+            continue;
+        }
+        let mut contents = String::new();
+        let mut escaped = false;
+        for (_, inner) in chars.by_ref() {
+            if escaped {
+                contents.push(inner);
+                escaped = false;
+            } else if inner == '\\' {
+                contents.push(inner);
+                escaped = true;
+            } else if inner == c {
+                break;
+            } else {
+                contents.push(inner);
+            }
+        }
+        literals.push((start, contents));
+    }
+    literals
+}
+
+/// Byte offset of the `)` closing the `(` that `text` starts with
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn scan(chars: &[char], refs: &mut Vec<String>) {
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' || c == '\'' {
+            i = scan_string(chars, i, refs);
+        } else if c == '#' {
+            // Comment until end of line
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && is_ident_char(chars[i]) {
+                i += 1;
+            }
+            // Extend with member accesses: a.b.c
+            while i + 1 < chars.len()
+                && chars[i] == '.'
+                && (chars[i + 1].is_ascii_alphabetic() || chars[i + 1] == '_')
+            {
+                i += 1;
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+            }
+
+            let path: String = chars[start..i].iter().collect();
+            let after_member = prev_non_space(chars, start) == Some('.');
+            let j = skip_spaces(chars, i);
+            let next = chars.get(j).copied();
+            let next_next = chars.get(j + 1).copied();
+            let is_function = next == Some('(');
+            let is_key = next == Some(':') || (next == Some('=') && next_next != Some('='));
+            let is_struct_literal = next == Some('{');
+            let is_keyword = KEYWORDS.contains(&path.as_str());
+
+            if !(after_member || is_function || is_key || is_struct_literal || is_keyword)
+                && !refs.contains(&path)
+            {
+                refs.push(path);
+            }
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Scan a string literal starting at `start`, returning the index after it
+fn scan_string(chars: &[char], start: usize, refs: &mut Vec<String>) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return i + 1,
+            '~' | '$' if chars.get(i + 1) == Some(&'{') => {
+                let end = matching_brace(chars, i + 1);
+                scan(&chars[i + 2..end], refs);
+                i = end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// Index of the `}` closing the `{` at `open` (or the end of input)
+fn matching_brace(chars: &[char], open: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    chars.len()
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn skip_spaces(chars: &[char], from: usize) -> usize {
+    let mut i = from;
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+fn prev_non_space(chars: &[char], before: usize) -> Option<char> {
+    chars[..before]
+        .iter()
+        .rev()
+        .find(|c| !c.is_whitespace())
+        .copied()
+}
@@ -0,0 +1,171 @@
+use crate::expr;
+use crate::info::WorkflowInfo;
+use serde::{Deserialize, Serialize};
+
+/// What reads a workflow input
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsumerKind {
+    /// An input of a call
+    Call,
+    /// The collection of a scatter, which every call inside it waits for
+    Scatter,
+    /// The condition of an `if` block
+    Conditional,
+    /// A workflow output expression
+    Output,
+}
+
+/// One place a workflow input ends up
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InputUse {
+    pub kind: ConsumerKind,
+    /// Call alias, scatter variable, `if` condition or output name
+    pub name: String,
+    /// Input of the call the value is passed to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+    /// Declarations the value flows through, from the input to the consumer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub via: Vec<String>,
+}
+
+/// Everything that reads one workflow input
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputFanOut {
+    pub name: String,
+    pub wdl_type: String,
+    /// The input has a default or an optional type, so callers can leave it out
+    pub optional: bool,
+    pub uses: Vec<InputUse>,
+    /// Distinct calls among `uses`
+    pub calls: usize,
+}
+
+/// Fan-out of every input of a workflow
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FanOutReport {
+    pub workflow: String,
+    pub inputs: Vec<InputFanOut>,
+    /// Inputs that nothing reads, candidates for removal
+    pub unused: Vec<String>,
+}
+
+/// Map each input of `workflow` to the call inputs, scatter and `if`
+/// expressions and outputs that read it, directly or through private
+/// declarations. A declaration nothing reads doesn't count as a use.
+pub fn fan_out(workflow: &WorkflowInfo) -> FanOutReport {
+    let mut consumers: Vec<(InputUse, &str)> = Vec::new();
+    for call in &workflow.calls {
+        for input in &call.inputs {
+            consumers.push((
+                consumer(ConsumerKind::Call, &call.name, Some(&input.name)),
+                &input.value,
+            ));
+        }
+    }
+    for scatter in &workflow.scatters {
+        consumers.push((
+            consumer(ConsumerKind::Scatter, &scatter.variable, None),
+            &scatter.expression,
+        ));
+    }
+    for conditional in &workflow.conditionals {
+        consumers.push((
+            consumer(ConsumerKind::Conditional, &conditional.expression, None),
+            &conditional.expression,
+        ));
+    }
+    for output in &workflow.outputs {
+        consumers.push((
+            consumer(ConsumerKind::Output, &output.name, None),
+            &output.expression,
+        ));
+    }
+
+    let mut inputs: Vec<InputFanOut> = workflow
+        .inputs
+        .iter()
+        .map(|input| InputFanOut {
+            name: input.name.clone(),
+            wdl_type: input.wdl_type.clone(),
+            optional: input.optional || input.default_value.is_some(),
+            uses: Vec::new(),
+            calls: 0,
+        })
+        .collect();
+    for (site, expression) in &consumers {
+        for (name, via) in input_sources(workflow, expression, &mut Vec::new()) {
+            let Some(input) = inputs.iter_mut().find(|input| input.name == name) else {
+                continue;
+            };
+            let found = InputUse {
+                via,
+                ..site.clone()
+            };
+            if !input.uses.contains(&found) {
+                input.uses.push(found);
+            }
+        }
+    }
+    for input in &mut inputs {
+        let mut calls: Vec<&str> = input
+            .uses
+            .iter()
+            .filter(|found| found.kind == ConsumerKind::Call)
+            .map(|found| found.name.as_str())
+            .collect();
+        calls.sort();
+        calls.dedup();
+        input.calls = calls.len();
+    }
+
+    FanOutReport {
+        workflow: workflow.name.clone(),
+        unused: inputs
+            .iter()
+            .filter(|input| input.uses.is_empty())
+            .map(|input| input.name.clone())
+            .collect(),
+        inputs,
+    }
+}
+
+fn consumer(kind: ConsumerKind, name: &str, parameter: Option<&str>) -> InputUse {
+    InputUse {
+        kind,
+        name: name.to_string(),
+        parameter: parameter.map(str::to_string),
+        via: Vec::new(),
+    }
+}
+
+/// Workflow inputs an expression reads, each with the declarations it
+/// passes through; `path` holds the declarations being followed
+fn input_sources(
+    workflow: &WorkflowInfo,
+    expression: &str,
+    path: &mut Vec<String>,
+) -> Vec<(String, Vec<String>)> {
+    let mut sources = Vec::new();
+    for root in expr::referenced_roots(expression) {
+        if workflow.inputs.iter().any(|input| input.name == root) {
+            let mut via = path.clone();
+            via.reverse();
+            sources.push((root, via));
+        } else if let Some(value) = workflow
+            .declarations
+            .iter()
+            .find(|d| d.name == root)
+            .and_then(|d| d.default_value.as_deref())
+        {
+            if path.contains(&root) {
+                continue;
+            }
+            path.push(root);
+            sources.extend(input_sources(workflow, value, path));
+            path.pop();
+        }
+    }
+    sources
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use ignore::WalkBuilder;
+use std::path::{Component, Path, PathBuf};
+
+/// Project-specific ignore file, using gitignore syntax
+pub const IGNORE_FILE: &str = ".wdlparseignore";
+
+/// Expand file arguments into a sorted, deduplicated list of WDL files.
+///
+/// Each argument may be a file (always included), a directory (searched
+/// recursively for `.wdl` files) or a glob pattern such as
+/// `workflows/**/*.wdl`, expanded without relying on the shell. Directory
+/// and glob searches skip hidden files and anything excluded by
+/// `.wdlparseignore` or `.gitignore` files.
+pub fn expand_inputs<S: AsRef<str>>(inputs: &[S]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let input = input.as_ref();
+        if is_glob(input) {
+            let matches = expand_glob(input)?;
+            if matches.is_empty() {
+                anyhow::bail!("No files match pattern: {}", input);
+            }
+            files.extend(matches);
+        } else {
+            let path = Path::new(input);
+            if !path.exists() {
+                anyhow::bail!("Path does not exist: {}", path.display());
+            }
+            if path.is_dir() {
+                files.extend(walk(path, None, |p| {
+                    p.extension().is_some_and(|ext| ext == "wdl")
+                })?);
+            } else {
+                files.push(path.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Whether an argument contains glob metacharacters
+pub fn is_glob(input: &str) -> bool {
+    input.contains(['*', '?', '[', '{'])
+}
+
+/// Files under `directory` whose path relative to it matches `pattern`,
+/// such as `**/*.wdl`, sorted. Hidden and ignored files are skipped as in
+/// `expand_inputs`.
+pub fn find(directory: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    if !directory.is_dir() {
+        anyhow::bail!("Not a directory: {}", directory.display());
+    }
+    let matcher = glob_matcher(pattern)?;
+    let base = clean_path(directory);
+    let mut files = walk(directory, None, |path| {
+        path.strip_prefix(&base)
+            .is_ok_and(|relative| matcher.is_match(relative))
+    })?;
+    files.sort();
+    Ok(files)
+}
+
+fn glob_matcher(pattern: &str) -> Result<GlobMatcher> {
+    Ok(GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .with_context(|| format!("Invalid glob pattern: {}", pattern))?
+        .compile_matcher())
+}
+
+fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let matcher = glob_matcher(pattern)?;
+
+    // Walk from the longest directory prefix without metacharacters
+    let mut base = PathBuf::new();
+    let mut remaining = 0;
+    let mut in_pattern = false;
+    for component in Path::new(pattern).components() {
+        let text = component.as_os_str().to_string_lossy();
+        if in_pattern || is_glob(&text) {
+            in_pattern = true;
+            remaining += 1;
+        } else {
+            base.push(component);
+        }
+    }
+    let max_depth = (!pattern.contains("**")).then_some(remaining);
+    let root = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    };
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    walk(&root, max_depth, |path| {
+        let relative = path.strip_prefix(".").unwrap_or(path);
+        matcher.is_match(relative) || matcher.is_match(path)
+    })
+}
+
+fn walk(
+    root: &Path,
+    max_depth: Option<usize>,
+    include: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let walker = WalkBuilder::new(root)
+        .max_depth(max_depth)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = clean_path(entry.path());
+        if include(&path) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Drop a leading `./` so reported paths match what users typed
+fn clean_path(path: &Path) -> PathBuf {
+    let mut components = path.components().peekable();
+    if components.peek() == Some(&Component::CurDir) {
+        components.next();
+    }
+    components.collect()
+}
@@ -0,0 +1,87 @@
+use crate::diagnostics::{self, DiagnosticGroup};
+use crate::lint::{Finding, Severity};
+use serde::{Deserialize, Serialize};
+use wdl_grammar::Diagnostic;
+
+/// Which parse diagnostics and lint findings are reported, and how severe
+/// they are. Codes are lint rule ids, and `syntax-error`, `syntax-warning`
+/// and `syntax-note` for parse diagnostics.
+#[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct DiagnosticFilter {
+    /// Report warnings as errors, so they fail the command
+    pub warnings_as_errors: bool,
+    /// Codes that are never reported
+    pub ignore: Vec<String>,
+    /// When not empty, only these codes are reported
+    pub only: Vec<String>,
+}
+
+impl DiagnosticFilter {
+    /// This filter with `overrides` (command-line flags) on top: ignored
+    /// codes add up, and a non-empty `only` list replaces this one
+    pub fn with(mut self, overrides: DiagnosticFilter) -> Self {
+        self.warnings_as_errors |= overrides.warnings_as_errors;
+        self.ignore.extend(overrides.ignore);
+        if !overrides.only.is_empty() {
+            self.only = overrides.only;
+        }
+        self
+    }
+
+    /// Whether diagnostics and findings with `code` are reported
+    pub fn keeps(&self, code: &str) -> bool {
+        !self.ignore.iter().any(|ignored| ignored == code)
+            && (self.only.is_empty() || self.only.iter().any(|only| only == code))
+    }
+
+    /// The parse diagnostics that are reported
+    pub fn diagnostics(&self, mut diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics.retain(|d| self.keeps(&diagnostics::code(d)));
+        diagnostics
+    }
+
+    /// Severity of a parse diagnostic as reported, e.g. `Warning` or `Error`
+    pub fn severity(&self, diagnostic: &Diagnostic) -> String {
+        let severity = format!("{:?}", diagnostic.severity());
+        if self.warnings_as_errors && severity == "Warning" {
+            "Error".to_string()
+        } else {
+            severity
+        }
+    }
+
+    /// Whether a parse diagnostic is reported as an error
+    pub fn is_error(&self, diagnostic: &Diagnostic) -> bool {
+        self.severity(diagnostic) == "Error"
+    }
+
+    /// Raise the warnings in `groups` to errors when asked to
+    pub fn escalate(&self, groups: &mut [DiagnosticGroup]) {
+        if !self.warnings_as_errors {
+            return;
+        }
+        for group in groups {
+            for located in std::iter::once(&mut group.cause).chain(&mut group.related) {
+                if located.severity == "warning" {
+                    located.severity = "error".to_string();
+                }
+            }
+        }
+    }
+
+    /// The lint findings that are reported, with warnings raised to errors
+    /// when asked to
+    pub fn findings(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        findings
+            .into_iter()
+            .filter(|finding| self.keeps(&finding.rule))
+            .map(|mut finding| {
+                if self.warnings_as_errors {
+                    finding.severity = Severity::Error;
+                }
+                finding
+            })
+            .collect()
+    }
+}
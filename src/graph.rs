@@ -0,0 +1,478 @@
+use crate::expr;
+use crate::info::WorkflowInfo;
+use crate::vendor;
+use crate::workspace::{SymbolKind, Workspace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Input,
+    Call,
+    Output,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub name: String,
+    /// Task or workflow targeted by a call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Outputs of the `from` call that `to` reads (e.g. `bam`, `bai`), in
+    /// order of first use; empty for edges from inputs or whole-call references
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<String>,
+}
+
+impl GraphEdge {
+    /// The consumed outputs as an edge label, e.g. `bam, bai`
+    pub fn label(&self) -> Option<String> {
+        (!self.variables.is_empty()).then(|| self.variables.join(", "))
+    }
+}
+
+/// Edges into and out of one node
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodeDegree {
+    pub id: String,
+    pub fan_in: usize,
+    pub fan_out: usize,
+}
+
+/// Shape metrics of a workflow graph
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GraphStats {
+    pub workflow: String,
+    pub nodes: usize,
+    pub edges: usize,
+    /// Calls on the longest dependency chain
+    pub longest_path: usize,
+    /// Number of calls that can run in parallel at each stage
+    pub stage_widths: Vec<usize>,
+    pub max_width: usize,
+    pub degrees: Vec<NodeDegree>,
+    /// Nodes with no edges at all
+    pub isolated: Vec<String>,
+}
+
+/// Data-flow graph of a workflow: inputs and calls feeding calls and outputs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorkflowGraph {
+    pub workflow: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// One workflow's graph within a [`WorkspaceGraph`], node ids prefixed so
+/// they are unique across the whole project
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubGraph {
+    /// `root`, or the prefixed id of the call that runs this workflow
+    pub id: String,
+    /// Path relative to the directory of the root workflow's file
+    pub file: String,
+    /// Sub-workflow calls between the root workflow and this one
+    pub depth: usize,
+    /// Graph containing the call that runs this workflow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(flatten)]
+    pub graph: WorkflowGraph,
+}
+
+/// Graphs of a root workflow and the sub-workflows it calls through local
+/// imports, recursively
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorkspaceGraph {
+    /// Levels of sub-workflows expanded, all of them when `None`
+    pub depth: Option<usize>,
+    /// The root workflow first, then each sub-workflow after its parent
+    pub graphs: Vec<SubGraph>,
+}
+
+impl WorkspaceGraph {
+    /// Expand sub-workflow calls of `workflow` in the workspace's root
+    /// document down to `depth` levels. Calls of workflows that can't be
+    /// resolved (remote or missing imports) stay plain call nodes.
+    pub fn build(workspace: &Workspace, workflow: &WorkflowInfo, depth: Option<usize>) -> Self {
+        let mut graph = WorkspaceGraph {
+            depth,
+            graphs: Vec::new(),
+        };
+        let root = workspace.root();
+        graph.expand(workspace, &root.path, workflow, None, 0);
+        graph
+    }
+
+    fn expand(
+        &mut self,
+        workspace: &Workspace,
+        path: &Path,
+        workflow: &WorkflowInfo,
+        call: Option<(&str, &str)>,
+        depth: usize,
+    ) {
+        let (id, parent, prefix) = match call {
+            Some((parent, call)) => (
+                call.to_string(),
+                Some(parent.to_string()),
+                format!("{}__", call),
+            ),
+            None => ("root".to_string(), None, String::new()),
+        };
+        let mut graph = WorkflowGraph::build(workflow);
+        graph.prefix(&prefix);
+        let calls: Vec<(String, String)> = graph
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node.id.clone(), node.target.clone()?)))
+            .collect();
+        let directory = workspace.root().path.parent().unwrap_or(Path::new(""));
+        let file = vendor::relative_path(directory, path);
+        self.graphs.push(SubGraph {
+            id: id.clone(),
+            file,
+            depth,
+            parent,
+            graph,
+        });
+
+        if self.depth.is_some_and(|max| depth >= max) {
+            return;
+        }
+        for (call, target) in calls {
+            let Some(symbol) = workspace.lookup(path, &target) else {
+                continue;
+            };
+            if symbol.kind != SymbolKind::Workflow {
+                continue;
+            }
+            let Some(document) = workspace.document(&symbol.path) else {
+                continue;
+            };
+            let Some(sub) = document
+                .info
+                .workflows
+                .iter()
+                .find(|w| w.name == symbol.name)
+            else {
+                continue;
+            };
+            // A workflow reached again through its own calls is not expanded twice
+            let file = vendor::relative_path(directory, &document.path);
+            if !self.runs_within(&id, &file, &sub.name) {
+                self.expand(
+                    workspace,
+                    &document.path,
+                    sub,
+                    Some((&id, &call)),
+                    depth + 1,
+                );
+            }
+        }
+    }
+
+    /// Whether graph `id` or one of its ancestors is `workflow` of `file`
+    fn runs_within(&self, id: &str, file: &str, workflow: &str) -> bool {
+        let mut current = self.graphs.iter().find(|graph| graph.id == id);
+        while let Some(graph) = current {
+            if graph.file == file && graph.graph.workflow == workflow {
+                return true;
+            }
+            current = graph
+                .parent
+                .as_ref()
+                .and_then(|parent| self.graphs.iter().find(|graph| &graph.id == parent));
+        }
+        false
+    }
+}
+
+impl WorkflowGraph {
+    pub fn build(workflow: &WorkflowInfo) -> Self {
+        let mut graph = WorkflowGraph {
+            workflow: workflow.name.clone(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        for input in &workflow.inputs {
+            graph.add_node(NodeKind::Input, &input.name, None);
+        }
+        for call in &workflow.calls {
+            graph.add_node(NodeKind::Call, &call.name, Some(call.target.clone()));
+        }
+        for output in &workflow.outputs {
+            graph.add_node(NodeKind::Output, &output.name, None);
+        }
+
+        for call in &workflow.calls {
+            let to = node_id(NodeKind::Call, &call.name);
+            let scope =
+                enclosing_scatter_variables(workflow, std::slice::from_ref(&call.name), None);
+            for input in &call.inputs {
+                graph.add_reference_edges(workflow, &input.value, &scope, &to);
+            }
+        }
+        // A call inside a scatter or conditional also waits for what the
+        // block's expression reads
+        let scatters = workflow
+            .scatters
+            .iter()
+            .map(|s| (&s.expression, &s.calls, Some(s.variable.as_str())));
+        let conditionals = workflow
+            .conditionals
+            .iter()
+            .map(|c| (&c.expression, &c.calls, None));
+        for (expression, calls, variable) in scatters.chain(conditionals) {
+            let scope = enclosing_scatter_variables(workflow, calls, variable);
+            for call in calls {
+                let to = node_id(NodeKind::Call, call);
+                graph.add_reference_edges(workflow, expression, &scope, &to);
+            }
+        }
+        for output in &workflow.outputs {
+            let to = node_id(NodeKind::Output, &output.name);
+            graph.add_reference_edges(workflow, &output.expression, &[], &to);
+        }
+
+        graph
+    }
+
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Number of edges ending at `id`
+    pub fn fan_in(&self, id: &str) -> usize {
+        self.edges.iter().filter(|edge| edge.to == id).count()
+    }
+
+    /// Number of edges starting at `id`
+    pub fn fan_out(&self, id: &str) -> usize {
+        self.edges.iter().filter(|edge| edge.from == id).count()
+    }
+
+    /// Nodes that no edge touches
+    pub fn isolated(&self) -> Vec<&GraphNode> {
+        self.nodes
+            .iter()
+            .filter(|node| self.fan_in(&node.id) == 0 && self.fan_out(&node.id) == 0)
+            .collect()
+    }
+
+    /// Calls grouped by stage: a call's stage is the number of calls on the
+    /// longest chain leading to it, so calls in one stage can run in parallel
+    pub fn stages(&self) -> Vec<Vec<&GraphNode>> {
+        let mut depths = HashMap::new();
+        let mut stages: Vec<Vec<&GraphNode>> = Vec::new();
+        for node in self.nodes.iter().filter(|n| n.kind == NodeKind::Call) {
+            let depth = self.call_depth(&node.id, &mut depths, &mut Vec::new());
+            if stages.len() < depth {
+                stages.resize_with(depth, Vec::new);
+            }
+            stages[depth - 1].push(node);
+        }
+        stages
+    }
+
+    /// Calls on the longest dependency chain
+    pub fn longest_path(&self) -> usize {
+        self.stages().len()
+    }
+
+    /// Most calls in any one stage
+    pub fn max_width(&self) -> usize {
+        self.stages().iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn stats(&self) -> GraphStats {
+        let stage_widths: Vec<usize> = self.stages().iter().map(Vec::len).collect();
+        GraphStats {
+            workflow: self.workflow.clone(),
+            nodes: self.nodes.len(),
+            edges: self.edges.len(),
+            longest_path: stage_widths.len(),
+            max_width: stage_widths.iter().copied().max().unwrap_or(0),
+            stage_widths,
+            degrees: self
+                .nodes
+                .iter()
+                .map(|node| NodeDegree {
+                    id: node.id.clone(),
+                    fan_in: self.fan_in(&node.id),
+                    fan_out: self.fan_out(&node.id),
+                })
+                .collect(),
+            isolated: self.isolated().iter().map(|node| node.id.clone()).collect(),
+        }
+    }
+
+    /// Calls on the longest chain ending at call `id`, itself included; a
+    /// cycle (which WDL forbids) is cut where it closes
+    fn call_depth<'a>(
+        &'a self,
+        id: &'a str,
+        depths: &mut HashMap<&'a str, usize>,
+        path: &mut Vec<&'a str>,
+    ) -> usize {
+        if let Some(depth) = depths.get(id) {
+            return *depth;
+        }
+        if path.contains(&id) {
+            return 0;
+        }
+        path.push(id);
+        let upstream = self
+            .edges
+            .iter()
+            .filter(|edge| edge.to == id)
+            .filter(|edge| self.node(&edge.from).map(|n| n.kind) == Some(NodeKind::Call))
+            .map(|edge| self.call_depth(&edge.from, depths, path))
+            .max()
+            .unwrap_or(0);
+        path.pop();
+        depths.insert(id, upstream + 1);
+        upstream + 1
+    }
+
+    /// Prepend `prefix` to every node id
+    pub fn prefix(&mut self, prefix: &str) {
+        for node in &mut self.nodes {
+            node.id.insert_str(0, prefix);
+        }
+        for edge in &mut self.edges {
+            edge.from.insert_str(0, prefix);
+            edge.to.insert_str(0, prefix);
+        }
+    }
+
+    fn add_node(&mut self, kind: NodeKind, name: &str, target: Option<String>) {
+        let id = node_id(kind, name);
+        if self.node(&id).is_none() {
+            self.nodes.push(GraphNode {
+                id,
+                kind,
+                name: name.to_string(),
+                target,
+            });
+        }
+    }
+
+    fn add_edge(&mut self, from: String, to: &str, variable: Option<String>) {
+        if from == to {
+            return;
+        }
+        let index = match self
+            .edges
+            .iter()
+            .position(|edge| edge.from == from && edge.to == to)
+        {
+            Some(index) => index,
+            None => {
+                self.edges.push(GraphEdge {
+                    from,
+                    to: to.to_string(),
+                    variables: Vec::new(),
+                });
+                self.edges.len() - 1
+            }
+        };
+        let variables = &mut self.edges[index].variables;
+        if let Some(variable) = variable.filter(|variable| !variables.contains(variable)) {
+            variables.push(variable);
+        }
+    }
+
+    /// Edges from the calls and workflow inputs an expression refers to.
+    /// Names in `scope` are scatter variables bound around the expression
+    /// and never create edges themselves.
+    fn add_reference_edges(
+        &mut self,
+        workflow: &WorkflowInfo,
+        expression: &str,
+        scope: &[&str],
+        to: &str,
+    ) {
+        let mut visited: Vec<String> = scope.iter().map(|name| name.to_string()).collect();
+        for (from, variable) in sources(workflow, expression, &mut visited) {
+            self.add_edge(from, to, variable);
+        }
+    }
+}
+
+/// Variables of the scatters whose bodies contain all of `calls`; `own` is
+/// the variable of the block being resolved, which is not yet in scope for
+/// its own collection expression
+fn enclosing_scatter_variables<'a>(
+    workflow: &'a WorkflowInfo,
+    calls: &[String],
+    own: Option<&str>,
+) -> Vec<&'a str> {
+    if calls.is_empty() {
+        return Vec::new();
+    }
+    workflow
+        .scatters
+        .iter()
+        .filter(|scatter| Some(scatter.variable.as_str()) != own)
+        .filter(|scatter| calls.iter().all(|call| scatter.calls.contains(call)))
+        .map(|scatter| scatter.variable.as_str())
+        .collect()
+}
+
+/// Node ids of the calls and workflow inputs an expression depends on,
+/// following intermediate declarations, each with the call output it reads
+/// (`bam` for `align.bam`). Names in `visited` (scatter variables in scope
+/// and declarations already followed) are skipped.
+fn sources(
+    workflow: &WorkflowInfo,
+    expression: &str,
+    visited: &mut Vec<String>,
+) -> Vec<(String, Option<String>)> {
+    let mut ids = Vec::new();
+    for path in expr::references(expression) {
+        let mut segments = path.split('.');
+        let root = segments.next().unwrap_or_default().to_string();
+        if visited.contains(&root) {
+            continue;
+        }
+
+        if workflow.calls.iter().any(|c| c.name == root) {
+            let output = segments.next().map(str::to_string);
+            ids.push((node_id(NodeKind::Call, &root), output));
+        } else if workflow.inputs.iter().any(|i| i.name == root) {
+            visited.push(root.clone());
+            ids.push((node_id(NodeKind::Input, &root), None));
+        } else if let Some(value) = workflow
+            .declarations
+            .iter()
+            .find(|d| d.name == root)
+            .and_then(|d| d.default_value.as_deref())
+        {
+            visited.push(root);
+            ids.extend(sources(workflow, value, visited));
+        }
+    }
+    ids
+}
+
+/// Mermaid-safe node id, e.g. `call_align`
+pub fn node_id(kind: NodeKind, name: &str) -> String {
+    let prefix = match kind {
+        NodeKind::Input => "input",
+        NodeKind::Call => "call",
+        NodeKind::Output => "output",
+    };
+    format!("{}_{}", prefix, name)
+}
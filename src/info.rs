@@ -1,3 +1,7 @@
+use crate::command::{CommandLanguage, CommandSection};
+use crate::expr;
+use crate::runtime::NormalizedRuntime;
+use crate::types::WdlType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Serialize, Deserialize, Debug)]
@@ -9,30 +13,118 @@ pub struct WdlInfo {
     pub imports: Vec<ImportInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Default, Serialize, Deserialize, Debug)]
 pub struct TaskInfo {
     pub name: String,
+    /// 1-based line of the `task` keyword
+    #[serde(default)]
+    pub line: usize,
+    /// Whether the task was extracted whole; only best-effort extraction
+    /// checks this, so it is `None` (and left out of JSON) otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete: Option<bool>,
+    /// Comment block directly above the task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
     pub inputs: Vec<InputInfo>,
     pub outputs: Vec<OutputInfo>,
+    /// Private declarations in the task body, outside the input section
+    #[serde(default)]
+    pub declarations: Vec<InputInfo>,
+    /// Command text between the delimiters, exactly as written
     pub command: Option<String>,
+    /// Delimiter style and dedented form of the command
+    pub command_section: Option<CommandSection>,
+    /// Language the command is written in, for highlighting and
+    /// language-aware checks
+    #[serde(default)]
+    pub command_language: Option<CommandLanguage>,
     pub runtime: Vec<RuntimeItem>,
+    /// `runtime` with dialect differences (attribute names, units) resolved
+    #[serde(default)]
+    pub normalized_runtime: NormalizedRuntime,
     pub meta: Vec<MetaItem>,
     pub parameter_meta: Vec<MetaItem>,
+    #[serde(flatten)]
+    pub authorship: Authorship,
+    /// Source text of the definition, with `info --include-source`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceExcerpt>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Default, Serialize, Deserialize, Debug)]
 pub struct WorkflowInfo {
     pub name: String,
+    /// 1-based line of the `workflow` keyword
+    #[serde(default)]
+    pub line: usize,
+    /// Whether the workflow was extracted whole; only best-effort extraction
+    /// checks this, so it is `None` (and left out of JSON) otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete: Option<bool>,
+    /// Comment block directly above the workflow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
     pub inputs: Vec<InputInfo>,
     pub outputs: Vec<OutputInfo>,
     pub calls: Vec<CallInfo>,
+    /// Private declarations in the workflow body (including nested blocks)
+    pub declarations: Vec<InputInfo>,
+    pub scatters: Vec<ScatterInfo>,
+    pub conditionals: Vec<ConditionalInfo>,
     pub meta: Vec<MetaItem>,
     pub parameter_meta: Vec<MetaItem>,
+    #[serde(flatten)]
+    pub authorship: Authorship,
+    /// Source text of the definition, with `info --include-source`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceExcerpt>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Default cap on the source text embedded for one definition
+pub const MAX_SOURCE_BYTES: usize = 64 * 1024;
+
+/// The exact text of a definition and where it sits in the file. Offsets
+/// count bytes of the text as read: UTF-8, `\n` line endings, no byte-order
+/// mark.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceExcerpt {
+    /// Offset of the `task` or `workflow` keyword
+    pub start: usize,
+    /// Offset just past the closing brace
+    pub end: usize,
+    pub text: String,
+    /// The text was cut at the size limit and ends before `end`
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl SourceExcerpt {
+    /// `range` of `content`, cut to at most `limit` bytes on a character boundary
+    pub fn new(content: &str, range: std::ops::Range<usize>, limit: usize) -> Self {
+        let mut cut = range.end.min(range.start + limit);
+        while !content.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        Self {
+            start: range.start,
+            end: range.end,
+            text: content[range.start..cut].to_string(),
+            truncated: cut < range.end,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug)]
 pub struct StructInfo {
     pub name: String,
+    /// 1-based line of the `struct` keyword
+    #[serde(default)]
+    pub line: usize,
+    /// Whether the struct was extracted whole; only best-effort extraction
+    /// checks this, so it is `None` (and left out of JSON) otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete: Option<bool>,
     pub fields: Vec<InputInfo>,
 }
 
@@ -46,15 +138,45 @@ pub struct ImportInfo {
 pub struct InputInfo {
     pub name: String,
     pub wdl_type: String,
+    /// Structured form of `wdl_type`, absent if the type text could not be parsed
+    pub wdl_type_parsed: Option<WdlType>,
     pub optional: bool,
     pub default_value: Option<String>,
+    /// Comment block directly above the declaration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct OutputInfo {
     pub name: String,
+    /// 1-based line and column where the declaration starts
+    #[serde(default)]
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
     pub wdl_type: String,
+    /// Structured form of `wdl_type`, absent if the type text could not be parsed
+    pub wdl_type_parsed: Option<WdlType>,
+    /// Empty when the declaration has no value
     pub expression: String,
+    /// Files the expression picks up from the task's working directory
+    #[serde(default, skip_serializing_if = "FilePatterns::is_empty")]
+    pub files: FilePatterns,
+}
+
+/// Files an output expression reads: `glob()` patterns, `stdout()`/`stderr()`
+/// and literal file names (for `File`/`Directory` outputs), placeholders kept
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilePatterns {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub globs: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stdout: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stderr: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub literals: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -62,9 +184,33 @@ pub struct CallInfo {
     pub name: String,
     pub target: String,
     pub alias: Option<String>,
+    /// 1-based line of the `call`
+    #[serde(default)]
+    pub line: usize,
     pub inputs: Vec<CallInputItem>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScatterInfo {
+    pub variable: String,
+    pub expression: String,
+    /// Names of the calls inside the scatter body, including nested blocks
+    pub calls: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConditionalInfo {
+    pub expression: String,
+    /// 1-based line of the `if`
+    #[serde(default)]
+    pub line: usize,
+    /// Names of the calls inside the `if` body, including nested blocks
+    pub calls: Vec<String>,
+    /// Names of the declarations inside the `if` body, including nested blocks
+    #[serde(default)]
+    pub declarations: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CallInputItem {
     pub name: String,
@@ -75,6 +221,8 @@ pub struct CallInputItem {
 pub struct RuntimeItem {
     pub key: String,
     pub value: String,
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -83,8 +231,184 @@ pub struct MetaItem {
     pub value: String,
 }
 
+/// Authorship and description keys from a `meta` section, unquoted
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Authorship {
+    /// `author`, `authors` or `maintainer`
+    pub author: Option<String>,
+    /// `email` or `contact`
+    pub email: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Authorship {
+    pub fn from_meta(meta: &[MetaItem]) -> Self {
+        let item = |keys: &[&str]| meta.iter().find(|item| keys.contains(&item.key.as_str()));
+        let find = |keys: &[&str]| item(keys).map(MetaItem::text);
+        Self {
+            // An array of authors reads like a comma-separated list
+            author: item(&["author", "authors", "maintainer"])
+                .map(|author| author.items().join(", ")),
+            email: find(&["email", "contact"]),
+            version: find(&["version"]),
+            description: find(&["description"]),
+        }
+    }
+
+    /// Individual names in `author`, which may list several separated by
+    /// commas or as an array
+    pub fn authors(&self) -> Vec<&str> {
+        self.author
+            .iter()
+            .flat_map(|author| author.split(','))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+}
+
+/// Order of the tasks, workflows, structs, imports and calls of a document
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// Source order: by line, then as they appear within a line
+    #[default]
+    Position,
+    /// By name (imports by URI), ties in source order
+    Name,
+}
+
 impl WdlInfo {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Put the collections in `order`. Sorts are stable, so calls, imports
+    /// and the call names of scatters and conditionals, which carry no line,
+    /// keep their source order under `position`.
+    pub fn sort(&mut self, order: SortBy) {
+        match order {
+            SortBy::Position => {
+                self.tasks.sort_by_key(|task| task.line);
+                self.workflows.sort_by_key(|workflow| workflow.line);
+                self.structs.sort_by_key(|definition| definition.line);
+            }
+            SortBy::Name => {
+                self.tasks
+                    .sort_by(|a, b| (&a.name, a.line).cmp(&(&b.name, b.line)));
+                self.workflows
+                    .sort_by(|a, b| (&a.name, a.line).cmp(&(&b.name, b.line)));
+                self.structs
+                    .sort_by(|a, b| (&a.name, a.line).cmp(&(&b.name, b.line)));
+                self.imports.sort_by(|a, b| a.uri.cmp(&b.uri));
+                for workflow in &mut self.workflows {
+                    workflow.calls.sort_by(|a, b| a.name.cmp(&b.name));
+                    for scatter in &mut workflow.scatters {
+                        scatter.calls.sort();
+                    }
+                    for conditional in &mut workflow.conditionals {
+                        conditional.calls.sort();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ImportInfo {
+    /// Namespace calls use for this import: the alias, or the file name without `.wdl`
+    pub fn namespace(&self) -> String {
+        if let Some(alias) = &self.alias {
+            return alias.clone();
+        }
+        let file = self.uri.rsplit('/').next().unwrap_or(&self.uri);
+        file.strip_suffix(".wdl").unwrap_or(file).to_string()
+    }
+}
+
+impl FilePatterns {
+    pub fn from_expression(expression: &str, wdl_type: Option<&WdlType>) -> Self {
+        let is_file = wdl_type.is_some_and(|t| !t.path_types().is_empty());
+        let mut patterns = FilePatterns {
+            stdout: expression.contains("stdout("),
+            stderr: expression.contains("stderr("),
+            ..Default::default()
+        };
+        for (start, literal) in expr::string_literals(expression) {
+            let before = expression[..start].trim_end();
+            if before.ends_with("glob(") {
+                patterns.globs.push(literal);
+            } else if is_file && !literal.is_empty() {
+                patterns.literals.push(literal);
+            }
+        }
+        patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty() && !self.stdout && !self.stderr && self.literals.is_empty()
+    }
+}
+
+impl MetaItem {
+    /// The value without surrounding quotes when it is a string literal
+    pub fn text(&self) -> String {
+        unquote(&self.value).to_string()
+    }
+
+    /// The elements of an array value without their quotes, or else the
+    /// value as a single element
+    pub fn items(&self) -> Vec<String> {
+        match expr::array_literal(&self.value) {
+            Some(elements) => elements
+                .into_iter()
+                .map(|element| unquote(element).to_string())
+                .collect(),
+            None => vec![self.text()],
+        }
+    }
+
+    /// Human description of a `parameter_meta` entry: a plain string, or the
+    /// `help` or `description` string of an object
+    pub fn description(&self) -> Option<String> {
+        let value = self.value.trim();
+        if value.starts_with('"') || value.starts_with('\'') {
+            return Some(self.text());
+        }
+        ["help", "description"].iter().find_map(|key| {
+            value.match_indices(key).find_map(|(start, _)| {
+                let rest = value[start + key.len()..].trim_start().strip_prefix(':')?;
+                let rest = rest.trim_start();
+                let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+                let end = rest[1..].find(quote)?;
+                Some(rest[1..1 + end].to_string())
+            })
+        })
+    }
+}
+
+/// `value` without surrounding quotes when it is a string literal
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+impl RuntimeItem {
+    /// The value as plain text if it is a string literal without placeholders
+    pub fn literal_value(&self) -> Option<&str> {
+        let value = self.value.trim();
+        let unquoted = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))?;
+        if unquoted.contains("~{") || unquoted.contains("${") {
+            None
+        } else {
+            Some(unquoted)
+        }
+    }
 }
@@ -0,0 +1,446 @@
+use crate::info::{InputInfo, MetaItem, StructInfo, WdlInfo};
+use crate::types::WdlType;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The inputs of the workflow or task an inputs file is written for
+pub struct InputSignature<'a> {
+    pub name: &'a str,
+    pub inputs: &'a [InputInfo],
+    pub parameter_meta: &'a [MetaItem],
+}
+
+impl<'a> InputSignature<'a> {
+    /// Select a workflow or task by name, defaulting to the first workflow, then the first task
+    pub fn select(info: &'a WdlInfo, name: Option<&str>) -> Result<Self> {
+        let workflows = info
+            .workflows
+            .iter()
+            .map(|w| (&w.name, &w.inputs, &w.parameter_meta));
+        let tasks = info
+            .tasks
+            .iter()
+            .map(|t| (&t.name, &t.inputs, &t.parameter_meta));
+        let mut candidates = workflows.chain(tasks);
+
+        let found = match name {
+            Some(name) => candidates.find(|(candidate, _, _)| candidate.as_str() == name),
+            None => candidates.next(),
+        };
+        match found {
+            Some((name, inputs, parameter_meta)) => Ok(Self {
+                name,
+                inputs,
+                parameter_meta,
+            }),
+            None => match name {
+                Some(name) => anyhow::bail!("No workflow or task named '{}'", name),
+                None => anyhow::bail!("No workflow or task found"),
+            },
+        }
+    }
+
+    /// Fully qualified key of an input, e.g. `my_workflow.sample_name`
+    pub fn key(&self, input: &InputInfo) -> String {
+        format!("{}.{}", self.name, input.name)
+    }
+}
+
+/// Whether an input has to be supplied (no default and not optional)
+pub fn is_required(input: &InputInfo) -> bool {
+    input.default_value.is_none() && !input.optional
+}
+
+/// Inputs JSON skeleton with `null` for each value to fill in.
+///
+/// Nonempty arrays (`Array[T]+`) get one placeholder element so the template
+/// shows that at least one value is needed. Optional inputs and inputs with
+/// defaults are only listed when `include_optional` is set.
+pub fn template(signature: &InputSignature, include_optional: bool) -> Map<String, Value> {
+    signature
+        .inputs
+        .iter()
+        .filter(|input| include_optional || is_required(input))
+        .map(|input| {
+            let value = match (&input.wdl_type_parsed, is_required(input)) {
+                (Some(wdl_type), true) => placeholder(wdl_type),
+                _ => Value::Null,
+            };
+            (signature.key(input), value)
+        })
+        .collect()
+}
+
+fn placeholder(wdl_type: &WdlType) -> Value {
+    match wdl_type {
+        WdlType::Array {
+            item,
+            nonempty: true,
+        } => Value::Array(vec![placeholder(item)]),
+        _ => Value::Null,
+    }
+}
+
+/// File formats recognised in input names (`input_bam`) and parameter_meta text
+const FILE_FORMATS: &[&str] = &[
+    "bam", "bai", "cram", "crai", "sam", "vcf", "bcf", "bed", "gtf", "gff", "fastq", "fq", "fasta",
+    "fa", "tsv", "csv", "json", "txt",
+];
+
+/// How deep struct fields are followed when synthesizing values
+const MAX_STRUCT_DEPTH: usize = 8;
+
+/// Inputs JSON with plausible example values instead of nulls, for smoke
+/// tests and documentation.
+///
+/// A `parameter_meta` entry's `example` is used when it fits the type, and
+/// file extensions are taken from the input name (`input_bam`), `*.ext`
+/// patterns or format names in its help text. Otherwise Files are named after
+/// the input, numbers are 1, Booleans false and Strings the input name.
+/// Inputs with a literal default keep it when `include_optional` lists them.
+pub fn synthesize(
+    signature: &InputSignature,
+    structs: &[StructInfo],
+    include_optional: bool,
+) -> Map<String, Value> {
+    signature
+        .inputs
+        .iter()
+        .filter(|input| include_optional || is_required(input))
+        .map(|input| {
+            let hint = signature
+                .parameter_meta
+                .iter()
+                .find(|item| item.key == input.name)
+                .map(|item| item.value.as_str())
+                .unwrap_or_default();
+            let fits = |value: &Value| {
+                input.wdl_type_parsed.as_ref().is_some_and(|wdl_type| {
+                    let mut problems = Vec::new();
+                    check_value(wdl_type, value, structs, "", &mut problems);
+                    problems.is_empty()
+                })
+            };
+
+            let example = meta_example(hint).filter(|value| fits(value));
+            let default = input
+                .default_value
+                .as_deref()
+                .and_then(|text| serde_json::from_str(text).ok())
+                .filter(|value| fits(value));
+            let value = example
+                .or(default)
+                .unwrap_or_else(|| match &input.wdl_type_parsed {
+                    Some(wdl_type) => {
+                        let extension = file_extension(&input.name, hint);
+                        example_value(wdl_type, &input.name, extension.as_deref(), structs, 0)
+                    }
+                    None => Value::Null,
+                });
+            (signature.key(input), value)
+        })
+        .collect()
+}
+
+fn example_value(
+    wdl_type: &WdlType,
+    name: &str,
+    extension: Option<&str>,
+    structs: &[StructInfo],
+    depth: usize,
+) -> Value {
+    match wdl_type {
+        WdlType::Optional { inner } => example_value(inner, name, extension, structs, depth),
+        WdlType::Primitive { name: primitive } => match primitive.as_str() {
+            "Boolean" => Value::Bool(false),
+            "Int" => Value::from(1),
+            "Float" => Value::from(1.0),
+            "File" => Value::from(file_name(name, extension)),
+            "Object" => Value::Object(Map::new()),
+            _ => Value::from(name),
+        },
+        WdlType::Array { item, .. } => Value::Array(vec![example_value(
+            item,
+            &format!("{}_1", name),
+            extension,
+            structs,
+            depth,
+        )]),
+        WdlType::Map { value, .. } => {
+            let mut entries = Map::new();
+            entries.insert(
+                "key".to_string(),
+                example_value(value, name, extension, structs, depth),
+            );
+            Value::Object(entries)
+        }
+        WdlType::Pair { left, right } => serde_json::json!({
+            "left": example_value(left, name, extension, structs, depth),
+            "right": example_value(right, name, extension, structs, depth),
+        }),
+        WdlType::Ref { name: struct_name } => {
+            let definition = structs.iter().find(|s| &s.name == struct_name);
+            match definition {
+                Some(definition) if depth < MAX_STRUCT_DEPTH => definition
+                    .fields
+                    .iter()
+                    .filter_map(|field| {
+                        let field_type = field.wdl_type_parsed.as_ref()?;
+                        let extension = file_extension(&field.name, "");
+                        let value = example_value(
+                            field_type,
+                            &field.name,
+                            extension.as_deref(),
+                            structs,
+                            depth + 1,
+                        );
+                        Some((field.name.clone(), value))
+                    })
+                    .collect(),
+                _ => Value::Object(Map::new()),
+            }
+        }
+    }
+}
+
+/// `input_bam` -> `input.bam`, otherwise the name plus the hinted extension
+fn file_name(name: &str, extension: Option<&str>) -> String {
+    if let Some((stem, format)) = name.rsplit_once('_') {
+        if FILE_FORMATS.contains(&format) && extension.is_none_or(|ext| ext == format) {
+            return format!("{}.{}", stem, format);
+        }
+    }
+    format!("{}.{}", name, extension.unwrap_or("txt"))
+}
+
+/// Extension from the input name, a `*.ext` pattern in its parameter_meta, or
+/// a format named in the help text
+fn file_extension(name: &str, hint: &str) -> Option<String> {
+    if let Some((_, format)) = name.rsplit_once('_') {
+        if FILE_FORMATS.contains(&format) {
+            return Some(format.to_string());
+        }
+    }
+    if let Some(start) = hint.find("*.") {
+        let extension: String = hint[start + 2..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '.')
+            .collect();
+        if !extension.is_empty() {
+            return Some(extension.trim_end_matches('.').to_string());
+        }
+    }
+    let lower = hint.to_ascii_lowercase();
+    lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|word| FILE_FORMATS.contains(word))
+        .map(str::to_string)
+}
+
+/// The `example` of a `parameter_meta` object, e.g. `{help: "...", example: 8}`
+fn meta_example(meta: &str) -> Option<Value> {
+    meta.match_indices("example").find_map(|(start, key)| {
+        let rest = meta[start + key.len()..].trim_start().strip_prefix(':')?;
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut end = rest.len();
+        for (i, c) in rest.char_indices() {
+            match c {
+                '"' => in_string = !in_string,
+                '[' | '{' if !in_string => depth += 1,
+                ']' | '}' if !in_string && depth > 0 => depth -= 1,
+                ',' | '}' | '\n' if !in_string && depth == 0 => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        serde_json::from_str(rest[..end].trim()).ok()
+    })
+}
+
+/// A problem found in an inputs file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputProblem {
+    pub key: String,
+    pub message: String,
+}
+
+/// Validate an inputs JSON object against a signature.
+///
+/// Reports unknown keys, missing required inputs and values that don't match
+/// the declared type, including empty arrays for nonempty (`+`) types and
+/// `null` where the type (or a nested type) is not optional.
+pub fn check(
+    signature: &InputSignature,
+    structs: &[StructInfo],
+    inputs: &Value,
+) -> Vec<InputProblem> {
+    let mut problems = Vec::new();
+    let Some(object) = inputs.as_object() else {
+        problems.push(InputProblem {
+            key: String::new(),
+            message: "inputs must be a JSON object".to_string(),
+        });
+        return problems;
+    };
+
+    for key in object.keys() {
+        let known = signature
+            .inputs
+            .iter()
+            .any(|input| signature.key(input) == *key);
+        // `workflow.call.input` keys set call inputs and aren't checked here
+        let call_input =
+            key.starts_with(&format!("{}.", signature.name)) && key.matches('.').count() > 1;
+        if !known && !call_input {
+            problems.push(InputProblem {
+                key: key.clone(),
+                message: "unknown input".to_string(),
+            });
+        }
+    }
+
+    for input in signature.inputs {
+        let key = signature.key(input);
+        match object.get(&key) {
+            None if is_required(input) => problems.push(InputProblem {
+                key,
+                message: format!("required input of type {} is missing", input.wdl_type),
+            }),
+            None => {}
+            Some(value) => {
+                if let Some(wdl_type) = &input.wdl_type_parsed {
+                    check_value(wdl_type, value, structs, &key, &mut problems);
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+fn check_value(
+    wdl_type: &WdlType,
+    value: &Value,
+    structs: &[StructInfo],
+    key: &str,
+    problems: &mut Vec<InputProblem>,
+) {
+    match (wdl_type, value) {
+        (WdlType::Optional { .. }, Value::Null) => {}
+        (WdlType::Optional { inner }, value) => check_value(inner, value, structs, key, problems),
+        (_, Value::Null) => report(
+            problems,
+            key,
+            format!("null is not allowed for non-optional type {}", wdl_type),
+        ),
+        (WdlType::Primitive { name }, value) => {
+            let matches = match name.as_str() {
+                "Boolean" => value.is_boolean(),
+                "Int" => value.is_i64() || value.is_u64(),
+                "Float" => value.is_number(),
+                "String" | "File" | "Directory" => value.is_string(),
+                "Object" => value.is_object(),
+                _ => true,
+            };
+            if !matches {
+                report(
+                    problems,
+                    key,
+                    format!("expected {}, found {}", name, describe(value)),
+                );
+            }
+        }
+        (WdlType::Array { item, nonempty }, Value::Array(items)) => {
+            if *nonempty && items.is_empty() {
+                report(
+                    problems,
+                    key,
+                    format!("{} requires at least one element", wdl_type),
+                );
+            }
+            for (index, element) in items.iter().enumerate() {
+                check_value(
+                    item,
+                    element,
+                    structs,
+                    &format!("{}[{}]", key, index),
+                    problems,
+                );
+            }
+        }
+        (
+            WdlType::Map {
+                value: value_type, ..
+            },
+            Value::Object(entries),
+        ) => {
+            for (entry_key, entry) in entries {
+                check_value(
+                    value_type,
+                    entry,
+                    structs,
+                    &format!("{}.{}", key, entry_key),
+                    problems,
+                );
+            }
+        }
+        (WdlType::Pair { left, right }, Value::Object(pair)) => {
+            match (pair.get("left"), pair.get("right")) {
+                (Some(l), Some(r)) => {
+                    check_value(left, l, structs, &format!("{}.left", key), problems);
+                    check_value(right, r, structs, &format!("{}.right", key), problems);
+                }
+                _ => report(
+                    problems,
+                    key,
+                    "expected a Pair object with 'left' and 'right'".to_string(),
+                ),
+            }
+        }
+        (WdlType::Ref { name }, Value::Object(fields)) => {
+            if let Some(definition) = structs.iter().find(|s| &s.name == name) {
+                for field in &definition.fields {
+                    let field_key = format!("{}.{}", key, field.name);
+                    match (fields.get(&field.name), &field.wdl_type_parsed) {
+                        (Some(value), Some(field_type)) => {
+                            check_value(field_type, value, structs, &field_key, problems)
+                        }
+                        (None, _) if !field.optional => problems.push(InputProblem {
+                            key: field_key,
+                            message: format!("missing field of type {}", field.wdl_type),
+                        }),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        (_, value) => report(
+            problems,
+            key,
+            format!("expected {}, found {}", wdl_type, describe(value)),
+        ),
+    }
+}
+
+fn report(problems: &mut Vec<InputProblem>, key: &str, message: String) {
+    problems.push(InputProblem {
+        key: key.to_string(),
+        message,
+    });
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(n) if n.is_f64() => "a float",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
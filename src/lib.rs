@@ -1,17 +1,90 @@
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 #[cfg(feature = "python")]
-use pyo3::types::PyDict;
+use pyo3::types::{PyByteArray, PyBytes, PyDict};
 #[cfg(feature = "python")]
-use serde_json;
+use std::collections::HashMap;
 #[cfg(feature = "python")]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "python")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "python")]
+use std::sync::{mpsc, Arc, Mutex};
 #[cfg(feature = "python")]
 use wdl_grammar::SyntaxTree;
 
+pub mod audit;
+pub mod backend;
+pub mod badge;
+pub mod baseline;
+pub mod bench;
+pub mod capabilities;
+pub mod catalog;
+pub mod cli;
+pub mod closure;
+pub mod command;
 pub mod commands;
+pub mod compare;
+pub mod compat;
+pub mod config;
+pub mod cromwell;
+pub mod diagnostics;
+pub mod diff;
+pub mod docs;
+pub mod edit;
+pub mod exit;
+pub mod expr;
+pub mod fanout;
+pub mod files;
+pub mod filter;
+pub mod graph;
 pub mod info;
+pub mod inputs;
+pub mod library;
+pub mod lineage;
+pub mod lines;
+pub mod lint;
+pub mod localization;
+pub mod logging;
+pub mod markdown;
+pub mod mermaid;
 pub mod metadata;
+pub mod network;
+pub mod normalize;
+pub mod numbers;
+pub mod output_schema;
+pub mod overrides;
+pub mod plan;
+pub mod plugin;
+pub mod progress;
+pub mod provenance;
+pub mod records;
+pub mod registry;
+pub mod rewrite;
+pub mod rpc;
+pub mod runtime;
+pub mod scaffold;
+pub mod scatter;
+pub mod schema;
+pub mod scripts;
+pub mod server;
+pub mod service;
+pub mod source;
+pub mod spec;
+pub mod stats;
+pub mod strip;
+pub mod struct_usage;
+pub mod suggest;
+pub mod suppress;
+pub mod template;
+pub mod terra;
+pub mod tree;
+pub mod types;
+pub mod units;
+pub mod vendor;
+pub mod version;
+pub mod wiring;
+pub mod workspace;
 
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum OutputFormat {
@@ -19,6 +92,10 @@ pub enum OutputFormat {
     Human,
     /// JSON format
     Json,
+    /// JSON Lines format (one compact object per file, streamed as each completes)
+    Jsonl,
+    /// Markdown tables (info and stats commands)
+    Markdown,
     /// Syntax tree format
     Tree,
 }
@@ -47,8 +124,12 @@ impl From<PyOutputFormat> for OutputFormat {
 #[derive(Clone, Debug)]
 #[pyclass]
 pub struct ParseResult {
+    /// The path, or how a document passed as bytes or a file object is named
     #[pyo3(get)]
     pub file_path: String,
+    /// The file parsed, as a `pathlib.Path`; `None` for bytes and file objects
+    #[pyo3(get)]
+    pub path: Option<PathBuf>,
     #[pyo3(get)]
     pub diagnostics_count: usize,
     #[pyo3(get)]
@@ -94,170 +175,396 @@ impl BasicMetadata {
     }
 }
 
-/// Parse a WDL file and return structured results
+/// Parsed WDL content shared by all outputs of a Python call, so the content
+/// is parsed, walked and scanned for metadata at most once
 #[cfg(feature = "python")]
-#[pyfunction]
-fn parse_wdl(
-    file_path: String,
-    format: Option<PyOutputFormat>,
-    verbose: Option<bool>,
-    extract_metadata: Option<bool>,
-) -> PyResult<ParseResult> {
-    let format = format.unwrap_or(PyOutputFormat::Human);
-    let verbose = verbose.unwrap_or(false);
-    let extract_metadata = extract_metadata.unwrap_or(false);
-    let path = PathBuf::from(&file_path);
+struct Analysis {
+    tree: SyntaxTree,
+    diagnostics: Vec<wdl_grammar::Diagnostic>,
+    has_errors: bool,
+    semantic_info: Option<info::WdlInfo>,
+    basic_metadata: Option<metadata::BasicWdlMetadata>,
+    filter: filter::DiagnosticFilter,
+}
 
-    // Read the file content
-    let content = std::fs::read_to_string(&path).map_err(|e| {
-        PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
-            "Failed to read file '{}': {}",
-            file_path, e
-        ))
-    })?;
+#[cfg(feature = "python")]
+impl Analysis {
+    fn new(content: &str, semantic_info: bool, extract_metadata: bool) -> Self {
+        let (tree, diagnostics) = SyntaxTree::parse(content);
+        let has_errors = diagnostics
+            .iter()
+            .any(|d| matches!(d.severity(), wdl_grammar::Severity::Error));
+        let semantic_info = semantic_info
+            .then(|| commands::extract_semantic_info(&tree.root(), info::SortBy::Position));
+        let basic_metadata = extract_metadata.then(|| {
+            metadata::BasicWdlMetadata::extract_from_text(content, info::SortBy::Position)
+        });
+
+        Self {
+            tree,
+            diagnostics,
+            has_errors,
+            semantic_info,
+            basic_metadata,
+            filter: filter::DiagnosticFilter::default(),
+        }
+    }
 
-    // Parse the WDL content
-    let (tree, diagnostics) = SyntaxTree::parse(&content);
-    let has_errors = diagnostics
-        .iter()
-        .any(|d| matches!(d.severity(), wdl_grammar::Severity::Error));
+    /// Keep only the diagnostics `filter` reports, at the severity it reports them
+    fn with_filter(mut self, filter: filter::DiagnosticFilter) -> Self {
+        self.diagnostics = filter.diagnostics(self.diagnostics);
+        self.has_errors = self.diagnostics.iter().any(|d| filter.is_error(d));
+        self.filter = filter;
+        self
+    }
 
-    // Extract basic metadata if requested
-    let basic_metadata = if extract_metadata {
-        Some(metadata::BasicWdlMetadata::extract_from_text(&content))
-    } else {
-        None
-    };
+    /// Replace the semantic info with best-effort extraction, which keeps
+    /// definitions that have syntax errors and marks them incomplete
+    fn with_partial_info(mut self) -> Self {
+        self.semantic_info = Some(commands::extract_partial_info(
+            &self.tree.root(),
+            &self.diagnostics,
+            info::SortBy::Position,
+        ));
+        self
+    }
 
-    // Generate output based on format
-    let output = match format {
-        PyOutputFormat::Tree => {
-            let mut result = String::new();
-            if verbose && !diagnostics.is_empty() {
-                result.push_str("Diagnostics:\n");
-                for diagnostic in &diagnostics {
-                    result.push_str(&format!(
-                        "  {:?}: {}\n",
-                        diagnostic.severity(),
-                        diagnostic.message()
-                    ));
-                }
-                result.push('\n');
+    /// Put the semantic info and fallback task names in `order`
+    fn with_sort_by(mut self, order: info::SortBy) -> Self {
+        if let Some(semantic_info) = &mut self.semantic_info {
+            semantic_info.sort(order);
+        }
+        if let Some(basic_metadata) = &mut self.basic_metadata {
+            if order == info::SortBy::Name {
+                basic_metadata.task_names.sort();
             }
-            result.push_str("Syntax Tree:\n");
-            result.push_str(&format!("{:#?}", tree));
-            result
         }
-        PyOutputFormat::Json => {
-            let semantic_info = commands::extract_semantic_info(&tree.root());
-            let mut json_output = serde_json::json!({
-                "file": file_path,
-                "diagnostics": diagnostics.len(),
-                "has_errors": has_errors,
-                "wdl": semantic_info
-            });
+        self
+    }
 
-            if let Some(metadata) = &basic_metadata {
-                json_output["basic_metadata"] = serde_json::to_value(metadata).map_err(|e| {
-                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                        "Failed to serialize basic metadata: {}",
-                        e
-                    ))
-                })?;
+    fn semantic_info(&self) -> &info::WdlInfo {
+        self.semantic_info
+            .as_ref()
+            .expect("semantic info requested when building the analysis")
+    }
+
+    /// The syntax tree rendered by [`tree::write_tree`]
+    fn tree_text(&self) -> String {
+        let mut buffer = Vec::new();
+        tree::write_tree(&self.tree.root(), &mut buffer).expect("writing to a Vec cannot fail");
+        String::from_utf8_lossy(&buffer).into_owned()
+    }
+
+    /// One `  Severity: message` line per diagnostic, in file order
+    fn diagnostic_lines(&self) -> String {
+        diagnostics::sorted(&self.diagnostics)
+            .into_iter()
+            .map(|d| format!("  {}: {}\n", self.filter.severity(d), d.message()))
+            .collect()
+    }
+
+    /// Root node kind followed by the diagnostic count (and details if verbose)
+    fn human_summary(&self, verbose: bool) -> String {
+        let mut result = format!("Root node: {:?}\n", self.tree.root().kind());
+        if !self.diagnostics.is_empty() {
+            result.push_str(&format!("Diagnostics: {}\n", self.diagnostics.len()));
+            if verbose {
+                result.push_str(&self.diagnostic_lines());
             }
+        } else {
+            result.push_str("No issues found\n");
+        }
+        result
+    }
 
-            serde_json::to_string_pretty(&json_output).map_err(|e| {
+    /// Parse result JSON, optionally tagged with the file it came from
+    fn parse_json(&self, file_path: Option<&str>) -> PyResult<String> {
+        let mut json_output = serde_json::json!({
+            "diagnostics": self.diagnostics.len(),
+            "has_errors": self.has_errors,
+            "wdl": self.semantic_info()
+        });
+        if let Some(file_path) = file_path {
+            json_output["file"] = serde_json::Value::from(file_path);
+        }
+        self.add_basic_metadata(&mut json_output)?;
+        to_json_string(&json_output)
+    }
+
+    fn add_basic_metadata(&self, json_output: &mut serde_json::Value) -> PyResult<()> {
+        if let Some(metadata) = &self.basic_metadata {
+            json_output["basic_metadata"] = serde_json::to_value(metadata).map_err(|e| {
                 PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                    "Failed to serialize to JSON: {}",
+                    "Failed to serialize basic metadata: {}",
                     e
                 ))
-            })?
+            })?;
         }
-        PyOutputFormat::Human => {
-            let mut result = String::new();
-            result.push_str(&format!("Parsed: {}\n", file_path));
-            result.push_str(&format!("Root node: {:?}\n", tree.root().kind()));
-            if !diagnostics.is_empty() {
-                result.push_str(&format!("Diagnostics: {}\n", diagnostics.len()));
-                if verbose {
-                    for diagnostic in &diagnostics {
-                        result.push_str(&format!(
-                            "  {:?}: {}\n",
-                            diagnostic.severity(),
-                            diagnostic.message()
-                        ));
-                    }
-                }
-            } else {
-                result.push_str("No issues found\n");
-            }
-            result
+        Ok(())
+    }
+}
+
+#[cfg(feature = "python")]
+fn to_json_string(value: &serde_json::Value) -> PyResult<String> {
+    serde_json::to_string_pretty(value).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Failed to serialize to JSON: {}",
+            e
+        ))
+    })
+}
+
+/// The diagnostic filter given by the optional Python arguments
+#[cfg(feature = "python")]
+fn py_filter(
+    warnings_as_errors: Option<bool>,
+    ignore: Option<Vec<String>>,
+    only: Option<Vec<String>>,
+) -> filter::DiagnosticFilter {
+    filter::DiagnosticFilter {
+        warnings_as_errors: warnings_as_errors.unwrap_or(false),
+        ignore: ignore.unwrap_or_default(),
+        only: only.unwrap_or_default(),
+    }
+}
+
+/// The collection order given by the optional `sort_by` argument
+#[cfg(feature = "python")]
+fn py_sort_by(sort_by: Option<String>) -> PyResult<info::SortBy> {
+    let Some(sort_by) = sort_by else {
+        return Ok(info::SortBy::default());
+    };
+    <info::SortBy as clap::ValueEnum>::from_str(&sort_by, true).map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid sort order: {}. Valid options are: position, name",
+            sort_by
+        ))
+    })
+}
+
+/// Diagram options from the keyword arguments of `mermaid_wdl` and
+/// `mermaid_wdl_string`, checked as the `mermaid` command checks its flags
+#[cfg(feature = "python")]
+fn py_diagram_options(
+    direction: Option<String>,
+    include_inputs: Option<bool>,
+    include_outputs: Option<bool>,
+    imports: Option<bool>,
+    runtime: Option<String>,
+    edge_labels: Option<bool>,
+    styles: Option<HashMap<String, String>>,
+) -> PyResult<mermaid::DiagramOptions> {
+    let value_error = |message: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(message);
+    let direction = direction
+        .map(|direction| {
+            <mermaid::Direction as clap::ValueEnum>::from_str(&direction, true).map_err(|_| {
+                value_error(format!(
+                    "Invalid direction: {}. Valid options are: TD, LR, BT, RL",
+                    direction
+                ))
+            })
+        })
+        .transpose()?;
+    let runtime = runtime
+        .map(|runtime| {
+            <mermaid::RuntimeAnnotation as clap::ValueEnum>::from_str(&runtime, true).map_err(
+                |_| {
+                    value_error(format!(
+                        "Invalid runtime annotation: {}. Valid options are: label, tooltip",
+                        runtime
+                    ))
+                },
+            )
+        })
+        .transpose()?;
+    let styles = styles.unwrap_or_default();
+    for class in styles.keys() {
+        mermaid::check_class(class).map_err(value_error)?;
+    }
+    Ok(mermaid::DiagramOptions {
+        imports: imports.unwrap_or(false),
+        runtime,
+        edge_labels: edge_labels.unwrap_or(false),
+        direction,
+        hide_inputs: !include_inputs.unwrap_or(true),
+        hide_outputs: !include_outputs.unwrap_or(true),
+        styles,
+        ..Default::default()
+    })
+}
+
+/// A WDL document passed to a Python function
+#[cfg(feature = "python")]
+struct PyWdlInput {
+    /// The file it was read from; `None` for bytes and file-like objects
+    path: Option<PathBuf>,
+    /// How results name it: the path, the `name` of a file object, or
+    /// `<bytes>`/`<stream>`
+    name: String,
+    content: String,
+}
+
+/// Read a document given as a path (`str` or `os.PathLike`), as `bytes`,
+/// or as a file-like object whose `read()` returns `str` or `bytes`
+#[cfg(feature = "python")]
+fn py_input(source: &Bound<'_, PyAny>) -> PyResult<PyWdlInput> {
+    let io_error =
+        |e: anyhow::Error| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{:#}", e));
+    let decode = |data: &Bound<'_, PyAny>| -> PyResult<String> {
+        if let Ok(text) = data.extract::<String>() {
+            return Ok(source::SourceText::normalize(&text).text);
         }
+        let bytes: Vec<u8> = data.extract()?;
+        source::SourceText::decode(&bytes)
+            .map(|source| source.text)
+            .map_err(io_error)
     };
 
+    if source.is_instance_of::<PyBytes>() || source.is_instance_of::<PyByteArray>() {
+        return Ok(PyWdlInput {
+            path: None,
+            name: "<bytes>".to_string(),
+            content: decode(source)?,
+        });
+    }
+    if source.hasattr("read")? {
+        let name = source
+            .getattr("name")
+            .and_then(|name| name.extract::<String>())
+            .unwrap_or_else(|_| "<stream>".to_string());
+        return Ok(PyWdlInput {
+            path: None,
+            name,
+            content: decode(&source.call_method0("read")?)?,
+        });
+    }
+    let path: PathBuf = source.extract().map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Expected a path, bytes or a file-like object, not {}",
+            source
+                .get_type()
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_default()
+        ))
+    })?;
+    let content = source::SourceText::read(&path).map_err(io_error)?.text;
+    Ok(PyWdlInput {
+        name: path.display().to_string(),
+        path: Some(path),
+        content,
+    })
+}
+
+/// Parse a WDL file and return structured results. The GIL is released
+/// while parsing, so other Python threads (and `parse_wdl_async`) keep running.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn parse_wdl(
+    py: Python<'_>,
+    file_path: Bound<'_, PyAny>,
+    format: Option<PyOutputFormat>,
+    verbose: Option<bool>,
+    extract_metadata: Option<bool>,
+    warnings_as_errors: Option<bool>,
+    ignore: Option<Vec<String>>,
+    only: Option<Vec<String>>,
+    sort_by: Option<String>,
+) -> PyResult<ParseResult> {
+    let format = format.unwrap_or(PyOutputFormat::Human);
+    let verbose = verbose.unwrap_or(false);
+    let extract_metadata = extract_metadata.unwrap_or(false);
+
+    let input = py_input(&file_path)?;
+    let filter = py_filter(warnings_as_errors, ignore, only);
+    let sort_by = py_sort_by(sort_by)?;
+    let (output, diagnostics_count, has_errors) = py.detach(|| -> PyResult<_> {
+        let analysis = Analysis::new(
+            &input.content,
+            matches!(format, PyOutputFormat::Json),
+            extract_metadata,
+        )
+        .with_filter(filter)
+        .with_sort_by(sort_by);
+
+        // Generate output based on format
+        let output = match format {
+            PyOutputFormat::Tree => {
+                let mut result = String::new();
+                if verbose && !analysis.diagnostics.is_empty() {
+                    result.push_str("Diagnostics:\n");
+                    result.push_str(&analysis.diagnostic_lines());
+                    result.push('\n');
+                }
+                result.push_str("Syntax Tree:\n");
+                result.push_str(&analysis.tree_text());
+                result
+            }
+            PyOutputFormat::Json => analysis.parse_json(Some(&input.name))?,
+            PyOutputFormat::Human => {
+                format!(
+                    "Parsed: {}\n{}",
+                    input.name,
+                    analysis.human_summary(verbose)
+                )
+            }
+        };
+        Ok((output, analysis.diagnostics.len(), analysis.has_errors))
+    })?;
+
     Ok(ParseResult {
-        file_path,
-        diagnostics_count: diagnostics.len(),
+        file_path: input.name,
+        path: input.path,
+        diagnostics_count,
         has_errors,
         output,
     })
 }
 
-/// Get information about a WDL file (version, tasks, workflows, etc.)
+/// Get information about a WDL file (version, tasks, workflows, etc.), with
+/// the GIL released while parsing
 #[cfg(feature = "python")]
 #[pyfunction]
 fn info_wdl(
-    file_path: String,
+    py: Python<'_>,
+    file_path: Bound<'_, PyAny>,
     format: Option<PyOutputFormat>,
     extract_metadata: Option<bool>,
+    best_effort: Option<bool>,
+    sort_by: Option<String>,
 ) -> PyResult<String> {
     let format = format.unwrap_or(PyOutputFormat::Human);
     let extract_metadata = extract_metadata.unwrap_or(false);
-    let path = PathBuf::from(&file_path);
 
-    let content = std::fs::read_to_string(&path).map_err(|e| {
-        PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
-            "Failed to read file '{}': {}",
-            file_path, e
-        ))
-    })?;
-
-    let (tree, _) = SyntaxTree::parse(&content);
-    let semantic_info = commands::extract_semantic_info(&tree.root());
-
-    // Extract basic metadata if requested
-    let basic_metadata = if extract_metadata {
-        Some(metadata::BasicWdlMetadata::extract_from_text(&content))
-    } else {
-        None
-    };
+    let input = py_input(&file_path)?;
+    let sort_by = py_sort_by(sort_by)?;
+    py.detach(|| {
+        let mut analysis = Analysis::new(&input.content, true, extract_metadata);
+        if best_effort.unwrap_or(false) {
+            analysis = analysis.with_partial_info();
+        }
+        let analysis = analysis.with_sort_by(sort_by);
+        info_output(&analysis, &input.name, format)
+    })
+}
 
+/// The `info_wdl` output of an analysis of the file `name`
+#[cfg(feature = "python")]
+fn info_output(analysis: &Analysis, name: &str, format: PyOutputFormat) -> PyResult<String> {
+    let semantic_info = analysis.semantic_info();
     let result = match format {
         PyOutputFormat::Json => {
             let mut json_output = serde_json::json!({
-                "file": file_path,
+                "file": name,
                 "wdl": semantic_info
             });
-
-            if let Some(metadata) = &basic_metadata {
-                json_output["basic_metadata"] = serde_json::to_value(metadata).map_err(|e| {
-                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                        "Failed to serialize basic metadata: {}",
-                        e
-                    ))
-                })?;
-            }
-
-            serde_json::to_string_pretty(&json_output).map_err(|e| {
-                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                    "Failed to serialize to JSON: {}",
-                    e
-                ))
-            })?
+            analysis.add_basic_metadata(&mut json_output)?;
+            to_json_string(&json_output)?
         }
         PyOutputFormat::Human => {
             let mut result = String::new();
-            result.push_str(&format!("WDL File: {}\n", file_path));
+            result.push_str(&format!("WDL File: {}\n", name));
             if let Some(version) = &semantic_info.version {
                 result.push_str(&format!("Version: {}\n", version));
             }
@@ -284,106 +591,371 @@ fn info_wdl(
 /// Parse WDL content from a string instead of a file
 #[cfg(feature = "python")]
 #[pyfunction]
+#[allow(clippy::too_many_arguments)]
 fn parse_wdl_string(
     py: Python<'_>,
     content: String,
     format: Option<PyOutputFormat>,
     verbose: Option<bool>,
     extract_metadata: Option<bool>,
+    warnings_as_errors: Option<bool>,
+    ignore: Option<Vec<String>>,
+    only: Option<Vec<String>>,
+    sort_by: Option<String>,
 ) -> PyResult<Py<PyDict>> {
     let format = format.unwrap_or(PyOutputFormat::Human);
     let verbose = verbose.unwrap_or(false);
     let extract_metadata = extract_metadata.unwrap_or(false);
 
-    // Parse the WDL content
-    let (tree, diagnostics) = SyntaxTree::parse(&content);
-    let has_errors = diagnostics
-        .iter()
-        .any(|d| matches!(d.severity(), wdl_grammar::Severity::Error));
+    let analysis = Analysis::new(
+        &content,
+        matches!(format, PyOutputFormat::Json),
+        extract_metadata,
+    )
+    .with_filter(py_filter(warnings_as_errors, ignore, only))
+    .with_sort_by(py_sort_by(sort_by)?);
 
     let dict = PyDict::new(py);
 
-    dict.set_item("diagnostics_count", diagnostics.len())?;
-    dict.set_item("has_errors", has_errors)?;
+    dict.set_item("diagnostics_count", analysis.diagnostics.len())?;
+    dict.set_item("has_errors", analysis.has_errors)?;
 
-    // Extract basic metadata if requested
-    if extract_metadata {
-        let basic_metadata = metadata::BasicWdlMetadata::extract_from_text(&content);
+    if let Some(basic_metadata) = &analysis.basic_metadata {
         dict.set_item(
             "basic_metadata",
             (
-                basic_metadata.version,
-                basic_metadata.workflow_name,
-                basic_metadata.task_names,
+                basic_metadata.version.clone(),
+                basic_metadata.workflow_name.clone(),
+                basic_metadata.task_names.clone(),
             ),
         )?;
     }
 
     // Add diagnostic details if verbose
     if verbose {
-        let diagnostic_list: Vec<(String, String)> = diagnostics
-            .iter()
-            .map(|d| (format!("{:?}", d.severity()), d.message().to_string()))
+        let diagnostic_list: Vec<(String, String)> = diagnostics::sorted(&analysis.diagnostics)
+            .into_iter()
+            .map(|d| (analysis.filter.severity(d), d.message().to_string()))
             .collect();
         dict.set_item("diagnostics", diagnostic_list)?;
     }
 
     // Generate output based on format
     let output = match format {
-        PyOutputFormat::Tree => {
-            format!("{:#?}", tree)
+        PyOutputFormat::Tree => analysis.tree_text(),
+        PyOutputFormat::Json => analysis.parse_json(None)?,
+        PyOutputFormat::Human => analysis.human_summary(verbose),
+    };
+
+    dict.set_item("output", output)?;
+    Ok(dict.unbind())
+}
+
+/// Call `callback(kind, text, (start, end), depth)` for every node and token
+/// of the syntax tree of `content` in source order. Kinds are the names the
+/// tree output shows, e.g. `TaskDefinitionNode` or `Ident`; spans are byte
+/// offsets. When the callback returns `False` for a node its children are
+/// skipped; exceptions it raises stop the walk and propagate.
+#[cfg(feature = "python")]
+#[pyfunction]
+fn walk(content: String, callback: Bound<'_, PyAny>, include_tokens: Option<bool>) -> PyResult<()> {
+    let include_tokens = include_tokens.unwrap_or(true);
+    let (tree, _) = SyntaxTree::parse(&content);
+    tree::walk(&tree.root(), &mut |element: &tree::Element| {
+        if element.is_token && !include_tokens {
+            return Ok(false);
         }
-        PyOutputFormat::Json => {
-            let semantic_info = commands::extract_semantic_info(&tree.root());
-            let mut json_output = serde_json::json!({
-                "diagnostics": diagnostics.len(),
-                "has_errors": has_errors,
-                "wdl": semantic_info
-            });
+        let result = callback.call1((
+            format!("{:?}", element.kind),
+            element.text.as_str(),
+            (element.range.start, element.range.end),
+            element.depth,
+        ))?;
+        Ok(!matches!(result.extract::<bool>(), Ok(false)))
+    })
+}
 
-            if extract_metadata {
-                let basic_metadata = metadata::BasicWdlMetadata::extract_from_text(&content);
-                json_output["basic_metadata"] =
-                    serde_json::to_value(basic_metadata).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                            "Failed to serialize basic metadata: {}",
-                            e
-                        ))
-                    })?;
-            }
+/// Flat task, input, call and runtime records of a WDL file, as JSON
+#[cfg(feature = "python")]
+#[pyfunction]
+fn records_wdl(file_path: Bound<'_, PyAny>, sort_by: Option<String>) -> PyResult<String> {
+    let input = py_input(&file_path)?;
+    let analysis = Analysis::new(&input.content, true, false).with_sort_by(py_sort_by(sort_by)?);
+    let mut records = records::Records::default();
+    records.add(&input.name, analysis.semantic_info());
+    serde_json::to_string(&records).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Failed to serialize to JSON: {}",
+            e
+        ))
+    })
+}
 
-            serde_json::to_string_pretty(&json_output).map_err(|e| {
-                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                    "Failed to serialize to JSON: {}",
-                    e
-                ))
-            })?
+/// Mermaid flowchart of a workflow (the first one by default) or a task in
+/// a WDL file, with the options of the `mermaid` command
+#[cfg(feature = "python")]
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn mermaid_wdl(
+    file_path: Bound<'_, PyAny>,
+    workflow: Option<String>,
+    task: Option<String>,
+    direction: Option<String>,
+    include_inputs: Option<bool>,
+    include_outputs: Option<bool>,
+    imports: Option<bool>,
+    runtime: Option<String>,
+    edge_labels: Option<bool>,
+    expand_subworkflows: Option<bool>,
+    depth: Option<usize>,
+    styles: Option<HashMap<String, String>>,
+) -> PyResult<String> {
+    let value_error = |message: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(message);
+    let input = py_input(&file_path)?;
+    let options = py_diagram_options(
+        direction,
+        include_inputs,
+        include_outputs,
+        imports,
+        runtime,
+        edge_labels,
+        styles,
+    )?;
+    if !expand_subworkflows.unwrap_or(false) {
+        if depth.is_some() {
+            return Err(value_error(
+                "depth only applies with expand_subworkflows".to_string(),
+            ));
         }
-        PyOutputFormat::Human => {
-            let mut result = String::new();
-            result.push_str(&format!("Root node: {:?}\n", tree.root().kind()));
-            if !diagnostics.is_empty() {
-                result.push_str(&format!("Diagnostics: {}\n", diagnostics.len()));
-                if verbose {
-                    for diagnostic in &diagnostics {
-                        result.push_str(&format!(
-                            "  {:?}: {}\n",
-                            diagnostic.severity(),
-                            diagnostic.message()
-                        ));
-                    }
-                }
-            } else {
-                result.push_str("No issues found\n");
+        let analysis = Analysis::new(&input.content, true, false);
+        return commands::mermaid_diagram(
+            analysis.semantic_info(),
+            &input.name,
+            workflow.as_deref(),
+            task.as_deref(),
+            &options,
+        )
+        .map_err(|e| value_error(format!("{:#}", e)));
+    }
+
+    // Imports are resolved next to the file, so sub-workflows need one on disk
+    let Some(path) = &input.path else {
+        return Err(value_error(
+            "expand_subworkflows needs a path to resolve imports from, not bytes or a file object"
+                .to_string(),
+        ));
+    };
+    if task.is_some() || options.imports || options.runtime.is_some() {
+        return Err(value_error(
+            "expand_subworkflows can't be combined with task, imports or runtime".to_string(),
+        ));
+    }
+    let graph = commands::workspace_graph(path, workflow, depth, Vec::new(), None)
+        .map_err(|e| value_error(format!("{:#}", e)))?;
+    Ok(mermaid::workspace_diagram(&graph, &options))
+}
+
+/// Mermaid flowchart of a workflow or task in WDL content, as `mermaid_wdl`
+/// draws it; sub-workflows can't be expanded without a file to resolve
+/// imports from
+#[cfg(feature = "python")]
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn mermaid_wdl_string(
+    content: String,
+    workflow: Option<String>,
+    task: Option<String>,
+    direction: Option<String>,
+    include_inputs: Option<bool>,
+    include_outputs: Option<bool>,
+    imports: Option<bool>,
+    runtime: Option<String>,
+    edge_labels: Option<bool>,
+    styles: Option<HashMap<String, String>>,
+) -> PyResult<String> {
+    let options = py_diagram_options(
+        direction,
+        include_inputs,
+        include_outputs,
+        imports,
+        runtime,
+        edge_labels,
+        styles,
+    )?;
+    let analysis = Analysis::new(&content, true, false);
+    commands::mermaid_diagram(
+        analysis.semantic_info(),
+        "<string>",
+        workflow.as_deref(),
+        task.as_deref(),
+        &options,
+    )
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))
+}
+
+/// What a worker of `scan_wdl` found in one file
+#[cfg(feature = "python")]
+struct ScanItem {
+    path: PathBuf,
+    /// The diagnostics count, whether any is an error, and the info as JSON
+    result: Result<(usize, bool, String), String>,
+}
+
+/// Iterator over the files of `scan_wdl`, parsed by worker threads at most a
+/// few files ahead of the caller
+#[cfg(feature = "python")]
+#[pyclass]
+pub struct WdlScan {
+    results: Mutex<mpsc::Receiver<ScanItem>>,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl WdlScan {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<Py<PyDict>>> {
+        // Every worker has finished once the channel is closed
+        let Some(item) = py.detach(|| self.results.lock().ok()?.recv().ok()) else {
+            return Ok(None);
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("file", item.path.display().to_string())?;
+        dict.set_item("path", &item.path)?;
+        match item.result {
+            Ok((diagnostics_count, has_errors, info)) => {
+                dict.set_item("diagnostics_count", diagnostics_count)?;
+                dict.set_item("has_errors", has_errors)?;
+                dict.set_item("info", info)?;
+                dict.set_item("error", py.None())?;
             }
-            result
+            Err(error) => {
+                dict.set_item("diagnostics_count", 0)?;
+                dict.set_item("has_errors", true)?;
+                dict.set_item("info", py.None())?;
+                dict.set_item("error", error)?;
+            }
+        }
+        Ok(Some(dict.unbind()))
+    }
+}
+
+/// Parse one file for `scan_wdl`; files that can't be read are reported
+/// rather than ending the scan
+#[cfg(feature = "python")]
+fn scan_file(
+    path: &Path,
+    sort_by: info::SortBy,
+    best_effort: bool,
+) -> Result<(usize, bool, String), String> {
+    let content = source::SourceText::read(path)
+        .map_err(|e| format!("{:#}", e))?
+        .text;
+    let mut analysis = Analysis::new(&content, true, false);
+    if best_effort {
+        analysis = analysis.with_partial_info();
+    }
+    let analysis = analysis.with_sort_by(sort_by);
+    let info = serde_json::to_string(analysis.semantic_info()).map_err(|e| e.to_string())?;
+    Ok((analysis.diagnostics.len(), analysis.has_errors, info))
+}
+
+/// Parse the files under `directory` matching `pattern` lazily, on `workers`
+/// threads (one per CPU when `None`). One worker yields files in path order,
+/// more yield them as they finish.
+#[cfg(feature = "python")]
+#[pyfunction]
+fn scan_wdl(
+    directory: PathBuf,
+    pattern: Option<String>,
+    workers: Option<usize>,
+    sort_by: Option<String>,
+    best_effort: Option<bool>,
+) -> PyResult<WdlScan> {
+    let pattern = pattern.unwrap_or_else(|| "**/*.wdl".to_string());
+    let workers = match workers {
+        Some(0) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "workers must be at least 1",
+            ))
         }
+        Some(workers) => workers,
+        None => std::thread::available_parallelism().map_or(1, usize::from),
     };
+    let sort_by = py_sort_by(sort_by)?;
+    let best_effort = best_effort.unwrap_or(false);
+    let files = Arc::new(
+        files::find(&directory, &pattern)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{:#}", e)))?,
+    );
+
+    // A bounded channel keeps the workers from parsing far ahead of the caller
+    let (sender, results) = mpsc::sync_channel(workers);
+    let next = Arc::new(AtomicUsize::new(0));
+    for _ in 0..workers.min(files.len()) {
+        let (files, next, sender) = (Arc::clone(&files), Arc::clone(&next), sender.clone());
+        std::thread::spawn(move || {
+            while let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                let item = ScanItem {
+                    path: path.clone(),
+                    result: scan_file(path, sort_by, best_effort),
+                };
+                // The iterator is gone, so nobody wants the rest
+                if sender.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    Ok(WdlScan {
+        results: Mutex::new(results),
+    })
+}
 
-    dict.set_item("output", output)?;
+/// Which subsystems this build includes, by name
+#[cfg(feature = "python")]
+#[pyfunction]
+fn features(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    for (name, enabled) in capabilities::FEATURES {
+        dict.set_item(name, enabled)?;
+    }
     Ok(dict.unbind())
 }
 
+/// WDL versions the parser reads, oldest first, as `version` statements
+/// write them
+#[cfg(feature = "python")]
+#[pyfunction]
+fn supported_wdl_versions() -> Vec<&'static str> {
+    spec::SpecVersion::all()
+        .map(spec::SpecVersion::name)
+        .collect()
+}
+
+/// A size in bytes as the command-line tool shows it, e.g. `1.5 GiB`, or
+/// `1.61 GB` with `si`
+#[cfg(feature = "python")]
+#[pyfunction]
+fn format_size(bytes: u64, si: Option<bool>) -> String {
+    let units = if si.unwrap_or(false) {
+        numbers::SizeUnits::Si
+    } else {
+        numbers::SizeUnits::Binary
+    };
+    numbers::size_in(bytes, units)
+}
+
+/// A number as the command-line tool shows it: `.` as the decimal separator,
+/// no grouping, rounded to `decimals` places without trailing zeros
+#[cfg(feature = "python")]
+#[pyfunction]
+fn format_number(value: f64, decimals: Option<usize>) -> String {
+    numbers::decimal(value, decimals.unwrap_or(numbers::DECIMALS))
+}
+
 /// A Python module implemented in Rust.
 #[cfg(feature = "python")]
 #[pymodule]
@@ -391,8 +963,19 @@ fn wdlparse(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyOutputFormat>()?;
     m.add_class::<ParseResult>()?;
     m.add_class::<BasicMetadata>()?;
+    m.add_class::<WdlScan>()?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add_function(wrap_pyfunction!(parse_wdl, m)?)?;
     m.add_function(wrap_pyfunction!(info_wdl, m)?)?;
     m.add_function(wrap_pyfunction!(parse_wdl_string, m)?)?;
+    m.add_function(wrap_pyfunction!(records_wdl, m)?)?;
+    m.add_function(wrap_pyfunction!(mermaid_wdl, m)?)?;
+    m.add_function(wrap_pyfunction!(mermaid_wdl_string, m)?)?;
+    m.add_function(wrap_pyfunction!(walk, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_wdl, m)?)?;
+    m.add_function(wrap_pyfunction!(format_size, m)?)?;
+    m.add_function(wrap_pyfunction!(format_number, m)?)?;
+    m.add_function(wrap_pyfunction!(features, m)?)?;
+    m.add_function(wrap_pyfunction!(supported_wdl_versions, m)?)?;
     Ok(())
 }
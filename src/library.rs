@@ -0,0 +1,291 @@
+use crate::closure;
+use crate::edit::{self, SourceEdit};
+use crate::info::WdlInfo;
+use crate::inputs::is_required;
+use crate::strip::{definition_name, line_range};
+use crate::suggest;
+use crate::workspace::import_namespace;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use wdl_grammar::{SyntaxKind, SyntaxNode};
+
+/// A parsed WDL file taking part in `add-task`
+pub struct Document<'a> {
+    pub source: &'a str,
+    pub root: &'a SyntaxNode,
+    pub info: &'a WdlInfo,
+}
+
+/// How `add-task` brings a library task into a file
+#[derive(Debug, Clone, Default)]
+pub struct AddTaskOptions {
+    /// Import the library with this URI instead of copying the task
+    pub import_uri: Option<String>,
+    /// Workflow that gets the call stub; the first one when `None`
+    pub workflow: Option<String>,
+    /// Add a call stub to the workflow
+    pub call: bool,
+}
+
+/// The edited source and what was added to it
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Added {
+    #[serde(skip)]
+    pub source: String,
+    pub task: String,
+    /// Structs copied along with the task
+    pub structs: Vec<String>,
+    /// Structs the task uses that the library doesn't define (e.g. imports
+    /// from elsewhere), so they could not be copied
+    pub missing_structs: Vec<String>,
+    /// URI of the import added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import: Option<String>,
+    /// Workflow the call stub was added to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
+    /// Name of the call stub
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call: Option<String>,
+}
+
+/// Add `task` from `library` to `target`: copy its definition and the structs
+/// it needs, or import the library, then call it from a workflow
+pub fn add_task(
+    target: &Document,
+    library: &Document,
+    task: &str,
+    options: &AddTaskOptions,
+) -> Result<Added> {
+    let Some(definition) = library.info.tasks.iter().find(|t| t.name == task) else {
+        let names = library.info.tasks.iter().map(|t| t.name.as_str());
+        let suggestions = suggest::similar_names(task, names);
+        anyhow::bail!(
+            "Task '{}' not found in the library{}",
+            task,
+            suggest::did_you_mean(&suggestions)
+        );
+    };
+
+    let mut added = Added {
+        task: task.to_string(),
+        ..Default::default()
+    };
+    let mut edits = Vec::new();
+    let target_name = match &options.import_uri {
+        None => {
+            if target.info.tasks.iter().any(|t| t.name == task) {
+                anyhow::bail!("The file already defines a task named '{}'", task);
+            }
+            let types = definition
+                .inputs
+                .iter()
+                .chain(&definition.declarations)
+                .map(|d| &d.wdl_type_parsed)
+                .chain(definition.outputs.iter().map(|o| &o.wdl_type_parsed))
+                .flatten();
+            let mut copies = Vec::new();
+            for name in closure::used_structs(library.info, types) {
+                if target.info.structs.iter().any(|s| s.name == name) {
+                    continue;
+                }
+                match definition_text(library, SyntaxKind::StructDefinitionNode, name) {
+                    Some(text) => {
+                        copies.push(text);
+                        added.structs.push(name.to_string());
+                    }
+                    None => added.missing_structs.push(name.to_string()),
+                }
+            }
+            copies.extend(definition_text(
+                library,
+                SyntaxKind::TaskDefinitionNode,
+                task,
+            ));
+
+            let separator = if target.source.ends_with("\n\n") {
+                ""
+            } else if target.source.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            edits.push(SourceEdit::insert(
+                target.source.len(),
+                format!("{}{}\n", separator, copies.join("\n\n")),
+            ));
+            task.to_string()
+        }
+        Some(uri) => {
+            let existing = target.info.imports.iter().find(|i| &i.uri == uri);
+            let namespace = match existing {
+                Some(import) => import_namespace(&import.uri, import.alias.as_deref()),
+                None => {
+                    let namespace = import_namespace(uri, None);
+                    if let Some(other) = target
+                        .info
+                        .imports
+                        .iter()
+                        .find(|i| import_namespace(&i.uri, i.alias.as_deref()) == namespace)
+                    {
+                        anyhow::bail!(
+                            "Namespace '{}' is already used by import \"{}\"",
+                            namespace,
+                            other.uri
+                        );
+                    }
+                    edits.push(import_edit(target, uri));
+                    added.import = Some(uri.clone());
+                    namespace
+                }
+            };
+            format!("{}.{}", namespace, task)
+        }
+    };
+
+    if options.call {
+        let workflow = match &options.workflow {
+            Some(name) => Some(
+                target
+                    .info
+                    .workflows
+                    .iter()
+                    .find(|w| &w.name == name)
+                    .ok_or_else(|| anyhow::anyhow!("Workflow '{}' not found", name))?,
+            ),
+            None => target.info.workflows.first(),
+        };
+        let node = workflow.and_then(|workflow| {
+            target
+                .root
+                .children()
+                .filter(|node| node.kind() == SyntaxKind::WorkflowDefinitionNode)
+                .find(|node| definition_name(node).as_deref() == Some(workflow.name.as_str()))
+        });
+        if let (Some(workflow), Some(node)) = (workflow, node) {
+            let taken = |name: &str| workflow.calls.iter().any(|call| call.name == name);
+            let call_name = (1..)
+                .map(|n| match n {
+                    1 => task.to_string(),
+                    n => format!("{}_{}", task, n),
+                })
+                .find(|name| !taken(name))
+                .expect("an unused call name");
+
+            let indent = indentation(target.source, &node);
+            let mut stub = String::new();
+            let required: Vec<String> = definition
+                .inputs
+                .iter()
+                .filter(|input| is_required(input))
+                .map(|input| format!("{} ({})", input.name, input.wdl_type))
+                .collect();
+            if !required.is_empty() {
+                stub.push_str(&format!(
+                    "{}# TODO: set the inputs of {}: {}\n",
+                    indent,
+                    call_name,
+                    required.join(", ")
+                ));
+            }
+            stub.push_str(&format!("{}call {}", indent, target_name));
+            if call_name != task {
+                stub.push_str(&format!(" as {}", call_name));
+            }
+            edits.push(call_edit(target.source, &node, &stub));
+            added.workflow = Some(workflow.name.clone());
+            added.call = Some(call_name);
+        }
+    }
+
+    added.source = edit::apply(target.source, &edits)?;
+    Ok(added)
+}
+
+/// Source text of a top-level definition with the comment block above it
+fn definition_text(document: &Document, kind: SyntaxKind, name: &str) -> Option<String> {
+    let node = document
+        .root
+        .children()
+        .filter(|node| node.kind() == kind)
+        .find(|node| definition_name(node).as_deref() == Some(name))?;
+    let range = line_range(document.source, edit::node_range(&node), true);
+    Some(document.source[range].trim_end().to_string())
+}
+
+/// An import statement after the last import, or else after the version
+/// statement
+fn import_edit(target: &Document, uri: &str) -> SourceEdit {
+    let statement = format!("import \"{}\"\n", uri);
+    let anchor = target
+        .root
+        .children()
+        .filter(|node| node.kind() == SyntaxKind::ImportStatementNode)
+        .last()
+        .map(|node| (node, false))
+        .or_else(|| {
+            target
+                .root
+                .children()
+                .find(|node| node.kind() == SyntaxKind::VersionStatementNode)
+                .map(|node| (node, true))
+        });
+    match anchor {
+        Some((node, after_version)) => {
+            let end = edit::node_range(&node).end;
+            let line_end = target.source[end..]
+                .find('\n')
+                .map_or(target.source.len(), |i| end + i + 1);
+            let text = if after_version {
+                format!("\n{}", statement)
+            } else {
+                statement
+            };
+            // A file ending right after the statement gets a line break first
+            let text = if target.source[..line_end].ends_with('\n') {
+                text
+            } else {
+                format!("\n{}", text)
+            };
+            SourceEdit::insert(line_end, text)
+        }
+        None => SourceEdit::insert(0, format!("{}\n", statement)),
+    }
+}
+
+/// Insert a call stub at the end of a workflow body, before its output
+/// section if it has one
+fn call_edit(source: &str, workflow: &SyntaxNode, stub: &str) -> SourceEdit {
+    let output = workflow
+        .children()
+        .find(|node| node.kind() == SyntaxKind::OutputSectionNode);
+    if let Some(output) = output {
+        let start = edit::node_range(&output).start;
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        if source[line_start..start].trim().is_empty() {
+            return SourceEdit::insert(line_start, format!("{}\n\n", stub));
+        }
+        return SourceEdit::insert(start, format!("\n{}\n\n", stub.trim_start()));
+    }
+    let brace = edit::node_range(workflow).end - 1;
+    let line_start = source[..brace].rfind('\n').map_or(0, |i| i + 1);
+    if source[line_start..brace].trim().is_empty() {
+        SourceEdit::insert(line_start, format!("\n{}\n", stub))
+    } else {
+        SourceEdit::insert(brace, format!("\n{}\n", stub))
+    }
+}
+
+/// Indentation of the statements in a workflow body, two spaces if empty
+fn indentation(source: &str, workflow: &SyntaxNode) -> String {
+    workflow
+        .children()
+        .next()
+        .map(|child| {
+            let start = edit::node_range(&child).start;
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            source[line_start..start].to_string()
+        })
+        .filter(|indent| !indent.is_empty() && indent.trim().is_empty())
+        .unwrap_or_else(|| "  ".to_string())
+}
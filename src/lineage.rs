@@ -0,0 +1,219 @@
+use crate::expr;
+use crate::info::WorkflowInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Output format for the `lineage` command
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum LineageFormat {
+    /// Indented provenance tree
+    Human,
+    /// JSON provenance tree
+    Json,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageKind {
+    Output,
+    Call,
+    Declaration,
+    Input,
+    ScatterVariable,
+    Unresolved,
+}
+
+impl LineageKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineageKind::Output => "output",
+            LineageKind::Call => "call",
+            LineageKind::Declaration => "declaration",
+            LineageKind::Input => "input",
+            LineageKind::ScatterVariable => "scatter_variable",
+            LineageKind::Unresolved => "unresolved",
+        }
+    }
+}
+
+/// A node in a provenance tree; `sources` are the values it is derived from
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LineageNode {
+    pub kind: LineageKind,
+    pub name: String,
+    /// Task targeted by a call, or the expression of an output/declaration/scatter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Call input or output member through which the value flows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<LineageNode>,
+}
+
+/// Trace each workflow output back to the calls and inputs it originates from
+pub fn workflow_lineage(workflow: &WorkflowInfo) -> Vec<LineageNode> {
+    workflow
+        .outputs
+        .iter()
+        .map(|output| LineageNode {
+            kind: LineageKind::Output,
+            name: output.name.clone(),
+            detail: Some(output.expression.clone()),
+            via: None,
+            sources: trace_expression(workflow, &output.expression, &mut Vec::new()),
+        })
+        .collect()
+}
+
+/// Resolve the references in an expression; `path` guards against cycles
+fn trace_expression(
+    workflow: &WorkflowInfo,
+    expression: &str,
+    path: &mut Vec<String>,
+) -> Vec<LineageNode> {
+    expr::references(expression)
+        .iter()
+        .filter_map(|reference| {
+            let mut parts = reference.splitn(3, '.');
+            let root = parts.next()?;
+            let member = parts.next().map(str::to_string);
+            if path.iter().any(|seen| seen == root) {
+                return None;
+            }
+            path.push(root.to_string());
+            let node = trace_name(workflow, root, member, path);
+            path.pop();
+            Some(node)
+        })
+        .collect()
+}
+
+fn trace_name(
+    workflow: &WorkflowInfo,
+    name: &str,
+    member: Option<String>,
+    path: &mut Vec<String>,
+) -> LineageNode {
+    let mut node = LineageNode {
+        kind: LineageKind::Unresolved,
+        name: name.to_string(),
+        detail: None,
+        via: member,
+        sources: Vec::new(),
+    };
+
+    if let Some(call) = workflow.calls.iter().find(|c| c.name == name) {
+        node.kind = LineageKind::Call;
+        node.detail = Some(call.target.clone());
+        for input in &call.inputs {
+            for mut source in trace_expression(workflow, &input.value, path) {
+                if source.via.is_none() || source.kind != LineageKind::Call {
+                    source.via = Some(input.name.clone());
+                }
+                node.sources.push(source);
+            }
+        }
+    } else if workflow.inputs.iter().any(|i| i.name == name) {
+        node.kind = LineageKind::Input;
+    } else if let Some(decl) = workflow.declarations.iter().find(|d| d.name == name) {
+        node.kind = LineageKind::Declaration;
+        if let Some(value) = &decl.default_value {
+            node.detail = Some(value.clone());
+            node.sources = trace_expression(workflow, value, path);
+        }
+    } else if let Some(scatter) = workflow.scatters.iter().find(|s| s.variable == name) {
+        node.kind = LineageKind::ScatterVariable;
+        node.detail = Some(scatter.expression.clone());
+        node.sources = trace_expression(workflow, &scatter.expression, path);
+    }
+
+    node
+}
+
+/// Render provenance trees as an indented text report
+pub fn to_text(nodes: &[LineageNode]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        write_text(node, 0, &mut out);
+    }
+    out
+}
+
+fn write_text(node: &LineageNode, depth: usize, out: &mut String) {
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(&format!("{} {}", node.kind.label(), node.name));
+    if let Some(via) = &node.via {
+        out.push_str(&format!(" (via {})", via));
+    }
+    if let Some(detail) = &node.detail {
+        out.push_str(&format!(" = {}", detail));
+    }
+    out.push('\n');
+    for source in &node.sources {
+        write_text(source, depth + 1, out);
+    }
+}
+
+/// Render provenance trees as a Mermaid flowchart from sources to outputs
+pub fn to_mermaid(nodes: &[LineageNode]) -> String {
+    let mut definitions = BTreeSet::new();
+    let mut edges = BTreeSet::new();
+    for node in nodes {
+        collect_mermaid(node, &mut definitions, &mut edges);
+    }
+
+    let mut out = String::from("flowchart LR\n");
+    for definition in definitions {
+        out.push_str(&format!("    {}\n", definition));
+    }
+    for edge in edges {
+        out.push_str(&format!("    {}\n", edge));
+    }
+    out
+}
+
+fn mermaid_id(node: &LineageNode) -> String {
+    let prefix = match node.kind {
+        LineageKind::Output => "output",
+        LineageKind::Call => "call",
+        LineageKind::Declaration => "decl",
+        LineageKind::Input => "input",
+        LineageKind::ScatterVariable => "scatter",
+        LineageKind::Unresolved => "unresolved",
+    };
+    format!("{}_{}", prefix, node.name)
+}
+
+fn collect_mermaid(
+    node: &LineageNode,
+    definitions: &mut BTreeSet<String>,
+    edges: &mut BTreeSet<String>,
+) {
+    let id = mermaid_id(node);
+    let definition = match node.kind {
+        LineageKind::Output => format!("{}[[\"output: {}\"]]", id, node.name),
+        LineageKind::Call => format!(
+            "{}[\"{} ({})\"]",
+            id,
+            node.name,
+            node.detail.as_deref().unwrap_or_default()
+        ),
+        LineageKind::Input => format!("{}([\"input: {}\"])", id, node.name),
+        LineageKind::Declaration => format!("{}{{\"{}\"}}", id, node.name),
+        LineageKind::ScatterVariable => format!("{}[/\"scatter: {}\"/]", id, node.name),
+        LineageKind::Unresolved => format!("{}[\"? {}\"]", id, node.name),
+    };
+    definitions.insert(definition);
+
+    for source in &node.sources {
+        let edge = match &source.via {
+            Some(via) => format!("{} -->|{}| {}", mermaid_id(source), via, id),
+            None => format!("{} --> {}", mermaid_id(source), id),
+        };
+        edges.insert(edge);
+        collect_mermaid(source, definitions, edges);
+    }
+}
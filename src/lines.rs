@@ -0,0 +1,80 @@
+use wdl_grammar::Diagnostic;
+
+/// Byte offsets of line starts in a document, built once so that every
+/// feature maps offsets to 1-based lines and columns the same way. Columns
+/// count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Self {
+            text: text.to_string(),
+            line_starts,
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// 1-based line containing byte `offset`; offsets past the end are on the last line
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+
+    /// 1-based line and column of byte `offset`, clamped to the end of the text
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = self.floor_char_boundary(offset);
+        let line = self.line(offset);
+        let start = self.line_starts[line - 1];
+        (line, self.text[start..offset].chars().count() + 1)
+    }
+
+    /// Byte offset of a 1-based line and column. A column past the end of
+    /// the line maps to the line's end.
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let text = self.line_text(line)?;
+        let start = self.line_starts[line - 1];
+        let within = text
+            .char_indices()
+            .nth(column.max(1) - 1)
+            .map_or(text.len(), |(index, _)| index);
+        Some(start + within)
+    }
+
+    /// Byte offset where a 1-based line starts
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.line_starts.get(line.checked_sub(1)?).copied()
+    }
+
+    /// Text of a 1-based line without its line ending
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        let start = self.line_start(line)?;
+        let end = self
+            .line_start(line + 1)
+            .map_or(self.text.len(), |next| next - 1);
+        let text = &self.text[start..end];
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Line and column where the first label of a parser diagnostic starts
+    pub fn diagnostic_position(&self, diagnostic: &Diagnostic) -> Option<(usize, usize)> {
+        let label = diagnostic.labels().next()?;
+        Some(self.position(label.span().start()))
+    }
+
+    fn floor_char_boundary(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
@@ -0,0 +1,1111 @@
+use crate::command::CommandStyle;
+use crate::config::{LintConfig, RuleLevel};
+use crate::expr;
+use crate::info::{RuntimeItem, TaskInfo, WdlInfo, WorkflowInfo};
+use crate::registry::ImageRef;
+use crate::suggest;
+use crate::workspace::import_namespace;
+use serde::{Deserialize, Serialize};
+
+/// A lint rule with its default level
+pub struct Rule {
+    pub id: &'static str,
+    pub default_level: RuleLevel,
+    pub description: &'static str,
+}
+
+/// All built-in lint rules
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "docker-latest-tag",
+        default_level: RuleLevel::Warning,
+        description: "Container image uses the mutable `latest` tag",
+    },
+    Rule {
+        id: "docker-untagged",
+        default_level: RuleLevel::Warning,
+        description: "Container image has no tag or digest",
+    },
+    Rule {
+        id: "docker-missing-digest",
+        default_level: RuleLevel::Off,
+        description: "Container image is not pinned to a sha256 digest",
+    },
+    Rule {
+        id: "cache-nondeterministic-command",
+        default_level: RuleLevel::Off,
+        description: "Task command uses randomness or the clock, so its outputs differ between runs",
+    },
+    Rule {
+        id: "cache-nondeterministic-input",
+        default_level: RuleLevel::Off,
+        description: "Value names a temporary file, e.g. `basename(write_lines(...))`, that differs between runs",
+    },
+    Rule {
+        id: "cache-unpinned-container",
+        default_level: RuleLevel::Off,
+        description: "Container image is not pinned to a digest, which can disable Cromwell call caching",
+    },
+    Rule {
+        id: "cache-hint-missing",
+        default_level: RuleLevel::Off,
+        description: "Task with a non-deterministic command doesn't declare `volatile: true` in meta",
+    },
+    Rule {
+        id: "constant-condition",
+        default_level: RuleLevel::Warning,
+        description: "Conditional is always true or always false",
+    },
+    Rule {
+        id: "unguarded-conditional-output",
+        default_level: RuleLevel::Warning,
+        description:
+            "Output of a call inside a conditional is used outside it without `select_first`",
+    },
+    Rule {
+        id: "unreachable-output",
+        default_level: RuleLevel::Error,
+        description: "Workflow output has no value, reads a call output that doesn't exist, or reads a call that may be skipped into a non-optional type",
+    },
+    Rule {
+        id: "command-unclosed-placeholder",
+        default_level: RuleLevel::Error,
+        description: "Command placeholder `~{` is never closed",
+    },
+    Rule {
+        id: "command-undeclared-reference",
+        default_level: RuleLevel::Warning,
+        description: "Command placeholder refers to a name the task doesn't declare",
+    },
+    Rule {
+        id: "workflow-undeclared-reference",
+        default_level: RuleLevel::Warning,
+        description: "Call input or workflow declaration refers to a name the workflow doesn't declare",
+    },
+    Rule {
+        id: "command-dollar-placeholder",
+        default_level: RuleLevel::Warning,
+        description: "Draft-2 style `${}` placeholder in a WDL 1.x command",
+    },
+    Rule {
+        id: "unresolved-call",
+        default_level: RuleLevel::Error,
+        description: "Call target is not a task in the file or a member of an imported namespace",
+    },
+    Rule {
+        id: "duplicate-call-name",
+        default_level: RuleLevel::Error,
+        description: "Two calls in a workflow have the same name, e.g. a task called twice without distinct aliases",
+    },
+    Rule {
+        id: "unknown-struct",
+        default_level: RuleLevel::Error,
+        description: "Type refers to a struct the file doesn't define",
+    },
+    Rule {
+        id: "spec-version",
+        default_level: RuleLevel::Error,
+        description: "Feature is newer than the `lint --spec` version or the file's declared version",
+    },
+    Rule {
+        id: "task-name-snake-case",
+        default_level: RuleLevel::Off,
+        description: "Task name is not snake_case",
+    },
+    Rule {
+        id: "struct-name-camel-case",
+        default_level: RuleLevel::Off,
+        description: "Struct name is not CamelCase",
+    },
+    Rule {
+        id: "workflow-output-prefix",
+        default_level: RuleLevel::Warning,
+        description:
+            "Workflow output name lacks the configured `lint.naming.workflow_output_prefix`",
+    },
+];
+
+/// Shell words whose result changes between runs, with what they read
+const NONDETERMINISTIC_WORDS: &[(&str, &str)] = &[
+    ("$RANDOM", "a random number"),
+    ("${RANDOM}", "a random number"),
+    ("date", "the current time"),
+    ("uuidgen", "a random UUID"),
+    ("/dev/urandom", "random bytes"),
+    ("/dev/random", "random bytes"),
+    ("shuf", "a random order"),
+];
+
+/// Functions that write a value to a new temporary file
+const WRITE_FUNCTIONS: &[&str] = &[
+    "write_lines",
+    "write_tsv",
+    "write_map",
+    "write_object",
+    "write_objects",
+    "write_json",
+];
+
+/// Functions that accept the optional outputs of conditional calls
+const OPTIONAL_GUARDS: &[&str] = &["select_first", "select_all", "defined"];
+
+/// How far declarations are followed when evaluating a condition
+const MAX_DEPTH: usize = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A single lint finding
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Finding {
+    pub rule: String,
+    pub severity: Severity,
+    /// Where the finding applies, e.g. `task align_reads`
+    pub location: String,
+    /// 1-based source line the finding is attached to, which is what
+    /// `# wdlparse-disable-next-line` comments match against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+    /// Similar in-scope names for a name that couldn't be resolved, closest
+    /// first; the message already ends with a "did you mean" hint for them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+}
+
+/// Collects findings, applying the configured level of each rule
+struct Linter<'a> {
+    config: &'a LintConfig,
+    findings: Vec<Finding>,
+}
+
+/// Level of a rule: the configured level, else the built-in default, else `fallback`
+pub fn rule_level(config: &LintConfig, rule_id: &str, fallback: RuleLevel) -> RuleLevel {
+    config.rules.get(rule_id).copied().unwrap_or_else(|| {
+        RULES
+            .iter()
+            .find(|rule| rule.id == rule_id)
+            .map_or(fallback, |rule| rule.default_level)
+    })
+}
+
+impl Linter<'_> {
+    fn level(&self, rule_id: &str) -> RuleLevel {
+        rule_level(self.config, rule_id, RuleLevel::Off)
+    }
+
+    fn report(&mut self, rule_id: &str, location: String, line: usize, message: String) {
+        self.report_unresolved(rule_id, location, line, message, Vec::new());
+    }
+
+    /// Report a name that couldn't be resolved, appending a "did you mean"
+    /// hint for `suggestions` to the message
+    fn report_unresolved(
+        &mut self,
+        rule_id: &str,
+        location: String,
+        line: usize,
+        message: String,
+        suggestions: Vec<String>,
+    ) {
+        let severity = match self.level(rule_id) {
+            RuleLevel::Off => return,
+            RuleLevel::Warning => Severity::Warning,
+            RuleLevel::Error => Severity::Error,
+        };
+        self.findings.push(Finding {
+            rule: rule_id.to_string(),
+            severity,
+            location,
+            line: Some(line),
+            message: message + &suggest::did_you_mean(&suggestions),
+            suggestions,
+        });
+    }
+}
+
+/// Run all enabled lint rules over the extracted WDL information
+pub fn lint(info: &WdlInfo, config: &LintConfig) -> Vec<Finding> {
+    let mut linter = Linter {
+        config,
+        findings: Vec::new(),
+    };
+
+    check_container_images(info, &mut linter);
+    check_command_placeholders(info, &mut linter);
+    check_naming(info, &mut linter);
+    check_struct_references(info, &mut linter);
+    check_call_caching(info, &mut linter);
+    for workflow in &info.workflows {
+        check_call_targets(info, workflow, &mut linter);
+        check_workflow_references(workflow, &mut linter);
+        check_call_names(workflow, &mut linter);
+        check_conditionals(info, workflow, &mut linter);
+        check_outputs(info, workflow, &mut linter);
+    }
+
+    linter.findings
+}
+
+/// Container images declared as string literals in task runtime sections
+pub fn container_images(info: &WdlInfo) -> Vec<(&str, &str)> {
+    info.tasks
+        .iter()
+        .flat_map(|task| task_images(task).map(move |(_, image)| (task.name.as_str(), image)))
+        .collect()
+}
+
+/// Container images declared as string literals in a task's runtime
+/// section, with the `docker` or `container` item that declares each
+fn task_images(task: &TaskInfo) -> impl Iterator<Item = (&RuntimeItem, &str)> {
+    task.runtime
+        .iter()
+        .filter(|item| matches!(item.key.as_str(), "docker" | "container"))
+        .filter_map(|item| Some((item, item.literal_value()?)))
+}
+
+fn check_container_images(info: &WdlInfo, linter: &mut Linter) {
+    for task in &info.tasks {
+        for (item, image) in task_images(task) {
+            let Some(image_ref) = ImageRef::parse(image) else {
+                continue;
+            };
+            let location = format!("task {}", task.name);
+
+            if image_ref.tag.as_deref() == Some("latest") && !image_ref.has_digest() {
+                linter.report(
+                    "docker-latest-tag",
+                    location.clone(),
+                    item.line,
+                    format!("image '{}' uses the mutable 'latest' tag", image),
+                );
+            } else if image_ref.tag.is_none() && image_ref.digest.is_none() {
+                linter.report(
+                    "docker-untagged",
+                    location.clone(),
+                    item.line,
+                    format!("image '{}' has no tag and defaults to 'latest'", image),
+                );
+            }
+
+            if !image_ref.has_digest() {
+                linter.report(
+                    "docker-missing-digest",
+                    location,
+                    item.line,
+                    format!("image '{}' is not pinned to a sha256 digest", image),
+                );
+            }
+        }
+    }
+}
+
+fn check_naming(info: &WdlInfo, linter: &mut Linter) {
+    for task in &info.tasks {
+        if !is_snake_case(&task.name) {
+            linter.report(
+                "task-name-snake-case",
+                format!("task {}", task.name),
+                task.line,
+                format!("task name '{}' should be snake_case", task.name),
+            );
+        }
+    }
+    for definition in &info.structs {
+        if !is_camel_case(&definition.name) {
+            linter.report(
+                "struct-name-camel-case",
+                format!("struct {}", definition.name),
+                definition.line,
+                format!("struct name '{}' should be CamelCase", definition.name),
+            );
+        }
+    }
+
+    // Only enforced once a prefix is configured
+    let config = linter.config;
+    let Some(prefix) = config.naming.workflow_output_prefix.as_deref() else {
+        return;
+    };
+    for workflow in &info.workflows {
+        for output in &workflow.outputs {
+            if !output.name.starts_with(prefix) {
+                linter.report(
+                    "workflow-output-prefix",
+                    format!(
+                        "workflow {} (line {}, column {})",
+                        workflow.name, output.line, output.column
+                    ),
+                    output.line,
+                    format!("output '{}' should start with '{}'", output.name, prefix),
+                );
+            }
+        }
+    }
+}
+
+/// `align_reads`, `step2`
+fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// `SampleInfo`, `Bam2`
+fn is_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn check_command_placeholders(info: &WdlInfo, linter: &mut Linter) {
+    let is_1x = info
+        .version
+        .as_deref()
+        .is_some_and(|version| version.starts_with('1'));
+
+    for task in &info.tasks {
+        let Some(section) = &task.command_section else {
+            continue;
+        };
+        let declared: Vec<&str> = task
+            .inputs
+            .iter()
+            .chain(&task.declarations)
+            .map(|decl| decl.name.as_str())
+            .collect();
+
+        for span in section.placeholder_spans() {
+            let (line, column) = section.position(span.offset);
+            let location = format!("task {} (line {}, column {})", task.name, line, column);
+            let written = format!("{}{{{}", span.sigil, span.expression);
+
+            if !span.closed {
+                linter.report(
+                    "command-unclosed-placeholder",
+                    location.clone(),
+                    line,
+                    format!("placeholder '{}' has no closing '}}'", written),
+                );
+                continue;
+            }
+            if is_1x && span.sigil == '$' && section.style == CommandStyle::Braces {
+                linter.report(
+                    "command-dollar-placeholder",
+                    location.clone(),
+                    line,
+                    format!(
+                        "'{}}}' uses draft-2 syntax; write '~{{{}}}'",
+                        written, span.expression
+                    ),
+                );
+            }
+            for name in expr::referenced_roots(&span.expression) {
+                if !declared.contains(&name.as_str()) {
+                    linter.report_unresolved(
+                        "command-undeclared-reference",
+                        location.clone(),
+                        line,
+                        format!(
+                            "'{}' in placeholder '{}}}' is not an input or declaration of the task",
+                            name, written
+                        ),
+                        suggest::similar_names(&name, declared.iter().copied()),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Patterns that defeat Cromwell call caching. Off by default, since call
+/// caching is specific to Cromwell.
+fn check_call_caching(info: &WdlInfo, linter: &mut Linter) {
+    for task in &info.tasks {
+        let location = format!("task {}", task.name);
+        let mut nondeterministic = Vec::new();
+        if let Some(section) = &task.command_section {
+            for (word, what, offset) in nondeterministic_words(&section.raw) {
+                let (line, column) = section.position(offset);
+                linter.report(
+                    "cache-nondeterministic-command",
+                    format!("{} (line {}, column {})", location, line, column),
+                    line,
+                    format!(
+                        "command uses '{}', which reads {}: its outputs differ between runs, so calls that read them never match a cached result",
+                        word, what
+                    ),
+                );
+                nondeterministic.push(word);
+            }
+            for span in section.placeholder_spans() {
+                if let Some(function) = temporary_file_name(&span.expression) {
+                    let (line, column) = section.position(span.offset);
+                    linter.report(
+                        "cache-nondeterministic-input",
+                        format!("{} (line {}, column {})", location, line, column),
+                        line,
+                        format!(
+                            "placeholder '{}' puts the name of a temporary file from '{}' into the command, which changes on every run, so the call is never found in the cache",
+                            span.expression, function
+                        ),
+                    );
+                }
+            }
+        }
+        for declaration in task.inputs.iter().chain(&task.declarations) {
+            let Some(value) = &declaration.default_value else {
+                continue;
+            };
+            if let Some(function) = temporary_file_name(value) {
+                linter.report(
+                    "cache-nondeterministic-input",
+                    location.clone(),
+                    task.line,
+                    format!(
+                        "'{}' is the name of a temporary file from '{}', which changes on every run; pass the file itself instead of its name",
+                        declaration.name, function
+                    ),
+                );
+            }
+        }
+
+        // An image without a digest is already a `docker-missing-digest`
+        // finding when that rule is on
+        if linter.level("docker-missing-digest") == RuleLevel::Off {
+            for (item, image) in task_images(task) {
+                if ImageRef::parse(image).is_some_and(|image_ref| !image_ref.has_digest()) {
+                    linter.report(
+                        "cache-unpinned-container",
+                        location.clone(),
+                        item.line,
+                        format!(
+                            "image '{}' is not pinned to a digest: Cromwell looks up the tag's digest on every run and disables call caching for the call when the lookup fails",
+                            image
+                        ),
+                    );
+                }
+            }
+        }
+
+        let declares_caching = task
+            .meta
+            .iter()
+            .any(|item| item.key == "volatile" && item.value.trim() == "true")
+            || task
+                .runtime
+                .iter()
+                .any(|item| item.key == "cacheable" && item.value.trim() == "false");
+        if !nondeterministic.is_empty() && !declares_caching {
+            linter.report(
+                "cache-hint-missing",
+                location,
+                task.line,
+                format!(
+                    "the command uses {} but the task doesn't declare 'volatile: true' in meta, so Cromwell may reuse a cached result instead of running it",
+                    nondeterministic
+                        .iter()
+                        .map(|word| format!("'{}'", word))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
+        }
+    }
+
+    for workflow in &info.workflows {
+        for call in &workflow.calls {
+            for input in &call.inputs {
+                if let Some(function) = temporary_file_name(&input.value) {
+                    linter.report(
+                        "cache-nondeterministic-input",
+                        format!("workflow {}", workflow.name),
+                        workflow.line,
+                        format!(
+                            "input '{}' of call '{}' is the name of a temporary file from '{}', which changes on every run, so the call is never found in the cache",
+                            input.name, call.name, function
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The first use of each non-deterministic shell word in a raw command,
+/// outside comment lines, with the char offset of the use
+fn nondeterministic_words(raw: &str) -> Vec<(&'static str, &'static str, usize)> {
+    let mut found: Vec<(&str, &str, usize)> = Vec::new();
+    let mut line_start = 0;
+    for line in raw.split('\n') {
+        let chars: Vec<char> = line.chars().collect();
+        if !line.trim_start().starts_with('#') {
+            for (word, what) in NONDETERMINISTIC_WORDS {
+                if found.iter().any(|(w, _, _)| w == word) {
+                    continue;
+                }
+                let pattern: Vec<char> = word.chars().collect();
+                // Commands must be whole shell words, so `date.txt` isn't `date`;
+                // variables and device paths can sit inside a word
+                let command = !word.starts_with(['$', '/']);
+                let separator = |c: char| {
+                    c.is_whitespace()
+                        || matches!(
+                            c,
+                            ';' | '|' | '&' | '(' | ')' | '`' | '<' | '>' | '"' | '\''
+                        )
+                };
+                let position = (0..chars.len()).find(|&i| {
+                    chars[i..].starts_with(&pattern)
+                        && (!command || i == 0 || separator(chars[i - 1]))
+                        && chars.get(i + pattern.len()).is_none_or(|&c| {
+                            if command {
+                                separator(c)
+                            } else {
+                                !(c.is_alphanumeric() || c == '_')
+                            }
+                        })
+                });
+                if let Some(i) = position {
+                    found.push((word, what, line_start + i));
+                }
+            }
+        }
+        line_start += chars.len() + 1;
+    }
+    found.sort_by_key(|(_, _, offset)| *offset);
+    found
+}
+
+/// The `write_*` function whose file an expression passes to `basename`,
+/// i.e. uses the temporary file's name rather than the file
+fn temporary_file_name(expression: &str) -> Option<&'static str> {
+    let compact: String = expression.split_whitespace().collect();
+    WRITE_FUNCTIONS
+        .iter()
+        .copied()
+        .find(|function| compact.contains(&format!("basename({}(", function)))
+}
+
+/// Calls must name a task of the file, or `namespace.name` with the
+/// namespace of an import; what an import contains isn't known here
+fn check_call_targets(info: &WdlInfo, workflow: &WorkflowInfo, linter: &mut Linter) {
+    let namespaces: Vec<String> = info
+        .imports
+        .iter()
+        .map(|import| import_namespace(&import.uri, import.alias.as_deref()))
+        .collect();
+    for call in &workflow.calls {
+        let (message, suggestions) = match call.target.split_once('.') {
+            None if info.tasks.iter().any(|task| task.name == call.target) => continue,
+            None => (
+                format!(
+                    "call '{}' names a task the file doesn't define",
+                    call.target
+                ),
+                suggest::similar_names(&call.target, info.tasks.iter().map(|t| t.name.as_str())),
+            ),
+            Some((namespace, _)) if namespaces.iter().any(|n| n == namespace) => continue,
+            Some((namespace, name)) => (
+                format!(
+                    "call '{}' uses namespace '{}', which no import provides",
+                    call.target, namespace
+                ),
+                suggest::similar_names(namespace, namespaces.iter().map(String::as_str))
+                    .into_iter()
+                    .map(|namespace| format!("{}.{}", namespace, name))
+                    .collect(),
+            ),
+        };
+        linter.report_unresolved(
+            "unresolved-call",
+            format!("workflow {}", workflow.name),
+            call.line,
+            message,
+            suggestions,
+        );
+    }
+}
+
+/// Call inputs and declarations may only read names the workflow declares:
+/// inputs, declarations, calls and scatter variables. Outputs are checked by
+/// `unreachable-output`.
+fn check_workflow_references(workflow: &WorkflowInfo, linter: &mut Linter) {
+    let known: Vec<&str> = workflow
+        .inputs
+        .iter()
+        .chain(&workflow.declarations)
+        .map(|d| d.name.as_str())
+        .chain(workflow.calls.iter().map(|c| c.name.as_str()))
+        .chain(workflow.scatters.iter().map(|s| s.variable.as_str()))
+        .collect();
+    let location = format!("workflow {}", workflow.name);
+
+    let mut sites: Vec<(String, &str, usize)> = Vec::new();
+    for call in &workflow.calls {
+        for input in &call.inputs {
+            sites.push((
+                format!("input '{}' of call '{}'", input.name, call.name),
+                input.value.as_str(),
+                call.line,
+            ));
+        }
+    }
+    for declaration in &workflow.declarations {
+        if let Some(value) = &declaration.default_value {
+            sites.push((
+                format!("declaration '{}'", declaration.name),
+                value.as_str(),
+                workflow.line,
+            ));
+        }
+    }
+
+    for (site, expression, line) in sites {
+        for name in expr::referenced_roots(expression) {
+            if known.contains(&name.as_str()) {
+                continue;
+            }
+            linter.report_unresolved(
+                "workflow-undeclared-reference",
+                location.clone(),
+                line,
+                format!(
+                    "{} reads '{}', which is not an input, declaration or call of the workflow",
+                    site, name
+                ),
+                suggest::similar_names(&name, known.iter().copied()),
+            );
+        }
+    }
+}
+
+/// Calls are named by their alias, else by their task, and every name in a
+/// workflow must be unique
+fn check_call_names(workflow: &WorkflowInfo, linter: &mut Linter) {
+    for (index, call) in workflow.calls.iter().enumerate() {
+        let Some(first) = workflow.calls[..index].iter().find(|c| c.name == call.name) else {
+            continue;
+        };
+        let message = match (&first.alias, &call.alias) {
+            (None, None) if first.target == call.target => {
+                let taken = |name: &str| workflow.calls.iter().any(|c| c.name == name);
+                let free = (2..)
+                    .map(|n| format!("{}_{}", call.name, n))
+                    .find(|name| !taken(name))
+                    .expect("an unused call name");
+                format!(
+                    "task '{}' is called more than once without an alias; name the calls apart, e.g. 'call {} as {}'",
+                    call.target, call.target, free
+                )
+            }
+            (None, None) => format!(
+                "calls '{}' and '{}' are both named '{}'; give one an alias with 'as'",
+                first.target, call.target, call.name
+            ),
+            (_, Some(_)) => format!(
+                "alias '{}' of call '{}' is already the name of call '{}'",
+                call.name, call.target, first.target
+            ),
+            (Some(_), None) => format!(
+                "call '{}' is named '{}', which the alias of call '{}' already uses",
+                call.target, call.name, first.target
+            ),
+        };
+        linter.report(
+            "duplicate-call-name",
+            format!("workflow {}", workflow.name),
+            call.line,
+            message,
+        );
+    }
+}
+
+/// Struct types must be defined in the file. Imports bring their structs
+/// along without this file naming them, so files with imports are skipped.
+fn check_struct_references(info: &WdlInfo, linter: &mut Linter) {
+    if !info.imports.is_empty() {
+        return;
+    }
+    let defined: Vec<&str> = info.structs.iter().map(|s| s.name.as_str()).collect();
+    let mut declarations = Vec::new();
+    for task in &info.tasks {
+        let location = format!("task {}", task.name);
+        for decl in task.inputs.iter().chain(&task.declarations) {
+            declarations.push((
+                location.clone(),
+                task.line,
+                &decl.name,
+                &decl.wdl_type_parsed,
+            ));
+        }
+        for output in &task.outputs {
+            declarations.push((
+                format!(
+                    "task {} (line {}, column {})",
+                    task.name, output.line, output.column
+                ),
+                output.line,
+                &output.name,
+                &output.wdl_type_parsed,
+            ));
+        }
+    }
+    for workflow in &info.workflows {
+        let location = format!("workflow {}", workflow.name);
+        for decl in workflow.inputs.iter().chain(&workflow.declarations) {
+            declarations.push((
+                location.clone(),
+                workflow.line,
+                &decl.name,
+                &decl.wdl_type_parsed,
+            ));
+        }
+        for output in &workflow.outputs {
+            declarations.push((
+                format!(
+                    "workflow {} (line {}, column {})",
+                    workflow.name, output.line, output.column
+                ),
+                output.line,
+                &output.name,
+                &output.wdl_type_parsed,
+            ));
+        }
+    }
+    for definition in &info.structs {
+        let location = format!("struct {}", definition.name);
+        for field in &definition.fields {
+            declarations.push((
+                location.clone(),
+                definition.line,
+                &field.name,
+                &field.wdl_type_parsed,
+            ));
+        }
+    }
+
+    for (location, line, name, wdl_type) in declarations {
+        let Some(wdl_type) = wdl_type else {
+            continue;
+        };
+        for struct_name in wdl_type.struct_names() {
+            if defined.contains(&struct_name) {
+                continue;
+            }
+            linter.report_unresolved(
+                "unknown-struct",
+                location.clone(),
+                line,
+                format!(
+                    "'{}' has type '{}', which is not a defined struct",
+                    name, struct_name
+                ),
+                suggest::similar_names(struct_name, defined.iter().copied()),
+            );
+        }
+    }
+}
+
+fn check_conditionals(info: &WdlInfo, workflow: &WorkflowInfo, linter: &mut Linter) {
+    let location = format!("workflow {}", workflow.name);
+    for conditional in &workflow.conditionals {
+        if let Some(value) = constant_condition(workflow, &conditional.expression, 0) {
+            let effect = if value {
+                "the block always runs"
+            } else {
+                "the block never runs"
+            };
+            linter.report(
+                "constant-condition",
+                location.clone(),
+                conditional.line,
+                format!(
+                    "condition 'if ({})' is always {}; {}",
+                    conditional.expression.trim(),
+                    value,
+                    effect
+                ),
+            );
+        }
+    }
+
+    // Calls and declarations inside a conditional are optional outside it;
+    // each site is (description, expression, call or declaration reading it,
+    // location, line). Sites that accept an optional value are skipped.
+    let mut sites: Vec<(String, &str, Option<&str>, String, usize)> = Vec::new();
+    for call in &workflow.calls {
+        let task = info.tasks.iter().find(|task| task.name == call.target);
+        for input in &call.inputs {
+            let accepts_optional = task
+                .and_then(|task| task.inputs.iter().find(|i| i.name == input.name))
+                .is_some_and(|declared| declared.optional);
+            if !accepts_optional {
+                sites.push((
+                    format!("input '{}' of call '{}'", input.name, call.name),
+                    input.value.as_str(),
+                    Some(call.name.as_str()),
+                    location.clone(),
+                    call.line,
+                ));
+            }
+        }
+    }
+    for declaration in workflow.declarations.iter().filter(|d| !d.optional) {
+        if let Some(value) = &declaration.default_value {
+            sites.push((
+                format!("declaration '{}'", declaration.name),
+                value.as_str(),
+                Some(declaration.name.as_str()),
+                location.clone(),
+                workflow.line,
+            ));
+        }
+    }
+    for output in &workflow.outputs {
+        if !output
+            .wdl_type_parsed
+            .as_ref()
+            .is_some_and(|t| t.is_optional())
+        {
+            sites.push((
+                format!("output '{}'", output.name),
+                output.expression.as_str(),
+                None,
+                format!(
+                    "workflow {} (line {}, column {})",
+                    workflow.name, output.line, output.column
+                ),
+                output.line,
+            ));
+        }
+    }
+
+    for (site, expression, reader, site_location, line) in sites {
+        // `if defined(x) then x else ...` still has an optional type, so
+        // only the unwrapping functions guard a read
+        let unguarded = expr::referenced_roots(&expr::without_calls(expression, OPTIONAL_GUARDS));
+        for conditional in &workflow.conditionals {
+            let in_block = |name: &str| {
+                conditional.calls.iter().any(|c| c == name)
+                    || conditional.declarations.iter().any(|d| d == name)
+            };
+            if reader.is_some_and(in_block) {
+                continue;
+            }
+            for name in unguarded.iter().filter(|name| in_block(name)) {
+                // Outputs reading a skippable call are `unreachable-output`
+                if reader.is_none() && workflow.calls.iter().any(|c| &c.name == name) {
+                    continue;
+                }
+                linter.report(
+                    "unguarded-conditional-output",
+                    site_location.clone(),
+                    line,
+                    format!(
+                        "{} reads '{}' from inside 'if ({})', which is optional here; unwrap it with select_first, select_all or defined",
+                        site,
+                        name,
+                        conditional.expression.trim()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Every workflow output must get a value: it needs an expression, the call
+/// outputs it reads must exist, and a call that may be skipped can only be
+/// read into an optional type or through a guard
+fn check_outputs(info: &WdlInfo, workflow: &WorkflowInfo, linter: &mut Linter) {
+    let known: Vec<&str> = workflow
+        .inputs
+        .iter()
+        .chain(&workflow.declarations)
+        .map(|d| d.name.as_str())
+        .chain(workflow.calls.iter().map(|c| c.name.as_str()))
+        .chain(workflow.outputs.iter().map(|o| o.name.as_str()))
+        .collect();
+
+    for output in &workflow.outputs {
+        let location = format!(
+            "workflow {} (line {}, column {})",
+            workflow.name, output.line, output.column
+        );
+        if output.expression.trim().is_empty() {
+            linter.report(
+                "unreachable-output",
+                location,
+                output.line,
+                format!("output '{}' is declared but never assigned", output.name),
+            );
+            continue;
+        }
+
+        let mut reported: Vec<String> = Vec::new();
+        for path in expr::references(&output.expression) {
+            let mut parts = path.split('.');
+            let root = parts.next().unwrap_or_default();
+            if reported.iter().any(|r| r == root) {
+                continue;
+            }
+            if !known.contains(&root) {
+                reported.push(root.to_string());
+                // `ns.task.out` names the call by its target instead of its name
+                let rest: Vec<&str> = parts.collect();
+                let by_target = workflow.calls.iter().find(|call| {
+                    rest.first()
+                        .is_some_and(|task| call.target == format!("{}.{}", root, task))
+                });
+                let suggestions = match by_target {
+                    Some(call) => {
+                        let mut path = vec![call.name.as_str()];
+                        path.extend(&rest[1..]);
+                        vec![path.join(".")]
+                    }
+                    None => suggest::similar_names(root, known.iter().copied()),
+                };
+                linter.report_unresolved(
+                    "unreachable-output",
+                    location.clone(),
+                    output.line,
+                    format!(
+                        "output '{}' reads '{}', which is not an input, declaration or call of the workflow",
+                        output.name, root
+                    ),
+                    suggestions,
+                );
+                continue;
+            }
+            // Only the outputs of local tasks are known
+            let Some(member) = parts.next() else {
+                continue;
+            };
+            let task = workflow
+                .calls
+                .iter()
+                .find(|c| c.name == root)
+                .and_then(|call| info.tasks.iter().find(|t| t.name == call.target))
+                .filter(|task| task.complete != Some(false));
+            let Some(task) = task else {
+                continue;
+            };
+            if task.outputs.iter().any(|o| o.name == member) {
+                continue;
+            }
+            reported.push(root.to_string());
+            linter.report_unresolved(
+                "unreachable-output",
+                location.clone(),
+                output.line,
+                format!(
+                    "output '{}' reads '{}.{}', but task '{}' has no output '{}'",
+                    output.name, root, member, task.name, member
+                ),
+                suggest::similar_names(member, task.outputs.iter().map(|o| o.name.as_str()))
+                    .into_iter()
+                    .map(|name| format!("{}.{}", root, name))
+                    .collect(),
+            );
+        }
+
+        if output
+            .wdl_type_parsed
+            .as_ref()
+            .is_some_and(|t| t.is_optional())
+        {
+            continue;
+        }
+        let unguarded =
+            expr::referenced_roots(&expr::without_calls(&output.expression, OPTIONAL_GUARDS));
+        for name in &unguarded {
+            // Nested conditionals all list the call; the innermost is last
+            let Some(conditional) = workflow
+                .conditionals
+                .iter()
+                .rev()
+                .find(|conditional| conditional.calls.contains(name))
+            else {
+                continue;
+            };
+            if tests_defined(&output.expression, name) {
+                continue;
+            }
+            linter.report(
+                "unreachable-output",
+                location.clone(),
+                output.line,
+                format!(
+                    "output '{}' has type '{}' but reads call '{}', which is skipped unless 'if ({})' holds; declare it '{}?' or unwrap it with select_first",
+                    output.name,
+                    output.wdl_type,
+                    name,
+                    conditional.expression.trim(),
+                    output.wdl_type
+                ),
+            );
+        }
+    }
+}
+
+/// Whether `expression` calls `defined` on `name` or one of its members,
+/// matching the whole identifier so `defined(x_other)` doesn't count for `x`
+fn tests_defined(expression: &str, name: &str) -> bool {
+    let compact: String = expression.split_whitespace().collect();
+    let needle = format!("defined({}", name);
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    compact.match_indices(&needle).any(|(start, _)| {
+        !compact[..start].ends_with(is_ident)
+            && !compact[start + needle.len()..].starts_with(is_ident)
+    })
+}
+
+/// The value of a condition that doesn't depend on inputs: literals,
+/// `!`, `&&`, `||` and non-input declarations with constant values
+fn constant_condition(workflow: &WorkflowInfo, expression: &str, depth: usize) -> Option<bool> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let expression = expr::strip_parens(expression);
+
+    let any = expr::split_operator(expression, "||");
+    if any.len() > 1 {
+        let values: Vec<Option<bool>> = any
+            .iter()
+            .map(|part| constant_condition(workflow, part, depth + 1))
+            .collect();
+        if values.contains(&Some(true)) {
+            return Some(true);
+        }
+        return values.iter().all(|v| *v == Some(false)).then_some(false);
+    }
+    let all = expr::split_operator(expression, "&&");
+    if all.len() > 1 {
+        let values: Vec<Option<bool>> = all
+            .iter()
+            .map(|part| constant_condition(workflow, part, depth + 1))
+            .collect();
+        if values.contains(&Some(false)) {
+            return Some(false);
+        }
+        return values.iter().all(|v| *v == Some(true)).then_some(true);
+    }
+
+    match expression {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ if expression.starts_with('!') => {
+            constant_condition(workflow, &expression[1..], depth + 1).map(|v| !v)
+        }
+        name => workflow
+            .declarations
+            .iter()
+            .find(|decl| decl.name == name)
+            .and_then(|decl| decl.default_value.as_deref())
+            .and_then(|value| constant_condition(workflow, value, depth + 1)),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::info::{InputInfo, StructInfo, WdlInfo};
+use crate::types::PATH_TYPES;
+use crate::workspace::SymbolKind;
+use serde::{Deserialize, Serialize};
+
+/// An input whose value includes files or directories the engine stages
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LocalizedInput {
+    pub name: String,
+    pub wdl_type: String,
+    /// `File` and/or `Directory`, including those inside struct fields
+    pub path_types: Vec<String>,
+    /// Callers can leave it out: the type is optional or it has a default
+    pub optional: bool,
+    /// Holds an `Array` or `Map` of paths, so the count is only known at runtime
+    pub collection: bool,
+    /// Structs with fields that hold the paths
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub through: Vec<String>,
+}
+
+/// The path inputs of one task or workflow
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Localization {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub inputs: Vec<LocalizedInput>,
+    /// Inputs holding files
+    pub files: usize,
+    /// Inputs holding directories
+    pub directories: usize,
+    /// Inputs callers must provide
+    pub required: usize,
+}
+
+/// File and Directory inputs of every task, then every workflow, in file order
+pub fn localization(info: &WdlInfo) -> Vec<Localization> {
+    let tasks = info
+        .tasks
+        .iter()
+        .map(|task| (SymbolKind::Task, &task.name, &task.inputs));
+    let workflows = info
+        .workflows
+        .iter()
+        .map(|workflow| (SymbolKind::Workflow, &workflow.name, &workflow.inputs));
+    tasks
+        .chain(workflows)
+        .map(|(kind, name, inputs)| {
+            let inputs: Vec<LocalizedInput> = inputs
+                .iter()
+                .filter_map(|input| localized(input, &info.structs))
+                .collect();
+            let count = |path_type: &str| {
+                inputs
+                    .iter()
+                    .filter(|input| input.path_types.iter().any(|t| t == path_type))
+                    .count()
+            };
+            Localization {
+                kind,
+                name: name.clone(),
+                files: count("File"),
+                directories: count("Directory"),
+                required: inputs.iter().filter(|input| !input.optional).count(),
+                inputs,
+            }
+        })
+        .collect()
+}
+
+fn localized(input: &InputInfo, structs: &[StructInfo]) -> Option<LocalizedInput> {
+    let wdl_type = input.wdl_type_parsed.as_ref()?;
+    let mut path_types = wdl_type.path_types();
+    let mut collection = wdl_type.is_collection() && !path_types.is_empty();
+    let mut visited: Vec<&str> = Vec::new();
+    let mut through = Vec::new();
+    // Each type with whether a collection encloses it
+    let mut pending = vec![(wdl_type, wdl_type.is_collection())];
+    while let Some((current, in_collection)) = pending.pop() {
+        for name in current.struct_names() {
+            if visited.contains(&name) {
+                continue;
+            }
+            visited.push(name);
+            let Some(definition) = structs.iter().find(|s| s.name == name) else {
+                continue;
+            };
+            for field_type in definition
+                .fields
+                .iter()
+                .filter_map(|field| field.wdl_type_parsed.as_ref())
+            {
+                let in_collection = in_collection || field_type.is_collection();
+                let field_paths = field_type.path_types();
+                if !field_paths.is_empty() {
+                    collection |= in_collection;
+                    path_types.extend(field_paths);
+                    if !through.contains(&name) {
+                        through.push(name);
+                    }
+                }
+                pending.push((field_type, in_collection));
+            }
+        }
+    }
+    if path_types.is_empty() {
+        return None;
+    }
+    path_types.sort_by_key(|name| PATH_TYPES.iter().position(|t| t == name));
+    path_types.dedup();
+    Some(LocalizedInput {
+        name: input.name.clone(),
+        wdl_type: input.wdl_type.clone(),
+        path_types: path_types.into_iter().map(str::to_string).collect(),
+        optional: input.optional || input.default_value.is_some(),
+        collection,
+        through: through.into_iter().map(str::to_string).collect(),
+    })
+}
@@ -0,0 +1,61 @@
+use tracing::level_filters::LevelFilter;
+
+/// Log level selectable with `--log-level`
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// Log output format selectable with `--log-format`
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum LogFormat {
+    /// Human-readable log lines
+    #[default]
+    Text,
+    /// One JSON object per log event
+    Json,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Resolve the effective level: an explicit `--log-level` wins, otherwise
+/// each `-L` raises the default `warn` level by one step
+pub fn level_filter(verbosity: u8, level: Option<LogLevel>) -> LevelFilter {
+    if let Some(level) = level {
+        return level.into();
+    }
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Install the global tracing subscriber writing to stderr
+pub fn init(verbosity: u8, level: Option<LogLevel>, format: LogFormat) {
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level_filter(verbosity, level))
+        .with_writer(std::io::stderr)
+        .with_target(false);
+
+    // Ignore the error if a subscriber is already installed (e.g. by an embedding application)
+    let _ = match format {
+        LogFormat::Text => builder.without_time().try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    };
+}
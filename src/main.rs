@@ -1,71 +1,379 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-use wdlparse::OutputFormat;
+use clap::Parser;
+use std::process::ExitCode;
+use wdlparse::cli::{Cli, Commands};
+use wdlparse::exit::ExitStatus;
+use wdlparse::filter::DiagnosticFilter;
+use wdlparse::network::NetworkAccess;
+use wdlparse::struct_usage::GraphMode;
+use wdlparse::{commands, logging, mermaid, normalize, numbers, strip};
 
-mod commands;
-mod info;
-pub mod metadata;
-
-#[derive(Parser)]
-#[command(name = "wdlparse")]
-#[command(about = "A command-line tool for parsing WDL (Workflow Description Language) files")]
-#[command(version)]
-pub struct Cli {
-    #[command(subcommand)]
-    command: Commands,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Parse a WDL file and display the syntax tree
-    Parse {
-        /// Path to the WDL file to parse
-        #[arg(value_name = "FILE")]
-        file: PathBuf,
-
-        /// Output format
-        #[arg(short, long, value_enum, default_value = "tree")]
-        format: OutputFormat,
-
-        /// Show detailed diagnostic information
-        #[arg(short, long)]
-        verbose: bool,
-
-        /// Extract basic metadata using robust fallback methods
-        #[arg(long)]
-        extract_metadata: bool,
-    },
-    /// Show information about a WDL file (version, tasks, workflows, etc.)
-    Info {
-        /// Path to the WDL file to analyze
-        #[arg(value_name = "FILE")]
-        file: PathBuf,
-
-        /// Output format
-        #[arg(short, long, value_enum, default_value = "human")]
-        format: OutputFormat,
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            // --help and --version are reported as "errors" by clap but print to stdout
+            let _ = e.print();
+            return if e.use_stderr() {
+                ExitStatus::Usage.into()
+            } else {
+                ExitStatus::Success.into()
+            };
+        }
+    };
+    logging::init(cli.verbosity, cli.log_level, cli.log_format);
+    if cli.normalize {
+        normalize::enable();
+    }
+    if cli.si {
+        numbers::set_size_units(numbers::SizeUnits::Si);
+    } else if cli.binary {
+        numbers::set_size_units(numbers::SizeUnits::Binary);
+    }
 
-        /// Extract basic metadata using robust fallback methods
-        #[arg(long)]
-        extract_metadata: bool,
-    },
+    match run(cli) {
+        Ok(()) => ExitStatus::Success.into(),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitStatus::of(&e).into()
+        }
+    }
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-
+fn run(cli: Cli) -> Result<()> {
+    let filter = DiagnosticFilter {
+        warnings_as_errors: cli.warnings_as_errors,
+        ignore: cli.ignore,
+        only: cli.only,
+    };
     match cli.command {
         Commands::Parse {
             file,
             format,
             verbose,
             extract_metadata,
-        } => commands::parse_command(file, format, verbose, extract_metadata),
+            max_output_bytes,
+            max_diagnostics,
+        } => commands::parse_command(
+            file,
+            format,
+            verbose,
+            extract_metadata,
+            commands::ParseLimits {
+                output_bytes: max_output_bytes,
+                diagnostics: max_diagnostics,
+            },
+            filter,
+            cli.config,
+            cli.sort_by,
+        ),
         Commands::Info {
             file,
             format,
             extract_metadata,
-        } => commands::info_command(file, format, extract_metadata),
+            wiring,
+            show_commands,
+            show_runtime,
+            task,
+            workflow,
+            best_effort,
+            include_source,
+            max_source_bytes,
+        } => commands::info_command(
+            file,
+            format,
+            commands::InfoOptions {
+                extract_metadata,
+                show_wiring: wiring,
+                show_commands,
+                show_runtime,
+                task,
+                workflow,
+                best_effort,
+                include_source: include_source.then_some(max_source_bytes),
+            },
+            cli.sort_by,
+        ),
+        Commands::Lint {
+            paths,
+            format,
+            resolve_digests,
+            plugins,
+            trust_plugins,
+            no_inline_config,
+            baseline,
+            update_baseline,
+            spec,
+        } => commands::lint_command(
+            paths,
+            format,
+            commands::LintOptions {
+                resolve_digests,
+                plugins,
+                trust_plugins,
+                inline_config: !no_inline_config,
+                baseline,
+                update_baseline,
+                network: NetworkAccess::from_offline_flag(cli.offline),
+                filter,
+                spec,
+            },
+            cli.config,
+            cli.sort_by,
+        ),
+        Commands::Closure {
+            file,
+            call,
+            workflow,
+            format,
+        } => commands::closure_command(file, call, workflow, format, cli.sort_by),
+        Commands::Strip {
+            file,
+            output,
+            comments,
+            meta,
+            import_dirs,
+        } => commands::strip_command(
+            file,
+            output,
+            strip::StripOptions { comments, meta },
+            import_dirs,
+            cli.config,
+        ),
+        Commands::AddTask {
+            file,
+            from,
+            task,
+            import,
+            workflow,
+            no_call,
+            output,
+        } => commands::add_task_command(
+            file,
+            from,
+            task,
+            import,
+            workflow,
+            !no_call,
+            output,
+            cli.sort_by,
+        ),
+        Commands::FixVersion {
+            paths,
+            target,
+            check,
+            format,
+        } => commands::fix_version_command(paths, target, check, format),
+        Commands::Vendor { file, dest, format } => commands::vendor_command(
+            file,
+            dest,
+            format,
+            NetworkAccess::from_offline_flag(cli.offline),
+        ),
+        Commands::RewriteImports {
+            paths,
+            rules,
+            check,
+            format,
+        } => commands::rewrite_imports_command(paths, rules, check, format, cli.config),
+        Commands::RuntimeOverrides {
+            file,
+            workflow,
+            format,
+        } => commands::runtime_overrides_command(file, workflow, format, cli.sort_by),
+        Commands::RuntimeSensitivity { file, task, format } => {
+            commands::runtime_sensitivity_command(file, task, format, cli.sort_by)
+        }
+        Commands::Lineage {
+            file,
+            workflow,
+            format,
+        } => commands::lineage_command(file, workflow, format, cli.sort_by),
+        Commands::Catalog {
+            paths,
+            owners,
+            format,
+        } => commands::catalog_command(paths, owners, format, cli.sort_by),
+        Commands::Docs {
+            paths,
+            output,
+            site,
+            index,
+            check,
+        } => commands::docs_command(paths, output, site, index, check, cli.sort_by),
+        Commands::Badge { file, workflow } => commands::badge_command(file, workflow, cli.sort_by),
+        Commands::Compare { sources, format } => commands::compare_command(sources, format),
+        Commands::Diff {
+            old,
+            new,
+            task,
+            context,
+            width,
+            format,
+        } => commands::diff_command(old, new, task, context, width, format, cli.sort_by),
+        Commands::CommandAudit { paths, format } => {
+            commands::command_audit_command(paths, format, cli.sort_by)
+        }
+        Commands::Stats {
+            file,
+            graph,
+            format,
+        } => commands::stats_command(file, graph, format, cli.sort_by),
+        Commands::Inputs {
+            file,
+            workflow,
+            optional,
+            synthesize,
+        } => commands::inputs_command(file, workflow, optional, synthesize, cli.sort_by),
+        Commands::InputUsage {
+            file,
+            workflow,
+            mermaid,
+            format,
+        } => commands::input_usage_command(file, workflow, mermaid, format, cli.sort_by),
+        Commands::ExtractScripts {
+            file,
+            dest,
+            reinject,
+            format,
+        } => commands::extract_scripts_command(file, dest, reinject, format, cli.sort_by),
+        Commands::Localization { file, format } => {
+            commands::localization_command(file, format, cli.sort_by)
+        }
+        Commands::ScaffoldTests {
+            file,
+            workflow,
+            style,
+            output,
+        } => commands::scaffold_tests_command(file, workflow, style, output, cli.sort_by),
+        Commands::New {
+            kind,
+            name,
+            tasks,
+            output,
+        } => commands::new_command(kind, name, tasks, output),
+        Commands::Schema {
+            file,
+            workflow,
+            style,
+            self_schema,
+        } => commands::schema_command(file, workflow, style, self_schema, cli.sort_by),
+        Commands::OptionsTemplate { file } => commands::options_template_command(file, cli.sort_by),
+        Commands::TerraConfig {
+            file,
+            workflow,
+            entity,
+        } => commands::terra_config_command(file, workflow, entity, cli.sort_by),
+        Commands::CheckInputs {
+            file,
+            inputs,
+            workflow,
+            format,
+        } => commands::check_inputs_command(file, inputs, workflow, format, cli.sort_by),
+        Commands::Mermaid {
+            file,
+            workflow,
+            task,
+            imports,
+            runtime,
+            edge_labels,
+            direction,
+            no_inputs,
+            no_outputs,
+            styles,
+            workspace,
+            depth,
+            import_dirs,
+        } => {
+            let options = mermaid::DiagramOptions {
+                imports,
+                runtime,
+                edge_labels,
+                direction,
+                hide_inputs: no_inputs,
+                hide_outputs: no_outputs,
+                styles: styles.into_iter().collect(),
+                ..Default::default()
+            };
+            if workspace {
+                commands::workspace_mermaid_command(
+                    file,
+                    workflow,
+                    depth,
+                    import_dirs,
+                    cli.config,
+                    options,
+                )
+            } else {
+                commands::mermaid_command(file, workflow, task, options, cli.sort_by)
+            }
+        }
+        Commands::Graph {
+            file,
+            mode,
+            workflow,
+            workspace,
+            depth,
+            import_dirs,
+            format,
+        } => {
+            if mode == GraphMode::Structs {
+                commands::struct_graph_command(
+                    file,
+                    workflow,
+                    depth,
+                    workspace,
+                    import_dirs,
+                    cli.config,
+                    format,
+                    cli.sort_by,
+                )
+            } else if workspace {
+                commands::workspace_graph_command(
+                    file,
+                    workflow,
+                    depth,
+                    import_dirs,
+                    cli.config,
+                    format,
+                )
+            } else {
+                commands::graph_command(file, workflow, format, cli.sort_by)
+            }
+        }
+        Commands::Plan {
+            file,
+            durations,
+            workflow,
+            mermaid,
+            format,
+        } => commands::plan_command(file, durations, workflow, mermaid, format, cli.sort_by),
+        Commands::Scatters {
+            file,
+            inputs,
+            workflow,
+            format,
+        } => commands::scatters_command(file, inputs, workflow, format, cli.sort_by),
+        Commands::Compat {
+            caller,
+            callee,
+            format,
+        } => commands::compat_command(caller, callee, format, cli.sort_by),
+        Commands::Bench {
+            paths,
+            iterations,
+            format,
+        } => commands::bench_command(paths, iterations, format),
+        Commands::Serve { port, host } => {
+            commands::serve_command(host, port, cli.config, cli.sort_by)
+        }
+        Commands::Rpc => commands::rpc_command(cli.config, cli.sort_by),
+        Commands::Completions { shell } => commands::completions_command(shell),
+        Commands::Manpages { dir } => commands::manpages_command(dir),
+        Commands::CheckBackend {
+            file,
+            profile,
+            format,
+        } => commands::check_backend_command(file, profile, cli.config, format, cli.sort_by),
     }
 }
@@ -0,0 +1,345 @@
+use crate::command::CommandLanguage;
+use crate::info::{Authorship, FilePatterns, InputInfo, MetaItem, TaskInfo, WdlInfo, WorkflowInfo};
+use crate::stats::FileStats;
+
+/// Render file information as Markdown, with an anchor per task and workflow
+pub fn info_markdown(file: &str, info: &WdlInfo) -> String {
+    let mut out = format!("# {}\n\n", file);
+    if let Some(version) = &info.version {
+        out.push_str(&format!("WDL version: `{}`\n\n", version));
+    }
+
+    if !info.workflows.is_empty() {
+        out.push_str("## Workflows\n\n");
+        out.push_str(&table(
+            &["Workflow", "Inputs", "Outputs", "Calls"],
+            info.workflows
+                .iter()
+                .map(|w| {
+                    vec![
+                        link("workflow", &w.name),
+                        w.inputs.len().to_string(),
+                        w.outputs.len().to_string(),
+                        w.calls.len().to_string(),
+                    ]
+                })
+                .collect(),
+        ));
+        for workflow in &info.workflows {
+            write_workflow(workflow, &mut out);
+        }
+    }
+
+    if !info.tasks.is_empty() {
+        out.push_str("## Tasks\n\n");
+        out.push_str(&table(
+            &["Task", "Inputs", "Outputs", "Runtime"],
+            info.tasks
+                .iter()
+                .map(|t| {
+                    vec![
+                        link("task", &t.name),
+                        t.inputs.len().to_string(),
+                        t.outputs.len().to_string(),
+                        t.runtime.len().to_string(),
+                    ]
+                })
+                .collect(),
+        ));
+        for task in &info.tasks {
+            write_task(task, &mut out);
+        }
+    }
+
+    if !info.structs.is_empty() {
+        out.push_str("## Structs\n\n");
+        for definition in &info.structs {
+            out.push_str(&format!("### `{}`\n\n", definition.name));
+            out.push_str(&declarations_table(&definition.fields, &[]));
+        }
+    }
+
+    if !info.imports.is_empty() {
+        out.push_str("## Imports\n\n");
+        out.push_str(&table(
+            &["URI", "Alias"],
+            info.imports
+                .iter()
+                .map(|i| {
+                    vec![
+                        code(&i.uri),
+                        i.alias.as_deref().map(code).unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+
+    out
+}
+
+/// Render file statistics as Markdown tables
+pub fn stats_markdown(stats: &FileStats) -> String {
+    let mut out = format!("# Statistics: {}\n\n", stats.file);
+    out.push_str(&table(
+        &["Lines", "Tasks", "Workflows", "Structs", "Imports", "Calls"],
+        vec![vec![
+            stats.lines.to_string(),
+            stats.tasks.to_string(),
+            stats.workflows.to_string(),
+            stats.structs.to_string(),
+            stats.imports.to_string(),
+            stats.calls.to_string(),
+        ]],
+    ));
+
+    if !stats.task_stats.is_empty() {
+        out.push_str("## Tasks\n\n");
+        out.push_str(&table(
+            &[
+                "Task",
+                "Inputs",
+                "Outputs",
+                "Command lines",
+                "CPU",
+                "Memory",
+                "Container",
+            ],
+            stats
+                .task_stats
+                .iter()
+                .map(|t| {
+                    vec![
+                        code(&t.name),
+                        t.inputs.to_string(),
+                        t.outputs.to_string(),
+                        t.command_lines.to_string(),
+                        t.cpu.as_deref().map(code).unwrap_or_default(),
+                        t.memory.as_deref().map(code).unwrap_or_default(),
+                        t.container.as_deref().map(code).unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+
+    for graph in &stats.graphs {
+        out.push_str(&format!("## Graph: `{}`\n\n", graph.workflow));
+        out.push_str(&table(
+            &["Nodes", "Edges", "Longest path", "Stage widths", "Isolated"],
+            vec![vec![
+                graph.nodes.to_string(),
+                graph.edges.to_string(),
+                graph.longest_path.to_string(),
+                graph
+                    .stage_widths
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                graph
+                    .isolated
+                    .iter()
+                    .map(|id| code(id))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]],
+        ));
+        out.push_str(&table(
+            &["Node", "Fan-in", "Fan-out"],
+            graph
+                .degrees
+                .iter()
+                .map(|d| vec![code(&d.id), d.fan_in.to_string(), d.fan_out.to_string()])
+                .collect(),
+        ));
+    }
+    out
+}
+
+/// Render one workflow's section on its own
+pub fn workflow_markdown(workflow: &WorkflowInfo) -> String {
+    let mut out = String::new();
+    write_workflow(workflow, &mut out);
+    out
+}
+
+/// Render one task's section on its own, followed by its command
+pub fn task_markdown(task: &TaskInfo) -> String {
+    let mut out = String::new();
+    write_task(task, &mut out);
+    if let Some(section) = &task.command_section {
+        let language = task.command_language.unwrap_or(CommandLanguage::Bash);
+        out.push_str(&format!("#### Command\n\n```{}\n", language.name()));
+        out.push_str(&section.dedented);
+        if !section.dedented.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str("```\n");
+    }
+    out
+}
+
+fn write_workflow(workflow: &WorkflowInfo, out: &mut String) {
+    out.push_str(&heading("workflow", &workflow.name));
+    out.push_str(&description(&workflow.authorship, workflow.doc.as_deref()));
+    if !workflow.inputs.is_empty() {
+        out.push_str("#### Inputs\n\n");
+        out.push_str(&declarations_table(
+            &workflow.inputs,
+            &workflow.parameter_meta,
+        ));
+    }
+    if !workflow.calls.is_empty() {
+        out.push_str("#### Calls\n\n");
+        out.push_str(&table(
+            &["Call", "Target"],
+            workflow
+                .calls
+                .iter()
+                .map(|c| vec![code(&c.name), code(&c.target)])
+                .collect(),
+        ));
+    }
+    if !workflow.outputs.is_empty() {
+        out.push_str("#### Outputs\n\n");
+        out.push_str(&table(
+            &["Name", "Type", "Expression"],
+            workflow
+                .outputs
+                .iter()
+                .map(|o| vec![code(&o.name), code(&o.wdl_type), code(&o.expression)])
+                .collect(),
+        ));
+    }
+}
+
+fn write_task(task: &TaskInfo, out: &mut String) {
+    out.push_str(&heading("task", &task.name));
+    out.push_str(&description(&task.authorship, task.doc.as_deref()));
+    if !task.inputs.is_empty() {
+        out.push_str("#### Inputs\n\n");
+        out.push_str(&declarations_table(&task.inputs, &task.parameter_meta));
+    }
+    if !task.outputs.is_empty() {
+        out.push_str("#### Outputs\n\n");
+        out.push_str(&table(
+            &["Name", "Type", "Expression", "Files"],
+            task.outputs
+                .iter()
+                .map(|o| {
+                    vec![
+                        code(&o.name),
+                        code(&o.wdl_type),
+                        code(&o.expression),
+                        file_patterns(&o.files),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+    if !task.runtime.is_empty() {
+        out.push_str("#### Runtime\n\n");
+        out.push_str(&table(
+            &["Key", "Value"],
+            task.runtime
+                .iter()
+                .map(|r| vec![code(&r.key), code(&r.value)])
+                .collect(),
+        ));
+    }
+}
+
+/// `glob:*.bam`, `stdout`, `stderr` and literal file names, comma-separated
+fn file_patterns(files: &FilePatterns) -> String {
+    let globs = files.globs.iter().map(|g| code(&format!("glob:{}", g)));
+    let streams = [("stdout", files.stdout), ("stderr", files.stderr)]
+        .into_iter()
+        .filter(|(_, used)| *used)
+        .map(|(name, _)| name.to_string());
+    let literals = files.literals.iter().map(|l| code(l));
+    globs
+        .chain(streams)
+        .chain(literals)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The `meta` description, or else the comment block above the definition
+fn description(authorship: &Authorship, doc: Option<&str>) -> String {
+    match authorship.description.as_deref().or(doc) {
+        Some(text) => format!("{}\n\n", text),
+        None => String::new(),
+    }
+}
+
+/// Declarations with their `parameter_meta` description, or else the
+/// comment block above each one
+fn declarations_table(declarations: &[InputInfo], parameter_meta: &[MetaItem]) -> String {
+    table(
+        &["Name", "Type", "Default", "Optional", "Description"],
+        declarations
+            .iter()
+            .map(|d| {
+                let description = parameter_meta
+                    .iter()
+                    .find(|item| item.key == d.name)
+                    .and_then(MetaItem::description)
+                    .or_else(|| d.doc.clone())
+                    .unwrap_or_default();
+                vec![
+                    code(&d.name),
+                    code(&d.wdl_type),
+                    d.default_value.as_deref().map(code).unwrap_or_default(),
+                    if d.optional { "yes" } else { "no" }.to_string(),
+                    cell(&description),
+                ]
+            })
+            .collect(),
+    )
+}
+
+/// Anchor id for a task or workflow section, e.g. `task-align_reads`
+pub fn anchor(kind: &str, name: &str) -> String {
+    format!("{}-{}", kind, name.to_lowercase())
+}
+
+fn heading(kind: &str, name: &str) -> String {
+    let title = match kind {
+        "task" => "Task",
+        _ => "Workflow",
+    };
+    format!(
+        "<a id=\"{}\"></a>\n\n### {}: `{}`\n\n",
+        anchor(kind, name),
+        title,
+        name
+    )
+}
+
+fn link(kind: &str, name: &str) -> String {
+    format!("[`{}`](#{})", name, anchor(kind, name))
+}
+
+/// Free text on one table row
+pub fn cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn code(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("`{}`", text.replace('|', "\\|"))
+}
+
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut out = format!("| {} |\n", headers.join(" | "));
+    out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+    for row in rows {
+        out.push_str(&format!("| {} |\n", row.join(" | ")));
+    }
+    out.push('\n');
+    out
+}
@@ -0,0 +1,565 @@
+use crate::expr;
+use crate::fanout::{ConsumerKind, FanOutReport};
+use crate::graph::{node_id, GraphEdge, GraphNode, NodeKind, WorkflowGraph, WorkspaceGraph};
+use crate::info::{TaskInfo, WdlInfo};
+use crate::markdown;
+use crate::stats::TaskStats;
+use crate::suggest;
+use std::collections::HashMap;
+
+/// Style applied to calls of tasks and workflows from imported files
+const IMPORTED_STYLE: &str = "fill:#eef3ff,stroke:#5b7bd5,stroke-dasharray:4 2";
+
+/// Style applied to highlighted nodes, such as a critical path
+const HIGHLIGHT_STYLE: &str = "fill:#ffe9e6,stroke:#d9534f,stroke-width:2px";
+
+/// Classes whose style `--style` can set: nodes by kind and calls from
+/// imported files
+pub const CLASSES: &[&str] = &["input", "call", "output", "imported"];
+
+/// Which way a flowchart runs
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Top to bottom
+    #[value(name = "TD")]
+    TopDown,
+    /// Left to right
+    #[value(name = "LR")]
+    LeftRight,
+    /// Bottom to top
+    #[value(name = "BT")]
+    BottomUp,
+    /// Right to left
+    #[value(name = "RL")]
+    RightLeft,
+}
+
+impl Direction {
+    /// The direction as Mermaid writes it after `flowchart`
+    pub fn code(self) -> &'static str {
+        match self {
+            Direction::TopDown => "TD",
+            Direction::LeftRight => "LR",
+            Direction::BottomUp => "BT",
+            Direction::RightLeft => "RL",
+        }
+    }
+}
+
+/// Where to show a task's cpu, memory and container on its node
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuntimeAnnotation {
+    /// Second line of the node label
+    Label,
+    /// Hover tooltip (links to the task's section in `info --format markdown`)
+    Tooltip,
+}
+
+/// Optional extras for workflow and task diagrams
+#[derive(Debug, Clone, Default)]
+pub struct DiagramOptions {
+    /// Link imported calls to a node for the file they come from
+    pub imports: bool,
+    /// Annotate calls (and the task command node) with runtime resources
+    pub runtime: Option<RuntimeAnnotation>,
+    /// Extra label line per node id
+    pub annotations: HashMap<String, String>,
+    /// Node ids drawn with the `highlight` class
+    pub highlight: Vec<String>,
+    /// Label edges from calls with the outputs the next node reads
+    pub edge_labels: bool,
+    /// Which way the flowchart runs, instead of the diagram's default
+    pub direction: Option<Direction>,
+    /// Leave out input nodes and their edges
+    pub hide_inputs: bool,
+    /// Leave out output nodes and their edges
+    pub hide_outputs: bool,
+    /// Style per class in `CLASSES`, replacing the default; classes of node
+    /// kinds are only styled when given here
+    pub styles: HashMap<String, String>,
+}
+
+impl DiagramOptions {
+    /// Whether nodes of `kind` are drawn
+    fn shows(&self, kind: NodeKind) -> bool {
+        match kind {
+            NodeKind::Input => !self.hide_inputs,
+            NodeKind::Call => true,
+            NodeKind::Output => !self.hide_outputs,
+        }
+    }
+
+    /// The `flowchart` line, running `default` unless a direction is set
+    fn header(&self, default: Direction) -> String {
+        format!("flowchart {}\n", self.direction.unwrap_or(default).code())
+    }
+
+    /// `classDef` and `class` lines giving `ids` the style of `class`: the
+    /// one set in `styles`, or else `default`
+    fn class_lines(&self, class: &str, default: Option<&str>, ids: &[&str]) -> String {
+        let style = self.styles.get(class).map(String::as_str).or(default);
+        match style {
+            Some(style) if !ids.is_empty() => format!(
+                "    classDef {} {}\n    class {} {}\n",
+                class,
+                style,
+                ids.join(","),
+                class
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Style lines for the classes of node kinds, for the nodes drawn
+    fn kind_classes<'a>(&self, nodes: impl IntoIterator<Item = &'a GraphNode>) -> String {
+        let nodes: Vec<&GraphNode> = nodes.into_iter().collect();
+        [
+            ("input", NodeKind::Input),
+            ("call", NodeKind::Call),
+            ("output", NodeKind::Output),
+        ]
+        .into_iter()
+        .filter(|(_, kind)| self.shows(*kind))
+        .map(|(class, kind)| {
+            let ids: Vec<&str> = nodes
+                .iter()
+                .filter(|node| node.kind == kind)
+                .map(|node| node.id.as_str())
+                .collect();
+            self.class_lines(class, None, &ids)
+        })
+        .collect()
+    }
+}
+
+/// Parse a `CLASS=STYLE` style override such as `call=fill:#fff,stroke:#333`
+pub fn parse_style(spec: &str) -> Result<(String, String), String> {
+    let (class, style) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("expected CLASS=STYLE, e.g. call=fill:#fff, not '{}'", spec))?;
+    check_class(class)?;
+    Ok((class.to_string(), style.to_string()))
+}
+
+/// Fail for a class `--style` can't set, suggesting close names
+pub fn check_class(class: &str) -> Result<(), String> {
+    if CLASSES.contains(&class) {
+        return Ok(());
+    }
+    let suggestions = suggest::similar_names(class, CLASSES.iter().copied());
+    Err(format!(
+        "unknown class '{}', expected one of {}{}",
+        class,
+        CLASSES.join(", "),
+        suggest::did_you_mean(&suggestions)
+    ))
+}
+
+/// Render a workflow graph as a Mermaid flowchart, top-down by default.
+/// Calls into an imported namespace carry a `[namespace]` badge and the
+/// `imported` class.
+pub fn workflow_diagram(graph: &WorkflowGraph, info: &WdlInfo, options: &DiagramOptions) -> String {
+    let mut out = options.header(Direction::TopDown);
+    let mut imported = Vec::new();
+    let mut tooltips = Vec::new();
+    let nodes: Vec<&GraphNode> = graph
+        .nodes
+        .iter()
+        .filter(|node| options.shows(node.kind))
+        .collect();
+    for node in &nodes {
+        let definition = match node.kind {
+            NodeKind::Input => format!("{}([\"{}\"])", node.id, escape(&node.name)),
+            NodeKind::Call => {
+                let mut label = escape(&call_label(node));
+                let task = node
+                    .target
+                    .as_ref()
+                    .and_then(|target| info.tasks.iter().find(|t| &t.name == target));
+                if let (Some(task), Some(annotation)) = (task, options.runtime) {
+                    if let Some(summary) = runtime_summary(task) {
+                        match annotation {
+                            RuntimeAnnotation::Label => {
+                                label.push_str(&format!("<br/>{}", escape(&summary)))
+                            }
+                            RuntimeAnnotation::Tooltip => {
+                                let anchor = markdown::anchor("task", &task.name);
+                                tooltips.push(tooltip(&node.id, &anchor, &summary));
+                            }
+                        }
+                    }
+                }
+                if let Some(note) = options.annotations.get(&node.id) {
+                    label.push_str(&format!("<br/>{}", escape(note)));
+                }
+                format!("{}[\"{}\"]", node.id, label)
+            }
+            NodeKind::Output => format!("{}[/\"{}\"/]", node.id, escape(&node.name)),
+        };
+        out.push_str(&format!("    {}\n", definition));
+        if let Some(namespace) = namespace(node) {
+            imported.push((node.id.as_str(), namespace));
+        }
+    }
+
+    if options.imports {
+        for import in &info.imports {
+            let namespace = import.namespace();
+            if imported.iter().any(|(_, ns)| *ns == namespace) {
+                out.push_str(&format!(
+                    "    import_{}{{{{\"{}\"}}}}\n",
+                    namespace,
+                    escape(&import.uri)
+                ));
+            }
+        }
+    }
+    for edge in shown_edges(&graph.edges, &graph.nodes, options) {
+        out.push_str(&edge_line(edge, options));
+    }
+    if options.imports {
+        for (id, namespace) in &imported {
+            if info.imports.iter().any(|i| i.namespace() == *namespace) {
+                out.push_str(&format!("    import_{} -.-> {}\n", namespace, id));
+            }
+        }
+    }
+
+    out.push_str(&options.kind_classes(nodes.iter().copied()));
+    let ids: Vec<&str> = imported.iter().map(|(id, _)| *id).collect();
+    out.push_str(&options.class_lines("imported", Some(IMPORTED_STYLE), &ids));
+    let highlighted: Vec<&str> = nodes
+        .iter()
+        .filter(|node| options.highlight.contains(&node.id))
+        .map(|node| node.id.as_str())
+        .collect();
+    out.push_str(&options.class_lines("highlight", Some(HIGHLIGHT_STYLE), &highlighted));
+    for line in tooltips {
+        out.push_str(&line);
+    }
+    out
+}
+
+/// Render a workspace graph as one flowchart: each workflow in a subgraph
+/// labeled with its file, and a dotted edge from every expanded sub-workflow
+/// call to the subgraph of the workflow it runs
+pub fn workspace_diagram(graph: &WorkspaceGraph, options: &DiagramOptions) -> String {
+    let mut out = options.header(Direction::TopDown);
+    let nodes: Vec<&GraphNode> = graph
+        .graphs
+        .iter()
+        .flat_map(|sub| &sub.graph.nodes)
+        .filter(|node| options.shows(node.kind))
+        .collect();
+    for sub in &graph.graphs {
+        out.push_str(&format!(
+            "    subgraph sub_{}[\"{} · {}\"]\n",
+            sub.id,
+            escape(&sub.graph.workflow),
+            escape(&sub.file)
+        ));
+        for node in sub
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| options.shows(node.kind))
+        {
+            let definition = match node.kind {
+                NodeKind::Input => format!("{}([\"{}\"])", node.id, escape(&node.name)),
+                NodeKind::Call => format!("{}[\"{}\"]", node.id, escape(&call_label(node))),
+                NodeKind::Output => format!("{}[/\"{}\"/]", node.id, escape(&node.name)),
+            };
+            out.push_str(&format!("        {}\n", definition));
+        }
+        out.push_str("    end\n");
+    }
+    let edges = graph.graphs.iter().flat_map(|sub| &sub.graph.edges);
+    let all_nodes = graph.graphs.iter().flat_map(|sub| &sub.graph.nodes);
+    for edge in shown_edges(edges, all_nodes, options) {
+        out.push_str(&edge_line(edge, options));
+    }
+    for sub in graph.graphs.iter().filter(|sub| sub.parent.is_some()) {
+        out.push_str(&format!("    {} -.-> sub_{}\n", sub.id, sub.id));
+    }
+    out.push_str(&options.kind_classes(nodes));
+    out
+}
+
+/// Style applied to workflow inputs that nothing reads
+const UNUSED_STYLE: &str = "fill:#f4f4f4,stroke:#999,stroke-dasharray:4 2,color:#777";
+
+/// Render an input fan-out report as a bipartite flowchart: workflow inputs
+/// on the left, the calls, blocks and outputs reading them on the right.
+/// Edges name the call input the value is passed to and any declarations it
+/// flows through; unused inputs get the dashed `unused` class.
+pub fn fanout_diagram(report: &FanOutReport) -> String {
+    let mut out = String::from("flowchart LR\n");
+    for input in &report.inputs {
+        out.push_str(&format!(
+            "    {}([\"{}: {}\"])\n",
+            node_id(NodeKind::Input, &input.name),
+            escape(&input.name),
+            escape(&input.wdl_type)
+        ));
+    }
+
+    let mut consumers: Vec<(ConsumerKind, &str)> = Vec::new();
+    for found in report.inputs.iter().flat_map(|input| &input.uses) {
+        if !consumers.contains(&(found.kind, found.name.as_str())) {
+            consumers.push((found.kind, found.name.as_str()));
+        }
+    }
+    let consumer_id = |kind: ConsumerKind, name: &str| match kind {
+        ConsumerKind::Call => node_id(NodeKind::Call, name),
+        ConsumerKind::Output => node_id(NodeKind::Output, name),
+        ConsumerKind::Scatter => format!("scatter_{}", name),
+        ConsumerKind::Conditional => {
+            let index = consumers
+                .iter()
+                .filter(|(kind, _)| *kind == ConsumerKind::Conditional)
+                .position(|(_, condition)| *condition == name)
+                .unwrap_or(0);
+            format!("if_{}", index + 1)
+        }
+    };
+    for (kind, name) in &consumers {
+        let id = consumer_id(*kind, name);
+        let definition = match kind {
+            ConsumerKind::Call => format!("{}[\"{}\"]", id, escape(name)),
+            ConsumerKind::Output => format!("{}[/\"{}\"/]", id, escape(name)),
+            ConsumerKind::Scatter => format!("{}{{{{\"scatter {}\"}}}}", id, escape(name)),
+            ConsumerKind::Conditional => format!("{}{{{{\"if {}\"}}}}", id, escape(name)),
+        };
+        out.push_str(&format!("    {}\n", definition));
+    }
+
+    for input in &report.inputs {
+        let from = node_id(NodeKind::Input, &input.name);
+        for found in &input.uses {
+            let mut label: Vec<String> = found.parameter.iter().cloned().collect();
+            if !found.via.is_empty() {
+                label.push(format!("via {}", found.via.join(" → ")));
+            }
+            let to = consumer_id(found.kind, &found.name);
+            if label.is_empty() {
+                out.push_str(&format!("    {} --> {}\n", from, to));
+            } else {
+                out.push_str(&format!(
+                    "    {} -->|\"{}\"| {}\n",
+                    from,
+                    escape(&label.join(", ")),
+                    to
+                ));
+            }
+        }
+    }
+
+    if !report.unused.is_empty() {
+        out.push_str(&format!("    classDef unused {}\n", UNUSED_STYLE));
+        let ids: Vec<String> = report
+            .unused
+            .iter()
+            .map(|name| node_id(NodeKind::Input, name))
+            .collect();
+        out.push_str(&format!("    class {} unused\n", ids.join(",")));
+    }
+    out
+}
+
+/// The edges that don't touch one of the `nodes` left out
+fn shown_edges<'a, 'b>(
+    edges: impl IntoIterator<Item = &'a GraphEdge>,
+    nodes: impl IntoIterator<Item = &'b GraphNode>,
+    options: &DiagramOptions,
+) -> Vec<&'a GraphEdge> {
+    let hidden: Vec<&str> = nodes
+        .into_iter()
+        .filter(|node| !options.shows(node.kind))
+        .map(|node| node.id.as_str())
+        .collect();
+    edges
+        .into_iter()
+        .filter(|edge| !hidden.contains(&edge.from.as_str()) && !hidden.contains(&edge.to.as_str()))
+        .collect()
+}
+
+/// A data-flow edge, labelled with the outputs it carries when
+/// `options.edge_labels` is set
+fn edge_line(edge: &GraphEdge, options: &DiagramOptions) -> String {
+    match edge.label().filter(|_| options.edge_labels) {
+        Some(label) => format!(
+            "    {} -->|\"{}\"| {}\n",
+            edge.from,
+            escape(&label),
+            edge.to
+        ),
+        None => format!("    {} --> {}\n", edge.from, edge.to),
+    }
+}
+
+/// Compact `cpu · memory · container` text for a task, if it declares any of them
+pub fn runtime_summary(task: &TaskInfo) -> Option<String> {
+    let stats = TaskStats::from_task(task);
+    let parts: Vec<String> = [
+        stats.cpu.map(|cpu| format!("cpu {}", cpu)),
+        stats.memory,
+        stats.container,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// A `click` line giving a node a hover tooltip
+fn tooltip(id: &str, anchor: &str, text: &str) -> String {
+    format!(
+        "    click {} \"#{}\" \"{}\"\n",
+        id,
+        anchor,
+        text.replace('"', "'")
+    )
+}
+
+/// `alias (task)` for aliased calls, so repeated calls of a task stay
+/// distinguishable, prefixed with a `[namespace]` badge for imported calls
+fn call_label(node: &GraphNode) -> String {
+    let Some(target) = &node.target else {
+        return node.name.clone();
+    };
+    let task = target.rsplit('.').next().unwrap_or(target);
+    let label = if task != node.name {
+        format!("{} ({})", node.name, task)
+    } else {
+        node.name.clone()
+    };
+    match namespace(node) {
+        Some(namespace) => format!("[{}] {}", namespace, label),
+        None => label,
+    }
+}
+
+/// Import namespace of a call such as `lib.align`
+fn namespace(node: &GraphNode) -> Option<&str> {
+    node.target
+        .as_deref()
+        .and_then(|target| target.rsplit_once('.'))
+        .map(|(namespace, _)| namespace)
+}
+
+/// Render a single task's data flow: inputs feed the command through its
+/// placeholders, and outputs come from the command (stdout, stderr, globs or
+/// files it writes) or directly from inputs.
+pub fn task_diagram(task: &TaskInfo, options: &DiagramOptions) -> String {
+    let mut out = options.header(Direction::LeftRight);
+    let inputs = if options.hide_inputs {
+        &[]
+    } else {
+        task.inputs.as_slice()
+    };
+    let outputs = if options.hide_outputs {
+        &[]
+    } else {
+        task.outputs.as_slice()
+    };
+    for input in inputs {
+        out.push_str(&format!(
+            "    input_{}([\"{}: {}\"])\n",
+            input.name,
+            escape(&input.name),
+            escape(&input.wdl_type)
+        ));
+    }
+    let mut label = format!("command ({})", escape(&task.name));
+    let summary = runtime_summary(task);
+    if let (Some(summary), Some(RuntimeAnnotation::Label)) = (&summary, options.runtime) {
+        label.push_str(&format!("<br/>{}", escape(summary)));
+    }
+    out.push_str(&format!("    command[[\"{}\"]]\n", label));
+    for output in outputs {
+        out.push_str(&format!(
+            "    output_{}[/\"{}: {}\"/]\n",
+            output.name,
+            escape(&output.name),
+            escape(&output.wdl_type)
+        ));
+    }
+
+    let placeholders = task
+        .command_section
+        .as_ref()
+        .map(|section| section.placeholders())
+        .unwrap_or_default();
+    for input in inputs {
+        let consuming: Vec<String> = placeholders
+            .iter()
+            .filter(|placeholder| expr::referenced_roots(placeholder).contains(&input.name))
+            .map(|placeholder| format!("~{{{}}}", placeholder))
+            .collect();
+        if !consuming.is_empty() {
+            out.push_str(&format!(
+                "    input_{} -->|\"{}\"| command\n",
+                input.name,
+                escape(&consuming.join(", "))
+            ));
+        }
+    }
+
+    for output in outputs {
+        let roots = expr::referenced_roots(&output.expression);
+        let from_inputs: Vec<&str> = task
+            .inputs
+            .iter()
+            .filter(|input| roots.contains(&input.name))
+            .map(|input| input.name.as_str())
+            .collect();
+        for input in from_inputs.iter().filter(|_| !options.hide_inputs) {
+            out.push_str(&format!("    input_{} --> output_{}\n", input, output.name));
+        }
+
+        match command_source(&output.expression) {
+            Some(source) => out.push_str(&format!(
+                "    command -->|{}| output_{}\n",
+                source, output.name
+            )),
+            None if from_inputs.is_empty() => {
+                out.push_str(&format!("    command --> output_{}\n", output.name))
+            }
+            None => {}
+        }
+    }
+
+    let input_ids: Vec<String> = inputs
+        .iter()
+        .map(|input| format!("input_{}", input.name))
+        .collect();
+    let output_ids: Vec<String> = outputs
+        .iter()
+        .map(|output| format!("output_{}", output.name))
+        .collect();
+    for (class, ids) in [
+        ("input", input_ids),
+        ("call", vec!["command".to_string()]),
+        ("output", output_ids),
+    ] {
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        out.push_str(&options.class_lines(class, None, &ids));
+    }
+    if let (Some(summary), Some(RuntimeAnnotation::Tooltip)) = (&summary, options.runtime) {
+        let anchor = markdown::anchor("task", &task.name);
+        out.push_str(&tooltip("command", &anchor, summary));
+    }
+    out
+}
+
+/// How an output expression reads what the command produced, if it names it
+fn command_source(expression: &str) -> Option<&'static str> {
+    ["stdout", "stderr", "glob"]
+        .into_iter()
+        .find(|function| expression.contains(&format!("{}(", function)))
+}
+
+/// Escape text for use inside a quoted Mermaid label
+pub fn escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
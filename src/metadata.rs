@@ -1,6 +1,6 @@
+use crate::info::SortBy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 
 /// Basic metadata extraction that works even with severely malformed WDL files
 #[derive(Default, Serialize, Deserialize, Debug)]
@@ -15,9 +15,10 @@ impl BasicWdlMetadata {
         Self::default()
     }
 
-    /// Extract basic metadata from WDL content using regex patterns
+    /// Extract basic metadata from WDL content using regex patterns, with
+    /// task names in `order`
     /// This is a fallback method that works even when the syntax tree parsing fails
-    pub fn extract_from_text(content: &str) -> Self {
+    pub fn extract_from_text(content: &str, order: SortBy) -> Self {
         let mut metadata = BasicWdlMetadata::new();
 
         // Extract version
@@ -31,7 +32,7 @@ impl BasicWdlMetadata {
         }
 
         // Extract task names (can be multiple)
-        metadata.task_names = Self::extract_task_names(content);
+        metadata.task_names = Self::extract_task_names(content, order);
 
         metadata
     }
@@ -55,23 +56,28 @@ impl BasicWdlMetadata {
             .map(|m| m.as_str().to_string())
     }
 
-    /// Extract all task names from WDL content
-    fn extract_task_names(content: &str) -> Vec<String> {
+    /// Extract all task names from WDL content, each once, in the order of
+    /// `--sort-by` (source order by default)
+    fn extract_task_names(content: &str, order: SortBy) -> Vec<String> {
         let task_regex = match Regex::new(r"(?m)^\s*task\s+([a-zA-Z_][a-zA-Z0-9_]*)\s*\{") {
             Ok(regex) => regex,
             Err(_) => return Vec::new(),
         };
 
-        let mut task_names = HashSet::new();
+        let mut names: Vec<String> = Vec::new();
 
         for captures in task_regex.captures_iter(content) {
             if let Some(task_match) = captures.get(1) {
-                task_names.insert(task_match.as_str().to_string());
+                let name = task_match.as_str();
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
             }
         }
 
-        let mut names: Vec<String> = task_names.into_iter().collect();
-        names.sort();
+        if order == SortBy::Name {
+            names.sort();
+        }
         names
     }
 }
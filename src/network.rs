@@ -0,0 +1,88 @@
+use crate::lint::{Finding, Severity};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Whether commands may make network requests; `--offline` turns it off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkAccess {
+    #[default]
+    Online,
+    Offline,
+}
+
+impl NetworkAccess {
+    pub fn from_offline_flag(offline: bool) -> Self {
+        if offline {
+            NetworkAccess::Offline
+        } else {
+            NetworkAccess::Online
+        }
+    }
+
+    /// Fail with a [`BlockedRequest`] instead of making a request while offline
+    pub fn check(self, kind: RequestKind, resource: &str) -> Result<()> {
+        match self {
+            NetworkAccess::Online => Ok(()),
+            NetworkAccess::Offline => Err(BlockedRequest {
+                kind,
+                resource: resource.to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+/// What a network request was for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    /// Downloading a remote import
+    RemoteImport,
+    /// Looking up the digest of a container image tag
+    RegistryDigest,
+}
+
+impl RequestKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            RequestKind::RemoteImport => "remote import",
+            RequestKind::RegistryDigest => "registry digest lookup for",
+        }
+    }
+}
+
+/// A request refused because the network is disabled
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockedRequest {
+    pub kind: RequestKind,
+    /// URL, or image reference for registry lookups
+    pub resource: String,
+}
+
+impl BlockedRequest {
+    /// The blocked request as a lint finding, for commands that report findings
+    pub fn finding(&self) -> Finding {
+        Finding {
+            rule: "network-disabled".to_string(),
+            severity: Severity::Warning,
+            location: self.resource.clone(),
+            line: None,
+            message: self.to_string(),
+            suggestions: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for BlockedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} skipped: network access is disabled by --offline",
+            self.kind.label(),
+            self.resource
+        )
+    }
+}
+
+impl std::error::Error for BlockedRequest {}
@@ -0,0 +1,159 @@
+use serde_json::Value;
+use std::sync::atomic::{self, AtomicBool};
+
+/// Collections whose order follows the source file or the order files were
+/// read; `--normalize` sorts them by name (or `uri`/`path`)
+const SORTED_KEYS: &[&str] = &[
+    "tasks",
+    "workflows",
+    "structs",
+    "imports",
+    "inputs",
+    "outputs",
+    "declarations",
+    "fields",
+    "calls",
+];
+
+/// Keys an element of a sorted collection is ordered by, first one present wins
+const SORT_BY: &[&str] = &["name", "uri", "path"];
+
+/// Keys whose string values (or arrays of strings) are file system paths;
+/// only these have machine-specific prefixes removed, so WDL content such as
+/// commands and defaults is left as written
+const PATH_KEYS: &[&str] = &[
+    "file",
+    "file_path",
+    "path",
+    "dest",
+    "caller",
+    "callee",
+    "old",
+    "new",
+    "options",
+];
+
+/// Timestamps are replaced by the Unix epoch
+const EPOCH: &str = "1970-01-01T00:00:00Z";
+
+/// Decimal places other numbers are rounded to
+const DECIMALS: i32 = 3;
+
+/// Whether JSON output is normalized (`--normalize`)
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Normalize all JSON output of the running command
+pub fn enable() {
+    ENABLED.store(true, atomic::Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(atomic::Ordering::Relaxed)
+}
+
+/// Rewrites a JSON report so it is the same on every machine and run:
+/// collections sorted, machine-specific prefixes removed from paths, timestamps
+/// fixed, wall-clock timings zeroed and other numbers rounded
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+    /// Path prefixes and what they are replaced with, most specific first
+    prefixes: Vec<(String, String)>,
+}
+
+impl Normalizer {
+    /// Paths under the current directory become relative, and the temporary
+    /// and home directories become `$TMPDIR/` and `~/`
+    pub fn from_env() -> Self {
+        let mut prefixes = Vec::new();
+        if let Ok(current) = std::env::current_dir() {
+            prefixes.push((current.display().to_string(), String::new()));
+        }
+        prefixes.push((
+            std::env::temp_dir().display().to_string(),
+            "$TMPDIR/".to_string(),
+        ));
+        if let Some(home) = std::env::var_os("HOME") {
+            prefixes.push((home.to_string_lossy().into_owned(), "~/".to_string()));
+        }
+        let prefixes = prefixes
+            .into_iter()
+            .map(|(prefix, replacement)| (prefix.trim_end_matches('/').to_string(), replacement))
+            .filter(|(prefix, _)| !prefix.is_empty())
+            .map(|(prefix, replacement)| (format!("{}/", prefix), replacement))
+            .collect();
+        Self { prefixes }
+    }
+
+    pub fn normalize(&self, value: &mut Value) {
+        self.normalize_value(None, value);
+    }
+
+    fn normalize_value(&self, key: Option<&str>, value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    self.normalize_value(Some(key), value);
+                }
+            }
+            Value::Array(items) => {
+                // Strings in a path-valued array are paths too
+                let item_key = key.filter(|key| PATH_KEYS.contains(key));
+                for item in items.iter_mut() {
+                    self.normalize_value(item_key, item);
+                }
+                if key.is_some_and(|key| SORTED_KEYS.contains(&key)) {
+                    sort(items);
+                }
+            }
+            Value::String(text) if key == Some("timestamp") => *text = EPOCH.to_string(),
+            Value::String(text) if key.is_some_and(|key| PATH_KEYS.contains(&key)) => {
+                *text = self.strip_prefixes(text)
+            }
+            Value::Number(number) if number.is_f64() => {
+                let timing = key.is_some_and(|key| key.ends_with("_ms"));
+                *value = round(if timing {
+                    0.0
+                } else {
+                    number.as_f64().unwrap_or(0.0)
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn strip_prefixes(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (prefix, replacement) in &self.prefixes {
+            text = text.replace(prefix.as_str(), replacement);
+            // The directory itself, e.g. a path argument of `.`
+            if text == prefix.trim_end_matches('/') {
+                text = match replacement.as_str() {
+                    "" => ".".to_string(),
+                    _ => replacement.trim_end_matches('/').to_string(),
+                };
+            }
+        }
+        text
+    }
+}
+
+/// Sort strings, or objects by their name; anything else keeps its order
+fn sort(items: &mut [Value]) {
+    if items.iter().all(Value::is_string) {
+        items.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+    } else if items.iter().all(|item| sort_key(item).is_some()) {
+        items.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+    }
+}
+
+fn sort_key(item: &Value) -> Option<&str> {
+    SORT_BY
+        .iter()
+        .find_map(|key| item.get(key).and_then(Value::as_str))
+}
+
+fn round(number: f64) -> Value {
+    let scale = 10f64.powi(DECIMALS);
+    let rounded = (number * scale).round() / scale;
+    serde_json::Number::from_f64(rounded).map_or(Value::Null, Value::Number)
+}
@@ -0,0 +1,77 @@
+use crate::units::{self, SizeUnit};
+use std::sync::atomic::{self, AtomicBool};
+
+/// Decimal places of numbers in human output
+pub const DECIMALS: usize = 2;
+
+/// Units sizes are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeUnits {
+    /// KiB, MiB, GiB, TiB: powers of 1024
+    #[default]
+    Binary,
+    /// KB, MB, GB, TB: powers of 1000
+    Si,
+}
+
+impl SizeUnits {
+    pub fn units(self) -> &'static [SizeUnit] {
+        match self {
+            SizeUnits::Binary => &SizeUnit::BINARY,
+            SizeUnits::Si => &SizeUnit::DECIMAL,
+        }
+    }
+}
+
+/// Whether sizes are shown in SI units (`--si`)
+static SI: AtomicBool = AtomicBool::new(false);
+
+/// Show every size of the running command in `units`
+pub fn set_size_units(units: SizeUnits) {
+    SI.store(units == SizeUnits::Si, atomic::Ordering::Relaxed);
+}
+
+pub fn size_units() -> SizeUnits {
+    if SI.load(atomic::Ordering::Relaxed) {
+        SizeUnits::Si
+    } else {
+        SizeUnits::Binary
+    }
+}
+
+/// `bytes` in the largest unit of the chosen system that keeps the amount
+/// at least 1, e.g. `1.5 GiB` or, with `--si`, `1.61 GB`. The system comes
+/// from the command line rather than the platform, so reports read the same
+/// on every machine.
+pub fn size(bytes: u64) -> String {
+    size_in(bytes, size_units())
+}
+
+/// `bytes` in the largest unit of `units` that keeps the amount at least 1
+pub fn size_in(bytes: u64, units: SizeUnits) -> String {
+    units::format_size(bytes, units.units())
+}
+
+/// `value` with up to [`DECIMALS`] decimal places
+pub fn number(value: f64) -> String {
+    decimal(value, DECIMALS)
+}
+
+/// `value` rounded to `decimals` places without trailing zeros: `1.50`
+/// is `1.5`, `2.00` is `2` and `-0.001` is `0`. Always `.` as the decimal
+/// separator and no digit grouping, whatever the locale.
+pub fn decimal(value: f64, decimals: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let text = format!("{:.*}", decimals, value);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
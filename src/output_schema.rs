@@ -0,0 +1,538 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+/// Version of the structure of wdlparse's JSON reports, carried in their
+/// `format_version` field. The major number changes when a field is removed,
+/// renamed or changes type; the minor number when fields are added.
+pub const FORMAT_VERSION: &str = "1.0";
+
+/// JSON Schema draft the output schemas are written in
+const SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Which of wdlparse's own JSON reports to describe
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfSchema {
+    /// All of them, keyed by report
+    All,
+    /// `info --format json`
+    Info,
+    /// `parse --format json`
+    Diagnostics,
+    /// `graph --format json`
+    Graph,
+}
+
+impl SelfSchema {
+    /// Schema for the report, or an object of all of them
+    pub fn schema(self) -> Value {
+        match self {
+            SelfSchema::All => json!({
+                "info": info_schema(),
+                "diagnostics": diagnostics_schema(),
+                "graph": graph_schema(),
+            }),
+            SelfSchema::Info => info_schema(),
+            SelfSchema::Diagnostics => diagnostics_schema(),
+            SelfSchema::Graph => graph_schema(),
+        }
+    }
+}
+
+/// Schema of the `info --format json` report: the `WdlInfo` of a file
+pub fn info_schema() -> Value {
+    let mut properties = header_properties();
+    properties["file"] = json!({ "type": "string" });
+    for (key, value) in wdl_info()["properties"].as_object().expect("object") {
+        properties[key] = value.clone();
+    }
+    properties["basic_metadata"] = json!({ "$ref": "#/definitions/BasicWdlMetadata" });
+    properties["wiring"] = json!({ "type": "array", "items": { "type": "object" } });
+    document(
+        "wdlparse info report",
+        "Tasks, workflows, structs and imports extracted from a WDL file",
+        properties,
+        &[
+            "format_version",
+            "file",
+            "version",
+            "tasks",
+            "workflows",
+            "structs",
+            "imports",
+        ],
+    )
+}
+
+/// Schema of the `parse --format json` report: grouped parse diagnostics
+/// and the `WdlInfo` of the file
+pub fn diagnostics_schema() -> Value {
+    let mut properties = header_properties();
+    properties["file"] = json!({ "type": "string" });
+    properties["diagnostics"] = json!({
+        "description": "Number of parse diagnostics reported",
+        "type": "integer",
+        "minimum": 0
+    });
+    properties["has_errors"] = json!({ "type": "boolean" });
+    properties["diagnostic_groups"] = json!({
+        "type": "array",
+        "items": { "$ref": "#/definitions/DiagnosticGroup" }
+    });
+    properties["omitted_diagnostic_groups"] = json!({
+        "description": "Root causes left out by --max-diagnostics",
+        "type": "integer",
+        "minimum": 0
+    });
+    properties["wdl"] = json!({ "$ref": "#/definitions/WdlInfo" });
+    properties["basic_metadata"] = json!({ "$ref": "#/definitions/BasicWdlMetadata" });
+    document(
+        "wdlparse diagnostics report",
+        "Parse diagnostics of a WDL file grouped by root cause",
+        properties,
+        &[
+            "format_version",
+            "file",
+            "diagnostics",
+            "has_errors",
+            "diagnostic_groups",
+            "wdl",
+        ],
+    )
+}
+
+/// Schema of the `graph --format json` report: the data-flow graph of each
+/// workflow in a file
+pub fn graph_schema() -> Value {
+    let mut properties = header_properties();
+    properties["file"] = json!({ "type": "string" });
+    properties["graphs"] = json!({
+        "type": "array",
+        "items": { "$ref": "#/definitions/WorkflowGraph" }
+    });
+    document(
+        "wdlparse graph report",
+        "Data-flow graphs of the workflows in a WDL file",
+        properties,
+        &["format_version", "file", "graphs"],
+    )
+}
+
+/// Properties every report has: its format version and what generated it
+fn header_properties() -> Value {
+    json!({
+        "format_version": {
+            "description": "Version of the report structure",
+            "const": FORMAT_VERSION
+        },
+        "generator": { "$ref": "#/definitions/Generator" }
+    })
+}
+
+/// A report schema with the definitions it refers to. Reports may gain
+/// fields within a format version, so additional properties are allowed.
+fn document(title: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    let all = definitions();
+    let mut used = BTreeSet::new();
+    references(&properties, &mut used);
+    let mut definitions = Map::new();
+    while let Some(name) = used
+        .iter()
+        .find(|name| !definitions.contains_key(*name))
+        .cloned()
+    {
+        let definition = all[name.as_str()].clone();
+        references(&definition, &mut used);
+        definitions.insert(name, definition);
+    }
+    json!({
+        "$schema": SCHEMA_DRAFT,
+        "title": title,
+        "description": description,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "definitions": definitions,
+    })
+}
+
+/// Names of the definitions `value` refers to with `$ref`
+fn references(value: &Value, names: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(name) = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|target| target.strip_prefix("#/definitions/"))
+            {
+                names.insert(name.to_string());
+            }
+            object.values().for_each(|value| references(value, names));
+        }
+        Value::Array(items) => items.iter().for_each(|item| references(item, names)),
+        _ => {}
+    }
+}
+
+fn nullable(kind: &str) -> Value {
+    json!({ "type": [kind, "null"] })
+}
+
+fn array_of(definition: &str) -> Value {
+    json!({ "type": "array", "items": { "$ref": format!("#/definitions/{}", definition) } })
+}
+
+fn strings() -> Value {
+    json!({ "type": "array", "items": { "type": "string" } })
+}
+
+fn wdl_info() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "version": nullable("string"),
+            "tasks": array_of("TaskInfo"),
+            "workflows": array_of("WorkflowInfo"),
+            "structs": array_of("StructInfo"),
+            "imports": array_of("ImportInfo"),
+        },
+        "required": ["version", "tasks", "workflows", "structs", "imports"]
+    })
+}
+
+fn definitions() -> Value {
+    let authorship = json!({
+        "author": nullable("string"),
+        "email": nullable("string"),
+        "version": nullable("string"),
+        "description": nullable("string"),
+    });
+    let mut task = json!({
+        "name": { "type": "string" },
+        "line": { "type": "integer", "minimum": 0 },
+        "complete": { "type": "boolean" },
+        "doc": { "type": "string" },
+        "inputs": array_of("InputInfo"),
+        "outputs": array_of("OutputInfo"),
+        "declarations": array_of("InputInfo"),
+        "command": nullable("string"),
+        "command_section": {
+            "oneOf": [{ "$ref": "#/definitions/CommandSection" }, { "type": "null" }]
+        },
+        "command_language": {
+            "enum": ["bash", "python", "r", "perl", "ruby", "javascript", null]
+        },
+        "runtime": array_of("RuntimeItem"),
+        "normalized_runtime": { "$ref": "#/definitions/NormalizedRuntime" },
+        "meta": array_of("MetaItem"),
+        "parameter_meta": array_of("MetaItem"),
+        "source": { "$ref": "#/definitions/SourceExcerpt" },
+    });
+    let mut workflow = json!({
+        "name": { "type": "string" },
+        "line": { "type": "integer", "minimum": 0 },
+        "complete": { "type": "boolean" },
+        "doc": { "type": "string" },
+        "inputs": array_of("InputInfo"),
+        "outputs": array_of("OutputInfo"),
+        "calls": array_of("CallInfo"),
+        "declarations": array_of("InputInfo"),
+        "scatters": array_of("ScatterInfo"),
+        "conditionals": array_of("ConditionalInfo"),
+        "meta": array_of("MetaItem"),
+        "parameter_meta": array_of("MetaItem"),
+        "source": { "$ref": "#/definitions/SourceExcerpt" },
+    });
+    for (key, value) in authorship.as_object().expect("object") {
+        task[key] = value.clone();
+        workflow[key] = value.clone();
+    }
+    let parsed_type = json!({
+        "oneOf": [{ "$ref": "#/definitions/WdlType" }, { "type": "null" }]
+    });
+    let located = json!({
+        "code": { "type": "string" },
+        "severity": { "enum": ["error", "warning", "note"] },
+        "message": { "type": "string" },
+        "line": nullable("integer"),
+        "column": nullable("integer"),
+    });
+    let mut group = located.clone();
+    group["related"] = array_of("Located");
+
+    json!({
+        "WdlInfo": wdl_info(),
+        "TaskInfo": {
+            "type": "object",
+            "properties": task,
+            "required": [
+                "name", "line", "inputs", "outputs", "declarations", "command",
+                "command_section", "command_language", "runtime", "normalized_runtime", "meta", "parameter_meta"
+            ]
+        },
+        "WorkflowInfo": {
+            "type": "object",
+            "properties": workflow,
+            "required": [
+                "name", "line", "inputs", "outputs", "calls", "declarations",
+                "scatters", "conditionals", "meta", "parameter_meta"
+            ]
+        },
+        "SourceExcerpt": {
+            "type": "object",
+            "properties": {
+                "start": { "type": "integer", "minimum": 0 },
+                "end": { "type": "integer", "minimum": 0 },
+                "text": { "type": "string" },
+                "truncated": { "type": "boolean" },
+            },
+            "required": ["start", "end", "text", "truncated"]
+        },
+        "StructInfo": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "line": { "type": "integer", "minimum": 0 },
+                "complete": { "type": "boolean" },
+                "fields": array_of("InputInfo"),
+            },
+            "required": ["name", "line", "fields"]
+        },
+        "ImportInfo": {
+            "type": "object",
+            "properties": {
+                "uri": { "type": "string" },
+                "alias": nullable("string"),
+            },
+            "required": ["uri", "alias"]
+        },
+        "InputInfo": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "wdl_type": { "type": "string" },
+                "wdl_type_parsed": parsed_type.clone(),
+                "optional": { "type": "boolean" },
+                "default_value": nullable("string"),
+                "doc": { "type": "string" },
+            },
+            "required": ["name", "wdl_type", "wdl_type_parsed", "optional", "default_value"]
+        },
+        "OutputInfo": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "line": { "type": "integer", "minimum": 0 },
+                "column": { "type": "integer", "minimum": 0 },
+                "wdl_type": { "type": "string" },
+                "wdl_type_parsed": parsed_type,
+                "expression": { "type": "string" },
+                "files": {
+                    "type": "object",
+                    "properties": {
+                        "globs": strings(),
+                        "stdout": { "type": "boolean" },
+                        "stderr": { "type": "boolean" },
+                        "literals": strings(),
+                    }
+                },
+            },
+            "required": ["name", "wdl_type", "wdl_type_parsed", "expression"]
+        },
+        "CallInfo": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "target": { "type": "string" },
+                "alias": nullable("string"),
+                "line": { "type": "integer", "minimum": 0 },
+                "inputs": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "value": { "type": "string" },
+                        },
+                        "required": ["name", "value"]
+                    }
+                },
+            },
+            "required": ["name", "target", "alias", "inputs"]
+        },
+        "ScatterInfo": {
+            "type": "object",
+            "properties": {
+                "variable": { "type": "string" },
+                "expression": { "type": "string" },
+                "calls": strings(),
+            },
+            "required": ["variable", "expression", "calls"]
+        },
+        "ConditionalInfo": {
+            "type": "object",
+            "properties": {
+                "expression": { "type": "string" },
+                "line": { "type": "integer", "minimum": 0 },
+                "calls": strings(),
+                "declarations": strings(),
+            },
+            "required": ["expression", "calls", "declarations"]
+        },
+        "RuntimeItem": {
+            "type": "object",
+            "properties": {
+                "key": { "type": "string" },
+                "value": { "type": "string" },
+                "line": { "type": "integer", "minimum": 0 },
+            },
+            "required": ["key", "value", "line"]
+        },
+        "MetaItem": {
+            "type": "object",
+            "properties": {
+                "key": { "type": "string" },
+                "value": { "type": "string" },
+            },
+            "required": ["key", "value"]
+        },
+        "CommandSection": {
+            "type": "object",
+            "properties": {
+                "style": { "enum": ["heredoc", "braces"] },
+                "raw": { "type": "string" },
+                "dedented": { "type": "string" },
+                "line": { "type": "integer", "minimum": 0 },
+                "column": { "type": "integer", "minimum": 0 },
+            },
+            "required": ["style", "raw", "dedented", "line", "column"]
+        },
+        "NormalizedRuntime": {
+            "type": "object",
+            "properties": {
+                "container": nullable("string"),
+                "memory_bytes": nullable("integer"),
+                "disks": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "mount": nullable("string"),
+                            "size_gb": { "type": "number" },
+                            "type": nullable("string"),
+                        },
+                        "required": ["mount", "size_gb", "type"]
+                    }
+                },
+            },
+            "required": ["container", "memory_bytes"]
+        },
+        "WdlType": {
+            "oneOf": [
+                type_kind("primitive", json!({ "name": { "type": "string" } })),
+                type_kind("array", json!({
+                    "item": { "$ref": "#/definitions/WdlType" },
+                    "nonempty": { "type": "boolean" },
+                })),
+                type_kind("map", json!({
+                    "key": { "$ref": "#/definitions/WdlType" },
+                    "value": { "$ref": "#/definitions/WdlType" },
+                })),
+                type_kind("pair", json!({
+                    "left": { "$ref": "#/definitions/WdlType" },
+                    "right": { "$ref": "#/definitions/WdlType" },
+                })),
+                type_kind("optional", json!({ "inner": { "$ref": "#/definitions/WdlType" } })),
+                type_kind("ref", json!({ "name": { "type": "string" } })),
+            ]
+        },
+        "BasicWdlMetadata": {
+            "type": "object",
+            "properties": {
+                "version": nullable("string"),
+                "workflow_name": nullable("string"),
+                "task_names": strings(),
+            },
+            "required": ["version", "workflow_name", "task_names"]
+        },
+        "Located": {
+            "type": "object",
+            "properties": located,
+            "required": ["code", "severity", "message", "line", "column"]
+        },
+        "DiagnosticGroup": {
+            "description": "The first diagnostic of a cascade and the ones that follow from it",
+            "type": "object",
+            "properties": group,
+            "required": ["code", "severity", "message", "line", "column"]
+        },
+        "WorkflowGraph": {
+            "type": "object",
+            "properties": {
+                "workflow": { "type": "string" },
+                "nodes": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "string" },
+                            "kind": { "enum": ["input", "call", "output"] },
+                            "name": { "type": "string" },
+                            "target": { "type": "string" },
+                        },
+                        "required": ["id", "kind", "name"]
+                    }
+                },
+                "edges": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "from": { "type": "string" },
+                            "to": { "type": "string" },
+                            "variables": {
+                                "description": "Outputs of the `from` call that `to` reads",
+                                "type": "array",
+                                "items": { "type": "string" }
+                            },
+                        },
+                        "required": ["from", "to"]
+                    }
+                },
+            },
+            "required": ["workflow", "nodes", "edges"]
+        },
+        "Generator": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "version": { "type": "string" },
+                "wdl_grammar_version": { "type": "string" },
+                "timestamp": { "type": "string", "format": "date-time" },
+                "inputs": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string" },
+                            "sha256": { "type": "string", "pattern": "^[0-9a-f]{64}$" },
+                        },
+                        "required": ["path", "sha256"]
+                    }
+                },
+                "options": strings(),
+            },
+            "required": ["name", "version", "wdl_grammar_version", "timestamp", "inputs", "options"]
+        },
+    })
+}
+
+/// One `kind` of the internally tagged `WdlType`
+fn type_kind(kind: &str, fields: Value) -> Value {
+    let mut properties = json!({ "kind": { "const": kind } });
+    let mut required = vec!["kind".to_string()];
+    for (key, value) in fields.as_object().expect("object") {
+        properties[key] = value.clone();
+        required.push(key.clone());
+    }
+    json!({ "type": "object", "properties": properties, "required": required })
+}
@@ -0,0 +1,223 @@
+use crate::expr;
+use crate::info::{CallInfo, InputInfo, TaskInfo, WdlInfo, WorkflowInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// A runtime attribute whose value depends on a task input, so each call
+/// can set it by binding that input
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeOverride {
+    pub key: String,
+    pub expression: String,
+    /// Task input the attribute reads, directly or through task declarations
+    pub input: String,
+    /// Members of `input` the expression reads (e.g. `mem_gb` of a
+    /// `runtime_attr` struct); empty when the input is used as a whole
+    pub fields: Vec<String>,
+    /// Default of the task input
+    pub default_value: Option<String>,
+    /// Value the call binds to the input; `None` leaves the default in place
+    pub binding: Option<String>,
+    /// Workflow inputs the bound value is computed from
+    pub workflow_inputs: Vec<String>,
+}
+
+/// Runtime attributes one call can override
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CallOverrides {
+    pub workflow: String,
+    pub call: String,
+    pub target: String,
+    /// Whether the target task was found in this file
+    pub resolved: bool,
+    pub overrides: Vec<RuntimeOverride>,
+}
+
+/// Whether a runtime attribute is fixed or depends on task inputs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeSource {
+    /// Reads no task input, e.g. `"4 GB"` or a declaration with a literal value
+    Static,
+    /// Computed from task inputs, e.g. `"~{3 * threads} GB"`
+    Computed,
+}
+
+/// A task input that a runtime attribute reads
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ControllingInput {
+    pub name: String,
+    /// Members of the input the expression reads; empty when it is used as a whole
+    pub fields: Vec<String>,
+    pub default_value: Option<String>,
+}
+
+/// How one runtime attribute of a task is determined
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSensitivity {
+    pub key: String,
+    pub expression: String,
+    pub line: usize,
+    pub source: RuntimeSource,
+    /// Task inputs the attribute reads, directly or through task declarations
+    pub inputs: Vec<ControllingInput>,
+}
+
+/// Static and computed runtime attributes of a task
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaskSensitivity {
+    pub task: String,
+    pub attributes: Vec<AttributeSensitivity>,
+    /// Distinct task inputs controlling any attribute, in declaration order
+    pub inputs: Vec<String>,
+}
+
+/// Which runtime attributes of `task` are literals and which inputs control
+/// the computed ones
+pub fn runtime_sensitivity(task: &TaskInfo) -> TaskSensitivity {
+    let attributes: Vec<AttributeSensitivity> = task
+        .runtime
+        .iter()
+        .map(|item| {
+            let inputs: Vec<ControllingInput> = task_inputs(task, &item.value)
+                .into_iter()
+                .map(|(input, fields)| ControllingInput {
+                    name: input.name.clone(),
+                    fields,
+                    default_value: input.default_value.clone(),
+                })
+                .collect();
+            AttributeSensitivity {
+                key: item.key.clone(),
+                expression: item.value.clone(),
+                line: item.line,
+                source: if inputs.is_empty() {
+                    RuntimeSource::Static
+                } else {
+                    RuntimeSource::Computed
+                },
+                inputs,
+            }
+        })
+        .collect();
+    let inputs = task
+        .inputs
+        .iter()
+        .filter(|input| {
+            attributes
+                .iter()
+                .any(|a| a.inputs.iter().any(|i| i.name == input.name))
+        })
+        .map(|input| input.name.clone())
+        .collect();
+    TaskSensitivity {
+        task: task.name.clone(),
+        attributes,
+        inputs,
+    }
+}
+
+/// The overridable runtime attributes of every call in `workflow`
+pub fn call_overrides(info: &WdlInfo, workflow: &WorkflowInfo) -> Vec<CallOverrides> {
+    workflow
+        .calls
+        .iter()
+        .map(|call| {
+            let task = info.tasks.iter().find(|t| t.name == call.target);
+            CallOverrides {
+                workflow: workflow.name.clone(),
+                call: call.name.clone(),
+                target: call.target.clone(),
+                resolved: task.is_some(),
+                overrides: task
+                    .map(|task| overrides(workflow, call, task))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+fn overrides(workflow: &WorkflowInfo, call: &CallInfo, task: &TaskInfo) -> Vec<RuntimeOverride> {
+    let mut overrides = Vec::new();
+    for item in &task.runtime {
+        for (input, fields) in task_inputs(task, &item.value) {
+            let binding = call.inputs.iter().find(|b| b.name == input.name);
+            overrides.push(RuntimeOverride {
+                key: item.key.clone(),
+                expression: item.value.clone(),
+                input: input.name.clone(),
+                fields,
+                default_value: input.default_value.clone(),
+                binding: binding.map(|b| b.value.clone()),
+                workflow_inputs: binding
+                    .map(|b| workflow_inputs(workflow, &b.value))
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    overrides
+}
+
+/// Task inputs an expression reads, following task declarations, with the
+/// member paths read from each
+fn task_inputs<'a>(task: &'a TaskInfo, expression: &str) -> Vec<(&'a InputInfo, Vec<String>)> {
+    let mut found: Vec<(&InputInfo, Vec<String>)> = Vec::new();
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![expression.to_string()];
+    while let Some(expression) = pending.pop() {
+        for path in expr::references(&expression) {
+            let (root, field) = match path.split_once('.') {
+                Some((root, field)) => (root, Some(field)),
+                None => (path.as_str(), None),
+            };
+            if let Some(input) = task.inputs.iter().find(|i| i.name == root) {
+                let index = match found.iter().position(|(i, _)| i.name == root) {
+                    Some(index) => index,
+                    None => {
+                        found.push((input, Vec::new()));
+                        found.len() - 1
+                    }
+                };
+                let fields = &mut found[index].1;
+                if let Some(field) = field.filter(|f| !fields.iter().any(|x| x == f)) {
+                    fields.push(field.to_string());
+                }
+            } else if let Some(declaration) = task.declarations.iter().find(|d| d.name == root) {
+                if seen.insert(declaration.name.as_str()) {
+                    pending.extend(declaration.default_value.clone());
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Workflow inputs a call input value is computed from, following workflow
+/// declarations and scatter variables
+fn workflow_inputs(workflow: &WorkflowInfo, value: &str) -> Vec<String> {
+    let mut inputs = BTreeSet::new();
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![value];
+    while let Some(expression) = pending.pop() {
+        for root in expr::referenced_roots(expression) {
+            if workflow.inputs.iter().any(|i| i.name == root) {
+                inputs.insert(root);
+            } else if let Some(declaration) = workflow.declarations.iter().find(|d| d.name == root)
+            {
+                if seen.insert(root) {
+                    pending.extend(declaration.default_value.as_deref());
+                }
+            } else if let Some(scatter) = workflow.scatters.iter().find(|s| s.variable == root) {
+                if seen.insert(root) {
+                    pending.push(&scatter.expression);
+                }
+            }
+        }
+    }
+    workflow
+        .inputs
+        .iter()
+        .filter(|i| inputs.contains(&i.name))
+        .map(|i| i.name.clone())
+        .collect()
+}
@@ -0,0 +1,149 @@
+use crate::graph::{node_id, NodeKind, WorkflowGraph};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Timing of one call when every call starts as soon as its dependencies finish
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CallTiming {
+    pub call: String,
+    pub duration: f64,
+    pub start: f64,
+    pub finish: f64,
+    pub critical: bool,
+}
+
+/// Schedule estimate for a workflow from per-task durations
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Plan {
+    pub workflow: String,
+    pub calls: Vec<CallTiming>,
+    /// Calls on the longest weighted chain, in execution order
+    pub critical_path: Vec<String>,
+    /// Sum of all call durations (one instance per call)
+    pub serial_time: f64,
+    /// Finish time of the last call with unlimited parallelism
+    pub parallel_time: f64,
+    /// Calls with no entry in the durations file, counted as 0
+    pub missing: Vec<String>,
+}
+
+/// Durations keyed by call alias or task name, e.g. `{"align": 3600, "qc": 120}`
+pub type Durations = BTreeMap<String, f64>;
+
+/// Duration of a call: its alias takes precedence over its task name
+fn duration_of(durations: &Durations, name: &str, target: Option<&str>) -> Option<f64> {
+    durations.get(name).copied().or_else(|| {
+        let task = target?.rsplit('.').next()?;
+        durations.get(task).copied()
+    })
+}
+
+pub fn plan(graph: &WorkflowGraph, durations: &Durations) -> Plan {
+    let calls: Vec<_> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Call)
+        .collect();
+
+    let mut missing = Vec::new();
+    let mut lengths = HashMap::new();
+    for call in &calls {
+        let duration = duration_of(durations, &call.name, call.target.as_deref());
+        if duration.is_none() {
+            missing.push(call.name.clone());
+        }
+        lengths.insert(call.id.as_str(), duration.unwrap_or(0.0));
+    }
+
+    let mut finishes = HashMap::new();
+    for call in &calls {
+        finish(graph, &call.id, &lengths, &mut finishes, &mut Vec::new());
+    }
+
+    // Walk back from the call that finishes last through the upstream call
+    // that finishes last
+    let mut critical_path = Vec::new();
+    let mut current = calls
+        .iter()
+        .max_by(|a, b| finishes[a.id.as_str()].total_cmp(&finishes[b.id.as_str()]))
+        .map(|call| call.id.as_str());
+    while let Some(id) = current {
+        critical_path.push(id);
+        current = upstream_calls(graph, id)
+            .into_iter()
+            .max_by(|a, b| finishes[a].total_cmp(&finishes[b]));
+    }
+    critical_path.reverse();
+
+    let timings = calls
+        .iter()
+        .map(|call| {
+            let id = call.id.as_str();
+            CallTiming {
+                call: call.name.clone(),
+                duration: lengths[id],
+                start: finishes[id] - lengths[id],
+                finish: finishes[id],
+                critical: critical_path.contains(&id),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Plan {
+        workflow: graph.workflow.clone(),
+        serial_time: timings.iter().map(|t| t.duration).sum(),
+        parallel_time: timings.iter().map(|t| t.finish).fold(0.0, f64::max),
+        critical_path: critical_path
+            .iter()
+            .filter_map(|id| graph.node(id).map(|node| node.name.clone()))
+            .collect(),
+        calls: timings,
+        missing,
+    }
+}
+
+impl Plan {
+    /// Node ids of the critical path, for highlighting in diagrams
+    pub fn critical_ids(&self) -> Vec<String> {
+        self.critical_path
+            .iter()
+            .map(|call| node_id(NodeKind::Call, call))
+            .collect()
+    }
+}
+
+/// Calls with an edge into call `id`
+fn upstream_calls<'a>(graph: &'a WorkflowGraph, id: &str) -> Vec<&'a str> {
+    graph
+        .edges
+        .iter()
+        .filter(|edge| edge.to == id)
+        .filter(|edge| graph.node(&edge.from).map(|n| n.kind) == Some(NodeKind::Call))
+        .map(|edge| edge.from.as_str())
+        .collect()
+}
+
+/// Earliest finish of call `id`; a cycle (which WDL forbids) is cut where it closes
+fn finish<'a>(
+    graph: &'a WorkflowGraph,
+    id: &'a str,
+    lengths: &HashMap<&str, f64>,
+    finishes: &mut HashMap<&'a str, f64>,
+    path: &mut Vec<&'a str>,
+) -> f64 {
+    if let Some(time) = finishes.get(id) {
+        return *time;
+    }
+    if path.contains(&id) {
+        return 0.0;
+    }
+    path.push(id);
+    let start = upstream_calls(graph, id)
+        .into_iter()
+        .map(|upstream| finish(graph, upstream, lengths, finishes, path))
+        .fold(0.0, f64::max);
+    path.pop();
+    let time = start + lengths.get(id).copied().unwrap_or(0.0);
+    finishes.insert(id, time);
+    time
+}
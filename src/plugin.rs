@@ -0,0 +1,104 @@
+use crate::config::{LintConfig, RuleLevel};
+use crate::info::WdlInfo;
+use crate::lint::{rule_level, Finding, Severity};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Prefix of rule executables looked up on `PATH`
+pub const PLUGIN_PREFIX: &str = "wdlparse-rule-";
+
+/// What a rule executable receives as JSON on stdin
+#[derive(Serialize)]
+pub struct PluginRequest<'a> {
+    pub file: &'a str,
+    pub source: &'a str,
+    pub info: &'a WdlInfo,
+}
+
+/// A finding as printed by a rule executable; `severity` defaults to warning
+#[derive(Deserialize)]
+struct PluginFinding {
+    rule: String,
+    severity: Option<Severity>,
+    #[serde(default)]
+    location: String,
+    line: Option<usize>,
+    message: String,
+    #[serde(default)]
+    suggestions: Vec<String>,
+}
+
+/// Executable for a configured plugin: a path as given, or `wdlparse-rule-<name>`
+pub fn executable(plugin: &str) -> PathBuf {
+    if plugin.contains('/') || plugin.starts_with(PLUGIN_PREFIX) {
+        PathBuf::from(plugin)
+    } else {
+        PathBuf::from(format!("{}{}", PLUGIN_PREFIX, plugin))
+    }
+}
+
+/// Run one rule executable over a file. It gets a `PluginRequest` on stdin and
+/// prints a JSON array of `{rule, severity, location, line, message}` on stdout;
+/// configured rule levels apply to its findings like to built-in ones.
+pub fn run(plugin: &str, request: &PluginRequest, config: &LintConfig) -> Result<Vec<Finding>> {
+    let program = executable(plugin);
+    let mut child = Command::new(&program)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start lint plugin '{}'", program.display()))?;
+
+    // Write from another thread so a plugin that answers before reading
+    // everything can't block on a full pipe
+    let payload = serde_json::to_vec(request)?;
+    let mut stdin = child.stdin.take().context("Plugin stdin unavailable")?;
+    let writer = std::thread::spawn(move || stdin.write_all(&payload));
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Lint plugin '{}' did not finish", program.display()))?;
+    // A plugin may exit without reading its input; that's not an error
+    let _ = writer.join();
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Lint plugin '{}' failed ({}): {}",
+            program.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let findings: Vec<PluginFinding> =
+        serde_json::from_slice(&output.stdout).with_context(|| {
+            format!(
+                "Lint plugin '{}' printed invalid findings",
+                program.display()
+            )
+        })?;
+
+    Ok(findings
+        .into_iter()
+        .filter_map(|finding| {
+            let fallback = match finding.severity {
+                Some(Severity::Error) => RuleLevel::Error,
+                _ => RuleLevel::Warning,
+            };
+            let severity = match rule_level(config, &finding.rule, fallback) {
+                RuleLevel::Off => return None,
+                RuleLevel::Warning => Severity::Warning,
+                RuleLevel::Error => Severity::Error,
+            };
+            Some(Finding {
+                rule: finding.rule,
+                severity,
+                location: finding.location,
+                line: finding.line,
+                message: finding.message,
+                suggestions: finding.suggestions,
+            })
+        })
+        .collect())
+}
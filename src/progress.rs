@@ -0,0 +1,55 @@
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+
+/// Progress bar on stderr for a batch of `len` files.
+///
+/// The bar is hidden when stderr is not a terminal or there is only one
+/// file, so piped and scripted runs are unaffected.
+pub fn file_progress(len: usize) -> ProgressBar {
+    if len < 2 || !std::io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(len as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{spinner} [{bar:30.cyan/blue}] {pos}/{len} {wide_msg}")
+            .expect("static template is valid")
+            .progress_chars("=> "),
+    );
+    bar
+}
+
+/// Outcome counts for a batch run, printed as a table at the end
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BatchSummary {
+    pub passed: usize,
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+impl BatchSummary {
+    /// Record one file; a file with errors is not also counted as having warnings
+    pub fn record(&mut self, errors: usize, warnings: usize) {
+        if errors > 0 {
+            self.errors += 1;
+        } else if warnings > 0 {
+            self.warnings += 1;
+        } else {
+            self.passed += 1;
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.passed + self.warnings + self.errors
+    }
+
+    pub fn print(&self) {
+        println!();
+        println!("{} {} file(s)", "Summary:".cyan().bold(), self.total());
+        println!("{}", "─".repeat(50));
+        println!("  {:<10} {:>6}", "passed".green(), self.passed);
+        println!("  {:<10} {:>6}", "warnings".yellow(), self.warnings);
+        println!("  {:<10} {:>6}", "errors".red(), self.errors);
+    }
+}
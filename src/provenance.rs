@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The wdl-grammar release series wdlparse requires, as written in
+/// Cargo.toml; the patch release depends on what Cargo resolved, so it is
+/// not reported
+pub const WDL_GRAMMAR_VERSION: &str = "0.17";
+
+/// Which tool, version and inputs produced a JSON report, so downstream
+/// systems can trace an artifact back to what made it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Generator {
+    pub name: String,
+    pub version: String,
+    pub wdl_grammar_version: String,
+    /// UTC time in RFC 3339 form; `SOURCE_DATE_EPOCH` replaces the clock so
+    /// reproducible builds get stable output
+    pub timestamp: String,
+    /// Files the command read, in the order it read them
+    pub inputs: Vec<InputDigest>,
+    /// Command-line arguments, without the program name
+    pub options: Vec<String>,
+}
+
+/// A file read by the command and the SHA-256 of its bytes as stored
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InputDigest {
+    pub path: String,
+    pub sha256: String,
+}
+
+/// Files read so far by the running command
+static INPUTS: Mutex<Vec<InputDigest>> = Mutex::new(Vec::new());
+
+/// Remember a file the command read, for the `inputs` of later reports
+pub fn record_input(path: &Path, bytes: &[u8]) {
+    let path = path.display().to_string();
+    let mut inputs = INPUTS.lock().unwrap_or_else(|e| e.into_inner());
+    if !inputs.iter().any(|input| input.path == path) {
+        inputs.push(InputDigest {
+            path,
+            sha256: sha256_hex(bytes),
+        });
+    }
+}
+
+/// The generator block for a report made now from the inputs read so far
+pub fn generator(options: Vec<String>) -> Generator {
+    Generator {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        wdl_grammar_version: WDL_GRAMMAR_VERSION.to_string(),
+        timestamp: rfc3339(timestamp_seconds()),
+        inputs: INPUTS.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        options,
+    }
+}
+
+/// Add a `generator` block to a JSON report; values other than objects are
+/// left alone
+pub fn stamp(value: &mut serde_json::Value, options: Vec<String>) {
+    if let serde_json::Value::Object(object) = value {
+        let generator = serde_json::to_value(generator(options)).expect("generator serializes");
+        object.insert("generator".to_string(), generator);
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn timestamp_seconds() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs())
+        })
+}
+
+/// Seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`
+fn rfc3339(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let time = seconds % 86_400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
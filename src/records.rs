@@ -0,0 +1,127 @@
+use crate::command::CommandLanguage;
+use crate::info::{InputInfo, WdlInfo};
+use crate::workspace::SymbolKind;
+use serde::Serialize;
+
+/// A task, with its resources resolved
+#[derive(Serialize, Debug, Clone)]
+pub struct TaskRecord {
+    pub file: String,
+    pub task: String,
+    pub line: usize,
+    /// The `meta` description, or else the comment above the task
+    pub description: Option<String>,
+    pub inputs: usize,
+    pub outputs: usize,
+    pub command_language: Option<CommandLanguage>,
+    pub container: Option<String>,
+    pub memory_bytes: Option<u64>,
+    /// Total size of the disks the task requests
+    pub disk_gb: Option<f64>,
+}
+
+/// An input of a task or workflow
+#[derive(Serialize, Debug, Clone)]
+pub struct InputRecord {
+    pub file: String,
+    pub kind: SymbolKind,
+    /// Name of the task or workflow
+    pub definition: String,
+    pub name: String,
+    pub wdl_type: String,
+    /// Callers can leave it out: the type is optional or it has a default
+    pub optional: bool,
+    pub default_value: Option<String>,
+}
+
+/// A call in a workflow
+#[derive(Serialize, Debug, Clone)]
+pub struct CallRecord {
+    pub file: String,
+    pub workflow: String,
+    /// The alias, or else the name of the task or workflow called
+    pub call: String,
+    /// Task or workflow called, as written (`namespace.name` when imported)
+    pub target: String,
+    pub alias: Option<String>,
+    /// Inputs the call passes
+    pub inputs: usize,
+}
+
+/// One `runtime` attribute of a task, as written
+#[derive(Serialize, Debug, Clone)]
+pub struct RuntimeRecord {
+    pub file: String,
+    pub task: String,
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// A document as flat tables: every record of a table has the same keys
+/// and only scalar values, ready to load into a data frame
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Records {
+    pub tasks: Vec<TaskRecord>,
+    pub inputs: Vec<InputRecord>,
+    pub calls: Vec<CallRecord>,
+    pub runtime: Vec<RuntimeRecord>,
+}
+
+impl Records {
+    /// Add the records of `info`, read from `file`
+    pub fn add(&mut self, file: &str, info: &WdlInfo) {
+        for task in &info.tasks {
+            let disks = &task.normalized_runtime.disks;
+            self.tasks.push(TaskRecord {
+                file: file.to_string(),
+                task: task.name.clone(),
+                line: task.line,
+                description: task
+                    .authorship
+                    .description
+                    .clone()
+                    .or_else(|| task.doc.clone()),
+                inputs: task.inputs.len(),
+                outputs: task.outputs.len(),
+                command_language: task.command_language,
+                container: task.normalized_runtime.container.clone(),
+                memory_bytes: task.normalized_runtime.memory_bytes,
+                disk_gb: (!disks.is_empty()).then(|| disks.iter().map(|d| d.size_gb).sum()),
+            });
+            self.add_inputs(file, SymbolKind::Task, &task.name, &task.inputs);
+            self.runtime
+                .extend(task.runtime.iter().map(|item| RuntimeRecord {
+                    file: file.to_string(),
+                    task: task.name.clone(),
+                    key: item.key.clone(),
+                    value: item.value.clone(),
+                    line: item.line,
+                }));
+        }
+        for workflow in &info.workflows {
+            self.add_inputs(file, SymbolKind::Workflow, &workflow.name, &workflow.inputs);
+            self.calls
+                .extend(workflow.calls.iter().map(|call| CallRecord {
+                    file: file.to_string(),
+                    workflow: workflow.name.clone(),
+                    call: call.name.clone(),
+                    target: call.target.clone(),
+                    alias: call.alias.clone(),
+                    inputs: call.inputs.len(),
+                }));
+        }
+    }
+
+    fn add_inputs(&mut self, file: &str, kind: SymbolKind, definition: &str, inputs: &[InputInfo]) {
+        self.inputs.extend(inputs.iter().map(|input| InputRecord {
+            file: file.to_string(),
+            kind,
+            definition: definition.to_string(),
+            name: input.name.clone(),
+            wdl_type: input.wdl_type.clone(),
+            optional: input.optional || input.default_value.is_some(),
+            default_value: input.default_value.clone(),
+        }));
+    }
+}
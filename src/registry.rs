@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fmt;
+
+const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.docker.distribution.manifest.v2+json, \
+    application/vnd.oci.image.manifest.v1+json";
+
+/// A container image reference such as `ubuntu:20.04` or `quay.io/org/tool@sha256:...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    /// Registry host, if the reference names one explicitly
+    pub registry: Option<String>,
+    /// Repository path without the registry host
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl ImageRef {
+    /// Parse an image reference; returns `None` for empty strings
+    pub fn parse(image: &str) -> Option<Self> {
+        let image = image.trim();
+        if image.is_empty() {
+            return None;
+        }
+
+        let (rest, digest) = match image.split_once('@') {
+            Some((rest, digest)) => (rest, Some(digest.to_string())),
+            None => (image, None),
+        };
+
+        // A tag is a colon after the last path separator (a colon before it is a registry port)
+        let last_slash = rest.rfind('/').map_or(0, |i| i + 1);
+        let (name, tag) = match rest[last_slash..].rfind(':') {
+            Some(i) => (
+                &rest[..last_slash + i],
+                Some(rest[last_slash + i + 1..].to_string()),
+            ),
+            None => (rest, None),
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, path))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                (Some(host.to_string()), path.to_string())
+            }
+            _ => (None, name.to_string()),
+        };
+
+        Some(Self {
+            registry,
+            repository,
+            tag,
+            digest,
+        })
+    }
+
+    /// Whether the reference is pinned to a sha256 digest
+    pub fn has_digest(&self) -> bool {
+        self.digest
+            .as_deref()
+            .is_some_and(|d| d.starts_with("sha256:"))
+    }
+
+    /// Registry host and repository path as used by the registry HTTP API
+    fn api_location(&self) -> (String, String) {
+        match self.registry.as_deref() {
+            None | Some("docker.io") | Some("index.docker.io") => {
+                let repository = if self.repository.contains('/') {
+                    self.repository.clone()
+                } else {
+                    format!("library/{}", self.repository)
+                };
+                (DOCKER_HUB_REGISTRY.to_string(), repository)
+            }
+            Some(host) => (host.to_string(), self.repository.clone()),
+        }
+    }
+}
+
+impl fmt::Display for ImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(registry) = &self.registry {
+            write!(f, "{}/", registry)?;
+        }
+        write!(f, "{}", self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{}", tag)?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{}", digest)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+/// Query the image's registry for the digest its tag currently points to
+pub fn resolve_digest(image: &ImageRef) -> Result<String> {
+    let (host, repository) = image.api_location();
+    let reference = image.tag.as_deref().unwrap_or("latest");
+    let url = format!("https://{}/v2/{}/manifests/{}", host, repository, reference);
+
+    let response = match ureq::head(&url).set("Accept", MANIFEST_ACCEPT).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(401, challenge)) => {
+            let token = fetch_token(challenge.header("www-authenticate").unwrap_or_default())
+                .with_context(|| format!("Failed to authenticate with {}", host))?;
+            ureq::head(&url)
+                .set("Accept", MANIFEST_ACCEPT)
+                .set("Authorization", &format!("Bearer {}", token))
+                .call()
+                .with_context(|| format!("Failed to fetch manifest for {}", image))?
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to fetch manifest for {}", image));
+        }
+    };
+
+    response
+        .header("docker-content-digest")
+        .map(str::to_string)
+        .with_context(|| format!("Registry returned no digest for {}", image))
+}
+
+/// Obtain an anonymous pull token from a `WWW-Authenticate: Bearer ...` challenge
+fn fetch_token(challenge: &str) -> Result<String> {
+    let params = challenge
+        .strip_prefix("Bearer ")
+        .context("Registry requires an unsupported authentication scheme")?;
+
+    let mut realm = None;
+    let mut request_params = Vec::new();
+    for param in params.split(',') {
+        if let Some((key, value)) = param.trim().split_once('=') {
+            let value = value.trim_matches('"');
+            if key == "realm" {
+                realm = Some(value);
+            } else {
+                request_params.push((key, value));
+            }
+        }
+    }
+
+    let mut request = ureq::get(realm.context("Authentication challenge has no realm")?);
+    for (key, value) in request_params {
+        request = request.query(key, value);
+    }
+    let response: TokenResponse = request.call()?.into_json()?;
+    response
+        .token
+        .or(response.access_token)
+        .context("Token response contained no token")
+}
@@ -0,0 +1,99 @@
+use crate::edit::{self, SourceEdit};
+use crate::lines::LineIndex;
+use crate::workspace::import_namespace;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wdl_grammar::{SyntaxKind, SyntaxTree};
+
+/// Import URI prefixes mapped to their replacements (e.g. a public GitHub
+/// prefix to an internal mirror)
+pub type RewriteRules = BTreeMap<String, String>;
+
+/// `uri` with the longest matching prefix in `rules` replaced; `None` when
+/// no rule matches
+pub fn rewrite_uri(uri: &str, rules: &RewriteRules) -> Option<String> {
+    rules
+        .iter()
+        .filter(|(from, _)| uri.starts_with(from.as_str()))
+        .max_by_key(|(from, _)| from.len())
+        .map(|(from, to)| format!("{}{}", to, &uri[from.len()..]))
+}
+
+/// Parse a `FROM=TO` rule given on the command line
+pub fn parse_rule(rule: &str) -> Result<(String, String)> {
+    match rule.split_once('=') {
+        Some((from, to)) if !from.is_empty() => Ok((from.to_string(), to.to_string())),
+        _ => anyhow::bail!("Invalid rewrite '{}': expected FROM=TO", rule),
+    }
+}
+
+/// One import whose URI was rewritten
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ImportRewrite {
+    pub line: usize,
+    pub from: String,
+    pub to: String,
+    /// Alias added so calls keep using the namespace the old URI gave the
+    /// import, when the new URI has a different file name
+    pub alias: Option<String>,
+}
+
+/// Source text with its imports rewritten
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Rewritten {
+    #[serde(skip)]
+    pub source: String,
+    pub rewrites: Vec<ImportRewrite>,
+}
+
+/// Rewrite the URI of every import in `source` that a rule matches
+pub fn rewrite_imports(source: &str, rules: &RewriteRules) -> Result<Rewritten> {
+    let (tree, _) = SyntaxTree::parse(source);
+    let lines = LineIndex::new(source);
+    let mut edits = Vec::new();
+    let mut rewrites = Vec::new();
+
+    for node in tree
+        .root()
+        .children()
+        .filter(|node| node.kind() == SyntaxKind::ImportStatementNode)
+    {
+        let tokens: Vec<_> = node
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .collect();
+        let Some(uri) = tokens
+            .iter()
+            .find(|token| token.kind() == SyntaxKind::LiteralStringText)
+        else {
+            continue;
+        };
+        let Some(rewritten) = rewrite_uri(uri.text(), rules).filter(|to| to != uri.text()) else {
+            continue;
+        };
+
+        edits.push(SourceEdit::replace_token(uri, rewritten.as_str()));
+        let has_alias = tokens
+            .iter()
+            .any(|token| token.kind() == SyntaxKind::AsKeyword);
+        let namespace = import_namespace(uri.text(), None);
+        let alias =
+            (!has_alias && import_namespace(&rewritten, None) != namespace).then_some(namespace);
+        if let Some(alias) = &alias {
+            let end = edit::node_range(&node).end;
+            edits.push(SourceEdit::insert(end, format!(" as {}", alias)));
+        }
+        rewrites.push(ImportRewrite {
+            line: lines.line(edit::token_range(uri).start),
+            from: uri.text().to_string(),
+            to: rewritten,
+            alias,
+        });
+    }
+
+    Ok(Rewritten {
+        source: edit::apply(source, &edits)?,
+        rewrites,
+    })
+}
@@ -0,0 +1,118 @@
+use crate::config::Config;
+use crate::info::SortBy;
+use crate::service;
+use anyhow::Result;
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Serve newline-delimited JSON-RPC 2.0 requests from `reader`, writing one
+/// response line per request to `writer` until end of input.
+///
+/// Every [`service::METHODS`] entry is callable with `{"content": "<wdl>"}`
+/// params; `methods` lists them.
+pub fn run<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    config: &Config,
+    order: SortBy,
+) -> Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(&line, config, order) {
+            writeln!(writer, "{}", response)?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle one request line, a request or a batch array of them answered
+/// with an array; notifications (requests without an `id`) get no response
+pub fn handle_line(line: &str, config: &Config, order: SortBy) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+
+    match request {
+        Value::Array(batch) if batch.is_empty() => Some(error_response(
+            Value::Null,
+            INVALID_REQUEST,
+            "Invalid Request",
+        )),
+        Value::Array(batch) => {
+            let responses: Vec<Value> = batch
+                .iter()
+                .filter_map(|request| handle_request(request, config, order))
+                .collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        request => handle_request(&request, config, order),
+    }
+}
+
+/// Handle one request object. Anything else, or an object without a
+/// `method`, is answered as an invalid request, so the client isn't left
+/// waiting for a response.
+fn handle_request(request: &Value, config: &Config, order: SortBy) -> Option<Value> {
+    if !request.get("method").is_some_and(Value::is_string) {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        return Some(error_response(id, INVALID_REQUEST, "Invalid Request"));
+    }
+
+    let id = request.get("id").cloned();
+    let result = dispatch(request, config, order);
+    let id = id?;
+
+    Some(match result {
+        Ok(result) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn dispatch(
+    request: &Value,
+    config: &Config,
+    order: SortBy,
+) -> std::result::Result<Value, (i64, String)> {
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    if method == "methods" {
+        return Ok(serde_json::json!(service::METHODS));
+    }
+    if !service::METHODS.contains(&method) {
+        return Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", method)));
+    }
+
+    let content = request
+        .get("params")
+        .and_then(|params| params.get("content"))
+        .and_then(Value::as_str)
+        .ok_or((
+            INVALID_PARAMS,
+            "params must contain a string 'content' field".to_string(),
+        ))?;
+
+    service::handle(method, content, config, order)
+        .map_err(|e| (INTERNAL_ERROR, format!("{:#}", e)))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
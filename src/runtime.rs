@@ -0,0 +1,125 @@
+use crate::expr;
+use crate::info::RuntimeItem;
+use crate::units::{self, SizeUnit};
+use serde::{Deserialize, Serialize};
+
+/// Runtime attributes in one shape regardless of dialect: `docker` or
+/// `container`, memory in any unit, and Cromwell or WDL 1.1 disk specs.
+/// Fields computed by expressions are left empty.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct NormalizedRuntime {
+    /// The image, or the first of a WDL 1.1 `container` array
+    pub container: Option<String>,
+    pub memory_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disks: Vec<DiskSpec>,
+}
+
+/// One disk request, e.g. `local-disk 100 SSD` or `/mnt/ref 20 GiB`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiskSpec {
+    /// Mount point; absent for the task's working disk (`local-disk`)
+    pub mount: Option<String>,
+    pub size_gb: f64,
+    /// `SSD`, `HDD` or `LOCAL` when given
+    #[serde(rename = "type")]
+    pub disk_type: Option<String>,
+}
+
+impl NormalizedRuntime {
+    pub fn from_items(items: &[RuntimeItem]) -> Self {
+        let mut runtime = Self::default();
+        for item in items {
+            match item.key.as_str() {
+                // `container` is the WDL 1.1 name and wins over `docker`
+                "container" => runtime.container = container(item).or(runtime.container),
+                "docker" => runtime.container = runtime.container.or_else(|| container(item)),
+                "memory" => runtime.memory_bytes = memory_bytes(item),
+                "disks" => runtime.disks = disks(item),
+                _ => {}
+            }
+        }
+        runtime
+    }
+}
+
+fn container(item: &RuntimeItem) -> Option<String> {
+    if let Some(image) = item.literal_value() {
+        return Some(image.to_string());
+    }
+    let first = expr::array_literal(&item.value)?.into_iter().next()?;
+    string_literal(first).map(str::to_string)
+}
+
+/// A string memory value, or a WDL 1.1 Int in bytes
+fn memory_bytes(item: &RuntimeItem) -> Option<u64> {
+    match item.literal_value() {
+        Some(text) => units::parse_size(text),
+        None => item.value.trim().parse().ok(),
+    }
+}
+
+/// Comma-separated Cromwell specs, a WDL 1.1 array of specs, or an Int in GiB
+fn disks(item: &RuntimeItem) -> Vec<DiskSpec> {
+    let specs: Vec<&str> = match item.literal_value() {
+        Some(text) => text.split(',').collect(),
+        None => match expr::array_literal(&item.value) {
+            Some(elements) => elements.into_iter().filter_map(string_literal).collect(),
+            None => {
+                return item
+                    .value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|size| units::to_bytes(size, SizeUnit::GiB))
+                    .map(|bytes| {
+                        vec![DiskSpec {
+                            mount: None,
+                            size_gb: SizeUnit::GB.convert(bytes),
+                            disk_type: None,
+                        }]
+                    })
+                    .unwrap_or_default();
+            }
+        },
+    };
+    specs.into_iter().filter_map(disk_spec).collect()
+}
+
+/// `local-disk 100 SSD`, `/mnt 10 HDD`, `100 GiB`, `/mnt 10 GiB`, `50`
+fn disk_spec(spec: &str) -> Option<DiskSpec> {
+    let mut words = spec.split_whitespace().peekable();
+    let mut mount = None;
+    if let Some(first) = words.peek() {
+        if first.parse::<f64>().is_err() {
+            if *first != "local-disk" {
+                mount = Some(first.to_string());
+            }
+            words.next();
+        }
+    }
+    let size: f64 = words.next()?.parse().ok()?;
+    let mut size_gb = size;
+    let mut disk_type = None;
+    for word in words {
+        match word.to_ascii_uppercase().as_str() {
+            "SSD" | "HDD" | "LOCAL" => disk_type = Some(word.to_ascii_uppercase()),
+            unit => {
+                let unit: SizeUnit = unit.parse().ok()?;
+                size_gb = SizeUnit::GB.convert(units::to_bytes(size, unit)?);
+            }
+        }
+    }
+    Some(DiskSpec {
+        mount,
+        size_gb,
+        disk_type,
+    })
+}
+
+fn string_literal(text: &str) -> Option<&str> {
+    let text = text.trim();
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+}
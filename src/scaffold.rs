@@ -0,0 +1,150 @@
+use crate::info::{InputInfo, OutputInfo, WorkflowInfo};
+use crate::inputs::is_required;
+use crate::types::WdlType;
+
+/// Test framework the generated module is written for
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestStyle {
+    /// pytest-wdl's `workflow_data` and `workflow_runner` fixtures
+    PytestWdl,
+    /// Plain pytest running `miniwdl run` in a temporary directory
+    Miniwdl,
+}
+
+/// A Python test module with one test per workflow: inputs to fill in, the
+/// expected outputs, and placeholder assertions
+pub fn test_module(wdl_path: &str, workflows: &[&WorkflowInfo], style: TestStyle) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("\"\"\"Tests for {}\n\n", wdl_path));
+    out.push_str("Generated by `wdlparse scaffold-tests`; replace the TODOs with real values.\n");
+    match style {
+        TestStyle::PytestWdl => out.push_str(
+            "File inputs and expected files are looked up in test_data.json (see pytest-wdl).\n",
+        ),
+        TestStyle::Miniwdl => {
+            out.push_str("Each test runs `miniwdl run` in its own temporary directory.\n")
+        }
+    }
+    out.push_str("\"\"\"\n");
+
+    if style == TestStyle::Miniwdl {
+        out.push_str("\nimport json\nimport subprocess\n\n");
+        out.push_str(&format!("WDL = {}\n\n\n", py_string(wdl_path)));
+        out.push_str(MINIWDL_RUNNER);
+    }
+
+    for workflow in workflows {
+        out.push_str("\n\n");
+        match style {
+            TestStyle::PytestWdl => pytest_wdl_test(&mut out, wdl_path, workflow),
+            TestStyle::Miniwdl => miniwdl_test(&mut out, workflow),
+        }
+    }
+    out
+}
+
+const MINIWDL_RUNNER: &str = r#"def run_workflow(inputs, tmp_path):
+    inputs_file = tmp_path / "inputs.json"
+    inputs_file.write_text(json.dumps(inputs))
+    result = subprocess.run(
+        ["miniwdl", "run", WDL, "--input", str(inputs_file), "--dir", str(tmp_path)],
+        check=True,
+        capture_output=True,
+        text=True,
+    )
+    return json.loads(result.stdout)["outputs"]
+"#;
+
+fn pytest_wdl_test(out: &mut String, wdl_path: &str, workflow: &WorkflowInfo) {
+    out.push_str(&format!(
+        "def test_{}(workflow_data, workflow_runner):\n",
+        workflow.name
+    ));
+    out.push_str("    inputs = {\n");
+    for input in &workflow.inputs {
+        let key = py_string(&input.name);
+        let value = if is_file(input.wdl_type_parsed.as_ref()) {
+            format!("workflow_data[{}]", key)
+        } else {
+            "None".to_string()
+        };
+        input_line(out, &key, &value, input);
+    }
+    out.push_str("    }\n    expected = {\n");
+    for output in &workflow.outputs {
+        let key = py_string(&output.name);
+        if is_file(output.wdl_type_parsed.as_ref()) {
+            out.push_str(&format!("        {}: workflow_data[{}],\n", key, key));
+        } else {
+            out.push_str(&format!(
+                "        {}: None,  # TODO: {}\n",
+                key, output.wdl_type
+            ));
+        }
+    }
+    out.push_str("    }\n");
+    out.push_str(&format!(
+        "    workflow_runner(\n        {},\n        inputs,\n        expected,\n        workflow_name={},\n    )\n",
+        py_string(wdl_path),
+        py_string(&workflow.name)
+    ));
+}
+
+fn miniwdl_test(out: &mut String, workflow: &WorkflowInfo) {
+    out.push_str(&format!("def test_{}(tmp_path):\n", workflow.name));
+    out.push_str("    inputs = {\n");
+    for input in &workflow.inputs {
+        let key = py_string(&format!("{}.{}", workflow.name, input.name));
+        input_line(out, &key, "None", input);
+    }
+    out.push_str("    }\n    outputs = run_workflow(inputs, tmp_path)\n");
+    for output in &workflow.outputs {
+        out.push_str(&output_assertion(workflow, output));
+    }
+}
+
+/// Required inputs are entries to fill in; the rest are commented out
+fn input_line(out: &mut String, key: &str, value: &str, input: &InputInfo) {
+    if is_required(input) {
+        if value == "None" {
+            out.push_str(&format!(
+                "        {}: None,  # TODO: {}\n",
+                key, input.wdl_type
+            ));
+        } else {
+            out.push_str(&format!("        {}: {},\n", key, value));
+        }
+    } else {
+        let note = match &input.default_value {
+            Some(default) => format!("{}, default {}", input.wdl_type, default),
+            None => input.wdl_type.clone(),
+        };
+        out.push_str(&format!("        # {}: {},  # {}\n", key, value, note));
+    }
+}
+
+fn output_assertion(workflow: &WorkflowInfo, output: &OutputInfo) -> String {
+    let key = py_string(&format!("{}.{}", workflow.name, output.name));
+    let optional = output
+        .wdl_type_parsed
+        .as_ref()
+        .is_some_and(|t| t.is_optional());
+    if optional {
+        format!("    assert {} in outputs  # TODO: check the value\n", key)
+    } else {
+        format!(
+            "    assert outputs[{}] is not None  # TODO: check the value\n",
+            key
+        )
+    }
+}
+
+/// A single `File` (or `File?`); arrays of files are left to fill in by hand
+fn is_file(wdl_type: Option<&WdlType>) -> bool {
+    matches!(wdl_type.map(WdlType::required), Some(WdlType::Primitive { name }) if name == "File")
+}
+
+/// A double-quoted Python string literal (JSON escaping is valid Python)
+fn py_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
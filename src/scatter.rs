@@ -0,0 +1,150 @@
+use crate::expr;
+use crate::info::WorkflowInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How far declarations are followed when evaluating a collection expression
+const MAX_DEPTH: usize = 16;
+
+/// A scatter's collection and, when it can be worked out, its length
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScatterWidth {
+    pub variable: String,
+    pub expression: String,
+    pub width: Option<usize>,
+    pub calls: Vec<String>,
+}
+
+/// Number of task instances a call runs: the product of the widths of the
+/// scatters around it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CallInstances {
+    pub call: String,
+    pub instances: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScatterReport {
+    pub workflow: String,
+    pub scatters: Vec<ScatterWidth>,
+    pub calls: Vec<CallInstances>,
+    /// Sum of all call instances, when every width is known
+    pub total_instances: Option<usize>,
+}
+
+/// Work out scatter widths from literals, defaults and (optionally) an inputs
+/// JSON object keyed `workflow.input`
+pub fn report(workflow: &WorkflowInfo, inputs: Option<&Value>) -> ScatterReport {
+    let env = Env { workflow, inputs };
+    let scatters: Vec<ScatterWidth> = workflow
+        .scatters
+        .iter()
+        .map(|scatter| ScatterWidth {
+            variable: scatter.variable.clone(),
+            expression: scatter.expression.clone(),
+            width: env.length(&scatter.expression, 0),
+            calls: scatter.calls.clone(),
+        })
+        .collect();
+
+    let calls: Vec<CallInstances> = workflow
+        .calls
+        .iter()
+        .map(|call| CallInstances {
+            call: call.name.clone(),
+            instances: scatters
+                .iter()
+                .filter(|scatter| scatter.calls.contains(&call.name))
+                .try_fold(1, |product: usize, scatter| {
+                    Some(product.saturating_mul(scatter.width?))
+                }),
+        })
+        .collect();
+
+    ScatterReport {
+        workflow: workflow.name.clone(),
+        total_instances: calls.iter().map(|c| c.instances).sum(),
+        scatters,
+        calls,
+    }
+}
+
+struct Env<'a> {
+    workflow: &'a WorkflowInfo,
+    inputs: Option<&'a Value>,
+}
+
+impl Env<'_> {
+    /// Length of an array-valued expression
+    fn length(&self, expression: &str, depth: usize) -> Option<usize> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        if let Some(items) = expr::array_literal(expression) {
+            return Some(items.len());
+        }
+        if let Some((function, args)) = expr::function_call(expression) {
+            let length = |arg: &str| self.length(arg, depth + 1);
+            return match (function, args.as_slice()) {
+                ("range", [n]) => self.int(n, depth + 1).map(|n| n.max(0) as usize),
+                ("zip", [a, b]) => Some(length(a)?.min(length(b)?)),
+                ("cross", [a, b]) => Some(length(a)?.saturating_mul(length(b)?)),
+                ("prefix" | "suffix", [_, array]) => length(array),
+                ("quote" | "squote", [array]) => length(array),
+                _ => None,
+            };
+        }
+
+        match self.lookup(expression)? {
+            Lookup::Value(Value::Array(items)) => Some(items.len()),
+            Lookup::Value(_) => None,
+            Lookup::Expression(default) => self.length(default, depth + 1),
+        }
+    }
+
+    /// Value of an `Int`-valued expression
+    fn int(&self, expression: &str, depth: usize) -> Option<i64> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        if let Ok(n) = expression.trim().parse() {
+            return Some(n);
+        }
+        if let Some((function, args)) = expr::function_call(expression) {
+            return match (function, args.as_slice()) {
+                ("length", [array]) => self.length(array, depth + 1).map(|n| n as i64),
+                _ => None,
+            };
+        }
+
+        match self.lookup(expression)? {
+            Lookup::Value(value) => value.as_i64(),
+            Lookup::Expression(default) => self.int(default, depth + 1),
+        }
+    }
+
+    /// A plain reference to a workflow input or declaration: the supplied
+    /// inputs value if there is one, otherwise its default expression
+    fn lookup(&self, expression: &str) -> Option<Lookup<'_>> {
+        let name = expression.trim();
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        let key = format!("{}.{}", self.workflow.name, name);
+        if let Some(value) = self.inputs.and_then(|inputs| inputs.get(&key)) {
+            return Some(Lookup::Value(value.clone()));
+        }
+        self.workflow
+            .inputs
+            .iter()
+            .chain(&self.workflow.declarations)
+            .find(|decl| decl.name == name)
+            .and_then(|decl| decl.default_value.as_deref())
+            .map(Lookup::Expression)
+    }
+}
+
+enum Lookup<'a> {
+    Value(Value),
+    Expression(&'a str),
+}
@@ -0,0 +1,230 @@
+use crate::info::{InputInfo, MetaItem, StructInfo, WdlInfo, WorkflowInfo};
+use crate::inputs::is_required;
+use crate::types::WdlType;
+use serde_json::{Map, Value};
+
+/// JSON Schema draft used for parameter schemas
+const SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema";
+
+/// How deep struct fields are expanded into nested object schemas
+const MAX_STRUCT_DEPTH: usize = 8;
+
+/// Layout of the generated parameter schema
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaStyle {
+    /// One object schema with a property per inputs JSON key
+    JsonSchema,
+    /// nf-core layout: workflow and task-level inputs as separate `definitions` groups
+    NfCore,
+}
+
+/// A parameter of the schema: an inputs JSON key and where it's declared
+struct Parameter<'a> {
+    key: String,
+    input: &'a InputInfo,
+    parameter_meta: &'a [MetaItem],
+}
+
+/// JSON Schema for the inputs JSON of `workflow`. Task-level inputs are the
+/// inputs of called tasks that the call doesn't set, which can be supplied
+/// as `workflow.call.input`.
+pub fn parameter_schema(info: &WdlInfo, workflow: &WorkflowInfo, style: SchemaStyle) -> Value {
+    let workflow_parameters: Vec<Parameter> = workflow
+        .inputs
+        .iter()
+        .map(|input| Parameter {
+            key: format!("{}.{}", workflow.name, input.name),
+            input,
+            parameter_meta: &workflow.parameter_meta,
+        })
+        .collect();
+    let mut task_parameters = Vec::new();
+    for call in &workflow.calls {
+        let Some(task) = info.tasks.iter().find(|task| task.name == call.target) else {
+            continue;
+        };
+        for input in &task.inputs {
+            if call.inputs.iter().all(|bound| bound.name != input.name) {
+                task_parameters.push(Parameter {
+                    key: format!("{}.{}.{}", workflow.name, call.name, input.name),
+                    input,
+                    parameter_meta: &task.parameter_meta,
+                });
+            }
+        }
+    }
+
+    let title = format!("{} pipeline parameters", workflow.name);
+    let description = workflow.authorship.description.clone().unwrap_or_default();
+    match style {
+        SchemaStyle::JsonSchema => {
+            let mut schema = group(
+                &title,
+                &description,
+                workflow_parameters.iter().chain(&task_parameters),
+                &info.structs,
+            );
+            schema.insert("$schema".to_string(), Value::from(SCHEMA_DRAFT));
+            Value::Object(schema)
+        }
+        SchemaStyle::NfCore => {
+            let mut definitions = Map::new();
+            definitions.insert(
+                "workflow_inputs".to_string(),
+                Value::Object(group(
+                    "Workflow inputs",
+                    &format!("Inputs of workflow {}", workflow.name),
+                    workflow_parameters.iter(),
+                    &info.structs,
+                )),
+            );
+            if !task_parameters.is_empty() {
+                definitions.insert(
+                    "task_inputs".to_string(),
+                    Value::Object(group(
+                        "Task inputs",
+                        "Inputs of called tasks that the workflow leaves open",
+                        task_parameters.iter(),
+                        &info.structs,
+                    )),
+                );
+            }
+            let all_of: Vec<Value> = definitions
+                .keys()
+                .map(|name| serde_json::json!({ "$ref": format!("#/definitions/{}", name) }))
+                .collect();
+            serde_json::json!({
+                "$schema": SCHEMA_DRAFT,
+                "title": title,
+                "description": description,
+                "type": "object",
+                "definitions": definitions,
+                "allOf": all_of,
+            })
+        }
+    }
+}
+
+/// An object schema with a property per parameter
+fn group<'p, 'a: 'p>(
+    title: &str,
+    description: &str,
+    parameters: impl Iterator<Item = &'p Parameter<'a>>,
+    structs: &[StructInfo],
+) -> Map<String, Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for parameter in parameters {
+        if is_required(parameter.input) {
+            required.push(Value::from(parameter.key.clone()));
+        }
+        properties.insert(parameter.key.clone(), property(parameter, structs));
+    }
+
+    let mut schema = Map::new();
+    schema.insert("title".to_string(), Value::from(title));
+    schema.insert("description".to_string(), Value::from(description));
+    schema.insert("type".to_string(), Value::from("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), Value::Array(required));
+    }
+    schema
+}
+
+fn property(parameter: &Parameter, structs: &[StructInfo]) -> Value {
+    let input = parameter.input;
+    let mut schema = match &input.wdl_type_parsed {
+        Some(wdl_type) => type_schema(wdl_type, structs, 0),
+        None => Map::new(),
+    };
+    let description = parameter
+        .parameter_meta
+        .iter()
+        .find(|item| item.key == input.name)
+        .and_then(MetaItem::description);
+    if let Some(description) = description {
+        schema.insert("description".to_string(), Value::from(description));
+    }
+    if let Some(default) = input
+        .default_value
+        .as_deref()
+        .and_then(|text| serde_json::from_str::<Value>(text).ok())
+    {
+        schema.insert("default".to_string(), default);
+    }
+    Value::Object(schema)
+}
+
+/// JSON Schema for values of a WDL type, as they appear in an inputs JSON
+fn type_schema(wdl_type: &WdlType, structs: &[StructInfo], depth: usize) -> Map<String, Value> {
+    let mut schema = Map::new();
+    let mut set = |key: &str, value: Value| {
+        schema.insert(key.to_string(), value);
+    };
+    match wdl_type {
+        WdlType::Optional { inner } => return type_schema(inner, structs, depth),
+        WdlType::Primitive { name } => match name.as_str() {
+            "Int" => set("type", Value::from("integer")),
+            "Float" => set("type", Value::from("number")),
+            "Boolean" => set("type", Value::from("boolean")),
+            "File" => {
+                set("type", Value::from("string"));
+                set("format", Value::from("file-path"));
+            }
+            "Directory" => {
+                set("type", Value::from("string"));
+                set("format", Value::from("directory-path"));
+            }
+            "Object" => set("type", Value::from("object")),
+            _ => set("type", Value::from("string")),
+        },
+        WdlType::Array { item, nonempty } => {
+            set("type", Value::from("array"));
+            set("items", Value::Object(type_schema(item, structs, depth)));
+            if *nonempty {
+                set("minItems", Value::from(1));
+            }
+        }
+        WdlType::Map { value, .. } => {
+            set("type", Value::from("object"));
+            set(
+                "additionalProperties",
+                Value::Object(type_schema(value, structs, depth)),
+            );
+        }
+        WdlType::Pair { left, right } => {
+            set("type", Value::from("object"));
+            set(
+                "properties",
+                serde_json::json!({
+                    "left": type_schema(left, structs, depth),
+                    "right": type_schema(right, structs, depth),
+                }),
+            );
+            set("required", serde_json::json!(["left", "right"]));
+        }
+        WdlType::Ref { name } => {
+            set("type", Value::from("object"));
+            let definition = structs.iter().find(|s| &s.name == name);
+            if let Some(definition) = definition.filter(|_| depth < MAX_STRUCT_DEPTH) {
+                let mut properties = Map::new();
+                let mut required = Vec::new();
+                for field in &definition.fields {
+                    let field_schema = match &field.wdl_type_parsed {
+                        Some(field_type) => type_schema(field_type, structs, depth + 1),
+                        None => Map::new(),
+                    };
+                    properties.insert(field.name.clone(), Value::Object(field_schema));
+                    if !field.optional {
+                        required.push(Value::from(field.name.clone()));
+                    }
+                }
+                set("title", Value::from(name.clone()));
+                set("properties", Value::Object(properties));
+                set("required", Value::Array(required));
+            }
+        }
+    }
+    schema
+}
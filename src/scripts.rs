@@ -0,0 +1,215 @@
+use crate::command::{shebang_language, strip_common_indent, CommandLanguage, CommandSection};
+use crate::expr;
+use crate::info::TaskInfo;
+use anyhow::{Context, Result};
+
+/// Shebang of scripts whose command doesn't start with its own
+const SHELL_SHEBANG: &str = "#!/usr/bin/env bash";
+
+/// Extensions of extracted scripts, by the language of the command
+pub const EXTENSIONS: &[&str] = &["sh", "py", "R", "pl", "rb", "js"];
+
+/// A task command written out as a standalone script
+#[derive(Debug, Clone)]
+pub struct Script {
+    pub task: String,
+    /// File name, the task name with an extension for the language
+    pub name: String,
+    /// Environment variables standing in for the placeholders, with the
+    /// placeholder each replaces, as written
+    pub variables: Vec<(String, String)>,
+    pub content: String,
+}
+
+/// The command of `task` as a script that reads each placeholder from an
+/// environment variable. A header comment names the source and lists the
+/// variables, so `reinject` can put the placeholders back.
+pub fn extract(task: &TaskInfo, file: &str) -> Option<Script> {
+    let section = task.command_section.as_ref()?;
+    let language = task.command_language.unwrap_or(CommandLanguage::Bash);
+
+    let raw: Vec<char> = section.raw.chars().collect();
+    let mut variables: Vec<(String, String)> = Vec::new();
+    let mut replaced = String::new();
+    let mut cursor = 0;
+    for span in section.placeholder_spans() {
+        let written: String = raw[span.offset..span.end].iter().collect();
+        let name = match variables.iter().find(|(_, w)| *w == written) {
+            Some((name, _)) => name.clone(),
+            None => {
+                let name = variable_name(&span.expression, &section.raw, &variables);
+                variables.push((name.clone(), written));
+                name
+            }
+        };
+        replaced.extend(&raw[cursor..span.offset]);
+        replaced.push_str(&format!("${{{}}}", name));
+        cursor = span.end;
+    }
+    replaced.extend(&raw[cursor..]);
+    let body = strip_common_indent(&replaced);
+
+    // A command with its own `#!` line keeps it first
+    let (shebang, body) = match body.strip_prefix("#!") {
+        Some(rest) => {
+            let (line, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+            (format!("#!{}", line), rest.to_string())
+        }
+        None => (SHELL_SHEBANG.to_string(), body),
+    };
+    let comment = comment_prefix(&shebang);
+    let mut content = format!(
+        "{}\n{} Command of task `{}` in {}, written by `wdlparse extract-scripts`.\n",
+        shebang, comment, task.name, file
+    );
+    if !variables.is_empty() {
+        content.push_str(&format!(
+            "{} WDL placeholders are environment variables; `--reinject` puts them back:\n",
+            comment
+        ));
+        for (name, written) in &variables {
+            content.push_str(&format!(
+                "{} {}={}\n",
+                comment,
+                name,
+                serde_json::to_string(written).unwrap_or_default()
+            ));
+        }
+    }
+    content.push('\n');
+    content.push_str(&body);
+    content.push('\n');
+
+    let extension = if shebang == SHELL_SHEBANG {
+        "sh"
+    } else {
+        extension(language)
+    };
+    Some(Script {
+        task: task.name.clone(),
+        name: format!("{}.{}", task.name, extension),
+        variables,
+        content,
+    })
+}
+
+/// The raw text of `section` with the command replaced by an extracted
+/// script: the header is dropped, its variables become placeholders again
+/// and the script is indented like the original command. `None` when the
+/// script still matches the command.
+pub fn reinject(script: &str, section: &CommandSection) -> Result<Option<String>> {
+    let mut lines = script.split('\n');
+    let shebang = lines.next().unwrap_or_default();
+    let mut variables: Vec<(String, String)> = Vec::new();
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+        let text = line
+            .trim_start_matches(['#', '/'])
+            .trim_start()
+            .split_once('=')
+            .filter(|(name, _)| is_variable_name(name));
+        if let Some((name, written)) = text {
+            let written: String = serde_json::from_str(written)
+                .with_context(|| format!("Invalid placeholder for {} in the header", name))?;
+            variables.push((name.to_string(), written));
+        }
+    }
+
+    let body = lines.collect::<Vec<_>>().join("\n");
+    let mut body = body.strip_suffix('\n').unwrap_or(&body).to_string();
+    for (name, written) in &variables {
+        body = body.replace(&format!("${{{}}}", name), written);
+    }
+    if section.dedented.starts_with("#!") {
+        body = format!("{}\n{}", shebang, body);
+    }
+    if body == section.dedented {
+        return Ok(None);
+    }
+    Ok(Some(reindent(&section.raw, &body)))
+}
+
+/// The extension of a script in `language`
+pub fn extension(language: CommandLanguage) -> &'static str {
+    match language {
+        CommandLanguage::Bash => "sh",
+        CommandLanguage::Python => "py",
+        CommandLanguage::R => "R",
+        CommandLanguage::Perl => "pl",
+        CommandLanguage::Ruby => "rb",
+        CommandLanguage::Javascript => "js",
+    }
+}
+
+/// The line comment marker of the interpreter `shebang` runs, so a command
+/// wrapped in a bash script is commented like one
+fn comment_prefix(shebang: &str) -> &'static str {
+    match shebang_language(shebang) {
+        Some(CommandLanguage::Javascript) => "//",
+        _ => "#",
+    }
+}
+
+/// An upper-case name for the variable replacing a placeholder, from the
+/// names it reads, that the command doesn't already use
+fn variable_name(expression: &str, raw: &str, taken: &[(String, String)]) -> String {
+    let roots = expr::referenced_roots(expression);
+    let base = if roots.is_empty() {
+        "WDL_VALUE".to_string()
+    } else {
+        roots.join("_").to_uppercase()
+    };
+    let used = |name: &str| {
+        taken.iter().any(|(taken, _)| taken == name)
+            || raw.contains(&format!("${}", name))
+            || raw.contains(&format!("${{{}", name))
+    };
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while used(&name) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// `body` indented like the command text `raw`, keeping the whitespace-only
+/// lines after the opening and before the closing delimiter
+fn reindent(raw: &str, body: &str) -> String {
+    let lines: Vec<&str> = raw.split('\n').collect();
+    let multiline = lines.len() > 1;
+    let lead = match lines.first() {
+        Some(first) if multiline && first.trim().is_empty() => format!("{}\n", first),
+        _ => String::new(),
+    };
+    let trail = match lines.last() {
+        Some(last) if multiline && last.trim().is_empty() => format!("\n{}", last),
+        _ => String::new(),
+    };
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .min_by_key(|indent| indent.len())
+        .unwrap_or_default();
+    let body: Vec<String> = body
+        .split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect();
+    format!("{}{}{}", lead, body.join("\n"), trail)
+}
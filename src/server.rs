@@ -0,0 +1,107 @@
+use crate::config::Config;
+use crate::info::SortBy;
+use crate::service;
+use anyhow::Result;
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest request body accepted, in bytes
+const MAX_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Serve `POST /<method>` endpoints for every [`service::METHODS`] entry plus `GET /health`.
+///
+/// Request bodies are raw WDL content, or JSON `{"content": "..."}` when sent
+/// with a JSON content type. Responses are always JSON.
+pub fn serve(host: &str, port: u16, config: &Config, order: SortBy) -> Result<()> {
+    let server = Server::http((host, port))
+        .map_err(|e| anyhow::anyhow!("Failed to bind {}:{}: {}", host, port, e))?;
+    tracing::info!(%host, port, "listening");
+
+    for request in server.incoming_requests() {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let start = std::time::Instant::now();
+        let status = respond(request, config, order);
+        tracing::info!(
+            method = %method,
+            %url,
+            status,
+            elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+            "handled request"
+        );
+    }
+
+    Ok(())
+}
+
+/// Handle one request and return the status code that was sent
+fn respond(mut request: Request, config: &Config, order: SortBy) -> u16 {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('/')
+        .to_string();
+
+    let method = request.method().clone();
+    let (status, body) = match (&method, path.as_str()) {
+        (Method::Get, "health") => (200, serde_json::json!({ "status": "ok" })),
+        (Method::Post, name) if service::METHODS.contains(&name) => {
+            match read_content(&mut request) {
+                Ok(content) => match service::handle(name, &content, config, order) {
+                    Ok(result) => (200, result),
+                    Err(e) => (500, error_body(&format!("{:#}", e))),
+                },
+                Err(message) => (400, error_body(&message)),
+            }
+        }
+        (_, name) if service::METHODS.contains(&name) => {
+            (405, error_body("Method not allowed; use POST"))
+        }
+        _ => (404, error_body(&format!("Unknown endpoint '/{}'", path))),
+    };
+
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        tracing::warn!("failed to send response: {}", e);
+    }
+    status
+}
+
+/// Read the WDL content from a request body (raw or `{"content": ...}` JSON)
+fn read_content(request: &mut Request) -> std::result::Result<String, String> {
+    let is_json = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Content-Type") && h.value.as_str().starts_with("application/json"));
+
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| format!("Failed to read request body: {}", e))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(format!("Request body exceeds {} bytes", MAX_BODY_BYTES));
+    }
+
+    if is_json {
+        let value: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| format!("Invalid JSON body: {}", e))?;
+        value["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "JSON body must contain a string 'content' field".to_string())
+    } else {
+        Ok(body)
+    }
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
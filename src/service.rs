@@ -0,0 +1,81 @@
+use crate::commands::extract_semantic_info;
+use crate::config::Config;
+use crate::diagnostics::{self, Located};
+use crate::graph::WorkflowGraph;
+use crate::info::SortBy;
+use crate::lineage;
+use crate::lines::LineIndex;
+use crate::lint;
+use crate::mermaid;
+use crate::suppress::Suppressions;
+use anyhow::Result;
+use wdl_grammar::{Diagnostic, SyntaxTree};
+
+/// Operations available to long-running integrations (HTTP server, JSON-RPC)
+pub const METHODS: &[&str] = &["parse", "info", "lint", "lineage", "mermaid"];
+
+/// Run a named operation against inline WDL content and return its JSON
+/// result, with collections in `order`
+pub fn handle(
+    method: &str,
+    content: &str,
+    config: &Config,
+    order: SortBy,
+) -> Result<serde_json::Value> {
+    let (tree, diagnostics) = SyntaxTree::parse(content);
+    let info = extract_semantic_info(&tree.root(), order);
+
+    let result = match method {
+        "parse" => serde_json::json!({
+            "diagnostics": diagnostics_json(&diagnostics, &LineIndex::new(content)),
+            "has_errors": diagnostics
+                .iter()
+                .any(|d| matches!(d.severity(), wdl_grammar::Severity::Error)),
+            "wdl": info
+        }),
+        "info" => serde_json::to_value(&info)?,
+        "lint" => serde_json::json!({
+            "findings": Suppressions::parse(content).apply(lint::lint(&info, &config.lint))
+        }),
+        "lineage" => serde_json::json!({
+            "workflows": info
+                .workflows
+                .iter()
+                .map(|workflow| serde_json::json!({
+                    "workflow": workflow.name,
+                    "outputs": lineage::workflow_lineage(workflow)
+                }))
+                .collect::<Vec<_>>()
+        }),
+        "mermaid" => serde_json::json!({
+            "workflows": info
+                .workflows
+                .iter()
+                .map(|workflow| serde_json::json!({
+                    "workflow": workflow.name,
+                    "diagram": mermaid::workflow_diagram(
+                        &WorkflowGraph::build(workflow),
+                        &info,
+                        &mermaid::DiagramOptions::default()
+                    )
+                }))
+                .collect::<Vec<_>>()
+        }),
+        _ => anyhow::bail!(
+            "Unknown method '{}' (available: {})",
+            method,
+            METHODS.join(", ")
+        ),
+    };
+
+    Ok(result)
+}
+
+/// Diagnostics as `{severity, message, line, column}` objects in file order;
+/// the position is null when the parser gave no location
+pub fn diagnostics_json(diagnostics: &[Diagnostic], lines: &LineIndex) -> serde_json::Value {
+    diagnostics::sorted(diagnostics)
+        .into_iter()
+        .map(|d| serde_json::json!(Located::new(d, lines)))
+        .collect()
+}
@@ -0,0 +1,208 @@
+use crate::lines::LineIndex;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const BOM: char = '\u{feff}';
+
+/// Line endings a file was written with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+/// Text encodings wdlparse reads. UTF-16 files are transcoded to UTF-8 on
+/// read and back on write.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+        })
+    }
+}
+
+/// How a file was stored before normalization, so rewrites can put it back
+/// the same way
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceFlavor {
+    #[serde(default)]
+    pub encoding: Encoding,
+    pub bom: bool,
+    pub line_ending: LineEnding,
+}
+
+impl SourceFlavor {
+    /// Normalized `text` converted back to this flavor
+    pub fn restore(&self, text: &str) -> String {
+        let mut restored = String::with_capacity(text.len() + 1);
+        if self.bom {
+            restored.push(BOM);
+        }
+        match self.line_ending {
+            LineEnding::Lf => restored.push_str(text),
+            LineEnding::Crlf => restored.push_str(&text.replace('\n', "\r\n")),
+        }
+        restored
+    }
+
+    /// Normalized `text` as the bytes of a file in this flavor
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let restored = self.restore(text);
+        match self.encoding {
+            Encoding::Utf8 => restored.into_bytes(),
+            Encoding::Utf16Le => restored.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            Encoding::Utf16Be => restored.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        }
+    }
+}
+
+/// Source text without a byte order mark and with `\n` line endings, so byte
+/// offsets, line numbers and regexes behave the same for files saved on
+/// Windows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceText {
+    pub text: String,
+    pub flavor: SourceFlavor,
+}
+
+impl SourceText {
+    /// A file counts as CRLF when most of its line breaks are `\r\n`; every
+    /// `\r\n` is normalized either way
+    pub fn normalize(raw: &str) -> Self {
+        let (bom, raw) = match raw.strip_prefix(BOM) {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let crlf = raw.matches("\r\n").count();
+        let lf = raw.matches('\n').count() - crlf;
+        let line_ending = if crlf > lf {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        };
+        let text = if crlf > 0 {
+            raw.replace("\r\n", "\n")
+        } else {
+            raw.to_string()
+        };
+        Self {
+            text,
+            flavor: SourceFlavor {
+                encoding: Encoding::Utf8,
+                bom,
+                line_ending,
+            },
+        }
+    }
+
+    /// Decode UTF-8 or UTF-16 (with a byte order mark, or ASCII-heavy text
+    /// without one). Other encodings can't be told apart reliably, so they
+    /// are reported by name rather than guessed at.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let utf8_error = match std::str::from_utf8(bytes) {
+            Ok(text) => return Ok(Self::normalize(text)),
+            Err(e) => e,
+        };
+        if bytes.starts_with(&[0xFF, 0xFE, 0, 0]) || bytes.starts_with(&[0, 0, 0xFE, 0xFF]) {
+            bail!("the file is encoded as UTF-32, which wdlparse can't read; save it as UTF-8");
+        }
+        let encoding = if bytes.starts_with(&[0xFF, 0xFE]) {
+            Some(Encoding::Utf16Le)
+        } else if bytes.starts_with(&[0xFE, 0xFF]) {
+            Some(Encoding::Utf16Be)
+        } else {
+            utf16_without_bom(bytes)
+        };
+
+        let Some(encoding) = encoding else {
+            let offset = utf8_error.valid_up_to();
+            let before = String::from_utf8_lossy(&bytes[..offset]);
+            let (line, column) = LineIndex::new(&before).position(before.len());
+            bail!(
+                "the file is not valid UTF-8 (byte 0x{:02X} at line {}, column {}); it looks like {}, which can't be converted unambiguously. Save it as UTF-8",
+                bytes[offset],
+                line,
+                column,
+                single_byte_encoding(bytes)
+            );
+        };
+
+        if bytes.len() % 2 != 0 {
+            bail!(
+                "the file looks like {} but has an odd number of bytes",
+                encoding
+            );
+        }
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| match encoding {
+                Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                _ => u16::from_le_bytes([pair[0], pair[1]]),
+            })
+            .collect();
+        let text = String::from_utf16(&units)
+            .with_context(|| format!("the file isn't valid {}", encoding))?;
+        let mut source = Self::normalize(&text);
+        source.flavor.encoding = encoding;
+        tracing::debug!(%encoding, "transcoded file to UTF-8");
+        Ok(source)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes =
+            fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+        Self::decode(&bytes).with_context(|| format!("Failed to read file: {}", path.display()))
+    }
+}
+
+/// UTF-16 without a byte order mark, recognized by the zero high bytes of
+/// ASCII characters: at least nine in ten code units of the file are ASCII,
+/// with the zero byte consistently on the same side
+fn utf16_without_bom(bytes: &[u8]) -> Option<Encoding> {
+    let pairs = bytes.len() / 2;
+    if pairs == 0 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let zero_high = |index: usize| {
+        bytes
+            .chunks_exact(2)
+            .filter(|pair| pair[index] == 0 && pair[1 - index] != 0)
+            .count()
+    };
+    let (le, be) = (zero_high(1), zero_high(0));
+    if le * 10 >= pairs * 9 && be == 0 {
+        Some(Encoding::Utf16Le)
+    } else if be * 10 >= pairs * 9 && le == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Windows-1252 uses 0x80-0x9F for printable characters where ISO-8859-1 has
+/// control codes, so those bytes tell the two apart
+fn single_byte_encoding(bytes: &[u8]) -> &'static str {
+    if bytes.iter().any(|byte| (0x80..0xA0).contains(byte)) {
+        "Windows-1252"
+    } else {
+        "ISO-8859-1 (Latin-1)"
+    }
+}
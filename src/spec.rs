@@ -0,0 +1,211 @@
+use crate::config::{LintConfig, RuleLevel};
+use crate::edit;
+use crate::lines::LineIndex;
+use crate::lint::{rule_level, Finding, Severity};
+use crate::strip::definition_name;
+use wdl_grammar::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// Rule id of the findings `lint --spec` adds
+pub const RULE: &str = "spec-version";
+
+/// WDL version whose features `lint --spec` accepts
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SpecVersion {
+    #[value(name = "1.0")]
+    V1_0,
+    #[value(name = "1.1")]
+    V1_1,
+    #[value(name = "1.2")]
+    V1_2,
+    Development,
+}
+
+impl SpecVersion {
+    /// Every version wdlparse reads, oldest first
+    pub fn all() -> impl Iterator<Item = Self> {
+        <Self as clap::ValueEnum>::value_variants().iter().copied()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SpecVersion::V1_0 => "1.0",
+            SpecVersion::V1_1 => "1.1",
+            SpecVersion::V1_2 => "1.2",
+            SpecVersion::Development => "development",
+        }
+    }
+
+    /// The version of a `version` statement, `None` for draft-2 and
+    /// versions this list doesn't know
+    pub fn from_declared(version: &str) -> Option<Self> {
+        match version {
+            "1.0" => Some(SpecVersion::V1_0),
+            "1.1" => Some(SpecVersion::V1_1),
+            "1.2" => Some(SpecVersion::V1_2),
+            "development" => Some(SpecVersion::Development),
+            _ => None,
+        }
+    }
+}
+
+/// A use of a feature that only some WDL versions have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureUse {
+    /// What the feature is, e.g. `'hints' section`
+    pub feature: &'static str,
+    /// First version with the feature
+    pub since: SpecVersion,
+    /// Enclosing task, workflow or struct with the position, as in lint findings
+    pub location: String,
+    pub line: usize,
+}
+
+/// Version-gated features used in a document: `after` clauses and calls
+/// without `input:` (1.1), and `hints` sections, multi-line strings and the
+/// `Directory` type (1.2)
+pub fn feature_uses(root: &SyntaxNode, lines: &LineIndex) -> Vec<FeatureUse> {
+    let mut uses = Vec::new();
+    let mut found = |feature, since, node: &SyntaxNode, offset: usize| {
+        let (line, column) = lines.position(offset);
+        uses.push(FeatureUse {
+            feature,
+            since,
+            location: format!("{} (line {}, column {})", location(node), line, column),
+            line,
+        });
+    };
+
+    for node in root.descendants() {
+        match node.kind() {
+            SyntaxKind::CallStatementNode => {
+                if let Some(after) = call_keyword(&node, "after") {
+                    let offset = edit::token_range(&after).start;
+                    found("call 'after' clause", SpecVersion::V1_1, &node, offset);
+                }
+                let has_inputs = node
+                    .descendants()
+                    .any(|child| child.kind() == SyntaxKind::CallInputItemNode);
+                if has_inputs && call_keyword(&node, "input").is_none() {
+                    let offset = edit::node_range(&node).start;
+                    found("call without 'input:'", SpecVersion::V1_1, &node, offset);
+                }
+            }
+            SyntaxKind::TaskDefinitionNode | SyntaxKind::WorkflowDefinitionNode => {
+                for section in node.children() {
+                    if first_token(&section).is_some_and(|token| token.text() == "hints") {
+                        let offset = edit::node_range(&section).start;
+                        found("'hints' section", SpecVersion::V1_2, &section, offset);
+                    }
+                }
+            }
+            SyntaxKind::LiteralStringNode => {
+                if first_token(&node).is_some_and(|token| token.text() == "<<<") {
+                    let offset = edit::node_range(&node).start;
+                    found("multi-line string", SpecVersion::V1_2, &node, offset);
+                }
+            }
+            // Before 1.2 `Directory` may be read as a struct name
+            SyntaxKind::PrimitiveTypeNode | SyntaxKind::TypeRefNode => {
+                if node.text().to_string().trim().trim_end_matches('?') == "Directory" {
+                    let offset = edit::node_range(&node).start;
+                    found("'Directory' type", SpecVersion::V1_2, &node, offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    uses
+}
+
+/// Findings for features newer than `spec`, or than the file's declared
+/// `version` (draft-2 when there is none)
+pub fn check(
+    root: &SyntaxNode,
+    source: &str,
+    declared: Option<&str>,
+    spec: SpecVersion,
+    config: &LintConfig,
+) -> Vec<Finding> {
+    let severity = match rule_level(config, RULE, RuleLevel::Error) {
+        RuleLevel::Off => return Vec::new(),
+        RuleLevel::Warning => Severity::Warning,
+        RuleLevel::Error => Severity::Error,
+    };
+    // Draft-2 predates every gated feature; unknown versions can't be compared
+    let newer_than_declared = |since: SpecVersion| match declared {
+        None | Some("draft-2") => true,
+        Some(version) => SpecVersion::from_declared(version).is_some_and(|v| since > v),
+    };
+
+    let lines = LineIndex::new(source);
+    let mut findings = Vec::new();
+    for found in feature_uses(root, &lines) {
+        let message = if found.since > spec {
+            format!(
+                "{} requires WDL {}, but --spec is {}",
+                found.feature,
+                found.since.name(),
+                spec.name()
+            )
+        } else if newer_than_declared(found.since) {
+            let declared = match declared {
+                Some(version) => format!("the file declares version {}", version),
+                None => "the file has no version statement (draft-2)".to_string(),
+            };
+            format!(
+                "{} requires WDL {}, but {}",
+                found.feature,
+                found.since.name(),
+                declared
+            )
+        } else {
+            continue;
+        };
+        findings.push(Finding {
+            rule: RULE.to_string(),
+            severity,
+            location: found.location,
+            line: Some(found.line),
+            message,
+            suggestions: Vec::new(),
+        });
+    }
+    findings
+}
+
+/// A keyword of the call statement itself, outside its input expressions
+fn call_keyword(call: &SyntaxNode, keyword: &str) -> Option<SyntaxToken> {
+    call.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| token.text() == keyword)
+        .find(|token| {
+            !token
+                .parent_ancestors()
+                .any(|node| node.kind() == SyntaxKind::CallInputItemNode)
+        })
+}
+
+fn first_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))
+}
+
+/// `task name`, `workflow name` or `struct name` enclosing `node`
+fn location(node: &SyntaxNode) -> String {
+    node.ancestors()
+        .find_map(|ancestor| {
+            let kind = match ancestor.kind() {
+                SyntaxKind::TaskDefinitionNode => "task",
+                SyntaxKind::WorkflowDefinitionNode => "workflow",
+                SyntaxKind::StructDefinitionNode => "struct",
+                _ => return None,
+            };
+            Some(format!(
+                "{} {}",
+                kind,
+                definition_name(&ancestor).unwrap_or_default()
+            ))
+        })
+        .unwrap_or_else(|| "document".to_string())
+}
@@ -0,0 +1,74 @@
+use crate::graph::GraphStats;
+use crate::info::{TaskInfo, WdlInfo};
+use serde::{Deserialize, Serialize};
+
+/// Size and structure counts for a WDL file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileStats {
+    pub file: String,
+    pub lines: usize,
+    pub tasks: usize,
+    pub workflows: usize,
+    pub structs: usize,
+    pub imports: usize,
+    pub calls: usize,
+    pub task_stats: Vec<TaskStats>,
+    /// Workflow graph metrics, filled in by `stats --graph`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub graphs: Vec<GraphStats>,
+}
+
+/// Per-task counts and resources
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskStats {
+    pub name: String,
+    pub inputs: usize,
+    pub outputs: usize,
+    pub command_lines: usize,
+    pub cpu: Option<String>,
+    pub memory: Option<String>,
+    /// `memory` in bytes, when it's a literal size
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    pub container: Option<String>,
+}
+
+impl TaskStats {
+    pub fn from_task(task: &TaskInfo) -> Self {
+        let runtime = |keys: &[&str]| {
+            task.runtime
+                .iter()
+                .find(|item| keys.contains(&item.key.as_str()))
+                .map(|item| item.literal_value().unwrap_or(&item.value).to_string())
+        };
+
+        Self {
+            name: task.name.clone(),
+            inputs: task.inputs.len(),
+            outputs: task.outputs.len(),
+            command_lines: task
+                .command_section
+                .as_ref()
+                .map(|section| section.dedented.lines().count())
+                .unwrap_or_default(),
+            cpu: runtime(&["cpu"]),
+            memory: runtime(&["memory"]),
+            memory_bytes: task.normalized_runtime.memory_bytes,
+            container: runtime(&["container", "docker"]),
+        }
+    }
+}
+
+pub fn file_stats(file: &str, content: &str, info: &WdlInfo) -> FileStats {
+    FileStats {
+        file: file.to_string(),
+        lines: content.lines().count(),
+        tasks: info.tasks.len(),
+        workflows: info.workflows.len(),
+        structs: info.structs.len(),
+        imports: info.imports.len(),
+        calls: info.workflows.iter().map(|w| w.calls.len()).sum(),
+        task_stats: info.tasks.iter().map(TaskStats::from_task).collect(),
+        graphs: Vec::new(),
+    }
+}
@@ -0,0 +1,244 @@
+use crate::closure;
+use crate::edit::{self, SourceEdit};
+use crate::workspace::{import_namespace, Workspace};
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use wdl_grammar::{SyntaxKind, SyntaxNode, SyntaxTree};
+
+/// What `strip` removes besides uncalled tasks, unused structs and imports
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StripOptions {
+    pub comments: bool,
+    /// `meta` and `parameter_meta` sections
+    pub meta: bool,
+}
+
+/// The stripped source and the names of the definitions taken out of it
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Stripped {
+    #[serde(skip)]
+    pub source: String,
+    pub tasks: Vec<String>,
+    pub structs: Vec<String>,
+    /// URIs of removed imports
+    pub imports: Vec<String>,
+}
+
+/// Remove from the workspace's root document the tasks no workflow calls,
+/// the structs none of the remaining declarations use, and the imports that
+/// provide neither a called task nor a used struct. Imports that couldn't be
+/// loaded are kept, since what they provide is unknown, and so are all tasks
+/// of a document without a workflow, which is a task library.
+pub fn strip(workspace: &Workspace, options: StripOptions) -> Result<Stripped> {
+    let document = workspace.root();
+    let info = &document.info;
+    let source = &document.content;
+
+    let targets: BTreeSet<&str> = info
+        .workflows
+        .iter()
+        .flat_map(|w| &w.calls)
+        .map(|call| call.target.as_str())
+        .collect();
+    let library = info.workflows.is_empty();
+    let kept = |name: &str| library || targets.contains(name);
+    let kept_tasks = info.tasks.iter().filter(|task| kept(&task.name));
+    let types = kept_tasks
+        .flat_map(|task| {
+            let outputs = task.outputs.iter().map(|o| &o.wdl_type_parsed);
+            let declarations = task.inputs.iter().chain(&task.declarations);
+            declarations.map(|d| &d.wdl_type_parsed).chain(outputs)
+        })
+        .chain(info.workflows.iter().flat_map(|workflow| {
+            let outputs = workflow.outputs.iter().map(|o| &o.wdl_type_parsed);
+            let declarations = workflow.inputs.iter().chain(&workflow.declarations);
+            declarations.map(|d| &d.wdl_type_parsed).chain(outputs)
+        }))
+        .flatten();
+    let used_structs = closure::used_structs(info, types);
+
+    let namespaces: BTreeSet<&str> = targets
+        .iter()
+        .filter_map(|target| target.split_once('.').map(|(namespace, _)| namespace))
+        .collect();
+    let imported = workspace.imports(document);
+    let import_used = |uri: &str, alias: Option<&str>| {
+        let namespace = import_namespace(uri, alias);
+        if namespaces.contains(namespace.as_str()) {
+            return true;
+        }
+        let loaded = imported
+            .iter()
+            .find(|(ns, _)| *ns == namespace)
+            .and_then(|(_, path)| workspace.document(path));
+        match loaded {
+            Some(imported) => imported
+                .info
+                .structs
+                .iter()
+                .any(|s| used_structs.contains(s.name.as_str())),
+            None => true,
+        }
+    };
+
+    let mut stripped = Stripped::default();
+    let mut removals: Vec<Range<usize>> = Vec::new();
+    let root = document.tree.root();
+    for node in root.children() {
+        match node.kind() {
+            SyntaxKind::TaskDefinitionNode => {
+                let Some(name) = definition_name(&node).filter(|name| !kept(name)) else {
+                    continue;
+                };
+                stripped.tasks.push(name);
+            }
+            SyntaxKind::StructDefinitionNode => {
+                let Some(name) =
+                    definition_name(&node).filter(|name| !used_structs.contains(name.as_str()))
+                else {
+                    continue;
+                };
+                stripped.structs.push(name);
+            }
+            SyntaxKind::ImportStatementNode => {
+                let uri = import_uri(&node);
+                let Some(import) = info
+                    .imports
+                    .iter()
+                    .find(|import| Some(&import.uri) == uri.as_ref())
+                    .filter(|import| !import_used(&import.uri, import.alias.as_deref()))
+                else {
+                    continue;
+                };
+                stripped.imports.push(import.uri.clone());
+            }
+            _ => continue,
+        }
+        add_removal(
+            &mut removals,
+            line_range(source, edit::node_range(&node), true),
+        );
+    }
+
+    if options.meta {
+        for node in root.descendants().filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::MetadataSectionNode | SyntaxKind::ParameterMetadataSectionNode
+            )
+        }) {
+            add_removal(
+                &mut removals,
+                line_range(source, edit::node_range(&node), false),
+            );
+        }
+    }
+    if options.comments {
+        for token in root
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .filter(|token| token.kind() == SyntaxKind::Comment)
+        {
+            add_removal(
+                &mut removals,
+                line_range(source, edit::token_range(&token), false),
+            );
+        }
+    }
+
+    let edits: Vec<SourceEdit> = removals.into_iter().map(SourceEdit::delete).collect();
+    let text = edit::apply(source, &edits)?;
+    stripped.source = collapse_blank_lines(&text)
+        .trim_start_matches('\n')
+        .to_string();
+    Ok(stripped)
+}
+
+/// `text` with the runs of blank lines removals leave cut to one, except in
+/// commands and strings, where blank lines are content
+fn collapse_blank_lines(text: &str) -> String {
+    let (tree, _) = SyntaxTree::parse(text);
+    let verbatim: Vec<Range<usize>> = tree
+        .root()
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::CommandSectionNode | SyntaxKind::LiteralStringNode
+            )
+        })
+        .map(|node| edit::node_range(&node))
+        .collect();
+
+    let blank_lines = Regex::new(r"\n(?:[ \t]*\n){2,}").expect("valid regex");
+    let mut collapsed = String::new();
+    let mut cursor = 0;
+    for run in blank_lines.find_iter(text) {
+        if verbatim
+            .iter()
+            .any(|range| run.start() < range.end && range.start < run.end())
+        {
+            continue;
+        }
+        collapsed.push_str(&text[cursor..run.start()]);
+        collapsed.push_str("\n\n");
+        cursor = run.end();
+    }
+    collapsed.push_str(&text[cursor..]);
+    collapsed
+}
+
+/// Record a removal unless an earlier one already covers part of it
+fn add_removal(removals: &mut Vec<Range<usize>>, range: Range<usize>) {
+    let overlaps = removals
+        .iter()
+        .any(|other| range.start < other.end && other.start < range.end);
+    if !overlaps {
+        removals.push(range);
+    }
+}
+
+/// `range` widened to whole lines when nothing else is on them, or else back
+/// over the spaces before it. With `doc`, the comment lines directly above
+/// are included too.
+pub fn line_range(source: &str, range: Range<usize>, doc: bool) -> Range<usize> {
+    let line_start = source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[range.end..]
+        .find('\n')
+        .map_or(source.len(), |i| range.end + i);
+    if !source[range.end..line_end].trim().is_empty() {
+        return range;
+    }
+    if !source[line_start..range.start].trim().is_empty() {
+        let start = source[..range.start].trim_end_matches([' ', '\t']).len();
+        return start..range.end;
+    }
+
+    let mut start = line_start;
+    while doc && start > 0 {
+        let previous = source[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+        if !source[previous..start - 1].trim_start().starts_with('#') {
+            break;
+        }
+        start = previous;
+    }
+    start..(line_end + 1).min(source.len())
+}
+
+/// Name of a task or struct definition
+pub fn definition_name(node: &SyntaxNode) -> Option<String> {
+    node.children_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| token.kind() == SyntaxKind::Ident)
+        .map(|token| token.text().to_string())
+}
+
+fn import_uri(node: &SyntaxNode) -> Option<String> {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| token.kind() == SyntaxKind::LiteralStringText)
+        .map(|token| token.text().to_string())
+}
@@ -0,0 +1,201 @@
+use crate::info::{InputInfo, WdlInfo};
+use crate::types::WdlType;
+use crate::workspace::SymbolKind;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What `graph` shows
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphMode {
+    /// Inputs, calls and outputs of each workflow and the data flowing between them
+    #[default]
+    Dataflow,
+    /// Which tasks, workflows and structs use each struct type
+    Structs,
+}
+
+/// A declaration whose type uses a struct
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StructUse {
+    /// Task, workflow or struct the declaration belongs to
+    pub kind: SymbolKind,
+    pub name: String,
+    pub file: String,
+    /// `input`, `output`, `declaration` (private) or `field`
+    pub section: String,
+    pub declaration: String,
+    /// The struct named in the declaration's type when this one is only
+    /// reached through its fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub through: Option<String>,
+}
+
+/// Everything that would be affected by changing one struct
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StructUsage {
+    pub name: String,
+    /// Files defining a struct of this name
+    pub defined_in: Vec<String>,
+    pub uses: Vec<StructUse>,
+    /// Distinct tasks among `uses`
+    pub tasks: usize,
+    /// Distinct workflows among `uses`
+    pub workflows: usize,
+}
+
+/// Struct usage across a set of files, one entry per struct name, sorted by
+/// name. Structs used but defined in none of the files are included too.
+pub fn struct_usage(documents: &[(String, &WdlInfo)]) -> Vec<StructUsage> {
+    let mut fields: BTreeMap<&str, &[InputInfo]> = BTreeMap::new();
+    let mut usage: BTreeMap<&str, StructUsage> = BTreeMap::new();
+    for (file, info) in documents {
+        for definition in &info.structs {
+            fields
+                .entry(definition.name.as_str())
+                .or_insert(&definition.fields);
+            entry(&mut usage, &definition.name)
+                .defined_in
+                .push(file.clone());
+        }
+    }
+
+    for (file, info) in documents {
+        let tasks = info.tasks.iter().map(|task| {
+            (
+                SymbolKind::Task,
+                &task.name,
+                [("input", &task.inputs), ("declaration", &task.declarations)],
+                task.outputs.iter(),
+            )
+        });
+        let workflows = info.workflows.iter().map(|workflow| {
+            (
+                SymbolKind::Workflow,
+                &workflow.name,
+                [
+                    ("input", &workflow.inputs),
+                    ("declaration", &workflow.declarations),
+                ],
+                workflow.outputs.iter(),
+            )
+        });
+        for (kind, name, sections, outputs) in tasks.chain(workflows) {
+            let declarations = sections
+                .into_iter()
+                .flat_map(|(section, declarations)| {
+                    declarations
+                        .iter()
+                        .map(move |d| (section, &d.name, d.wdl_type_parsed.as_ref()))
+                })
+                .chain(outputs.map(|o| ("output", &o.name, o.wdl_type_parsed.as_ref())));
+            for (section, declaration, wdl_type) in declarations {
+                let Some(wdl_type) = wdl_type else {
+                    continue;
+                };
+                let site = StructUse {
+                    kind,
+                    name: name.clone(),
+                    file: file.clone(),
+                    section: section.to_string(),
+                    declaration: declaration.clone(),
+                    through: None,
+                };
+                record(&mut usage, &fields, wdl_type, &site);
+            }
+        }
+        for definition in &info.structs {
+            for field in &definition.fields {
+                let Some(wdl_type) = &field.wdl_type_parsed else {
+                    continue;
+                };
+                let site = StructUse {
+                    kind: SymbolKind::Struct,
+                    name: definition.name.clone(),
+                    file: file.clone(),
+                    section: "field".to_string(),
+                    declaration: field.name.clone(),
+                    through: None,
+                };
+                record(&mut usage, &fields, wdl_type, &site);
+            }
+        }
+    }
+
+    usage
+        .into_values()
+        .map(|mut usage| {
+            let count = |kind: SymbolKind| {
+                let mut names: Vec<(&str, &str)> = usage
+                    .uses
+                    .iter()
+                    .filter(|site| site.kind == kind)
+                    .map(|site| (site.file.as_str(), site.name.as_str()))
+                    .collect();
+                names.sort();
+                names.dedup();
+                names.len()
+            };
+            usage.tasks = count(SymbolKind::Task);
+            usage.workflows = count(SymbolKind::Workflow);
+            usage
+        })
+        .collect()
+}
+
+/// Record `site` as a use of the structs `wdl_type` names, and as an
+/// indirect use of the structs reachable through their fields
+fn record<'a>(
+    usage: &mut BTreeMap<&'a str, StructUsage>,
+    fields: &BTreeMap<&'a str, &'a [InputInfo]>,
+    wdl_type: &'a WdlType,
+    site: &StructUse,
+) {
+    for named in wdl_type.struct_names() {
+        add(usage, named, site.clone());
+        let mut seen = vec![named];
+        let mut pending = vec![named];
+        while let Some(current) = pending.pop() {
+            let nested = fields
+                .get(current)
+                .into_iter()
+                .flat_map(|fields| fields.iter())
+                .filter_map(|field| field.wdl_type_parsed.as_ref())
+                .flat_map(|wdl_type| wdl_type.struct_names());
+            for reached in nested {
+                if seen.contains(&reached) {
+                    continue;
+                }
+                seen.push(reached);
+                pending.push(reached);
+                add(
+                    usage,
+                    reached,
+                    StructUse {
+                        through: Some(named.to_string()),
+                        ..site.clone()
+                    },
+                );
+            }
+        }
+    }
+}
+
+fn add<'a>(usage: &mut BTreeMap<&'a str, StructUsage>, name: &'a str, site: StructUse) {
+    let uses = &mut entry(usage, name).uses;
+    if !uses.contains(&site) {
+        uses.push(site);
+    }
+}
+
+fn entry<'a, 'b>(
+    usage: &'b mut BTreeMap<&'a str, StructUsage>,
+    name: &'a str,
+) -> &'b mut StructUsage {
+    usage.entry(name).or_insert_with(|| StructUsage {
+        name: name.to_string(),
+        defined_in: Vec::new(),
+        uses: Vec::new(),
+        tasks: 0,
+        workflows: 0,
+    })
+}
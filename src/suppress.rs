@@ -0,0 +1,96 @@
+use crate::lint::Finding;
+
+/// Comment turning rules off for the whole file
+pub const DISABLE: &str = "wdlparse-disable";
+/// Comment turning rules off for the next line of code
+pub const DISABLE_NEXT_LINE: &str = "wdlparse-disable-next-line";
+
+/// Rules named by a suppression comment; none named means every rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rules {
+    All,
+    Only(Vec<String>),
+}
+
+impl Rules {
+    pub fn covers(&self, rule: &str) -> bool {
+        match self {
+            Rules::All => true,
+            Rules::Only(rules) => rules.iter().any(|r| r == rule),
+        }
+    }
+}
+
+/// Suppression comments found in a WDL source file
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    pub file: Vec<Rules>,
+    /// 1-based line of code each `wdlparse-disable-next-line` applies to
+    pub lines: Vec<(usize, Rules)>,
+}
+
+impl Suppressions {
+    /// Collect `# wdlparse-disable [rule, ...]` and
+    /// `# wdlparse-disable-next-line [rule, ...]` comments; text after `--`
+    /// is a free-form reason
+    pub fn parse(source: &str) -> Self {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut suppressions = Self::default();
+        for (index, line) in lines.iter().enumerate() {
+            let Some((_, comment)) = line.split_once('#') else {
+                continue;
+            };
+            let comment = comment.trim_start();
+            if let Some(rules) = directive(comment, DISABLE_NEXT_LINE) {
+                // Blank lines and further comments between the directive and
+                // the code it refers to are skipped
+                let target = lines[index + 1..]
+                    .iter()
+                    .position(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+                    .map(|offset| index + offset + 2);
+                if let Some(target) = target {
+                    suppressions.lines.push((target, rules));
+                }
+            } else if let Some(rules) = directive(comment, DISABLE) {
+                suppressions.file.push(rules);
+            }
+        }
+        suppressions
+    }
+
+    pub fn suppresses(&self, finding: &Finding) -> bool {
+        self.file.iter().any(|rules| rules.covers(&finding.rule))
+            || finding.line.is_some_and(|line| {
+                self.lines
+                    .iter()
+                    .any(|(target, rules)| *target == line && rules.covers(&finding.rule))
+            })
+    }
+
+    /// Drop suppressed findings
+    pub fn apply(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        findings
+            .into_iter()
+            .filter(|finding| !self.suppresses(finding))
+            .collect()
+    }
+}
+
+/// Rules named after `name` at the start of a comment, if it's that directive
+fn directive(comment: &str, name: &str) -> Option<Rules> {
+    let rest = comment.strip_prefix(name)?;
+    if !(rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    let rest = rest.split("--").next().unwrap_or_default();
+    let rules: Vec<String> = rest
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|rule| !rule.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some(if rules.is_empty() {
+        Rules::All
+    } else {
+        Rules::Only(rules)
+    })
+}
@@ -0,0 +1,180 @@
+use anyhow::Result;
+
+/// What `wdlparse new` scaffolds
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NewKind {
+    /// A workflow calling stub tasks one after another
+    Workflow,
+    /// A single stub task
+    Task,
+}
+
+/// WDL version the generated files declare
+pub const WDL_VERSION: &str = "1.0";
+
+/// Container image the stub tasks run in until a real one is filled in
+const DEFAULT_IMAGE: &str = "ubuntu:22.04";
+
+/// Keywords and type names that cannot name a task or workflow
+const RESERVED: &[&str] = &[
+    "alias",
+    "Array",
+    "as",
+    "Boolean",
+    "call",
+    "command",
+    "Directory",
+    "else",
+    "env",
+    "false",
+    "File",
+    "Float",
+    "hints",
+    "if",
+    "import",
+    "in",
+    "input",
+    "Int",
+    "left",
+    "Map",
+    "meta",
+    "null",
+    "Object",
+    "object",
+    "output",
+    "Pair",
+    "parameter_meta",
+    "requirements",
+    "right",
+    "runtime",
+    "scatter",
+    "String",
+    "struct",
+    "task",
+    "then",
+    "true",
+    "version",
+    "workflow",
+];
+
+/// Fail unless `name` can name a task or workflow
+pub fn check_name(name: &str) -> Result<()> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        anyhow::bail!(
+            "'{}' is not a valid WDL name (a letter followed by letters, digits or underscores)",
+            name
+        );
+    }
+    if RESERVED.contains(&name) {
+        anyhow::bail!("'{}' is a WDL keyword and cannot be used as a name", name);
+    }
+    Ok(())
+}
+
+/// A WDL file with a workflow that calls `tasks` in order, each call taking
+/// the previous call's output file, and a stub for each task
+pub fn workflow_file(name: &str, tasks: &[String]) -> Result<String> {
+    check_name(name)?;
+    if tasks.is_empty() {
+        anyhow::bail!("A workflow needs at least one task (--tasks)");
+    }
+    for (index, task) in tasks.iter().enumerate() {
+        check_name(task)?;
+        if task == name {
+            anyhow::bail!("Task '{}' has the same name as the workflow", task);
+        }
+        if tasks[..index].contains(task) {
+            anyhow::bail!("Task '{}' is listed more than once", task);
+        }
+    }
+
+    let mut out = format!("version {}\n\n", WDL_VERSION);
+    out.push_str(&format!("workflow {} {{\n", name));
+    out.push_str("  meta {\n");
+    out.push_str("    description: \"TODO: what the workflow does\"\n");
+    out.push_str("    author: \"TODO\"\n");
+    out.push_str("  }\n\n");
+    out.push_str("  parameter_meta {\n");
+    out.push_str(&format!(
+        "    input_file: \"TODO: file passed to {}\"\n",
+        tasks[0]
+    ));
+    out.push_str("  }\n\n");
+    out.push_str("  input {\n    File input_file\n  }\n");
+
+    let mut previous = "input_file".to_string();
+    for task in tasks {
+        out.push_str(&format!(
+            "\n  call {} {{\n    input:\n      input_file = {}\n  }}\n",
+            task, previous
+        ));
+        previous = format!("{}.output_file", task);
+    }
+
+    out.push_str(&format!(
+        "\n  output {{\n    File output_file = {}\n  }}\n}}\n",
+        previous
+    ));
+    for task in tasks {
+        out.push('\n');
+        out.push_str(&task_definition(task));
+    }
+    Ok(out)
+}
+
+/// A WDL file with a single stub task
+pub fn task_file(name: &str) -> Result<String> {
+    check_name(name)?;
+    Ok(format!(
+        "version {}\n\n{}",
+        WDL_VERSION,
+        task_definition(name)
+    ))
+}
+
+/// A task with a placeholder command that copies its input to its output,
+/// runtime attributes exposed as inputs, and meta and parameter_meta stubs
+fn task_definition(name: &str) -> String {
+    format!(
+        r#"task {name} {{
+  meta {{
+    description: "TODO: what {name} does"
+  }}
+
+  parameter_meta {{
+    input_file: "TODO: describe the input"
+    docker: "Container image to run the command in"
+    cpu: "Number of CPUs"
+    memory_gb: "Memory in GB"
+  }}
+
+  input {{
+    File input_file
+    String docker = "{image}"
+    Int cpu = 1
+    Int memory_gb = 4
+  }}
+
+  command <<<
+    set -euo pipefail
+    # TODO: replace with the {name} command
+    cp "~{{input_file}}" {name}.out
+  >>>
+
+  output {{
+    File output_file = "{name}.out"
+  }}
+
+  runtime {{
+    docker: docker
+    cpu: cpu
+    memory: "~{{memory_gb}} GB"
+  }}
+}}
+"#,
+        name = name,
+        image = DEFAULT_IMAGE
+    )
+}
@@ -0,0 +1,70 @@
+use crate::info::{InputInfo, WorkflowInfo};
+use serde_json::{Map, Value};
+
+/// Name fragments of inputs that are usually shared by every row of a data
+/// table (reference genomes, indexes, resource bundles) and so belong in
+/// workspace attributes
+const WORKSPACE_HINTS: &[&str] = &[
+    "ref",
+    "reference",
+    "genome",
+    "dbsnp",
+    "known",
+    "resource",
+    "interval",
+    "dict",
+    "annotation",
+];
+
+/// A Terra/AnVIL method configuration for `workflow` run on rows of
+/// `entity` tables.
+///
+/// Inputs with defaults are left out so Terra uses the default, shared
+/// resources such as reference files map to `workspace.<name>`, and every
+/// other input and every output maps to the `this.<name>` column.
+pub fn method_config(workflow: &WorkflowInfo, entity: &str) -> Value {
+    let inputs: Map<String, Value> = workflow
+        .inputs
+        .iter()
+        .filter(|input| input.default_value.is_none())
+        .map(|input| {
+            let key = format!("{}.{}", workflow.name, input.name);
+            (key, Value::from(attribute(input)))
+        })
+        .collect();
+    let outputs: Map<String, Value> = workflow
+        .outputs
+        .iter()
+        .map(|output| {
+            let key = format!("{}.{}", workflow.name, output.name);
+            (key, Value::from(format!("this.{}", output.name)))
+        })
+        .collect();
+
+    serde_json::json!({
+        "name": workflow.name,
+        "namespace": null,
+        "rootEntityType": entity,
+        "methodRepoMethod": {
+            "sourceRepo": "dockstore",
+            "methodPath": null,
+            "methodVersion": null
+        },
+        "inputs": inputs,
+        "outputs": outputs,
+        "prerequisites": {},
+        "methodConfigVersion": 1,
+        "deleted": false
+    })
+}
+
+/// `workspace.<name>` for shared resources, otherwise `this.<name>`
+fn attribute(input: &InputInfo) -> String {
+    let lower = input.name.to_ascii_lowercase();
+    let shared = lower.split('_').any(|part| WORKSPACE_HINTS.contains(&part));
+    if shared {
+        format!("workspace.{}", input.name)
+    } else {
+        format!("this.{}", input.name)
+    }
+}
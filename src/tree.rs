@@ -0,0 +1,161 @@
+use crate::edit;
+use std::io::{self, Write};
+use std::ops::Range;
+use wdl_grammar::{SyntaxKind, SyntaxNode};
+
+/// Input size above which `--format tree` output gets very large
+pub const LARGE_INPUT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Write a syntax tree one element per line (`Kind@start..end`, tokens with
+/// their text), indented by depth. Output is streamed to `writer` as the tree
+/// is walked instead of being built up as a single string.
+pub fn write_tree<W: Write>(node: &SyntaxNode, writer: &mut W) -> io::Result<()> {
+    write_node(node, 0, writer)
+}
+
+fn write_node<W: Write>(node: &SyntaxNode, depth: usize, writer: &mut W) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{:indent$}{:?}@{:?}",
+        "",
+        node.kind(),
+        node.text_range(),
+        indent = depth * 2
+    )?;
+    for child in node.children_with_tokens() {
+        if let Some(child_node) = child.as_node() {
+            write_node(child_node, depth + 1, writer)?;
+        } else if let Some(token) = child.as_token() {
+            writeln!(
+                writer,
+                "{:indent$}{:?}@{:?} {:?}",
+                "",
+                token.kind(),
+                token.text_range(),
+                token.text(),
+                indent = (depth + 1) * 2
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// A node or token as [`walk`] visits it
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub kind: SyntaxKind,
+    /// Source text, for a node including all of its descendants
+    pub text: String,
+    /// Byte range in the source
+    pub range: Range<usize>,
+    /// 0 for the node the walk starts at
+    pub depth: usize,
+    pub is_token: bool,
+}
+
+/// Visit `node` and every node and token under it in source order. `visit`
+/// returns whether to go into a node's children; an error stops the walk.
+pub fn walk<E>(
+    node: &SyntaxNode,
+    visit: &mut dyn FnMut(&Element) -> Result<bool, E>,
+) -> Result<(), E> {
+    walk_node(node, 0, visit)
+}
+
+fn walk_node<E>(
+    node: &SyntaxNode,
+    depth: usize,
+    visit: &mut dyn FnMut(&Element) -> Result<bool, E>,
+) -> Result<(), E> {
+    let element = Element {
+        kind: node.kind(),
+        text: node.text().to_string(),
+        range: usize::from(node.text_range().start())..usize::from(node.text_range().end()),
+        depth,
+        is_token: false,
+    };
+    if !visit(&element)? {
+        return Ok(());
+    }
+    for child in node.children_with_tokens() {
+        if let Some(child_node) = child.as_node() {
+            walk_node(child_node, depth + 1, visit)?;
+        } else if let Some(token) = child.as_token() {
+            visit(&Element {
+                kind: token.kind(),
+                text: token.text().to_string(),
+                range: edit::token_range(token),
+                depth: depth + 1,
+                is_token: true,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// A writer that accepts at most `limit` bytes and then fails with
+/// [`io::ErrorKind::WriteZero`], so producers stop early
+pub struct LimitedWriter<W: Write> {
+    inner: W,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl<W: Write> LimitedWriter<W> {
+    pub fn new(inner: W, limit: usize) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+            truncated: false,
+        }
+    }
+
+    /// Whether output was cut off at the limit
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for LimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            self.truncated = true;
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "output byte limit reached",
+            ));
+        }
+        let len = buf.len().min(self.remaining);
+        let written = self.inner.write(&buf[..len])?;
+        self.remaining -= written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write a tree, stopping after `max_bytes` if given. Returns whether the
+/// output was truncated.
+pub fn write_tree_limited<W: Write>(
+    node: &SyntaxNode,
+    writer: &mut W,
+    max_bytes: Option<usize>,
+) -> io::Result<bool> {
+    let Some(max_bytes) = max_bytes else {
+        write_tree(node, writer)?;
+        return Ok(false);
+    };
+
+    let mut limited = LimitedWriter::new(&mut *writer, max_bytes);
+    match write_tree(node, &mut limited) {
+        Ok(()) => Ok(false),
+        Err(_) if limited.truncated() => Ok(true),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Built-in WDL types that take no parameters
+pub const PRIMITIVE_TYPES: &[&str] = &[
+    "Boolean",
+    "Int",
+    "Float",
+    "String",
+    "File",
+    "Directory",
+    "Object",
+];
+
+/// Primitive types whose values are paths the engine stages for a task
+pub const PATH_TYPES: &[&str] = &["File", "Directory"];
+
+/// A parsed WDL type
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WdlType {
+    /// `Int`, `String`, `File`, ... (`Object` is included here)
+    Primitive { name: String },
+    /// `Array[T]`, or `Array[T]+` when `nonempty`
+    Array { item: Box<WdlType>, nonempty: bool },
+    Map {
+        key: Box<WdlType>,
+        value: Box<WdlType>,
+    },
+    Pair {
+        left: Box<WdlType>,
+        right: Box<WdlType>,
+    },
+    /// `T?`
+    Optional { inner: Box<WdlType> },
+    /// A struct (or other user-defined) type referenced by name
+    Ref { name: String },
+}
+
+impl WdlType {
+    /// Parse type text such as `Map[String?, Array[File]+]?`; `None` if malformed
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parser = Parser {
+            chars: text.chars().filter(|c| !c.is_whitespace()).collect(),
+            pos: 0,
+        };
+        let parsed = parser.parse_type()?;
+        (parser.pos == parser.chars.len()).then_some(parsed)
+    }
+
+    /// Whether the outermost type is optional (`Map[String?, Int]` is not)
+    pub fn is_optional(&self) -> bool {
+        matches!(self, WdlType::Optional { .. })
+    }
+
+    /// The type without an outer `?`
+    pub fn required(&self) -> &WdlType {
+        match self {
+            WdlType::Optional { inner } => inner,
+            other => other,
+        }
+    }
+
+    /// Whether the type is or contains the primitive `name`, e.g. `File` in `Array[File]?`
+    pub fn contains(&self, name: &str) -> bool {
+        match self {
+            WdlType::Primitive { name: primitive } => primitive == name,
+            WdlType::Array { item, .. } => item.contains(name),
+            WdlType::Map { key, value } => key.contains(name) || value.contains(name),
+            WdlType::Pair { left, right } => left.contains(name) || right.contains(name),
+            WdlType::Optional { inner } => inner.contains(name),
+            WdlType::Ref { .. } => false,
+        }
+    }
+
+    /// The path types (`File`, `Directory`) this type is or contains, in
+    /// that order
+    pub fn path_types(&self) -> Vec<&'static str> {
+        PATH_TYPES
+            .iter()
+            .copied()
+            .filter(|name| self.contains(name))
+            .collect()
+    }
+
+    /// Whether the type holds a number of values only known at runtime: it
+    /// is or contains an `Array` or `Map`
+    pub fn is_collection(&self) -> bool {
+        match self {
+            WdlType::Primitive { .. } | WdlType::Ref { .. } => false,
+            WdlType::Array { .. } | WdlType::Map { .. } => true,
+            WdlType::Pair { left, right } => left.is_collection() || right.is_collection(),
+            WdlType::Optional { inner } => inner.is_collection(),
+        }
+    }
+
+    /// Names of the structs this type refers to, outermost first
+    pub fn struct_names(&self) -> Vec<&str> {
+        match self {
+            WdlType::Primitive { .. } => Vec::new(),
+            WdlType::Array { item, .. } => item.struct_names(),
+            WdlType::Map { key, value } => {
+                let mut names = key.struct_names();
+                names.extend(value.struct_names());
+                names
+            }
+            WdlType::Pair { left, right } => {
+                let mut names = left.struct_names();
+                names.extend(right.struct_names());
+                names
+            }
+            WdlType::Optional { inner } => inner.struct_names(),
+            WdlType::Ref { name } => vec![name.as_str()],
+        }
+    }
+
+    /// Whether a value of this type can be bound where `target` is expected.
+    ///
+    /// Follows the WDL coercion rules: `T` to `T?`, `Int` to `Float`,
+    /// `String` to and from `File`/`Directory`, and element-wise for compound
+    /// types. Array emptiness is only known at runtime, so `Array[T]` may bind
+    /// to `Array[T]+`.
+    pub fn is_coercible_to(&self, target: &WdlType) -> bool {
+        match (self, target) {
+            (WdlType::Optional { inner }, WdlType::Optional { inner: target }) => {
+                inner.is_coercible_to(target)
+            }
+            (WdlType::Optional { .. }, _) => false,
+            (source, WdlType::Optional { inner }) => source.is_coercible_to(inner),
+            (WdlType::Primitive { name }, WdlType::Primitive { name: target }) => {
+                name == target
+                    || matches!(
+                        (name.as_str(), target.as_str()),
+                        ("Int", "Float")
+                            | ("String", "File" | "Directory")
+                            | ("File" | "Directory", "String")
+                    )
+            }
+            (WdlType::Array { item, .. }, WdlType::Array { item: target, .. }) => {
+                item.is_coercible_to(target)
+            }
+            (
+                WdlType::Map { key, value },
+                WdlType::Map {
+                    key: target_key,
+                    value: target_value,
+                },
+            ) => key.is_coercible_to(target_key) && value.is_coercible_to(target_value),
+            (
+                WdlType::Pair { left, right },
+                WdlType::Pair {
+                    left: target_left,
+                    right: target_right,
+                },
+            ) => left.is_coercible_to(target_left) && right.is_coercible_to(target_right),
+            (WdlType::Ref { name }, WdlType::Ref { name: target }) => name == target,
+            // Objects and maps can initialize structs
+            (WdlType::Primitive { name }, WdlType::Ref { .. }) => name == "Object",
+            (WdlType::Map { .. }, WdlType::Ref { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for WdlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WdlType::Primitive { name } | WdlType::Ref { name } => write!(f, "{}", name),
+            WdlType::Array { item, nonempty } => {
+                write!(f, "Array[{}]{}", item, if *nonempty { "+" } else { "" })
+            }
+            WdlType::Map { key, value } => write!(f, "Map[{}, {}]", key, value),
+            WdlType::Pair { left, right } => write!(f, "Pair[{}, {}]", left, right),
+            WdlType::Optional { inner } => write!(f, "{}?", inner),
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse_type(&mut self) -> Option<WdlType> {
+        let name = self.ident()?;
+        let mut parsed = match name.as_str() {
+            "Array" => {
+                let mut params = self.params(1)?;
+                let item = Box::new(params.remove(0));
+                let nonempty = self.eat('+');
+                WdlType::Array { item, nonempty }
+            }
+            "Map" => {
+                let mut params = self.params(2)?;
+                let value = Box::new(params.remove(1));
+                let key = Box::new(params.remove(0));
+                WdlType::Map { key, value }
+            }
+            "Pair" => {
+                let mut params = self.params(2)?;
+                let right = Box::new(params.remove(1));
+                let left = Box::new(params.remove(0));
+                WdlType::Pair { left, right }
+            }
+            _ if PRIMITIVE_TYPES.contains(&name.as_str()) => WdlType::Primitive { name },
+            _ => WdlType::Ref { name },
+        };
+
+        if self.eat('?') {
+            parsed = WdlType::Optional {
+                inner: Box::new(parsed),
+            };
+        }
+        Some(parsed)
+    }
+
+    /// `[T]` or `[T, U]` with exactly `count` parameters
+    fn params(&mut self, count: usize) -> Option<Vec<WdlType>> {
+        if !self.eat('[') {
+            return None;
+        }
+        let mut params = vec![self.parse_type()?];
+        while self.eat(',') {
+            params.push(self.parse_type()?);
+        }
+        (self.eat(']') && params.len() == count).then_some(params)
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        let start = self.pos;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| self.chars[start..self.pos].iter().collect())
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.chars.get(self.pos) == Some(&expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
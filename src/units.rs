@@ -0,0 +1,125 @@
+use crate::numbers;
+use std::fmt;
+use std::str::FromStr;
+
+/// A unit of memory or disk size. As in WDL, `KB` to `TB` are powers of 1000
+/// and `KiB` to `TiB` powers of 1024.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    B,
+    KB,
+    MB,
+    GB,
+    TB,
+    KiB,
+    MiB,
+    GiB,
+    TiB,
+}
+
+impl SizeUnit {
+    pub const DECIMAL: [SizeUnit; 5] = [
+        SizeUnit::B,
+        SizeUnit::KB,
+        SizeUnit::MB,
+        SizeUnit::GB,
+        SizeUnit::TB,
+    ];
+    pub const BINARY: [SizeUnit; 5] = [
+        SizeUnit::B,
+        SizeUnit::KiB,
+        SizeUnit::MiB,
+        SizeUnit::GiB,
+        SizeUnit::TiB,
+    ];
+
+    pub fn bytes(self) -> u64 {
+        match self {
+            SizeUnit::B => 1,
+            SizeUnit::KB => 1_000,
+            SizeUnit::MB => 1_000_000,
+            SizeUnit::GB => 1_000_000_000,
+            SizeUnit::TB => 1_000_000_000_000,
+            SizeUnit::KiB => 1 << 10,
+            SizeUnit::MiB => 1 << 20,
+            SizeUnit::GiB => 1 << 30,
+            SizeUnit::TiB => 1 << 40,
+        }
+    }
+
+    /// `bytes` expressed in this unit
+    pub fn convert(self, bytes: u64) -> f64 {
+        bytes as f64 / self.bytes() as f64
+    }
+}
+
+impl FromStr for SizeUnit {
+    type Err = String;
+
+    /// Case-insensitive; `G` means `GB` and `Gi` means `GiB`
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "b" => Ok(SizeUnit::B),
+            "k" | "kb" => Ok(SizeUnit::KB),
+            "m" | "mb" => Ok(SizeUnit::MB),
+            "g" | "gb" => Ok(SizeUnit::GB),
+            "t" | "tb" => Ok(SizeUnit::TB),
+            "ki" | "kib" => Ok(SizeUnit::KiB),
+            "mi" | "mib" => Ok(SizeUnit::MiB),
+            "gi" | "gib" => Ok(SizeUnit::GiB),
+            "ti" | "tib" => Ok(SizeUnit::TiB),
+            _ => Err(format!("unknown size unit '{}'", unit.trim())),
+        }
+    }
+}
+
+impl fmt::Display for SizeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SizeUnit::B => "B",
+            SizeUnit::KB => "KB",
+            SizeUnit::MB => "MB",
+            SizeUnit::GB => "GB",
+            SizeUnit::TB => "TB",
+            SizeUnit::KiB => "KiB",
+            SizeUnit::MiB => "MiB",
+            SizeUnit::GiB => "GiB",
+            SizeUnit::TiB => "TiB",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Bytes in a size such as `"8GB"`, `"512 MiB"`, `"4G"` or `"1.5 TiB"`
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (amount, unit) = text.split_at(split);
+    let amount: f64 = amount.parse().ok()?;
+    to_bytes(amount, unit.parse().ok()?)
+}
+
+/// Bytes in `amount` of `unit`; `None` for negative or non-finite amounts
+pub fn to_bytes(amount: f64, unit: SizeUnit) -> Option<u64> {
+    let bytes = amount * unit.bytes() as f64;
+    (bytes.is_finite() && bytes >= 0.0).then_some(bytes.round() as u64)
+}
+
+/// `bytes` in the largest unit of `units` that keeps the amount at least 1,
+/// with up to two decimals: `format_size(4 << 30, &SizeUnit::BINARY)` is `4 GiB`
+pub fn format_size(bytes: u64, units: &[SizeUnit]) -> String {
+    let unit = units
+        .iter()
+        .copied()
+        .filter(|unit| unit.bytes() <= bytes.max(1))
+        .max_by_key(|unit| unit.bytes())
+        .unwrap_or(SizeUnit::B);
+    format_in(bytes, unit)
+}
+
+/// `bytes` in `unit` with up to two decimals, e.g. `1.5 GiB`
+pub fn format_in(bytes: u64, unit: SizeUnit) -> String {
+    format!("{} {}", numbers::number(unit.convert(bytes)), unit)
+}
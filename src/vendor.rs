@@ -0,0 +1,245 @@
+use crate::commands::extract_semantic_info;
+use crate::info::SortBy;
+use crate::network::{BlockedRequest, NetworkAccess, RequestKind};
+use crate::rewrite::{self, RewriteRules};
+use crate::source::SourceText;
+use crate::workspace::{is_remote_uri, resolve_import};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use wdl_grammar::SyntaxTree;
+
+/// Name of the manifest written into the vendor directory
+pub const MANIFEST_FILE: &str = "vendor.json";
+
+/// A downloaded import and where it was written
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VendoredImport {
+    pub url: String,
+    /// Path relative to the vendor directory
+    pub path: PathBuf,
+}
+
+/// A local file whose remote imports were pointed at the vendored copies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RewrittenFile {
+    pub file: PathBuf,
+    pub rewrites: Vec<rewrite::ImportRewrite>,
+}
+
+/// What `vendor` downloaded and rewrote
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct VendorReport {
+    pub downloaded: Vec<VendoredImport>,
+    pub rewritten: Vec<RewrittenFile>,
+    /// Downloads refused because the network is disabled; when there are
+    /// any, nothing is written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked: Vec<BlockedRequest>,
+}
+
+/// Download every remote import reachable from `root` (through local imports
+/// and through the downloaded files themselves) into `dest`, and rewrite the
+/// import statements to relative paths. Files are laid out as
+/// `dest/<host>/<url path>`, so relative imports between files of the same
+/// host keep working unchanged. `fetch` returns the text at a URL.
+///
+/// Everything is downloaded before anything is written, so a failed
+/// download leaves the files as they were.
+pub fn vendor(
+    root: &Path,
+    dest: &Path,
+    fetch: impl Fn(&str) -> Result<String>,
+) -> Result<VendorReport> {
+    let mut report = VendorReport::default();
+
+    // Local files: find their remote imports and follow their local ones
+    let mut remote: Vec<String> = Vec::new();
+    let mut local_files = Vec::new();
+    let mut local_seen = BTreeSet::new();
+    let mut local = vec![fs::canonicalize(root)
+        .with_context(|| format!("File does not exist: {}", root.display()))?];
+    while let Some(file) = local.pop() {
+        if !local_seen.insert(file.clone()) {
+            continue;
+        }
+        let source = SourceText::read(&file)?;
+        let dir = file.parent().unwrap_or_else(|| Path::new(""));
+        let mut remote_imports = Vec::new();
+        for uri in import_uris(&source.text) {
+            if is_remote_uri(&uri) {
+                remote_imports.push(uri);
+            } else {
+                let path = resolve_import(dir, uri.strip_prefix("file://").unwrap_or(&uri), &[]);
+                if let Ok(path) = fs::canonicalize(path) {
+                    local.push(path);
+                }
+            }
+        }
+        if !remote_imports.is_empty() {
+            remote.extend(remote_imports.iter().cloned());
+            local_files.push((file, source, remote_imports));
+        }
+    }
+
+    // Remote files, following their imports
+    let mut downloaded: BTreeMap<String, String> = BTreeMap::new();
+    let mut failed = BTreeSet::new();
+    while let Some(url) = remote.pop() {
+        if downloaded.contains_key(&url) || failed.contains(&url) {
+            continue;
+        }
+        let text = match fetch(&url) {
+            Ok(text) => text,
+            Err(e) => match e.downcast::<BlockedRequest>() {
+                Ok(blocked) => {
+                    report.blocked.push(blocked);
+                    failed.insert(url);
+                    continue;
+                }
+                Err(e) => return Err(e.context(format!("Failed to download {}", url))),
+            },
+        };
+        for uri in import_uris(&text) {
+            if is_remote_uri(&uri) {
+                remote.push(uri);
+            } else {
+                remote.push(join_url(&url, &uri));
+            }
+        }
+        downloaded.insert(url, text);
+    }
+    if !report.blocked.is_empty() {
+        return Ok(report);
+    }
+
+    fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
+    let dest = fs::canonicalize(dest)?;
+    for (url, text) in &downloaded {
+        let relative = local_path(url);
+        let path = dest.join(&relative);
+        let dir = path.parent().unwrap_or(dest.as_path());
+        let rules = vendored_paths(dir, &dest, import_uris(text));
+        let text = rewrite::rewrite_imports(text, &rules)?.source;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        fs::write(&path, text)
+            .with_context(|| format!("Failed to write file: {}", path.display()))?;
+        tracing::info!(url = %url, path = %path.display(), "vendored import");
+        report.downloaded.push(VendoredImport {
+            url: url.clone(),
+            path: relative,
+        });
+    }
+
+    for (file, source, remote_imports) in local_files {
+        let dir = file.parent().unwrap_or_else(|| Path::new(""));
+        let rules = vendored_paths(dir, &dest, remote_imports);
+        let rewritten = rewrite::rewrite_imports(&source.text, &rules)?;
+        fs::write(&file, source.flavor.encode(&rewritten.source))
+            .with_context(|| format!("Failed to write file: {}", file.display()))?;
+        report.rewritten.push(RewrittenFile {
+            file,
+            rewrites: rewritten.rewrites,
+        });
+    }
+
+    let manifest = serde_json::json!({ "imports": report.downloaded });
+    let manifest_path = dest.join(MANIFEST_FILE);
+    fs::write(
+        &manifest_path,
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )
+    .with_context(|| format!("Failed to write file: {}", manifest_path.display()))?;
+    Ok(report)
+}
+
+/// Download a remote file as normalized text
+pub fn fetch_url(url: &str, network: NetworkAccess) -> Result<String> {
+    network.check(RequestKind::RemoteImport, url)?;
+    let response = ureq::get(url).call()?;
+    let mut bytes = Vec::new();
+    response.into_reader().read_to_end(&mut bytes)?;
+    Ok(SourceText::decode(&bytes)?.text)
+}
+
+/// Rules pointing the remote `uris` at their vendored copies, as paths
+/// relative to `dir`
+fn vendored_paths(dir: &Path, dest: &Path, uris: Vec<String>) -> RewriteRules {
+    uris.into_iter()
+        .filter(|uri| is_remote_uri(uri))
+        .map(|uri| {
+            let path = relative_path(dir, &dest.join(local_path(&uri)));
+            (uri, path)
+        })
+        .collect()
+}
+
+fn import_uris(source: &str) -> Vec<String> {
+    let (tree, _) = SyntaxTree::parse(source);
+    extract_semantic_info(&tree.root(), SortBy::Position)
+        .imports
+        .into_iter()
+        .map(|import| import.uri)
+        .collect()
+}
+
+/// Where a URL is vendored, relative to the vendor directory:
+/// `<host>/<path>`, without the query string and with `..` segments dropped
+fn local_path(url: &str) -> PathBuf {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let mut path = PathBuf::new();
+    for (index, segment) in rest.split('/').enumerate() {
+        if segment.is_empty() || segment == "." || segment == ".." {
+            continue;
+        }
+        // Ports use `:`, which isn't allowed in Windows file names
+        if index == 0 {
+            path.push(segment.replace(':', "_"));
+        } else {
+            path.push(segment);
+        }
+    }
+    path
+}
+
+/// A relative import inside the file at `base` as an absolute URL
+fn join_url(base: &str, relative: &str) -> String {
+    let relative = relative.strip_prefix("file://").unwrap_or(relative);
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let mut segments: Vec<&str> = rest.split('/').collect();
+    segments.pop();
+    for segment in relative.split('/') {
+        match segment {
+            "" | "." => {}
+            // Never above the host
+            ".." if segments.len() > 1 => {
+                segments.pop();
+            }
+            ".." => {}
+            _ => segments.push(segment),
+        }
+    }
+    format!("{}://{}", scheme, segments.join("/"))
+}
+
+/// `to` relative to the directory `from`, with `/` separators as WDL imports
+/// use; both must be absolute
+pub fn relative_path(from: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.join("/")
+}
@@ -0,0 +1,159 @@
+use crate::edit::{self, SourceEdit};
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use wdl_grammar::{SyntaxKind, SyntaxTree};
+
+/// Version inserted when a file has none
+pub const DEFAULT_TARGET: &str = "1.0";
+
+/// What `fix` did to a file's version statement
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChange {
+    /// Already the first statement of the file
+    Unchanged,
+    /// The file had no version statement
+    Inserted,
+    /// The statement came after other definitions
+    Moved,
+}
+
+/// A file's version statement after `fix`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VersionFix {
+    pub change: VersionChange,
+    pub version: String,
+    #[serde(skip)]
+    pub source: String,
+}
+
+/// Put a version statement at the head of `source`: after the file's header
+/// comments, before any definitions. Files without one get `version
+/// <target>`; an existing statement keeps its version and is only moved.
+pub fn fix(source: &str, target: &str) -> Result<VersionFix> {
+    let head = head_offset(source);
+    let Some((range, version)) = find_statement(source) else {
+        let text = if head == 0 {
+            format!("version {}\n\n", target)
+        } else {
+            format!("\nversion {}\n", target)
+        };
+        return Ok(VersionFix {
+            change: VersionChange::Inserted,
+            version: target.to_string(),
+            source: edit::apply(source, &[SourceEdit::insert(head, text)])?,
+        });
+    };
+
+    let first_code = first_code_line(source);
+    if first_code.is_none_or(|start| start >= range.start) {
+        return Ok(VersionFix {
+            change: VersionChange::Unchanged,
+            version,
+            source: source.to_string(),
+        });
+    }
+
+    let text = if head == 0 {
+        format!("version {}\n\n", version)
+    } else {
+        format!("\nversion {}\n", version)
+    };
+    let edits = [
+        SourceEdit::insert(head, text),
+        SourceEdit::delete(statement_lines(source, range)),
+    ];
+    Ok(VersionFix {
+        change: VersionChange::Moved,
+        version,
+        source: edit::apply(source, &edits)?,
+    })
+}
+
+/// Range and version of the version statement. The parser only recognizes
+/// one at the start of a file, so a misplaced statement is found by its line.
+fn find_statement(source: &str) -> Option<(Range<usize>, String)> {
+    let (tree, _) = SyntaxTree::parse(source);
+    let node = tree
+        .root()
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::VersionStatementNode);
+    if let Some(node) = node {
+        let version = node
+            .children_with_tokens()
+            .filter_map(|element| element.into_token())
+            .find(|token| token.kind() == SyntaxKind::Version)
+            .map(|token| token.text().to_string());
+        if let Some(version) = version {
+            return Some((edit::node_range(&node), version));
+        }
+    }
+
+    let statement =
+        Regex::new(r"(?m)^[ \t]*version[ \t]+([^\s#]+)[ \t]*(?:#.*)?$").expect("valid regex");
+    let captures = statement.captures(source)?;
+    let line = captures.get(0)?;
+    Some((line.range(), captures[1].to_string()))
+}
+
+/// Offset of the first line that isn't blank or a comment
+fn first_code_line(source: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Where a version statement belongs: after the last leading comment block
+/// that a blank line separates from what follows. A comment directly above
+/// the first definition documents that definition, so it stays attached.
+fn head_offset(source: &str) -> usize {
+    let mut head = 0;
+    let mut offset = 0;
+    let mut after_comment = false;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if after_comment {
+                head = offset;
+            }
+            after_comment = false;
+        } else if trimmed.starts_with('#') {
+            after_comment = true;
+        } else {
+            break;
+        }
+        offset += line.len();
+    }
+    head
+}
+
+/// The lines holding `range`, plus the blank line separating them from the
+/// rest when there is one on both sides (or before them at the end of the
+/// file), so removing the statement doesn't leave a double gap
+fn statement_lines(source: &str, range: Range<usize>) -> Range<usize> {
+    let mut start = source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let mut end = source[range.end..]
+        .find('\n')
+        .map_or(source.len(), |i| range.end + i + 1);
+    let previous = source[..start]
+        .strip_suffix('\n')
+        .map(|before| before.rsplit('\n').next().unwrap_or(before))
+        .filter(|line| line.trim().is_empty());
+    let Some(previous) = previous else {
+        return start..end;
+    };
+    match source[end..].split_inclusive('\n').next() {
+        Some(next) if next.trim().is_empty() => end += next.len(),
+        None => start -= previous.len() + 1,
+        Some(_) => {}
+    }
+    start..end
+}
@@ -0,0 +1,131 @@
+use crate::expr;
+use crate::info::{CallInfo, WdlInfo, WorkflowInfo};
+use serde::{Deserialize, Serialize};
+
+/// Where the value bound to a call input comes from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    WorkflowInput,
+    CallOutput,
+    Declaration,
+    ScatterVariable,
+    Literal,
+    /// An expression combining several values
+    Expression,
+    Unresolved,
+}
+
+/// A bound call input and the source of its value
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Binding {
+    pub input: String,
+    pub value: String,
+    pub source: SourceKind,
+    /// Names referenced by the value expression
+    pub references: Vec<String>,
+}
+
+/// A task input left unbound by a call
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnboundInput {
+    pub name: String,
+    pub wdl_type: String,
+    /// Default expression used when the input is not supplied
+    pub default_value: Option<String>,
+    /// Whether the input must be supplied by the caller (no default, not optional)
+    pub required: bool,
+}
+
+/// Input wiring of a single call
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CallWiring {
+    pub workflow: String,
+    pub call: String,
+    pub target: String,
+    /// Whether the target task was found in this file
+    pub resolved: bool,
+    pub bindings: Vec<Binding>,
+    pub unbound: Vec<UnboundInput>,
+}
+
+/// Compute the input wiring of every call in every workflow
+pub fn call_wiring(info: &WdlInfo) -> Vec<CallWiring> {
+    info.workflows
+        .iter()
+        .flat_map(|workflow| {
+            workflow
+                .calls
+                .iter()
+                .map(move |call| wire_call(info, workflow, call))
+        })
+        .collect()
+}
+
+fn wire_call(info: &WdlInfo, workflow: &WorkflowInfo, call: &CallInfo) -> CallWiring {
+    let task = info.tasks.iter().find(|t| t.name == call.target);
+
+    let bindings = call
+        .inputs
+        .iter()
+        .map(|input| {
+            let references = expr::references(&input.value);
+            Binding {
+                input: input.name.clone(),
+                value: input.value.clone(),
+                source: classify(workflow, &input.value, &references),
+                references,
+            }
+        })
+        .collect();
+
+    let unbound = task
+        .map(|task| {
+            task.inputs
+                .iter()
+                .filter(|input| !call.inputs.iter().any(|b| b.name == input.name))
+                .map(|input| UnboundInput {
+                    name: input.name.clone(),
+                    wdl_type: input.wdl_type.clone(),
+                    default_value: input.default_value.clone(),
+                    required: input.default_value.is_none() && !input.optional,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    CallWiring {
+        workflow: workflow.name.clone(),
+        call: call.name.clone(),
+        target: call.target.clone(),
+        resolved: task.is_some(),
+        bindings,
+        unbound,
+    }
+}
+
+fn classify(workflow: &WorkflowInfo, value: &str, references: &[String]) -> SourceKind {
+    let [reference] = references else {
+        return if references.is_empty() {
+            SourceKind::Literal
+        } else {
+            SourceKind::Expression
+        };
+    };
+    if reference != value.trim() {
+        return SourceKind::Expression;
+    }
+
+    let root = reference.split('.').next().unwrap_or_default();
+    if workflow.calls.iter().any(|c| c.name == root) {
+        SourceKind::CallOutput
+    } else if workflow.inputs.iter().any(|i| i.name == root) {
+        SourceKind::WorkflowInput
+    } else if workflow.declarations.iter().any(|d| d.name == root) {
+        SourceKind::Declaration
+    } else if workflow.scatters.iter().any(|s| s.variable == root) {
+        SourceKind::ScatterVariable
+    } else {
+        SourceKind::Unresolved
+    }
+}
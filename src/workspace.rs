@@ -0,0 +1,339 @@
+use crate::commands::extract_semantic_info;
+use crate::edit::{self, SourceEdit};
+use crate::info::{SortBy, WdlInfo};
+use crate::rewrite::{self, RewriteRules};
+use crate::source::{SourceFlavor, SourceText};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use wdl_grammar::{Diagnostic, SyntaxTree};
+
+/// A replacement of a byte range of a document's text
+pub type TextEdit = SourceEdit;
+
+/// A parsed WDL file held by a [`Workspace`]
+#[derive(Debug)]
+pub struct Document {
+    pub path: PathBuf,
+    /// Text with any byte order mark removed and `\n` line endings
+    pub content: String,
+    /// How the file was stored on disk, for writing it back
+    pub flavor: SourceFlavor,
+    pub tree: SyntaxTree,
+    pub diagnostics: Vec<Diagnostic>,
+    pub info: WdlInfo,
+}
+
+impl Document {
+    /// Parse a document from in-memory content, used as given
+    pub fn from_content(path: PathBuf, content: String) -> Self {
+        let (tree, diagnostics) = SyntaxTree::parse(&content);
+        let info = extract_semantic_info(&tree.root(), SortBy::Position);
+        Self {
+            path,
+            content,
+            flavor: SourceFlavor::default(),
+            tree,
+            diagnostics,
+            info,
+        }
+    }
+
+    /// Read and parse a document from disk, normalizing a byte order mark
+    /// and CRLF line endings
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = SourceText::read(path)?;
+        let mut document = Self::from_content(path.to_path_buf(), source.text);
+        document.flavor = source.flavor;
+        Ok(document)
+    }
+
+    /// Apply text edits and reparse the document.
+    ///
+    /// Edit ranges refer to the text before any of the edits are applied and
+    /// must not overlap. wdl-grammar has no incremental parser, so the whole
+    /// document is reparsed; unchanged content is left untouched and an edit
+    /// set that leaves the text identical skips reparsing entirely.
+    pub fn apply_edits(&mut self, edits: &[TextEdit]) -> Result<()> {
+        let content = edit::apply(&self.content, edits)
+            .with_context(|| format!("Invalid edits for {}", self.path.display()))?;
+
+        if content != self.content {
+            let flavor = self.flavor;
+            *self = Self::from_content(self.path.clone(), content);
+            self.flavor = flavor;
+        }
+        Ok(())
+    }
+
+    /// Local files imported by this document, paired with their import namespace.
+    /// Remote (`http://`, `https://`) imports are not included.
+    pub fn local_imports(&self) -> Vec<(String, PathBuf)> {
+        self.resolve_imports(&ImportOptions::default())
+    }
+
+    /// Like [`Document::local_imports`], with URIs rewritten by
+    /// `options.rewrites` first (so a rewrite can point a remote import at a
+    /// local mirror) and missing files looked up in `options.dirs` (see
+    /// [`resolve_import`]). Namespaces still come from the URIs as written.
+    pub fn resolve_imports(&self, options: &ImportOptions) -> Vec<(String, PathBuf)> {
+        let base = self.path.parent().unwrap_or_else(|| Path::new(""));
+        self.info
+            .imports
+            .iter()
+            .filter_map(|import| {
+                let uri = rewrite::rewrite_uri(&import.uri, &options.rewrites)
+                    .unwrap_or_else(|| import.uri.clone());
+                if is_remote_uri(&uri) {
+                    return None;
+                }
+                let path = uri.strip_prefix("file://").unwrap_or(&uri);
+                Some((
+                    import_namespace(&import.uri, import.alias.as_deref()),
+                    resolve_import(base, path, &options.dirs),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// How a [`Workspace`] resolves imports beyond paths relative to the importing file
+#[derive(Debug, Default, Clone)]
+pub struct ImportOptions {
+    /// Extra directories searched for imports (`--import-dir`)
+    pub dirs: Vec<PathBuf>,
+    /// URI prefix rewrites applied before resolving
+    pub rewrites: RewriteRules,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Task,
+    Workflow,
+    Struct,
+}
+
+/// A named definition found in a workspace document
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// A root WDL document together with every local file it (transitively) imports.
+///
+/// Documents are parsed once and cached by path; [`Workspace::invalidate`]
+/// reparses a single file and loads any imports it newly references.
+#[derive(Debug)]
+pub struct Workspace {
+    root: PathBuf,
+    imports: ImportOptions,
+    documents: BTreeMap<PathBuf, Document>,
+    /// Imports that could not be loaded, with the error message
+    errors: BTreeMap<PathBuf, String>,
+}
+
+impl Workspace {
+    /// Load a root document and all local imports reachable from it
+    pub fn load(root: impl AsRef<Path>) -> Result<Self> {
+        Self::load_with_options(root, ImportOptions::default())
+    }
+
+    /// Load a root document, resolving imports that aren't found next to the
+    /// importing file against `import_dirs`, in order
+    pub fn load_with_import_dirs(
+        root: impl AsRef<Path>,
+        import_dirs: Vec<PathBuf>,
+    ) -> Result<Self> {
+        let options = ImportOptions {
+            dirs: import_dirs,
+            ..ImportOptions::default()
+        };
+        Self::load_with_options(root, options)
+    }
+
+    /// Load a root document, resolving imports with `imports`
+    pub fn load_with_options(root: impl AsRef<Path>, imports: ImportOptions) -> Result<Self> {
+        let root = normalize_path(root.as_ref());
+        let mut workspace = Self {
+            root: root.clone(),
+            imports,
+            documents: BTreeMap::new(),
+            errors: BTreeMap::new(),
+        };
+        let document = Document::load(&root)?;
+        workspace.insert(document);
+        Ok(workspace)
+    }
+
+    /// The root document
+    pub fn root(&self) -> &Document {
+        &self.documents[&self.root]
+    }
+
+    /// Look up a loaded document by path
+    pub fn document(&self, path: impl AsRef<Path>) -> Option<&Document> {
+        self.documents.get(&normalize_path(path.as_ref()))
+    }
+
+    /// Local imports of a loaded document, resolved the way the workspace loads them
+    pub fn imports(&self, document: &Document) -> Vec<(String, PathBuf)> {
+        document.resolve_imports(&self.imports)
+    }
+
+    /// All loaded documents, ordered by path
+    pub fn documents(&self) -> impl Iterator<Item = &Document> {
+        self.documents.values()
+    }
+
+    /// Imports that failed to load, keyed by the path that was tried
+    pub fn errors(&self) -> &BTreeMap<PathBuf, String> {
+        &self.errors
+    }
+
+    /// Reparse a document from disk after it changed
+    pub fn invalidate(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = normalize_path(path.as_ref());
+        let document = Document::load(&path)?;
+        self.insert(document);
+        Ok(())
+    }
+
+    /// Replace a document's content without reading from disk (e.g. unsaved editor buffers)
+    pub fn update(&mut self, path: impl AsRef<Path>, content: String) {
+        let path = normalize_path(path.as_ref());
+        self.insert(Document::from_content(path, content));
+    }
+
+    /// Apply text edits to a loaded document, reparse it and load any new imports
+    pub fn apply_edits(&mut self, path: impl AsRef<Path>, edits: &[TextEdit]) -> Result<()> {
+        let path = normalize_path(path.as_ref());
+        let mut document = self
+            .documents
+            .remove(&path)
+            .with_context(|| format!("Document not loaded: {}", path.display()))?;
+        let result = document.apply_edits(edits);
+        self.insert(document);
+        result
+    }
+
+    /// Resolve a possibly namespaced name (`task`, `lib.task`, `lib.Struct`) as seen from `from`
+    pub fn lookup(&self, from: impl AsRef<Path>, name: &str) -> Option<Symbol> {
+        let document = self.document(from)?;
+        match name.split_once('.') {
+            Some((namespace, rest)) => {
+                let (_, path) = self
+                    .imports(document)
+                    .into_iter()
+                    .find(|(ns, _)| ns == namespace)?;
+                self.lookup(normalize_path(&path), rest)
+            }
+            None => find_symbol(document, name),
+        }
+    }
+
+    /// All symbols defined across the workspace
+    pub fn symbols(&self) -> Vec<Symbol> {
+        self.documents
+            .values()
+            .flat_map(|document| {
+                let info = &document.info;
+                let tasks = info.tasks.iter().map(|t| (SymbolKind::Task, &t.name));
+                let workflows = info
+                    .workflows
+                    .iter()
+                    .map(|w| (SymbolKind::Workflow, &w.name));
+                let structs = info.structs.iter().map(|s| (SymbolKind::Struct, &s.name));
+                tasks
+                    .chain(workflows)
+                    .chain(structs)
+                    .map(|(kind, name)| Symbol {
+                        kind,
+                        name: name.clone(),
+                        path: document.path.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Cache a document and load any imports not yet in the workspace
+    fn insert(&mut self, document: Document) {
+        let imports = self.imports(&document);
+        self.errors.remove(&document.path);
+        self.documents.insert(document.path.clone(), document);
+
+        for (_, path) in imports {
+            let path = normalize_path(&path);
+            if self.documents.contains_key(&path) || self.errors.contains_key(&path) {
+                continue;
+            }
+            match Document::load(&path) {
+                Ok(imported) => self.insert(imported),
+                Err(e) => {
+                    self.errors.insert(path, format!("{:#}", e));
+                }
+            }
+        }
+    }
+}
+
+fn find_symbol(document: &Document, name: &str) -> Option<Symbol> {
+    let info = &document.info;
+    let kind = if info.tasks.iter().any(|t| t.name == name) {
+        SymbolKind::Task
+    } else if info.workflows.iter().any(|w| w.name == name) {
+        SymbolKind::Workflow
+    } else if info.structs.iter().any(|s| s.name == name) {
+        SymbolKind::Struct
+    } else {
+        return None;
+    };
+    Some(Symbol {
+        kind,
+        name: name.to_string(),
+        path: document.path.clone(),
+    })
+}
+
+/// Whether an import URI refers to a remote resource
+pub fn is_remote_uri(uri: &str) -> bool {
+    uri.starts_with("http://") || uri.starts_with("https://")
+}
+
+/// The namespace an import is referenced by: its alias, or the file stem of its URI
+pub fn import_namespace(uri: &str, alias: Option<&str>) -> String {
+    alias.map(str::to_string).unwrap_or_else(|| {
+        let file = uri.rsplit('/').next().unwrap_or(uri);
+        file.strip_suffix(".wdl").unwrap_or(file).to_string()
+    })
+}
+
+/// Path of a local import `uri` seen from a file in `base`. Like Cromwell,
+/// which resolves imports against the importing file and then the root of the
+/// imports zip, each of `import_dirs` is tried after `base`: first with the
+/// URI as written, then with just its file name, for zips flattened with
+/// `zip -j` where files import each other by bare name. When nothing exists,
+/// the path next to the importing file is returned so errors name it.
+pub fn resolve_import(base: &Path, uri: &str, import_dirs: &[PathBuf]) -> PathBuf {
+    let relative = base.join(uri);
+    if relative.exists() {
+        return relative;
+    }
+    let file_name = Path::new(uri).file_name();
+    import_dirs
+        .iter()
+        .flat_map(|dir| [Some(dir.join(uri)), file_name.map(|name| dir.join(name))])
+        .flatten()
+        .find(|path| path.exists())
+        .unwrap_or(relative)
+}
+
+/// Canonicalize existing paths so the same file is cached once
+fn normalize_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
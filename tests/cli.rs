@@ -56,9 +56,269 @@ fn test_info_command() {
         .stdout(predicate::str::contains("hello_world"));
 }
 
+#[test]
+fn test_json_generator_block() {
+    let output = cmd()
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .arg("info")
+        .arg(FILE_PATH)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let generator = &json["generator"];
+    assert_eq!(generator["name"], "wdlparse");
+    assert_eq!(generator["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(generator["wdl_grammar_version"], "0.17");
+    assert_eq!(generator["timestamp"], "2023-11-14T22:13:20Z");
+    assert_eq!(
+        generator["options"],
+        serde_json::json!(["info", FILE_PATH, "--format", "json"])
+    );
+    let bytes = std::fs::read(FILE_PATH).unwrap();
+    assert_eq!(generator["inputs"][0]["path"], FILE_PATH);
+    assert_eq!(
+        generator["inputs"][0]["sha256"],
+        wdlparse::provenance::sha256_hex(&bytes)
+    );
+    assert_eq!(
+        wdlparse::provenance::sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+
+    cmd()
+        .arg("catalog")
+        .arg(FILE_PATH)
+        .args(["--format", "jsonl"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("generator").not());
+}
+
+#[test]
+fn test_normalize() {
+    let file = std::env::current_dir()
+        .unwrap()
+        .join("examples/complex_example.wdl");
+    let output = cmd()
+        .arg("info")
+        .arg(&file)
+        .args(["--format", "json", "--normalize"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file"], "examples/complex_example.wdl");
+    assert_eq!(
+        json["generator"]["inputs"][0]["path"],
+        "examples/complex_example.wdl"
+    );
+    assert_eq!(json["generator"]["timestamp"], "1970-01-01T00:00:00Z");
+    let names = |key: &str, field: &str| -> Vec<String> {
+        json[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item[field].as_str().unwrap().to_string())
+            .collect()
+    };
+    for (key, field) in [("tasks", "name"), ("imports", "uri")] {
+        let mut sorted = names(key, field);
+        sorted.sort();
+        assert_eq!(names(key, field), sorted);
+    }
+
+    let output = cmd()
+        .args([
+            "bench",
+            "examples/hello_world.wdl",
+            "--format",
+            "json",
+            "--normalize",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.to_string().contains("\"mean_ms\":0.0"));
+
+    // Only paths are rewritten, never WDL content that mentions one
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("scratch.wdl");
+    let scratch = std::env::temp_dir()
+        .join("scratch.txt")
+        .display()
+        .to_string();
+    std::fs::write(
+        &wdl,
+        format!(
+            "version 1.0\n\ntask t {{\n  command <<< echo hi > {} >>>\n}}\n",
+            scratch
+        ),
+    )
+    .unwrap();
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json", "--normalize"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["file"].as_str().unwrap().starts_with("$TMPDIR/"));
+    assert!(json.to_string().contains(&format!("echo hi > {}", scratch)));
+}
+
 #[test]
 fn test_nonexistent_file() {
-    cmd().arg("parse").arg("nonexistent.wdl").assert().failure();
+    cmd().arg("parse").arg("nonexistent.wdl").assert().code(3);
+}
+
+#[test]
+fn test_parse_errors_exit_code() {
+    cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_parse_diagnostic_positions() {
+    cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "human", "--verbose"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("(line "));
+
+    // `-v` is still short for `--verbose`, not a log level
+    cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "human", "-v"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("(line "));
+
+    let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "parse", "params": {"content": "version 1.0\n\ntask t {\n  command <<< >>>\n  output {\n}\n"}}"#;
+    cmd()
+        .arg("rpc")
+        .write_stdin(format!("{}\n", request))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""line":"#))
+        .stdout(predicate::str::contains(r#""column":"#));
+}
+
+#[test]
+fn test_parse_diagnostic_groups() {
+    let output = cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let groups = json["diagnostic_groups"].as_array().unwrap();
+    assert!(!groups.is_empty());
+    assert!(json.get("omitted_diagnostic_groups").is_none());
+    let grouped: usize = groups
+        .iter()
+        .map(|g| 1 + g["related"].as_array().map_or(0, Vec::len))
+        .sum();
+    assert!(grouped <= json["diagnostics"].as_u64().unwrap() as usize);
+    let lines: Vec<u64> = groups.iter().filter_map(|g| g["line"].as_u64()).collect();
+    assert!(lines.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    let output = cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "json", "--max-diagnostics", "1"])
+        .output()
+        .unwrap();
+    let limited: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(limited["diagnostic_groups"].as_array().unwrap().len(), 1);
+    assert_eq!(limited["diagnostic_groups"][0], groups[0]);
+    if groups.len() > 1 {
+        assert_eq!(limited["omitted_diagnostic_groups"], groups.len() - 1);
+        cmd()
+            .arg("parse")
+            .arg("examples/malformed.wdl")
+            .args(["--format", "human", "--verbose", "--max-diagnostics", "1"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("not shown (--max-diagnostics)"));
+    }
+}
+
+#[test]
+fn test_bom_and_crlf_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("windows.wdl");
+    std::fs::write(
+        &wdl,
+        "\u{feff}version 1.0\r\n\r\n# Says hi\r\ntask hello {\r\n  command <<< echo hi >>>\r\n}\r\n",
+    )
+    .unwrap();
+
+    cmd().arg("parse").arg(&wdl).assert().success();
+
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["version"], "1.0");
+    assert_eq!(json["tasks"][0]["line"], 4);
+    assert_eq!(json["tasks"][0]["doc"], "Says hi");
+}
+
+#[test]
+fn test_non_utf8_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let utf16 = dir.path().join("utf16.wdl");
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in
+        "version 1.0\r\n\r\ntask hello {\r\n  command <<< echo hi >>>\r\n}\r\n".encode_utf16()
+    {
+        bytes.extend(unit.to_le_bytes());
+    }
+    std::fs::write(&utf16, bytes).unwrap();
+
+    cmd()
+        .arg("info")
+        .arg(&utf16)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""name": "hello""#));
+
+    let latin1 = dir.path().join("latin1.wdl");
+    std::fs::write(
+        &latin1,
+        b"version 1.0\n\n# Caf\xe9\ntask hello {\n  command <<< echo hi >>>\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("info")
+        .arg(&latin1)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("ISO-8859-1"))
+        .stderr(predicate::str::contains("line 3, column 6"));
+}
+
+#[test]
+fn test_usage_error_exit_code() {
+    cmd().arg("parse").arg("--no-such-flag").assert().code(3);
 }
 
 #[test]
@@ -71,3 +331,3534 @@ fn test_help_message() {
         .stdout(predicate::str::contains("parse"))
         .stdout(predicate::str::contains("info"));
 }
+
+#[test]
+fn test_check_backend_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(
+        &config,
+        "[backends.small]\nmax_cpu = 1\nmax_memory = \"4G\"\nrequire_container = true\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("check-backend")
+        .arg("examples/complex_example.wdl")
+        .arg("--profile")
+        .arg("small")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(4)
+        .stdout(predicate::str::contains("call_variants"))
+        .stdout(predicate::str::contains("memory"));
+
+    cmd()
+        .arg("check-backend")
+        .arg(FILE_PATH)
+        .arg("--profile")
+        .arg("missing")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Unknown backend profile"));
+}
+
+#[test]
+fn test_lint_docker_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("latest.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask t {\n  command <<< echo hi >>>\n  runtime {\n    docker: \"ubuntu:latest\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("docker-latest-tag"));
+
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(&config, "[lint.rules]\ndocker-missing-digest = \"error\"\n").unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("docker-missing-digest"));
+}
+
+#[test]
+fn test_lint_conditionals() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("conditional.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String v = \"\"\n  }\n  command <<< >>>\n  output {\n    String out = \"x\"\n  }\n}\n\ntask loose {\n  input {\n    String? m\n  }\n  command <<< >>>\n}\n\nworkflow w {\n  input {\n    Boolean run = true\n  }\n  Boolean debug = false\n  if (!debug && false) {\n    call t as dbg\n  }\n  if (run) {\n    call t as opt\n    String inner = opt.out\n  }\n  call t as after { input: v = opt.out }\n  call t as safe { input: v = select_first([opt.out, \"\"]) }\n  call loose { input: m = opt.out }\n  String copied = opt.out\n  String fallback = if defined(inner) then select_first([inner]) else \"\"\n  String unwrapped = if defined(inner) then inner else \"\"\n  output {\n    String? maybe = opt.out\n    String leaked = inner\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("constant-condition"))
+        .stdout(predicate::str::contains(
+            "'if (!debug && false)' is always false",
+        ))
+        .stdout(predicate::str::contains("if (run)' is always").not())
+        .stdout(predicate::str::contains(
+            "input 'v' of call 'after' reads call 'opt'",
+        ))
+        .stdout(predicate::str::contains("call 'safe'").not())
+        .stdout(predicate::str::contains("output 'maybe'").not())
+        .stdout(predicate::str::contains("\"line\": 25"))
+        .stdout(predicate::str::contains("declaration 'fallback'").not())
+        .stdout(predicate::str::contains(
+            "declaration 'unwrapped' reads 'inner' from inside 'if (run)'",
+        ))
+        .stdout(predicate::str::contains("workflow w (line 40, column 5)"));
+
+    // Findings sit on the `if` line, so a comment above it suppresses them
+    let content = std::fs::read_to_string(&wdl).unwrap().replace(
+        "  if (!debug && false) {",
+        "  # wdlparse-disable-next-line constant-condition\n  if (!debug && false) {",
+    );
+    std::fs::write(&wdl, content).unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("constant-condition").not());
+}
+
+#[test]
+fn test_lint_unreachable_outputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("outputs.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask t {\n  command <<< >>>\n  output {\n    File bam = \"a.bam\"\n  }\n}\n\nworkflow w {\n  input {\n    Boolean run\n  }\n  call t\n  call t as opt_extra\n  if (run) {\n    call t as opt\n  }\n  output {\n    File typo = t.bma\n    File skipped = opt.bam\n    File? maybe = opt.bam\n    File picked = select_first([opt.bam, t.bam])\n    File missing\n    File prefixed = if defined(opt_extra.bam) then opt.bam else t.bam\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("unreachable-output"))
+        .stdout(predicate::str::contains("workflow w (line 20, column 5)"))
+        .stdout(predicate::str::contains(
+            "output 'typo' reads 't.bma', but task 't' has no output 'bma'; did you mean 't.bam'?",
+        ))
+        .stdout(predicate::str::contains(
+            "output 'skipped' has type 'File' but reads call 'opt', which is skipped unless 'if (run)' holds",
+        ))
+        .stdout(predicate::str::contains("workflow w (line 24, column 5)"))
+        .stdout(predicate::str::contains(
+            "output 'missing' is declared but never assigned",
+        ))
+        .stdout(predicate::str::contains("output 'maybe'").not())
+        .stdout(predicate::str::contains("output 'picked'").not())
+        .stdout(predicate::str::contains(
+            "output 'prefixed' has type 'File' but reads call 'opt'",
+        ))
+        .stdout(predicate::str::contains("unguarded-conditional-output").not());
+}
+
+#[test]
+fn test_sort_by() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("order.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\nimport \"b.wdl\"\nimport \"a.wdl\"\n\ntask zeta {\n  command <<< >>>\n}\n\ntask alpha {\n  command <<< >>>\n}\n\nworkflow w {\n  call zeta\n  call alpha\n}\n",
+    )
+    .unwrap();
+
+    let names = |sort_by: &str| {
+        let output = cmd()
+            .arg("info")
+            .arg(&wdl)
+            .args([
+                "--format",
+                "json",
+                "--extract-metadata",
+                "--sort-by",
+                sort_by,
+            ])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let list = |items: &serde_json::Value, field: &str| -> Vec<String> {
+            items
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item[field].as_str().unwrap().to_string())
+                .collect()
+        };
+        (
+            list(&json["tasks"], "name"),
+            list(&json["imports"], "uri"),
+            list(&json["workflows"][0]["calls"], "name"),
+            json["basic_metadata"]["task_names"].clone(),
+        )
+    };
+
+    let (tasks, imports, calls, fallback) = names("position");
+    assert_eq!(tasks, ["zeta", "alpha"]);
+    assert_eq!(imports, ["b.wdl", "a.wdl"]);
+    assert_eq!(calls, ["zeta", "alpha"]);
+    assert_eq!(fallback, serde_json::json!(["zeta", "alpha"]));
+
+    let (tasks, imports, calls, fallback) = names("name");
+    assert_eq!(tasks, ["alpha", "zeta"]);
+    assert_eq!(imports, ["a.wdl", "b.wdl"]);
+    assert_eq!(calls, ["alpha", "zeta"]);
+    assert_eq!(fallback, serde_json::json!(["alpha", "zeta"]));
+}
+
+#[test]
+fn test_info_include_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("source.wdl");
+    let content = "version 1.0\n\n# Says hello\ntask greet {\n  command <<< echo \"héllo\" >>>\n}\n\nworkflow w {\n  call greet\n}\n";
+    std::fs::write(&wdl, content).unwrap();
+
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json", "--include-source"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let task = &json["tasks"][0]["source"];
+    let start = content.find("task greet").unwrap();
+    let end = content.find("}\n\nworkflow").unwrap() + 1;
+    assert_eq!(task["start"], start);
+    assert_eq!(task["end"], end);
+    assert_eq!(task["text"], &content[start..end]);
+    assert_eq!(task["truncated"], false);
+    assert_eq!(
+        json["workflows"][0]["source"]["text"],
+        "workflow w {\n  call greet\n}"
+    );
+
+    // The cut never splits a character: 'é' takes bytes 34 and 35
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args([
+            "--format",
+            "json",
+            "--include-source",
+            "--max-source-bytes",
+            "35",
+        ])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let task = &json["tasks"][0]["source"];
+    assert_eq!(task["text"], "task greet {\n  command <<< echo \"h");
+    assert_eq!(task["truncated"], true);
+    assert_eq!(task["end"], end);
+
+    cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"source\"").not());
+}
+
+#[test]
+fn test_docs_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let sources = dir.path().join("pipelines");
+    std::fs::create_dir_all(sources.join("qc")).unwrap();
+    std::fs::write(
+        sources.join("align.wdl"),
+        "version 1.0\n\n# Aligns reads\ntask align {\n  input {\n    File reads\n    Int threads = 4\n  }\n  command <<< >>>\n  meta {\n    keywords: [\"bwa\", \"alignment\"]\n  }\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        sources.join("qc/check.wdl"),
+        "version 1.0\n\nworkflow check {\n  meta {\n    description: \"Quality control\"\n    tags: \"qc, fastq\"\n  }\n}\n",
+    )
+    .unwrap();
+    let out = dir.path().join("site");
+
+    cmd()
+        .arg("docs")
+        .arg(&sources)
+        .arg("--output")
+        .arg(&out)
+        .args(["--index", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 page(s)"));
+    assert!(out.join("align.md").exists());
+    assert!(std::fs::read_to_string(out.join("qc/check.md"))
+        .unwrap()
+        .contains("Workflow: `check`"));
+    assert!(std::fs::read_to_string(out.join("index.md"))
+        .unwrap()
+        .contains("[`align`](align.md#task-align)"));
+
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("search_index.json")).unwrap())
+            .unwrap();
+    let documents = index["documents"].as_array().unwrap();
+    assert_eq!(documents.len(), 2);
+    let align = documents.iter().find(|d| d["name"] == "align").unwrap();
+    assert_eq!(align["kind"], "task");
+    assert_eq!(align["location"], "align.md#task-align");
+    assert_eq!(align["description"], "Aligns reads");
+    assert_eq!(align["inputs"], serde_json::json!(["reads", "threads"]));
+    assert_eq!(align["keywords"], serde_json::json!(["bwa", "alignment"]));
+    let check = documents.iter().find(|d| d["name"] == "check").unwrap();
+    assert_eq!(check["location"], "qc/check.md#workflow-check");
+    assert_eq!(check["keywords"], serde_json::json!(["qc", "fastq"]));
+
+    cmd()
+        .arg("docs")
+        .arg(&sources)
+        .arg("--output")
+        .arg(&out)
+        .args(["--index", "lunr"])
+        .assert()
+        .success();
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("search_index.json")).unwrap())
+            .unwrap();
+    let align = index["docs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["title"] == "task align")
+        .unwrap();
+    assert_eq!(align["location"], "align.md#task-align");
+    assert_eq!(
+        align["text"],
+        "Aligns reads\nInputs: reads threads\nKeywords: bwa alignment"
+    );
+}
+
+#[test]
+fn test_docs_mkdocs_site() {
+    let dir = tempfile::tempdir().unwrap();
+    let sources = dir.path().join("pipelines");
+    std::fs::create_dir_all(&sources).unwrap();
+    std::fs::write(
+        sources.join("main.wdl"),
+        "version 1.0\n\ntask greet {\n  command <<< echo hi >>>\n}\n\nworkflow hello {\n  call greet\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        sources.join("other.wdl"),
+        "version 1.0\n\ntask greet {\n  command <<< echo hello >>>\n}\n",
+    )
+    .unwrap();
+    let out = dir.path().join("site");
+
+    cmd()
+        .arg("docs")
+        .arg(&sources)
+        .arg("--output")
+        .arg(&out)
+        .args(["--site", "mkdocs", "--index", "json"])
+        .assert()
+        .success();
+
+    let config = std::fs::read_to_string(out.join("mkdocs.yml")).unwrap();
+    assert!(config.contains("site_name: \"WDL documentation\""));
+    assert!(config.contains("- name: mermaid"));
+    assert!(config.contains("      - \"hello\": \"workflows/hello.md\"\n"));
+    assert!(config.contains("      - \"greet\": \"tasks/greet.md\"\n"));
+    assert!(config.contains("      - \"greet\": \"tasks/greet-2.md\"\n"));
+
+    let workflow = std::fs::read_to_string(out.join("docs/workflows/hello.md")).unwrap();
+    assert!(workflow.contains("```mermaid\nflowchart TD\n"));
+    let task = std::fs::read_to_string(out.join("docs/tasks/greet-2.md")).unwrap();
+    assert!(task.contains("echo hello"));
+    assert!(task.contains("other.wdl`"));
+    assert!(std::fs::read_to_string(out.join("docs/index.md"))
+        .unwrap()
+        .contains("[`hello`](workflows/hello.md)"));
+
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("docs/search_index.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        index["documents"][0]["location"],
+        "workflows/hello.md#workflow-hello"
+    );
+}
+
+#[test]
+fn test_docs_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let sources = dir.path().join("pipelines");
+    std::fs::create_dir_all(&sources).unwrap();
+    let wdl = sources.join("main.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask greet {\n  command <<< echo hi >>>\n}\n\ntask wave {\n  command <<< >>>\n}\n",
+    )
+    .unwrap();
+    let out = dir.path().join("site");
+    let docs = |check: bool| {
+        let mut command = cmd();
+        command
+            .arg("docs")
+            .arg(&sources)
+            .arg("--output")
+            .arg(&out)
+            .args(["--site", "mkdocs", "--index", "lunr"]);
+        if check {
+            command.arg("--check");
+        }
+        command.assert()
+    };
+
+    docs(true)
+        .code(4)
+        .stdout(predicate::str::contains("mkdocs.yml: missing"));
+    docs(false).success();
+    docs(true)
+        .success()
+        .stdout(predicate::str::contains("Summary: 0 out of date"));
+
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask greet {\n  command <<< echo hello >>>\n}\n",
+    )
+    .unwrap();
+    docs(true)
+        .code(4)
+        .stdout(predicate::str::contains("docs/tasks/greet.md: out of date"))
+        .stdout(predicate::str::contains("docs/tasks/wave.md: stale"))
+        .stderr(predicate::str::contains("file(s) out of date"));
+    // Checking never writes
+    assert!(std::fs::read_to_string(out.join("docs/tasks/greet.md"))
+        .unwrap()
+        .contains("echo hi"));
+}
+
+#[test]
+fn test_badge() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("pipeline.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask a {\n  command <<< >>>\n  runtime {\n    docker: \"ubuntu:22.04\"\n  }\n}\n\ntask b {\n  command <<< >>>\n  runtime {\n    docker: \"ubuntu:22.04\"\n  }\n}\n\nworkflow main {\n  call a\n  call b\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("badge")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "![WDL](https://img.shields.io/badge/WDL-valid-brightgreen)",
+        ))
+        .stdout(predicate::str::contains(
+            "![WDL version](https://img.shields.io/badge/WDL%20version-1.1-blue)",
+        ))
+        .stdout(predicate::str::contains(
+            "![tasks](https://img.shields.io/badge/tasks-2-blue)",
+        ))
+        .stdout(predicate::str::contains("**Containers:** `ubuntu:22.04`\n"))
+        .stdout(predicate::str::contains("```mermaid\nflowchart TD\n"));
+
+    cmd()
+        .arg("badge")
+        .arg(&wdl)
+        .args(["--workflow", "mian"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'main'"));
+}
+
+#[test]
+fn test_lint_duplicate_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("duplicates.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask t {\n  command <<< >>>\n}\n\ntask u {\n  command <<< >>>\n}\n\nworkflow w {\n  call t\n  call t\n  call u as first\n  call t as first\n  call u\n  call t as u_copy\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("duplicate-call-name"))
+        .stdout(predicate::str::contains(
+            "task 't' is called more than once without an alias; name the calls apart, e.g. 'call t as t_2'",
+        ))
+        .stdout(predicate::str::contains(
+            "alias 'first' of call 't' is already the name of call 'u'",
+        ))
+        .stdout(predicate::str::contains("\"line\": 13"))
+        .stdout(predicate::str::contains("\"line\": 15"))
+        .stdout(predicate::str::contains("u_copy").not());
+}
+
+#[test]
+fn test_lint_spec() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("spec.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    Int n = 1\n  }\n  command <<< >>>\n}\n\nworkflow w {\n  call t as first\n  call t as second after first { n = 2 }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("spec-version").not());
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--spec", "1.1", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("spec-version").not());
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--spec", "1.0", "--format", "json"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "call 'after' clause requires WDL 1.1, but --spec is 1.0",
+        ))
+        .stdout(predicate::str::contains(
+            "call without 'input:' requires WDL 1.1, but --spec is 1.0",
+        ))
+        .stdout(predicate::str::contains("workflow w (line 12, column 3)"));
+
+    let older = dir.path().join("older.wdl");
+    std::fs::write(
+        &older,
+        "version 1.0\n\ntask t {\n  input {\n    Directory d\n  }\n  command <<< >>>\n}\n",
+    )
+    .unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&older)
+        .args(["--spec", "development", "--format", "json"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "'Directory' type requires WDL 1.2, but the file declares version 1.0",
+        ))
+        .stdout(predicate::str::contains("task t (line 5, column 5)"));
+}
+
+#[test]
+fn test_lint_call_caching() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("caching.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask stamp {\n  input {\n    Array[String] names\n  }\n  String list = basename(write_lines(names))\n  command <<<\n    # date in a comment is fine\n    echo \"$(date +%s) $RANDOM\" > out.txt\n  >>>\n  runtime {\n    docker: \"ubuntu:22.04\"\n  }\n}\n\ntask pinned {\n  meta {\n    volatile: true\n  }\n  command <<<\n    uuidgen\n  >>>\n  runtime {\n    docker: \"ubuntu@sha256:0000000000000000000000000000000000000000000000000000000000000000\"\n  }\n}\n\ntask flagged {\n  meta {\n    volatile: false\n  }\n  command <<<\n    uuidgen\n  >>>\n}\n\ntask cached {\n  command <<<\n    shuf -n 1 names.txt > date.txt\n  >>>\n  runtime {\n    cacheable: true\n  }\n}\n\ntask uncached {\n  command <<<\n    head -c 8 /dev/urandom > seed\n  >>>\n  runtime {\n    cacheable: false\n  }\n}\n",
+    )
+    .unwrap();
+
+    // Off unless enabled
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache-").not());
+
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(
+        &config,
+        "[lint.rules]\ncache-nondeterministic-command = \"warning\"\ncache-nondeterministic-input = \"warning\"\ncache-unpinned-container = \"warning\"\ncache-hint-missing = \"warning\"\n",
+    )
+    .unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("task stamp (line 10, column 13)"))
+        .stdout(predicate::str::contains(
+            "command uses 'date', which reads the current time",
+        ))
+        .stdout(predicate::str::contains("command uses '$RANDOM'"))
+        .stdout(predicate::str::contains(
+            "'list' is the name of a temporary file from 'write_lines'",
+        ))
+        .stdout(predicate::str::contains(
+            "image 'ubuntu:22.04' is not pinned to a digest",
+        ))
+        .stdout(predicate::str::contains(
+            "the command uses 'date', '$RANDOM' but the task doesn't declare 'volatile: true'",
+        ))
+        .stdout(predicate::str::contains("task pinned (line 22, column 5)"))
+        .stdout(predicate::str::contains("task pinned\"").not())
+        .stdout(predicate::str::contains(
+            "the command uses 'uuidgen' but the task doesn't declare 'volatile: true'",
+        ))
+        // `date.txt` is a file name, and only `cacheable: false` opts out
+        .stdout(predicate::str::contains(
+            "the command uses 'shuf' but the task doesn't declare",
+        ))
+        .stdout(predicate::str::contains("command uses '/dev/urandom'"))
+        .stdout(predicate::str::contains("the command uses '/dev/urandom' but").not());
+
+    // An unpinned image is reported once, by `docker-missing-digest`, when both are on
+    std::fs::write(
+        &config,
+        "[lint.rules]\ncache-unpinned-container = \"warning\"\ndocker-missing-digest = \"warning\"\n",
+    )
+    .unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("docker-missing-digest"))
+        .stdout(predicate::str::contains("cache-unpinned-container").not());
+}
+
+#[test]
+fn test_lint_command_placeholders() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("placeholders.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask t {\n  input {\n    String name\n  }\n  String greeting = \"hi\"\n  command {\n    echo ${name} ~{greeting} ~{missing}\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("command-dollar-placeholder"))
+        .stdout(predicate::str::contains("task t (line 9, column 10)"))
+        .stdout(predicate::str::contains(
+            "'missing' in placeholder '~{missing}' is not an input or declaration",
+        ))
+        .stdout(predicate::str::contains("task t (line 9, column 30)"))
+        .stdout(predicate::str::contains("'greeting' in placeholder").not());
+}
+
+#[test]
+fn test_lint_suggestions() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("typos.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\nstruct SampleInfo {\n  String id\n}\n\ntask align_reads {\n  input {\n    SampleInfo sample\n    Int threads\n  }\n  command <<<\n    echo ~{thread}\n  >>>\n}\n\nworkflow w {\n  input {\n    SampelInfo s\n    Int thread_count\n  }\n  Int doubled = thread_cont * 2\n  call align_read { input: sample = s, threads = thread_count }\n  call align_reads { input: sample = s, threads = doubld }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(
+        "call 'align_read' names a task the file doesn't define; did you mean 'align_reads'?"
+    ));
+    assert!(stdout.contains(
+        "'s' has type 'SampelInfo', which is not a defined struct; did you mean 'SampleInfo'?"
+    ));
+    assert!(stdout.contains("did you mean 'threads'?"));
+    assert!(stdout.contains(
+        "declaration 'doubled' reads 'thread_cont', which is not an input, declaration or call of the workflow; did you mean 'thread_count'?"
+    ));
+    assert!(stdout.contains(
+        "input 'threads' of call 'align_reads' reads 'doubld', which is not an input, declaration or call of the workflow; did you mean 'doubled'?"
+    ));
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let call = json["files"][0]["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["rule"] == "unresolved-call")
+        .unwrap();
+    assert_eq!(call["suggestions"], serde_json::json!(["align_reads"]));
+    assert_eq!(call["line"], 23);
+}
+
+#[test]
+fn test_diagnostic_filters() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("latest.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask t {\n  command <<< >>>\n  runtime {\n    docker: \"ubuntu:latest\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd().arg("lint").arg(&wdl).assert().success();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--warnings-as-errors")
+        .assert()
+        .code(2);
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--warnings-as-errors", "--ignore", "docker-latest-tag"])
+        .assert()
+        .success();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--only", "docker-untagged", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("docker-latest-tag").not());
+
+    std::fs::write(
+        dir.path().join(".wdlparse.toml"),
+        "[diagnostics]\nwarnings_as_errors = true\n",
+    )
+    .unwrap();
+    cmd()
+        .current_dir(dir.path())
+        .arg("lint")
+        .arg("latest.wdl")
+        .assert()
+        .code(2);
+
+    cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .args(["--ignore", "syntax-error", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"has_errors\": false"));
+    cmd()
+        .arg("parse")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "json"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("\"code\": \"syntax-error\""));
+}
+
+#[test]
+fn test_lint_naming_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("naming.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\nstruct sample_info {\n  String id\n}\n\ntask AlignReads {\n  command <<< >>>\n}\n\nworkflow w {\n  call AlignReads\n  output {\n    String out_id = \"x\"\n    String result = \"y\"\n    # wdlparse-disable-next-line workflow-output-prefix\n    String legacy = \"z\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("task-name-snake-case").not())
+        .stdout(predicate::str::contains("workflow-output-prefix").not());
+
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(
+        &config,
+        "[lint.rules]\ntask-name-snake-case = \"error\"\nstruct-name-camel-case = \"warning\"\n\n[lint.naming]\nworkflow_output_prefix = \"out_\"\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "task name 'AlignReads' should be snake_case",
+        ))
+        .stdout(predicate::str::contains(
+            "struct name 'sample_info' should be CamelCase",
+        ))
+        .stdout(predicate::str::contains(
+            "output 'result' should start with 'out_'",
+        ))
+        .stdout(predicate::str::contains("output 'out_id'").not())
+        .stdout(predicate::str::contains("output 'legacy'").not());
+}
+
+#[test]
+fn test_lint_suppression_comments() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("suppressed.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\n# wdlparse-disable docker-untagged -- base images are vetted\ntask t {\n  command <<< echo hi >>>\n  runtime {\n    # wdlparse-disable-next-line docker-latest-tag\n\n    docker: \"ubuntu:latest\"\n  }\n}\n\ntask u {\n  command <<< echo hi >>>\n  runtime {\n    docker: \"debian\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--no-inline-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("'ubuntu:latest' uses the mutable"))
+        .stdout(predicate::str::contains("'debian' has no tag"));
+}
+
+#[test]
+fn test_lint_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("legacy.wdl");
+    let task = |name: &str| {
+        format!(
+            "task {} {{\n  command <<< echo hi >>>\n  runtime {{\n    docker: \"ubuntu:latest\"\n  }}\n}}\n",
+            name
+        )
+    };
+    std::fs::write(&wdl, format!("version 1.0\n\n{}", task("old"))).unwrap();
+    let baseline = dir.path().join("baseline.json");
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(&config, "[lint.rules]\ndocker-latest-tag = \"error\"\n").unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--baseline")
+        .arg(&baseline)
+        .arg("--update-baseline")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&baseline)
+        .unwrap()
+        .contains("docker-latest-tag"));
+
+    // Shifting the known finding down a few lines doesn't make it new
+    std::fs::write(&wdl, format!("version 1.0\n\n\n\n{}", task("old"))).unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--baseline")
+        .arg(&baseline)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found"));
+
+    std::fs::write(
+        &wdl,
+        format!("version 1.0\n\n{}\n{}", task("old"), task("new")),
+    )
+    .unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--baseline")
+        .arg(&baseline)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("task new"))
+        .stdout(predicate::str::contains("task old").not());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_lint_plugin_executable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("plugin.wdl");
+    std::fs::write(&wdl, "version 1.1\n\ntask t {\n  command <<< >>>\n}\n").unwrap();
+    let plugin = dir.path().join("wdlparse-rule-owner");
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\ngrep -q '\"name\":\"t\"' || exit 1\necho '[{\"rule\": \"org-require-owner\", \"severity\": \"error\", \"location\": \"task t\", \"message\": \"meta.owner is missing\"}]'\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--plugin")
+        .arg(&plugin)
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("org-require-owner"))
+        .stdout(predicate::str::contains("meta.owner is missing"));
+
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(&config, "[lint.rules]\norg-require-owner = \"off\"\n").unwrap();
+
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--plugin")
+        .arg(&plugin)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("org-require-owner").not());
+
+    // Paths in the config are relative to it, whatever the working directory
+    let plugins = "[lint]\nplugins = [\"./wdlparse-rule-owner\"]\n";
+    std::fs::write(&config, plugins).unwrap();
+    cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("meta.owner is missing"));
+
+    // A discovered config's plugins need --trust-plugins
+    std::fs::write(dir.path().join(".wdlparse.toml"), plugins).unwrap();
+    cmd()
+        .current_dir(dir.path())
+        .arg("lint")
+        .arg("plugin.wdl")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("--trust-plugins"));
+    cmd()
+        .current_dir(dir.path())
+        .arg("lint")
+        .arg("plugin.wdl")
+        .arg("--trust-plugins")
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("meta.owner is missing"));
+}
+
+#[test]
+fn test_lint_glob_with_ignore_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let task = "version 1.0\n\ntask t {\n  command <<< echo hi >>>\n}\n";
+    std::fs::create_dir_all(dir.path().join("workflows/nested")).unwrap();
+    std::fs::create_dir_all(dir.path().join("vendor")).unwrap();
+    std::fs::write(dir.path().join("workflows/nested/kept.wdl"), task).unwrap();
+    std::fs::write(dir.path().join("vendor/skipped.wdl"), task).unwrap();
+    std::fs::write(dir.path().join(".wdlparseignore"), "vendor/\n").unwrap();
+
+    cmd()
+        .current_dir(dir.path())
+        .arg("lint")
+        .arg("**/*.wdl")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("workflows/nested/kept.wdl"))
+        .stdout(predicate::str::contains("skipped.wdl").not());
+
+    cmd()
+        .current_dir(dir.path())
+        .arg("lint")
+        .arg("missing/*.wdl")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No files match pattern"));
+}
+
+#[test]
+fn test_lint_batch_summary() {
+    cmd()
+        .arg("lint")
+        .arg("examples")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Summary:"))
+        .stdout(predicate::str::contains("passed"));
+}
+
+#[test]
+fn test_info_heredoc_command_section() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("heredoc.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  command <<<\n    echo hi\n      echo ${HOME}\n  >>>\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("info")
+        .arg(&wdl)
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"style\": \"heredoc\""))
+        .stdout(predicate::str::contains(
+            "\"raw\": \"\\n    echo hi\\n      echo ${HOME}\\n  \"",
+        ))
+        .stdout(predicate::str::contains(
+            "\"dedented\": \"echo hi\\n  echo ${HOME}\"",
+        ));
+}
+
+#[test]
+fn test_info_parsed_types() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("types.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    Map[String?, Int] counts\n  }\n  command <<< >>>\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let input = &json["tasks"][0]["inputs"][0];
+    assert_eq!(input["optional"], false);
+    assert_eq!(input["wdl_type_parsed"]["kind"], "map");
+    assert_eq!(input["wdl_type_parsed"]["key"]["kind"], "optional");
+}
+
+#[test]
+fn test_catalog_owners() {
+    cmd()
+        .arg("catalog")
+        .arg("examples")
+        .arg("--owners")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Genomics Team <genomics@example.com>",
+        ))
+        .stdout(predicate::str::contains("(no author)"));
+
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"author\": \"Genomics Team\""));
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("pair.wdl"),
+        "version 1.0\n\nworkflow pair {\n  meta {\n    authors: [\"Ada Lovelace\", \"Alan Turing\"]\n  }\n}\n",
+    )
+    .unwrap();
+    cmd()
+        .arg("catalog")
+        .arg(dir.path())
+        .arg("--owners")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Ada Lovelace (1 workflow(s), 0 task(s))",
+        ))
+        .stdout(predicate::str::contains(
+            "Alan Turing (1 workflow(s), 0 task(s))",
+        ))
+        .stdout(predicate::str::contains("\"").not());
+    cmd()
+        .arg("info")
+        .arg(dir.path().join("pair.wdl"))
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"author\": \"Ada Lovelace, Alan Turing\"",
+        ));
+}
+
+#[test]
+fn test_compare() {
+    let dir = tempfile::tempdir().unwrap();
+    let task = |name: &str, command: &str| {
+        format!(
+            "task {} {{\n  command <<< {} >>>\n  runtime {{ docker: \"ubuntu\" }}\n}}\n",
+            name, command
+        )
+    };
+    let write = |file: &str, tasks: &[String]| {
+        let path = dir.path().join(file);
+        std::fs::write(&path, format!("version 1.0\n\n{}", tasks.concat())).unwrap();
+        path
+    };
+    let a = write(
+        "a.wdl",
+        &[task("align", "bwa"), task("sort", "samtools sort")],
+    );
+    // Same tasks reformatted and commented, one renamed, one changed
+    let b = write(
+        "b.wdl",
+        &[
+            format!("# Aligner\n{}", task("align", "bwa").replace("  ", "    ")),
+            task("sort_bam", "samtools sort"),
+        ],
+    );
+    let c = write("c.wdl", &[task("align", "bwa mem"), task("qc", "fastqc")]);
+
+    let output = cmd()
+        .arg("compare")
+        .args([&a, &b, &c])
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let align = &json["tasks"][0];
+    assert_eq!(align["name"], "align");
+    assert_eq!(align["status"], "diverged");
+    assert_eq!(align["variants"], serde_json::json!(["A", "A", "B"]));
+    assert_eq!(json["tasks"][1]["name"], "qc");
+    assert_eq!(json["tasks"][1]["status"], "unique");
+    assert_eq!(json["renamed"][0]["tasks"][0]["name"], "sort");
+    assert_eq!(json["renamed"][0]["tasks"][1]["name"], "sort_bam");
+    assert_eq!(json["sources"][0]["identical"], 1);
+    assert_eq!(json["sources"][0]["unique"], 1);
+
+    cmd().arg("compare").arg(&a).assert().failure().code(3);
+}
+
+#[test]
+fn test_diff() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |file: &str, command: &str, docker: &str, extra: &str| {
+        let path = dir.path().join(file);
+        std::fs::write(
+            &path,
+            format!(
+                "version 1.0\n\ntask align {{\n  command <<<\n{}\n  >>>\n  runtime {{\n    cpu: 2\n    docker: \"{}\"\n  }}\n}}\n{}",
+                command, docker, extra
+            ),
+        )
+        .unwrap();
+        path
+    };
+    let old = write(
+        "old.wdl",
+        "    set -e\n    bwa mem ref.fa reads.fq\n    samtools index out.bam",
+        "bwa:0.7.17",
+        "\ntask qc {\n  command <<< fastqc >>>\n}\n",
+    );
+    let new = write(
+        "new.wdl",
+        "    set -e\n    bwa mem -t 4 ref.fa reads.fq\n    samtools index out.bam",
+        "bwa:0.7.18",
+        "",
+    );
+
+    let output = cmd()
+        .arg("diff")
+        .args([&old, &new])
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let align = &json["tasks"][0];
+    assert_eq!(align["name"], "align");
+    assert_eq!(align["status"], "changed");
+    let command = align["command"].as_array().unwrap();
+    assert_eq!(command[0]["kind"], "same");
+    assert_eq!(command[1]["kind"], "changed");
+    assert_eq!(command[1]["old"]["text"], "bwa mem ref.fa reads.fq");
+    assert_eq!(command[1]["new"]["text"], "bwa mem -t 4 ref.fa reads.fq");
+    assert_eq!(align["runtime"][1]["kind"], "changed");
+    assert_eq!(json["tasks"][1]["name"], "qc");
+    assert_eq!(json["tasks"][1]["status"], "removed");
+
+    cmd()
+        .arg("diff")
+        .args([&old, &new])
+        .args(["--width", "80"])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "   2 bwa mem ref.fa reads.fq           │    2 bwa mem -t 4 ref.fa reads.fq",
+        ));
+
+    cmd()
+        .arg("diff")
+        .args([&old, &new])
+        .args(["--task", "algin"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'align'"));
+}
+
+#[test]
+fn test_info_best_effort() {
+    let output = cmd()
+        .arg("info")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "json", "--best-effort"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let task = json["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["name"] == "broken_task")
+        .unwrap();
+    assert_eq!(task["complete"], false);
+    assert_eq!(task["line"], 5);
+    assert_eq!(json["workflows"][0]["name"], "broken_workflow");
+    assert_eq!(json["workflows"][0]["complete"], false);
+
+    let output = cmd()
+        .arg("info")
+        .arg("examples/hello_world.wdl")
+        .args(["--format", "json", "--best-effort"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|t| t["complete"] == true));
+
+    // Without --best-effort nothing is checked, so nothing is claimed
+    let output = cmd()
+        .arg("info")
+        .arg("examples/malformed.wdl")
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("\"complete\""));
+
+    cmd()
+        .arg("catalog")
+        .arg("examples/malformed.wdl")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("broken_workflow (incomplete)"));
+}
+
+#[test]
+fn test_markdown_format() {
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .arg("--format")
+        .arg("markdown")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<a id=\"task-align_reads\"></a>"))
+        .stdout(predicate::str::contains(
+            "| Task | Inputs | Outputs | Runtime |",
+        ));
+
+    cmd()
+        .arg("stats")
+        .arg("examples/complex_example.wdl")
+        .arg("--format")
+        .arg("markdown")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("| Lines | Tasks |"));
+}
+
+#[test]
+fn test_info_scoped_to_task_or_workflow() {
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .args(["--task", "align_reads"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Task: align_reads"))
+        .stdout(predicate::str::contains(r#"aligner: String = "bwa""#))
+        .stdout(predicate::str::contains("set -euo pipefail"))
+        .stdout(predicate::str::contains("call_variants").not());
+
+    let output = cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .args(["--workflow", "genomics_pipeline", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["workflow"]["name"], "genomics_pipeline");
+    assert!(json.get("tasks").is_none());
+
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .args(["--task", "nope"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Task 'nope' not found"));
+}
+
+#[test]
+fn test_info_show_commands_and_runtime() {
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Command:").not())
+        .stdout(predicate::str::contains("Runtime:").not());
+
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .args(["--show-commands", "--show-runtime"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Command:"))
+        .stdout(predicate::str::contains("Runtime:"))
+        .stdout(predicate::str::contains("        set -euo pipefail"))
+        .stdout(predicate::str::contains("        docker  "));
+}
+
+#[test]
+fn test_doc_comments() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("docs.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\n# Unrelated header\n\n# Align reads to the reference.\n# Uses bwa mem.\ntask align {\n  input {\n    Int threads = 4 # trailing note\n    # Reads to align\n    File reads\n    # Documented in parameter_meta instead\n    String sample\n  }\n  command <<< >>>\n  parameter_meta {\n    sample: \"Sample name\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let task = &json["tasks"][0];
+    assert_eq!(task["doc"], "Align reads to the reference.\nUses bwa mem.");
+    assert!(task["inputs"][0].get("doc").is_none());
+    assert_eq!(task["inputs"][1]["doc"], "Reads to align");
+
+    cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Align reads to the reference.\nUses bwa mem.\n\n",
+        ))
+        .stdout(predicate::str::contains(
+            "| `reads` | `File` |  | no | Reads to align |",
+        ))
+        .stdout(predicate::str::contains("| no | Sample name |"));
+}
+
+#[test]
+fn test_closure_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("somatic.wdl");
+    std::fs::write(
+        &wdl,
+        r#"version 1.0
+
+import "qc.wdl" as qc
+
+struct Reference {
+  File fasta
+}
+
+struct Sample {
+  String name
+  File reads
+}
+
+workflow somatic {
+  input {
+    Array[Sample] samples
+    Reference reference
+    Boolean run_qc = true
+    String unused
+  }
+  scatter (sample in samples) {
+    call trim { input: reads = sample.reads }
+    call align as align_tumor { input: reads = trim.trimmed, ref = reference }
+  }
+  if (run_qc) {
+    call qc.report { input: bams = align_tumor.bam }
+  }
+}
+
+task trim {
+  input {
+    File reads
+  }
+  command <<< >>>
+  output {
+    File trimmed = "trimmed.fq"
+  }
+}
+
+task align {
+  input {
+    File reads
+    Reference ref
+  }
+  command <<< >>>
+  output {
+    File bam = "out.bam"
+  }
+}
+"#,
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("closure")
+        .arg(&wdl)
+        .args(["--call", "align_tumor", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let closure = &json["closure"];
+    assert_eq!(closure["calls"], serde_json::json!(["trim"]));
+    assert_eq!(closure["tasks"], serde_json::json!(["trim", "align"]));
+    assert_eq!(
+        closure["inputs"],
+        serde_json::json!(["samples", "reference"])
+    );
+    assert_eq!(
+        closure["structs"],
+        serde_json::json!(["Reference", "Sample"])
+    );
+    assert_eq!(closure["imports"], serde_json::json!([]));
+
+    cmd()
+        .arg("closure")
+        .arg(&wdl)
+        .args(["--call", "report"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imports: 1"))
+        .stdout(predicate::str::contains("qc.wdl"))
+        .stdout(predicate::str::contains("run_qc"));
+
+    cmd()
+        .arg("closure")
+        .arg(&wdl)
+        .args(["--call", "missing"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Call 'missing' not found"));
+}
+
+#[test]
+fn test_add_task() {
+    let dir = tempfile::tempdir().unwrap();
+    let library = dir.path().join("library.wdl");
+    std::fs::write(
+        &library,
+        "version 1.0\n\nstruct Sample {\n  String id\n  File bam\n}\n\n# Sort a BAM by coordinate\ntask samtools_sort {\n  input {\n    Sample sample\n    Int threads = 2\n  }\n  command <<< samtools sort ~{sample.bam} >>>\n  output {\n    File sorted = \"sorted.bam\"\n  }\n}\n\ntask samtools_index {\n  command <<< >>>\n}\n",
+    )
+    .unwrap();
+    let pipeline = "version 1.0\n\nworkflow main {\n  input {\n    File reads\n  }\n\n  call align { input: reads = reads }\n\n  output {\n    File bam = align.bam\n  }\n}\n\ntask align {\n  input {\n    File reads\n  }\n  command <<< >>>\n  output {\n    File bam = \"out.bam\"\n  }\n}\n";
+    let copied = dir.path().join("copied.wdl");
+    std::fs::write(&copied, pipeline).unwrap();
+
+    cmd()
+        .arg("add-task")
+        .arg(&copied)
+        .arg("--from")
+        .arg(&library)
+        .args(["--task", "samtools_sort"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("struct Sample"));
+    let content = std::fs::read_to_string(&copied).unwrap();
+    assert!(content.contains("# Sort a BAM by coordinate\ntask samtools_sort {"));
+    assert!(content.contains("  # TODO: set the inputs of samtools_sort: sample (Sample)\n  call samtools_sort\n\n  output {"));
+    assert!(!content.contains("samtools_index"));
+    let output = cmd()
+        .arg("parse")
+        .arg(&copied)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["diagnostics"], 0);
+    assert_eq!(json["wdl"]["structs"][0]["name"], "Sample");
+    assert_eq!(
+        json["wdl"]["workflows"][0]["calls"][1]["target"],
+        "samtools_sort"
+    );
+
+    let imported = dir.path().join("imported.wdl");
+    std::fs::write(&imported, pipeline).unwrap();
+    cmd()
+        .arg("add-task")
+        .arg(&imported)
+        .arg("--from")
+        .arg(&library)
+        .args(["--task", "samtools_sort", "--import"])
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(&imported).unwrap();
+    assert!(content.starts_with("version 1.0\n\nimport \"library.wdl\"\n\nworkflow main"));
+    assert!(content.contains("call library.samtools_sort\n"));
+    assert!(!content.contains("task samtools_sort"));
+
+    cmd()
+        .arg("add-task")
+        .arg(&imported)
+        .arg("--from")
+        .arg(&library)
+        .args(["--task", "samtools_srot"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'samtools_sort'"));
+}
+
+#[test]
+fn test_strip_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("pipeline.wdl");
+    std::fs::write(
+        &wdl,
+        r#"version 1.0
+
+import "missing.wdl" as missing
+
+struct Sample {
+  String name
+}
+
+# Left over from an older version
+struct Unused {
+  Int count
+}
+
+workflow pipeline {
+  input {
+    Sample sample
+  }
+  call greet { input: name = sample.name }
+}
+
+task greet {
+  input {
+    String name
+  }
+  command <<< echo ~{name} >>>
+  meta {
+    description: "Say hello"
+  }
+}
+
+# Not called anywhere
+task farewell {
+  command <<< echo bye >>>
+}
+"#,
+    )
+    .unwrap();
+
+    cmd()
+        .arg("strip")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("task greet"))
+        .stdout(predicate::str::contains("struct Sample"))
+        .stdout(predicate::str::contains("description"))
+        .stdout(predicate::str::contains("farewell").not())
+        .stdout(predicate::str::contains("Unused").not())
+        .stdout(predicate::str::contains("Not called anywhere").not())
+        .stdout(predicate::str::contains("import \"missing.wdl\""));
+
+    let stripped = dir.path().join("stripped.wdl");
+    cmd()
+        .arg("strip")
+        .arg(&wdl)
+        .args(["--comments", "--meta", "--output"])
+        .arg(&stripped)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed: 1 task(s), 1 struct(s), 0 import(s)",
+        ));
+    let content = std::fs::read_to_string(&stripped).unwrap();
+    assert!(content.contains("call greet"));
+    assert!(!content.contains('#'));
+    assert!(!content.contains("meta"));
+
+    cmd().arg("parse").arg(&stripped).assert().success();
+}
+
+#[test]
+fn test_strip_keeps_libraries_and_command_blank_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let library = dir.path().join("tasks.wdl");
+    std::fs::write(
+        &library,
+        "version 1.0\n\ntask index {\n  command <<< >>>\n}\n\ntask sort {\n  command <<< >>>\n}\n",
+    )
+    .unwrap();
+
+    // Without a workflow nothing calls the tasks, but they are the point
+    cmd()
+        .arg("strip")
+        .arg(&library)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("task index"))
+        .stdout(predicate::str::contains("task sort"));
+
+    let wdl = dir.path().join("main.wdl");
+    let command = "  command <<<\n    python <<CODE\n    import sys\n\n\n    print(sys.argv)\n    CODE\n  >>>\n";
+    std::fs::write(
+        &wdl,
+        format!(
+            "version 1.0\n\nworkflow main {{\n  call run\n}}\n\ntask unused {{\n  command <<< >>>\n}}\n\ntask run {{\n{}}}\n",
+            command
+        ),
+    )
+    .unwrap();
+    let output = cmd().arg("strip").arg(&wdl).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(command));
+    assert!(!stdout.contains("unused"));
+    assert!(stdout.contains("}\n\ntask run {"));
+}
+
+#[test]
+fn test_strip_import_search_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let lib = dir.path().join("lib");
+    std::fs::create_dir(&lib).unwrap();
+    std::fs::write(
+        lib.join("types.wdl"),
+        "version 1.0\n\nstruct Sample {\n  String name\n}\n",
+    )
+    .unwrap();
+    let wdl = dir.path().join("main.wdl");
+    std::fs::write(
+        &wdl,
+        r#"version 1.0
+
+import "types.wdl"
+import "unused.wdl"
+
+workflow main {
+  input {
+    Sample sample
+  }
+}
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        lib.join("unused.wdl"),
+        "version 1.0\n\ntask unused {\n  command <<< >>>\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("strip")
+        .arg(&wdl)
+        .arg("--import-dir")
+        .arg(&lib)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("import \"types.wdl\""))
+        .stdout(predicate::str::contains("unused.wdl").not());
+
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(&config, "[imports]\nsearch_paths = [\"lib\"]\n").unwrap();
+    cmd()
+        .arg("strip")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unused.wdl").not());
+}
+
+/// Serve `files` (URL path → body) over HTTP on a free local port until the
+/// test process exits
+fn serve_files(files: &'static [(&'static str, &'static str)]) -> String {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", server.server_addr());
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = match files.iter().find(|(path, _)| *path == request.url()) {
+                Some((_, body)) => tiny_http::Response::from_string(*body),
+                None => tiny_http::Response::from_string("not found").with_status_code(404),
+            };
+            let _ = request.respond(response);
+        }
+    });
+    address
+}
+
+#[test]
+fn test_vendor_command() {
+    let address = serve_files(&[
+        (
+            "/org/tasks/align.wdl",
+            "version 1.0\n\nimport \"utils.wdl\"\n\ntask align {\n  command <<< >>>\n}\n",
+        ),
+        (
+            "/org/tasks/utils.wdl",
+            "version 1.0\n\ntask index {\n  command <<< >>>\n}\n",
+        ),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("main.wdl");
+    std::fs::write(
+        &wdl,
+        format!(
+            "version 1.0\n\nimport \"{}/org/tasks/align.wdl\"\n\nworkflow main {{\n  call align.align\n}}\n",
+            address
+        ),
+    )
+    .unwrap();
+
+    let vendor = dir.path().join("vendor");
+    let output = cmd()
+        .arg("vendor")
+        .arg(&wdl)
+        .arg("--dest")
+        .arg(&vendor)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["downloaded"].as_array().unwrap().len(), 2);
+
+    let host = address.trim_start_matches("http://").replace(':', "_");
+    let content = std::fs::read_to_string(&wdl).unwrap();
+    assert!(content.contains(&format!("import \"vendor/{}/org/tasks/align.wdl\"\n", host)));
+    assert!(vendor.join(&host).join("org/tasks/utils.wdl").exists());
+    assert!(vendor.join("vendor.json").exists());
+
+    // The rewritten file resolves entirely from the vendored copies
+    cmd()
+        .arg("strip")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/org/tasks/align.wdl"));
+
+    // Missing files fail with the URL
+    std::fs::write(
+        &wdl,
+        format!("version 1.0\n\nimport \"{}/org/missing.wdl\"\n", address),
+    )
+    .unwrap();
+    cmd()
+        .arg("vendor")
+        .arg(&wdl)
+        .arg("--dest")
+        .arg(&vendor)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Failed to download"))
+        .stderr(predicate::str::contains("/org/missing.wdl"));
+}
+
+#[test]
+fn test_offline_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("hello.wdl");
+    std::fs::copy(FILE_PATH, &wdl).unwrap();
+    let original = std::fs::read_to_string(&wdl).unwrap();
+
+    let output = cmd()
+        .arg("lint")
+        .arg(&wdl)
+        .args(["--resolve-digests", "--offline", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let findings = json["files"][0]["findings"].as_array().unwrap();
+    let blocked: Vec<_> = findings
+        .iter()
+        .filter(|f| f["rule"] == "network-disabled")
+        .collect();
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0]["location"], "ubuntu:20.04");
+    assert_eq!(std::fs::read_to_string(&wdl).unwrap(), original);
+
+    // Offline, vendor lists the downloads and leaves the files alone
+    let main = dir.path().join("main.wdl");
+    let content = "version 1.0\n\nimport \"https://example.com/tasks/align.wdl\"\n";
+    std::fs::write(&main, content).unwrap();
+    let vendor = dir.path().join("vendor");
+    let output = cmd()
+        .arg("--offline")
+        .arg("vendor")
+        .arg(&main)
+        .arg("--dest")
+        .arg(&vendor)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["blocked"],
+        serde_json::json!([{
+            "kind": "remote_import",
+            "resource": "https://example.com/tasks/align.wdl"
+        }])
+    );
+    assert_eq!(std::fs::read_to_string(&main).unwrap(), content);
+    assert!(!vendor.exists());
+}
+
+#[test]
+fn test_rewrite_imports_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("main.wdl");
+    std::fs::write(
+        &wdl,
+        r#"version 1.0
+
+import "https://raw.githubusercontent.com/org/tasks/main/align.wdl"
+import "https://raw.githubusercontent.com/org/tasks/main/qc.wdl" as qc
+import "local.wdl"
+
+workflow main {
+  call align.align
+}
+"#,
+    )
+    .unwrap();
+
+    cmd()
+        .arg("rewrite-imports")
+        .arg(&wdl)
+        .args([
+            "--map",
+            "https://raw.githubusercontent.com/org/=https://mirror.internal/org/",
+            "--check",
+        ])
+        .assert()
+        .code(4)
+        .stdout(predicate::str::contains(
+            "line 3: https://raw.githubusercontent.com/org/tasks/main/align.wdl → https://mirror.internal/org/tasks/main/align.wdl",
+        ))
+        .stdout(predicate::str::contains("2 import(s) to rewrite"));
+    assert!(std::fs::read_to_string(&wdl)
+        .unwrap()
+        .contains("raw.githubusercontent.com"));
+
+    // The longer prefix wins, and a changed file name keeps the old namespace
+    let config = dir.path().join("wdlparse.toml");
+    std::fs::write(
+        &config,
+        r#"[imports.rewrites]
+"https://raw.githubusercontent.com/org/" = "https://mirror.internal/org/"
+"https://raw.githubusercontent.com/org/tasks/main/align.wdl" = "https://mirror.internal/align-v2.wdl"
+"#,
+    )
+    .unwrap();
+    cmd()
+        .arg("rewrite-imports")
+        .arg(&wdl)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(&wdl).unwrap();
+    assert!(content.contains("import \"https://mirror.internal/align-v2.wdl\" as align\n"));
+    assert!(content.contains("import \"https://mirror.internal/org/tasks/main/qc.wdl\" as qc\n"));
+    assert!(content.contains("import \"local.wdl\"\n"));
+
+    cmd()
+        .arg("rewrite-imports")
+        .arg(&wdl)
+        .args(["--map", "no-equals-sign"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("expected FROM=TO"));
+}
+
+#[test]
+fn test_fix_version_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.wdl");
+    std::fs::write(
+        &missing,
+        "# Copyright header\n\n# Say hello\ntask hello {\n  command <<< echo hi >>>\n}\n",
+    )
+    .unwrap();
+    let misplaced = dir.path().join("misplaced.wdl");
+    std::fs::write(
+        &misplaced,
+        "task hello {\n  command <<< echo hi >>>\n}\n\nversion 1.1\n",
+    )
+    .unwrap();
+    std::fs::copy("examples/hello_world.wdl", dir.path().join("ok.wdl")).unwrap();
+
+    cmd()
+        .arg("fix-version")
+        .arg(dir.path())
+        .arg("--check")
+        .assert()
+        .code(4)
+        .stdout(predicate::str::contains("missing version statement"))
+        .stdout(predicate::str::contains(
+            "version 1.1 is not at the top of the file",
+        ))
+        .stdout(predicate::str::contains("ok.wdl").not());
+    assert!(!std::fs::read_to_string(&missing)
+        .unwrap()
+        .contains("version"));
+
+    cmd()
+        .arg("fix-version")
+        .arg(dir.path())
+        .args(["--target", "1.2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("inserted version 1.2"))
+        .stdout(predicate::str::contains("Summary: 2 fixed"));
+    assert_eq!(
+        std::fs::read_to_string(&missing).unwrap(),
+        "# Copyright header\n\nversion 1.2\n\n# Say hello\ntask hello {\n  command <<< echo hi >>>\n}\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&misplaced).unwrap(),
+        "version 1.1\n\ntask hello {\n  command <<< echo hi >>>\n}\n"
+    );
+
+    cmd()
+        .arg("fix-version")
+        .arg(dir.path())
+        .arg("--check")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_runtime_overrides_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("overrides.wdl");
+    std::fs::write(
+        &wdl,
+        r#"version 1.0
+
+struct RuntimeAttr {
+  Int? cpu
+}
+
+workflow align_all {
+  input {
+    Int memory_gb
+    RuntimeAttr? attrs
+  }
+  Int padded = memory_gb + 2
+  call align { input: mem_gb = padded, runtime_attr = attrs }
+  call align as align_default
+}
+
+task align {
+  input {
+    Int mem_gb = 4
+    RuntimeAttr? runtime_attr
+  }
+  Int disk = mem_gb * 2
+  command <<< >>>
+  runtime {
+    memory: "~{mem_gb} GB"
+    disks: "local-disk ~{disk} HDD"
+    cpu: select_first([runtime_attr.cpu, 1])
+    docker: "ubuntu:22.04"
+  }
+}
+"#,
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("runtime-overrides")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let align = &json["calls"][0];
+    assert_eq!(align["call"], "align");
+    let keys: Vec<_> = align["overrides"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["key"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, ["memory", "disks", "cpu"]);
+    assert_eq!(align["overrides"][0]["binding"], "padded");
+    assert_eq!(
+        align["overrides"][0]["workflow_inputs"],
+        serde_json::json!(["memory_gb"])
+    );
+    assert_eq!(align["overrides"][1]["input"], "mem_gb");
+    assert_eq!(align["overrides"][2]["fields"], serde_json::json!(["cpu"]));
+    assert_eq!(
+        align["overrides"][2]["workflow_inputs"],
+        serde_json::json!(["attrs"])
+    );
+    assert_eq!(
+        json["calls"][1]["overrides"][0]["binding"],
+        serde_json::Value::Null
+    );
+
+    cmd()
+        .arg("runtime-overrides")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Runtime Overrides: align_all"))
+        .stdout(predicate::str::contains("memory_gb"))
+        .stdout(predicate::str::contains(
+            "unbound; set align_all.align_default.mem_gb to override (default: 4)",
+        ));
+}
+
+#[test]
+fn test_runtime_sensitivity() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("sensitivity.wdl");
+    std::fs::write(
+        &wdl,
+        r#"version 1.0
+
+task align {
+  input {
+    File reads
+    Int threads = 4
+  }
+  Int disk = ceil(size(reads, "GB")) * 2
+  command <<< >>>
+  runtime {
+    docker: "ubuntu:22.04"
+    cpu: threads
+    memory: "~{3 * threads} GB"
+    disks: "local-disk ~{disk} HDD"
+  }
+}
+
+task report {
+  command <<< >>>
+}
+"#,
+    )
+    .unwrap();
+
+    let output = cmd()
+        .args([
+            "runtime-sensitivity",
+            wdl.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let align = &json["tasks"][0];
+    assert_eq!(align["inputs"], serde_json::json!(["reads", "threads"]));
+    let attributes = align["attributes"].as_array().unwrap();
+    assert_eq!(attributes[0]["source"], "static");
+    assert_eq!(attributes[0]["inputs"], serde_json::json!([]));
+    assert_eq!(attributes[2]["source"], "computed");
+    assert_eq!(attributes[2]["inputs"][0]["name"], "threads");
+    assert_eq!(attributes[2]["inputs"][0]["default_value"], "4");
+    assert_eq!(attributes[3]["inputs"][0]["name"], "reads");
+    assert_eq!(json["tasks"][1]["attributes"], serde_json::json!([]));
+
+    cmd()
+        .args([
+            "runtime-sensitivity",
+            wdl.to_str().unwrap(),
+            "--task",
+            "align",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "computed from threads (default 4)",
+        ))
+        .stdout(predicate::str::contains("report").not());
+
+    cmd()
+        .args([
+            "runtime-sensitivity",
+            wdl.to_str().unwrap(),
+            "--task",
+            "algn",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'align'?"));
+}
+
+#[test]
+fn test_mermaid_command() {
+    cmd()
+        .arg("mermaid")
+        .arg("examples/complex_example.wdl")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart TD"))
+        .stdout(predicate::str::contains("call_align_reads"));
+
+    cmd()
+        .arg("mermaid")
+        .arg("examples/complex_example.wdl")
+        .arg("--task")
+        .arg("align_reads")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart LR"))
+        .stdout(predicate::str::contains(
+            "input_threads -->|\"~{threads}\"| command",
+        ));
+}
+
+#[test]
+fn test_mermaid_layout_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("layout.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String v\n  }\n  command <<< echo ~{v} >>>\n  output {\n    String out = read_string(stdout())\n  }\n}\n\nworkflow w {\n  input {\n    String name\n  }\n  call t { input: v = name }\n  output {\n    String result = t.out\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .args(["--direction", "lr", "--no-inputs"])
+        .args(["--style", "call=fill:#eef", "--style", "output=stroke:#333"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart LR"))
+        .stdout(predicate::str::contains("input_name").not())
+        .stdout(predicate::str::contains("call_t --> output_result"))
+        .stdout(predicate::str::contains(
+            "classDef call fill:#eef\n    class call_t call",
+        ))
+        .stdout(predicate::str::contains("class output_result output"));
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .args(["--task", "t", "--no-outputs", "--direction", "TD"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart TD"))
+        .stdout(predicate::str::contains("input_v -->"))
+        .stdout(predicate::str::contains("output_out").not());
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .args(["--style", "cal=fill:#eef"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("did you mean 'call'?"));
+}
+
+#[test]
+fn test_mermaid_aliased_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("aliases.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask align {\n  command <<< >>>\n}\n\nworkflow w {\n  call align as align_tumor\n  call align as align_normal\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "call_align_tumor[\"align_tumor (align)\"]",
+        ))
+        .stdout(predicate::str::contains(
+            "call_align_normal[\"align_normal (align)\"]",
+        ));
+}
+
+#[test]
+fn test_mermaid_dependencies_through_declarations_and_conditionals() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("deps.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String v = \"\"\n  }\n  command <<< >>>\n  output {\n    String out = \"x\"\n    Boolean flag = true\n  }\n}\n\nworkflow w {\n  call t as a\n  String x = a.out\n  call t as b { input: v = x }\n  if (a.flag) {\n    call t as c\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("call_a --> call_b"))
+        .stdout(predicate::str::contains("call_a --> call_c"));
+}
+
+#[test]
+fn test_mermaid_scatter_variable_is_not_a_dependency() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("scatter.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String v = \"\"\n  }\n  command <<< >>>\n  output {\n    String out = \"x\"\n  }\n}\n\nworkflow w {\n  input {\n    Array[String] samples\n  }\n  call t as s\n  scatter (s in samples) {\n    call t as each { input: v = s }\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("input_samples --> call_each"))
+        .stdout(predicate::str::contains("call_s --> call_each").not());
+}
+
+#[test]
+fn test_mermaid_imported_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("main.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\nimport \"lib/tasks.wdl\"\n\ntask local {\n  command <<< >>>\n}\n\nworkflow w {\n  call tasks.align\n  call tasks.align as again\n  call local\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .arg("--imports")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("call_align[\"[tasks] align\"]"))
+        .stdout(predicate::str::contains(
+            "call_again[\"[tasks] again (align)\"]",
+        ))
+        .stdout(predicate::str::contains("call_local[\"local\"]"))
+        .stdout(predicate::str::contains(
+            "import_tasks{{\"lib/tasks.wdl\"}}",
+        ))
+        .stdout(predicate::str::contains("import_tasks -.-> call_again"))
+        .stdout(predicate::str::contains(
+            "class call_align,call_again imported",
+        ));
+}
+
+#[test]
+fn test_mermaid_runtime_annotations() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("runtime.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask big {\n  command <<< >>>\n  runtime {\n    cpu: 4\n    memory: \"8 GB\"\n    container: \"ubuntu:22.04\"\n  }\n}\n\nworkflow w {\n  call big\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .args(["--runtime", "label"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "call_big[\"big<br/>cpu 4 · 8 GB · ubuntu:22.04\"]",
+        ));
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .args(["--task", "big", "--runtime", "tooltip"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "click command \"#task-big\" \"cpu 4 · 8 GB · ubuntu:22.04\"",
+        ));
+}
+
+#[test]
+fn test_stats_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("graph.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String v = \"\"\n  }\n  command <<< >>>\n  output {\n    String out = \"x\"\n  }\n}\n\nworkflow w {\n  input {\n    String unused\n  }\n  call t as a\n  call t as b { input: v = a.out }\n  call t as c { input: v = a.out }\n  call t as d { input: v = b.out + c.out }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("stats")
+        .arg(&wdl)
+        .arg("--graph")
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"longest_path\": 3"))
+        .stdout(predicate::str::contains("\"max_width\": 2"));
+
+    cmd()
+        .arg("stats")
+        .arg(&wdl)
+        .arg("--graph")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(": input_unused"));
+
+    cmd()
+        .arg("stats")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Longest path").not());
+}
+
+#[test]
+fn test_stats_memory_total() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("memory.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask a {\n  command <<< >>>\n  runtime {\n    memory: \"3 GiB\"\n  }\n}\n\ntask b {\n  command <<< >>>\n  runtime {\n    memory: \"1024M\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("stats")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3.95 GiB across 2 task(s)"));
+    cmd()
+        .arg("stats")
+        .arg(&wdl)
+        .arg("--si")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4.25 GB across 2 task(s)"));
+    cmd()
+        .args(["stats", "--si", "--binary"])
+        .arg(&wdl)
+        .assert()
+        .code(3);
+
+    cmd()
+        .arg("stats")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"memory_bytes\": 1024000000"));
+}
+
+#[test]
+fn test_plan_critical_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("plan.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String v = \"\"\n  }\n  command <<< >>>\n  output {\n    String out = \"x\"\n  }\n}\n\nworkflow w {\n  call t as a\n  call t as b { input: v = a.out }\n  call t as c { input: v = a.out }\n  call t as d { input: v = b.out + c.out }\n}\n",
+    )
+    .unwrap();
+    let durations = dir.path().join("durations.json");
+    std::fs::write(&durations, r#"{"a": 10, "b": 5, "c": 30, "t": 1}"#).unwrap();
+
+    cmd()
+        .arg("plan")
+        .arg(&wdl)
+        .arg("--durations")
+        .arg(&durations)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"serial_time\": 46.0"))
+        .stdout(predicate::str::contains("\"parallel_time\": 41.0"));
+
+    cmd()
+        .arg("plan")
+        .arg(&wdl)
+        .arg("--durations")
+        .arg(&durations)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a → c → d"));
+
+    cmd()
+        .arg("plan")
+        .arg(&wdl)
+        .arg("--durations")
+        .arg(&durations)
+        .arg("--mermaid")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "class call_a,call_c,call_d highlight",
+        ));
+}
+
+#[test]
+fn test_scatters_width_from_inputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("scatter.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  command <<< >>>\n}\n\nworkflow w {\n  input {\n    Array[String] samples\n    Int shards = 3\n  }\n  call t as setup\n  scatter (s in samples) {\n    scatter (i in range(shards)) {\n      call t as work\n    }\n  }\n}\n",
+    )
+    .unwrap();
+    let inputs = dir.path().join("inputs.json");
+    std::fs::write(&inputs, r#"{"w.samples": ["a", "b", "c", "d"]}"#).unwrap();
+
+    cmd()
+        .arg("scatters")
+        .arg(&wdl)
+        .arg("--inputs")
+        .arg(&inputs)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("length(samples)=4"))
+        .stdout(predicate::str::contains("length(range(shards))=3"))
+        .stdout(predicate::str::contains("work: 12"))
+        .stdout(predicate::str::contains(": 13"));
+
+    cmd()
+        .arg("scatters")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("length(samples)=unknown"));
+}
+
+#[test]
+fn test_command_audit() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("audit.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask fetch {\n  input {\n    String url\n  }\n  command <<<\n    OUT=result.txt\n    curl -s ~{url} -H \"Authorization: $API_TOKEN\" > $OUT\n    cp $OUT /mnt/shared/result.txt\n    cat /etc/hosts > /dev/null\n  >>>\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("command-audit")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"API_TOKEN\""))
+        .stdout(predicate::str::contains("\"OUT\"").not())
+        .stdout(predicate::str::contains("\"tool\": \"curl\""))
+        .stdout(predicate::str::contains("\"/etc/hosts\""))
+        .stdout(predicate::str::contains("/dev/null").not())
+        .stdout(predicate::str::contains(
+            "\"writes_outside\": [\n            \"/mnt/shared/result.txt\"",
+        ));
+}
+
+#[test]
+fn test_command_language() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("languages.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask py {\n  command <<<\n    set -e\n    python3 <<CODE\n    import sys\n    print(sys.argv)\n    CODE\n  >>>\n}\n\ntask plot {\n  command <<<\n    Rscript -e 'df <- read.csv(\"~{1}\"); print(df$value)'\n  >>>\n}\n\ntask shell {\n  command <<<\n    echo start\n    sort in.txt > out.txt\n    python -c 'print(1)'\n  >>>\n}\n\ntask script {\n  command <<<\n    #!/usr/bin/env perl\n    print \"hi\\n\";\n  >>>\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let languages: Vec<&str> = json["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["command_language"].as_str().unwrap())
+        .collect();
+    assert_eq!(languages, ["python", "r", "bash", "perl"]);
+
+    cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--task", "py", "--format", "markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("```python\nset -e\n"));
+    // `$value` is an R field, not an environment variable
+    cmd()
+        .arg("command-audit")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"value\"").not());
+}
+
+#[test]
+fn test_extract_scripts() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("align.wdl");
+    let original = "version 1.0\n\ntask align {\n  input {\n    File reads\n    Int threads = 4\n  }\n  command <<<\n    set -e\n    bwa mem -t ~{threads} ~{reads} > out.sam\n  >>>\n}\n\ntask empty {\n  command <<< >>>\n}\n";
+    std::fs::write(&wdl, original).unwrap();
+    let scripts = dir.path().join("scripts");
+
+    cmd()
+        .arg("extract-scripts")
+        .arg(&wdl)
+        .arg("--dest")
+        .arg(&scripts)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("THREADS = ~{threads}"))
+        .stdout(predicate::str::contains("2 script(s)"));
+    let script = std::fs::read_to_string(scripts.join("align.sh")).unwrap();
+    assert!(script.starts_with("#!/usr/bin/env bash\n"));
+    assert!(script.contains("# READS=\"~{reads}\"\n"));
+    assert!(script.ends_with("\n\nset -e\nbwa mem -t ${THREADS} ${READS} > out.sam\n"));
+
+    let reinject = || {
+        cmd()
+            .arg("extract-scripts")
+            .arg(&wdl)
+            .arg("--dest")
+            .arg(&scripts)
+            .arg("--reinject")
+            .assert()
+            .success()
+    };
+    reinject().stdout(predicate::str::contains("Summary: 0 updated"));
+    assert_eq!(std::fs::read_to_string(&wdl).unwrap(), original);
+
+    std::fs::write(
+        scripts.join("align.sh"),
+        script.replace("> out.sam", "| samtools sort -@ ${THREADS} -o out.bam"),
+    )
+    .unwrap();
+    reinject().stdout(predicate::str::contains("align: updated"));
+    assert_eq!(
+        std::fs::read_to_string(&wdl).unwrap(),
+        original.replace("> out.sam", "| samtools sort -@ ~{threads} -o out.bam")
+    );
+}
+
+#[test]
+fn test_extract_scripts_wrapped_in_bash() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("report.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask report {\n  input {\n    String name\n  }\n  command <<<\n    node <<EOF\n    const name = \"~{name}\";\n    console.log(name);\n    EOF\n  >>>\n}\n",
+    )
+    .unwrap();
+    let scripts = dir.path().join("scripts");
+
+    cmd()
+        .arg("extract-scripts")
+        .arg(&wdl)
+        .arg("--dest")
+        .arg(&scripts)
+        .assert()
+        .success();
+    // Bash runs the script, so the header is commented for bash
+    let script = std::fs::read_to_string(scripts.join("report.sh")).unwrap();
+    assert!(script.starts_with("#!/usr/bin/env bash\n# Command of task `report`"));
+    assert!(script.contains("# NAME=\"~{name}\"\n"));
+    assert!(!script.contains("//"));
+}
+
+#[test]
+fn test_info_output_file_patterns() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("outputs.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\ntask t {\n  input {\n    String prefix\n  }\n  command <<< >>>\n  output {\n    Array[File] bams = glob(\"*.bam\")\n    File log = stdout()\n    File report = \"~{prefix}.html\"\n    String label = \"not a file\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"*.bam\""))
+        .stdout(predicate::str::contains("\"stdout\": true"))
+        .stdout(predicate::str::contains("\"~{prefix}.html\""))
+        .stdout(predicate::str::contains("\"not a file\"\n").not());
+}
+
+#[test]
+fn test_info_normalized_runtime() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("runtime.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask t {\n  command <<< >>>\n  runtime {\n    docker: \"ubuntu:22.04\"\n    memory: \"4096 MiB\"\n    disks: \"local-disk 100 SSD, /mnt/ref 50 HDD\"\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("info")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let runtime = &json["tasks"][0]["normalized_runtime"];
+    assert_eq!(runtime["container"], "ubuntu:22.04");
+    assert_eq!(runtime["memory_bytes"], 4096u64 * 1024 * 1024);
+    assert_eq!(
+        runtime["disks"],
+        serde_json::json!([
+            {"mount": null, "size_gb": 100.0, "type": "SSD"},
+            {"mount": "/mnt/ref", "size_gb": 50.0, "type": "HDD"}
+        ])
+    );
+}
+
+#[test]
+fn test_schema_nf_core() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("schema.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\ntask align {\n  input {\n    File reads\n    Int threads = 4\n  }\n  command <<< >>>\n  parameter_meta {\n    threads: \"Aligner threads\"\n  }\n}\n\nworkflow w {\n  meta {\n    description: \"Align reads\"\n  }\n  input {\n    Array[File]+ reads\n    String? label\n  }\n  parameter_meta {\n    reads: { help: \"FASTQ files\", example: [\"a.fq\"] }\n  }\n  call align { input: reads = reads[0] }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("schema")
+        .arg(&wdl)
+        .args(["--style", "nf-core"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["description"], "Align reads");
+    let workflow_inputs = &json["definitions"]["workflow_inputs"];
+    let reads = &workflow_inputs["properties"]["w.reads"];
+    assert_eq!(reads["type"], "array");
+    assert_eq!(reads["items"]["format"], "file-path");
+    assert_eq!(reads["minItems"], 1);
+    assert_eq!(reads["description"], "FASTQ files");
+    assert_eq!(workflow_inputs["required"], serde_json::json!(["w.reads"]));
+    let threads = &json["definitions"]["task_inputs"]["properties"]["w.align.threads"];
+    assert_eq!(threads["default"], 4);
+    assert_eq!(threads["description"], "Aligner threads");
+    assert!(json["definitions"]["task_inputs"]["properties"]
+        .get("w.align.reads")
+        .is_none());
+}
+
+#[test]
+fn test_options_template() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("options.wdl");
+    let task = |name: &str, image: &str, cpu: u32| {
+        format!(
+            "task {} {{\n  command <<< >>>\n  runtime {{\n    container: \"{}\"\n    cpu: {}\n    memory: \"~{{cpu * 2}} GB\"\n  }}\n}}\n",
+            name, image, cpu
+        )
+    };
+    std::fs::write(
+        &wdl,
+        format!(
+            "version 1.1\n\n{}{}{}",
+            task("a", "ubuntu:22.04", 2),
+            task("b", "ubuntu:22.04", 4),
+            task("c", "debian:12", 4)
+        ),
+    )
+    .unwrap();
+
+    let output = cmd().arg("options-template").arg(&wdl).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let defaults = &json["default_runtime_attributes"];
+    assert_eq!(defaults["docker"], "ubuntu:22.04");
+    assert_eq!(defaults["cpu"], 4);
+    assert!(defaults.get("memory").is_none());
+    assert_eq!(json["read_from_cache"], true);
+    assert!(json["final_workflow_outputs_dir"].is_null());
+}
+
+#[test]
+fn test_self_schemas() {
+    for (report, published) in [
+        ("info", include_str!("../schemas/info.schema.json")),
+        (
+            "diagnostics",
+            include_str!("../schemas/diagnostics.schema.json"),
+        ),
+        ("graph", include_str!("../schemas/graph.schema.json")),
+    ] {
+        cmd()
+            .args(["schema", "--self", report])
+            .assert()
+            .success()
+            .stdout(published);
+    }
+    let output = cmd().args(["schema", "--self"]).output().unwrap();
+    let all: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(all["graph"]["title"], "wdlparse graph report");
+
+    for args in [
+        ["info", FILE_PATH, "--format", "json"],
+        ["parse", FILE_PATH, "--format", "json"],
+        ["graph", "examples/complex_example.wdl", "--format", "json"],
+    ] {
+        let output = cmd().args(args).output().unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(
+            json["format_version"],
+            wdlparse::output_schema::FORMAT_VERSION
+        );
+    }
+
+    let output = cmd()
+        .args(["graph", "examples/complex_example.wdl", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let graph = &json["graphs"][0];
+    assert!(graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|n| n["kind"] == "call"));
+    assert!(!graph["edges"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_edge_labels() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("labels.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\nworkflow w {\n  call align\n  String index = align.bai\n  call call_variants { input: bam = align.bam, bai = index }\n  output {\n    File vcf = call_variants.vcf\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("graph")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let edges = json["graphs"][0]["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edges[0]["from"], "call_align");
+    assert_eq!(edges[0]["variables"], serde_json::json!(["bam", "bai"]));
+
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .arg("--edge-labels")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "call_align -->|\"bam, bai\"| call_call_variants",
+        ))
+        .stdout(predicate::str::contains(
+            "call_call_variants -->|\"vcf\"| output_vcf",
+        ));
+    cmd()
+        .arg("mermaid")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "call_align --> call_call_variants",
+        ));
+}
+
+#[test]
+fn test_workspace_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("lib")).unwrap();
+    let write = |file: &str, content: &str| {
+        std::fs::write(dir.path().join(file), content).unwrap();
+    };
+    write(
+        "main.wdl",
+        "version 1.0\n\nimport \"lib/align.wdl\"\n\nworkflow main {\n  input {\n    File reads\n  }\n  call align.align_reads { input: reads = reads }\n  output {\n    File bam = align_reads.bam\n  }\n}\n",
+    );
+    write(
+        "lib/align.wdl",
+        "version 1.0\n\nimport \"index.wdl\"\n\nworkflow align_reads {\n  input {\n    File reads\n  }\n  call index.index_bam { input: bam = reads }\n  output {\n    File bam = index_bam.bai\n  }\n}\n",
+    );
+    write(
+        "lib/index.wdl",
+        "version 1.0\n\nworkflow index_bam {\n  input {\n    File bam\n  }\n  call bai\n  output {\n    File bai = bam\n  }\n}\n\ntask bai {\n  command <<< samtools index >>>\n}\n",
+    );
+    let main = dir.path().join("main.wdl");
+
+    let output = cmd()
+        .arg("graph")
+        .arg(&main)
+        .args(["--workspace", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let graphs = json["graphs"].as_array().unwrap();
+    assert_eq!(graphs.len(), 3);
+    assert_eq!(graphs[0]["id"], "root");
+    assert_eq!(graphs[0]["file"], "main.wdl");
+    assert_eq!(graphs[1]["id"], "call_align_reads");
+    assert_eq!(graphs[1]["parent"], "root");
+    assert_eq!(graphs[1]["file"], "lib/align.wdl");
+    assert_eq!(graphs[2]["workflow"], "index_bam");
+    assert_eq!(graphs[2]["depth"], 2);
+    assert!(graphs[2]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|n| n["id"] == "call_align_reads__call_index_bam__call_bai"));
+
+    let output = cmd()
+        .arg("graph")
+        .arg(&main)
+        .args(["--workspace", "--depth", "1", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["graphs"].as_array().unwrap().len(), 2);
+
+    cmd()
+        .arg("mermaid")
+        .arg(&main)
+        .arg("--workspace")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "subgraph sub_call_align_reads[\"align_reads · lib/align.wdl\"]",
+        ))
+        .stdout(predicate::str::contains(
+            "call_align_reads -.-> sub_call_align_reads",
+        ));
+
+    cmd()
+        .args(["graph", FILE_PATH, "--depth", "1"])
+        .assert()
+        .failure()
+        .code(3);
+}
+
+#[test]
+fn test_struct_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("types.wdl"),
+        "version 1.0\n\nstruct Read {\n  File fastq\n}\n\nstruct Sample {\n  String id\n  Array[Read] reads\n}\n",
+    )
+    .unwrap();
+    let main = dir.path().join("main.wdl");
+    std::fs::write(
+        &main,
+        "version 1.0\n\nimport \"types.wdl\"\n\nworkflow main {\n  input {\n    Array[Sample] samples\n  }\n}\n\ntask trim {\n  input {\n    Read read\n  }\n  command <<< trim >>>\n  output {\n    Read trimmed = read\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("graph")
+        .arg(&main)
+        .args(["--mode", "structs", "--workspace", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let read = &json["structs"][0];
+    assert_eq!(read["name"], "Read");
+    assert_eq!(read["defined_in"], serde_json::json!(["types.wdl"]));
+    assert_eq!(read["tasks"], 1);
+    assert_eq!(read["workflows"], 1);
+    let uses = read["uses"].as_array().unwrap();
+    assert!(uses
+        .iter()
+        .any(|u| u["kind"] == "workflow" && u["through"] == "Sample"));
+    assert!(uses
+        .iter()
+        .any(|u| u["kind"] == "struct" && u["name"] == "Sample" && u["section"] == "field"));
+    assert!(uses
+        .iter()
+        .any(|u| u["kind"] == "task" && u["section"] == "output" && u["declaration"] == "trimmed"));
+
+    // Without --workspace only the file's own declarations count
+    cmd()
+        .arg("graph")
+        .arg(&main)
+        .args(["--mode", "structs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("not defined in these files"));
+
+    cmd()
+        .arg("graph")
+        .arg(&main)
+        .args(["--mode", "structs", "-w", "main"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_localization() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("staging.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.2\n\nstruct Sample {\n  String id\n  Array[File] reads\n}\n\ntask t {\n  input {\n    File reference\n    Directory? index\n    Array[Sample] samples\n    Int threads = 4\n  }\n  command <<< >>>\n}\n\nworkflow w {\n  input {\n    String name\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .args(["localization", wdl.to_str().unwrap(), "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let task = &json["definitions"][0];
+    assert_eq!(task["name"], "t");
+    assert_eq!(task["files"], 2);
+    assert_eq!(task["directories"], 1);
+    assert_eq!(task["required"], 2);
+    let inputs = task["inputs"].as_array().unwrap();
+    assert_eq!(inputs.len(), 3);
+    assert_eq!(inputs[1]["path_types"], serde_json::json!(["Directory"]));
+    assert_eq!(inputs[1]["optional"], true);
+    assert_eq!(inputs[2]["through"], serde_json::json!(["Sample"]));
+    assert_eq!(inputs[2]["collection"], true);
+    assert_eq!(json["definitions"][1]["inputs"], serde_json::json!([]));
+
+    cmd()
+        .args(["localization", wdl.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 Directory, 2 required"))
+        .stdout(predicate::str::contains("via struct Sample"));
+}
+
+#[test]
+fn test_input_usage() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("usage.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\nworkflow w {\n  input {\n    File reads\n    File ref\n    Int threads = 4\n  }\n  String index = ref + \".fai\"\n  Int unused_cpu = threads\n  call align { input: fastq = reads, fai = index }\n  output {\n    File bam = align.bam\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("input-usage")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let inputs = json["inputs"].as_array().unwrap();
+    assert_eq!(inputs[0]["uses"][0]["name"], "align");
+    assert_eq!(inputs[0]["uses"][0]["parameter"], "fastq");
+    assert_eq!(inputs[1]["uses"][0]["via"], serde_json::json!(["index"]));
+    assert_eq!(inputs[1]["calls"], 1);
+    assert_eq!(json["unused"], serde_json::json!(["threads"]));
+
+    cmd()
+        .arg("input-usage")
+        .arg(&wdl)
+        .arg("--mermaid")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "input_ref -->|\"fai, via index\"| call_align",
+        ))
+        .stdout(predicate::str::contains("class input_threads unused"));
+}
+
+#[test]
+fn test_terra_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("terra.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\nworkflow align {\n  input {\n    File reads\n    File ref_fasta\n    Int threads = 4\n  }\n  output {\n    File bam = reads\n  }\n}\n",
+    )
+    .unwrap();
+
+    let output = cmd()
+        .arg("terra-config")
+        .arg(&wdl)
+        .args(["--entity", "participant"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["rootEntityType"], "participant");
+    assert_eq!(json["inputs"]["align.reads"], "this.reads");
+    assert_eq!(json["inputs"]["align.ref_fasta"], "workspace.ref_fasta");
+    assert!(json["inputs"].get("align.threads").is_none());
+    assert_eq!(json["outputs"]["align.bam"], "this.bam");
+}
+
+#[test]
+fn test_new_workflow() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("align_pipeline.wdl");
+    cmd()
+        .args(["new", "workflow", "--name", "align_pipeline"])
+        .args(["--tasks", "bwa,sort,markdup", "-o"])
+        .arg(&wdl)
+        .assert()
+        .success();
+
+    let output = cmd()
+        .arg("parse")
+        .arg(&wdl)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["diagnostics"], 0);
+    assert_eq!(json["wdl"]["version"], "1.0");
+    assert_eq!(json["wdl"]["tasks"].as_array().unwrap().len(), 3);
+    let calls = &json["wdl"]["workflows"][0]["calls"];
+    assert_eq!(calls[1]["name"], "sort");
+    assert_eq!(calls[1]["inputs"][0]["value"], "bwa.output_file");
+    assert_eq!(
+        json["wdl"]["workflows"][0]["outputs"][0]["expression"],
+        "markdup.output_file"
+    );
+
+    // An existing file is not overwritten, and names must be WDL identifiers
+    cmd()
+        .args(["new", "task", "--name", "index", "-o"])
+        .arg(&wdl)
+        .assert()
+        .failure();
+    cmd()
+        .args([
+            "new",
+            "workflow",
+            "--name",
+            "align-pipeline",
+            "--tasks",
+            "bwa",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a valid WDL name"));
+    cmd()
+        .args(["new", "workflow", "--name", "align", "--tasks", "File"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'File' is a WDL keyword"));
+}
+
+#[test]
+fn test_scaffold_tests() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("pipeline.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.0\n\nworkflow pipeline {\n  input {\n    File reads\n    String sample\n    Int threads = 4\n  }\n  output {\n    File bam = reads\n    Int count = threads\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("scaffold-tests")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "def test_pipeline(workflow_data, workflow_runner):",
+        ))
+        .stdout(predicate::str::contains(
+            "\"reads\": workflow_data[\"reads\"],",
+        ))
+        .stdout(predicate::str::contains(
+            "\"sample\": None,  # TODO: String",
+        ))
+        .stdout(predicate::str::contains(
+            "# \"threads\": None,  # Int, default 4",
+        ))
+        .stdout(predicate::str::contains("\"count\": None,  # TODO: Int"));
+
+    let output = dir.path().join("test_pipeline.py");
+    cmd()
+        .arg("scaffold-tests")
+        .arg(&wdl)
+        .args(["--style", "miniwdl", "-o"])
+        .arg(&output)
+        .assert()
+        .success();
+    let module = std::fs::read_to_string(&output).unwrap();
+    assert!(module.contains("def test_pipeline(tmp_path):"));
+    assert!(module.contains("\"pipeline.sample\": None,"));
+    assert!(module.contains("assert outputs[\"pipeline.bam\"] is not None"));
+}
+
+#[test]
+fn test_compat_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let callee = dir.path().join("sub.wdl");
+    std::fs::write(
+        &callee,
+        "version 1.1\n\nworkflow sub {\n  input {\n    String sample\n    Int n\n  }\n  output {\n    File out = \"x\"\n  }\n}\n",
+    )
+    .unwrap();
+    let caller = dir.path().join("main.wdl");
+    std::fs::write(
+        &caller,
+        "version 1.1\n\nimport \"sub.wdl\" as s\n\nworkflow main {\n  input {\n    Int count\n  }\n  call s.sub { input: sample = count, extra = 1 }\n  output {\n    File o = sub.missing\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("compat")
+        .arg(&caller)
+        .arg(&callee)
+        .assert()
+        .code(4)
+        .stdout(predicate::str::contains("missing_input"))
+        .stdout(predicate::str::contains("extra_input"))
+        .stdout(predicate::str::contains("type_mismatch"))
+        .stdout(predicate::str::contains("missing_output"));
+}
+
+#[test]
+fn test_inputs_template_and_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("inputs.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\nworkflow w {\n  input {\n    Array[File]+ reads\n    Int? threads\n  }\n}\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("inputs")
+        .arg(&wdl)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"w.reads\": [\n    null\n  ]"))
+        .stdout(predicate::str::contains("w.threads").not());
+
+    let inputs = dir.path().join("inputs.json");
+    std::fs::write(&inputs, r#"{"w.reads": [], "w.threads": 4}"#).unwrap();
+    cmd()
+        .arg("check-inputs")
+        .arg(&wdl)
+        .arg(&inputs)
+        .assert()
+        .code(4)
+        .stdout(predicate::str::contains("requires at least one element"));
+
+    std::fs::write(&inputs, r#"{"w.reads": ["a.bam"], "w.threads": null}"#).unwrap();
+    cmd()
+        .arg("check-inputs")
+        .arg(&wdl)
+        .arg(&inputs)
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_inputs_synthesize() {
+    let dir = tempfile::tempdir().unwrap();
+    let wdl = dir.path().join("synth.wdl");
+    std::fs::write(
+        &wdl,
+        "version 1.1\n\nstruct Sample {\n  String id\n  File reads\n}\n\nworkflow w {\n  input {\n    File input_bam\n    File variants\n    Array[Sample] samples\n    Int threads = 4\n    Boolean dry_run\n    Int memory_gb\n  }\n  parameter_meta {\n    variants: { help: \"Called variants in VCF format\" }\n    memory_gb: { help: \"for example 16\", example: 16 }\n  }\n}\n",
+    )
+    .unwrap();
+
+    let inputs = dir.path().join("inputs.json");
+    let output = cmd()
+        .arg("inputs")
+        .arg(&wdl)
+        .args(["--synthesize", "--optional"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["w.input_bam"], "input.bam");
+    assert_eq!(json["w.variants"], "variants.vcf");
+    assert_eq!(json["w.samples"][0]["reads"], "reads.txt");
+    assert_eq!(json["w.threads"], 4);
+    assert_eq!(json["w.dry_run"], false);
+    assert_eq!(json["w.memory_gb"], 16);
+
+    std::fs::write(&inputs, &output.stdout).unwrap();
+    cmd()
+        .arg("check-inputs")
+        .arg(&wdl)
+        .arg(&inputs)
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_lineage_command() {
+    cmd()
+        .arg("lineage")
+        .arg("examples/complex_example.wdl")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"workflow\": \"genomics_pipeline\"",
+        ))
+        .stdout(predicate::str::contains("\"scatter_variable\""));
+
+    cmd()
+        .arg("lineage")
+        .arg(FILE_PATH)
+        .arg("--format")
+        .arg("mermaid")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart LR"))
+        .stdout(predicate::str::contains(
+            "input_greeting_name -->|name| call_say_hello",
+        ));
+
+    cmd()
+        .arg("lineage")
+        .arg("examples/complex_example.wdl")
+        .args(["--workflow", "genomics_pipelin"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Workflow 'genomics_pipelin' not found in examples/complex_example.wdl; did you mean 'genomics_pipeline'?",
+        ));
+}
+
+#[test]
+fn test_info_wiring() {
+    cmd()
+        .arg("info")
+        .arg(FILE_PATH)
+        .arg("--wiring")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Call Wiring"))
+        .stdout(predicate::str::contains("[workflow input]"));
+
+    cmd()
+        .arg("info")
+        .arg("examples/complex_example.wdl")
+        .arg("--wiring")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"source\": \"scatter_variable\""))
+        .stdout(predicate::str::contains(
+            "\"default_value\": \"\\\"bwa\\\"\"",
+        ));
+}
+
+#[test]
+fn test_parse_tree_max_output_bytes() {
+    cmd()
+        .arg("parse")
+        .arg(FILE_PATH)
+        .arg("--max-output-bytes")
+        .arg("64")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("RootNode@"))
+        .stderr(predicate::str::contains("output truncated after 64 bytes"));
+}
+
+#[test]
+fn test_bench_command() {
+    cmd()
+        .arg("bench")
+        .arg("examples")
+        .arg("--iterations")
+        .arg("2")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"parse\""))
+        .stdout(predicate::str::contains("hello_world.wdl"));
+}
+
+#[test]
+fn test_bench_jsonl_streams_one_object_per_file() {
+    let output = cmd()
+        .arg("bench")
+        .arg("examples/hello_world.wdl")
+        .arg("examples/complex_example.wdl")
+        .arg("--iterations")
+        .arg("1")
+        .arg("--format")
+        .arg("jsonl")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let value: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(value["file"].is_string());
+    }
+}
+
+#[test]
+fn test_json_logging() {
+    cmd()
+        .arg("info")
+        .arg(FILE_PATH)
+        .arg("-LL")
+        .arg("--log-format")
+        .arg("json")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("\"level\":\"DEBUG\""))
+        .stderr(predicate::str::contains("read file"));
+}
+
+#[test]
+fn test_rpc_command() {
+    let requests = concat!(
+        r#"{"jsonrpc": "2.0", "id": 1, "method": "info", "params": {"content": "version 1.0\ntask t {\n  command <<< echo hi >>>\n}\n"}}"#,
+        "\n",
+        r#"{"jsonrpc": "2.0", "id": 2, "method": "bogus", "params": {}}"#,
+        "\n",
+        "not json\n",
+    );
+
+    cmd()
+        .arg("rpc")
+        .write_stdin(requests)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""name":"t""#))
+        .stdout(predicate::str::contains(r#""code":-32601"#))
+        .stdout(predicate::str::contains(r#""code":-32700"#));
+
+    // Every line that isn't a notification gets an answer
+    let output = cmd()
+        .arg("rpc")
+        .write_stdin(concat!(
+            "42\n",
+            r#"{"jsonrpc": "2.0", "id": 3}"#,
+            "\n",
+            "[]\n",
+            r#"[{"jsonrpc": "2.0", "id": 4, "method": "methods"}, {"jsonrpc": "2.0", "method": "methods"}, 7]"#,
+            "\n",
+        ))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["id"], serde_json::Value::Null);
+    assert_eq!(responses[0]["error"]["code"], -32600);
+    assert_eq!(responses[1]["id"], 3);
+    assert_eq!(responses[1]["error"]["code"], -32600);
+    assert_eq!(responses[2]["error"]["code"], -32600);
+    let batch = responses[3].as_array().unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[0]["id"], 4);
+    assert_eq!(batch[1]["error"]["code"], -32600);
+}
+
+/// A `wdlparse serve` process, killed when dropped
+struct ServeProcess(std::process::Child);
+
+impl Drop for ServeProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[test]
+fn test_serve_command() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let _server = ServeProcess(
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("wdlparse"))
+            .args(["serve", "--port", &port.to_string()])
+            .spawn()
+            .unwrap(),
+    );
+    let address = format!("http://127.0.0.1:{}", port);
+
+    let mut health = None;
+    for _ in 0..100 {
+        match ureq::get(&format!("{}/health", address)).call() {
+            Ok(response) => {
+                health = Some(response.into_string().unwrap());
+                break;
+            }
+            Err(_) => std::thread::sleep(std::time::Duration::from_millis(50)),
+        }
+    }
+    assert_eq!(health.as_deref(), Some(r#"{"status":"ok"}"#));
+
+    let wdl = std::fs::read_to_string(FILE_PATH).unwrap();
+    let expected = [
+        ("parse", "\"has_errors\":false"),
+        ("info", "\"name\":\"say_hello\""),
+        ("lint", "\"findings\""),
+        ("lineage", "\"outputs\""),
+        ("mermaid", "flowchart"),
+    ];
+    for (endpoint, fragment) in expected {
+        let body = ureq::post(&format!("{}/{}", address, endpoint))
+            .send_string(&wdl)
+            .unwrap()
+            .into_string()
+            .unwrap();
+        assert!(body.contains(fragment), "/{}: {}", endpoint, body);
+    }
+
+    let body = ureq::post(&format!("{}/info", address))
+        .set("Content-Type", "application/json")
+        .send_json(serde_json::json!({ "content": wdl }))
+        .unwrap()
+        .into_string()
+        .unwrap();
+    assert!(body.contains("\"name\":\"say_hello\""));
+
+    let status = |result: Result<ureq::Response, ureq::Error>| match result {
+        Ok(response) => response.status(),
+        Err(ureq::Error::Status(code, _)) => code,
+        Err(e) => panic!("request failed: {}", e),
+    };
+    assert_eq!(status(ureq::get(&format!("{}/bogus", address)).call()), 404);
+    assert_eq!(status(ureq::get(&format!("{}/info", address)).call()), 405);
+    let oversized = "x".repeat(16 * 1024 * 1024 + 1);
+    assert_eq!(
+        status(ureq::post(&format!("{}/parse", address)).send_string(&oversized)),
+        400
+    );
+}
+
+#[test]
+fn test_completions_and_manpages() {
+    cmd()
+        .arg("completions")
+        .arg("bash")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_wdlparse"));
+
+    let dir = tempfile::tempdir().unwrap();
+    cmd().arg("manpages").arg(dir.path()).assert().success();
+    assert!(dir.path().join("wdlparse.1").exists());
+    assert!(dir.path().join("wdlparse-info.1").exists());
+}
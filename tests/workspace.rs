@@ -0,0 +1,218 @@
+use std::fs;
+use wdl_grammar::{SyntaxKind, SyntaxTree};
+use wdlparse::edit::{self, SourceEdit, TriviaKind};
+use wdlparse::source::LineEnding;
+use wdlparse::workspace::{ImportOptions, SymbolKind, TextEdit, Workspace};
+
+#[test]
+fn test_workspace_resolves_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("lib.wdl"),
+        "version 1.0\n\ntask greet {\n  command <<< echo hi >>>\n}\n",
+    )
+    .unwrap();
+    let root = dir.path().join("main.wdl");
+    fs::write(
+        &root,
+        "version 1.0\n\nimport \"lib.wdl\" as lib\n\nworkflow main {\n  call lib.greet\n}\n",
+    )
+    .unwrap();
+
+    let mut workspace = Workspace::load(&root).unwrap();
+    assert_eq!(workspace.documents().count(), 2);
+    assert_eq!(
+        workspace.root().info.workflows[0].calls[0].target,
+        "lib.greet"
+    );
+
+    let symbol = workspace.lookup(&root, "lib.greet").unwrap();
+    assert_eq!(symbol.kind, SymbolKind::Task);
+    assert!(symbol.path.ends_with("lib.wdl"));
+
+    fs::write(
+        dir.path().join("lib.wdl"),
+        "version 1.0\n\ntask wave {\n  command <<< echo bye >>>\n}\n",
+    )
+    .unwrap();
+    workspace.invalidate(dir.path().join("lib.wdl")).unwrap();
+    assert!(workspace.lookup(&root, "lib.greet").is_none());
+    assert!(workspace.lookup(&root, "lib.wave").is_some());
+}
+
+#[test]
+fn test_workspace_searches_import_dirs() {
+    let dir = tempfile::tempdir().unwrap();
+    let lib = dir.path().join("lib");
+    fs::create_dir_all(lib.join("tasks")).unwrap();
+    fs::write(
+        lib.join("greet.wdl"),
+        "version 1.0\n\ntask greet {\n  command <<< echo hi >>>\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        lib.join("tasks/wave.wdl"),
+        "version 1.0\n\ntask wave {\n  command <<< echo bye >>>\n}\n",
+    )
+    .unwrap();
+    let root = dir.path().join("main.wdl");
+    fs::write(
+        &root,
+        "version 1.0\n\nimport \"greet.wdl\"\nimport \"tasks/wave.wdl\"\nimport \"shared/greet.wdl\" as flat\n\nworkflow main {\n  call greet.greet\n}\n",
+    )
+    .unwrap();
+
+    let workspace = Workspace::load(&root).unwrap();
+    assert_eq!(workspace.errors().len(), 3);
+
+    let workspace = Workspace::load_with_import_dirs(&root, vec![lib]).unwrap();
+    assert!(workspace.errors().is_empty());
+    assert!(workspace.lookup(&root, "greet.greet").is_some());
+    assert!(workspace.lookup(&root, "wave.wave").is_some());
+    // Flattened: `shared/greet.wdl` is found by its file name
+    assert!(workspace.lookup(&root, "flat.greet").is_some());
+}
+
+#[test]
+fn test_workspace_rewrites_remote_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    let mirror = dir.path().join("mirror");
+    fs::create_dir(&mirror).unwrap();
+    fs::write(
+        mirror.join("greet.wdl"),
+        "version 1.0\n\ntask greet {\n  command <<< echo hi >>>\n}\n",
+    )
+    .unwrap();
+    let root = dir.path().join("main.wdl");
+    fs::write(
+        &root,
+        "version 1.0\n\nimport \"https://raw.githubusercontent.com/org/tasks/main/greet.wdl\"\n\nworkflow main {\n  call greet.greet\n}\n",
+    )
+    .unwrap();
+
+    let workspace = Workspace::load(&root).unwrap();
+    assert_eq!(workspace.documents().count(), 1);
+
+    let options = ImportOptions {
+        rewrites: [(
+            "https://raw.githubusercontent.com/org/tasks/main/".to_string(),
+            format!("{}/", mirror.display()),
+        )]
+        .into(),
+        ..ImportOptions::default()
+    };
+    let workspace = Workspace::load_with_options(&root, options).unwrap();
+    assert_eq!(workspace.documents().count(), 2);
+    assert!(workspace.lookup(&root, "greet.greet").is_some());
+}
+
+#[test]
+fn test_apply_edits_reparses_document() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("main.wdl");
+    let content = "version 1.0\n\ntask greet {\n  command <<< echo hi >>>\n}\n";
+    fs::write(&root, content).unwrap();
+
+    let mut workspace = Workspace::load(&root).unwrap();
+    let start = content.find("greet").unwrap();
+    workspace
+        .apply_edits(
+            &root,
+            &[TextEdit {
+                range: start..start + "greet".len(),
+                text: "wave".to_string(),
+            }],
+        )
+        .unwrap();
+    assert_eq!(workspace.root().info.tasks[0].name, "wave");
+
+    let overlapping = [
+        TextEdit {
+            range: 0..5,
+            text: String::new(),
+        },
+        TextEdit {
+            range: 3..8,
+            text: String::new(),
+        },
+    ];
+    assert!(workspace.apply_edits(&root, &overlapping).is_err());
+}
+
+#[test]
+fn test_edit_apply() {
+    let source = "version 1.0\n# héllo\n";
+    let edits = [
+        SourceEdit::insert(0, "b"),
+        SourceEdit::replace(8..11, "1.1"),
+        SourceEdit::insert(0, "a"),
+        SourceEdit::delete(12..21),
+    ];
+    assert_eq!(edit::apply(source, &edits).unwrap(), "baversion 1.1\n");
+
+    let overlapping = [SourceEdit::delete(0..5), SourceEdit::delete(3..8)];
+    assert!(edit::apply(source, &overlapping)
+        .unwrap_err()
+        .to_string()
+        .contains("overlaps an earlier edit"));
+    let out_of_range = [SourceEdit::insert(source.len() + 1, "x")];
+    assert!(edit::apply(source, &out_of_range)
+        .unwrap_err()
+        .to_string()
+        .contains("outside the"));
+    let accent = source.find('é').unwrap();
+    let splitting = [SourceEdit::delete(accent..accent + 1)];
+    assert!(edit::apply(source, &splitting)
+        .unwrap_err()
+        .to_string()
+        .contains("splits a UTF-8 character"));
+}
+
+#[test]
+fn test_edit_trivia_and_node_range() {
+    let source = "version 1.0\n\n# greets\ntask greet {\n  command <<< echo hi >>>\n}  # done\n";
+    let (tree, _) = SyntaxTree::parse(source);
+    let root = tree.root();
+
+    let trivia = edit::trivia(&root);
+    assert!(trivia.iter().all(|t| source[t.range.clone()] == t.text));
+    assert!(trivia
+        .iter()
+        .any(|t| t.kind == TriviaKind::Whitespace && t.text == "\n\n"));
+    let comments: Vec<&str> = trivia
+        .iter()
+        .filter(|t| t.kind == TriviaKind::Comment)
+        .map(|t| t.text.trim_end())
+        .collect();
+    assert_eq!(comments, ["# greets", "# done"]);
+
+    let task = root
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::TaskDefinitionNode)
+        .unwrap();
+    let range = edit::node_range(&task);
+    assert!(source[range.clone()].starts_with("task greet {"));
+    assert!(source[range].ends_with(">>>\n}"));
+}
+
+#[test]
+fn test_load_normalizes_bom_and_crlf() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("main.wdl");
+    fs::write(
+        &root,
+        "\u{feff}version 1.0\r\n\r\ntask greet {\r\n  command <<< echo hi >>>\r\n}\r\n",
+    )
+    .unwrap();
+
+    let workspace = Workspace::load(&root).unwrap();
+    let document = workspace.root();
+    assert!(document.content.starts_with("version 1.0\n\n"));
+    assert!(!document.content.contains('\r'));
+    assert!(document.flavor.bom);
+    assert_eq!(document.flavor.line_ending, LineEnding::Crlf);
+    assert_eq!(
+        document.flavor.restore(&document.content),
+        fs::read_to_string(&root).unwrap()
+    );
+}